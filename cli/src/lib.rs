@@ -1,8 +1,15 @@
-use std::{cell::RefCell, path::PathBuf, rc::Rc, time::Instant};
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+    rc::Rc,
+    time::Instant,
+};
 
 use async_trait::async_trait;
 use capnp::message::ReaderOptions;
 use capnp_rpc::{pry, rpc_twoparty_capnp, twoparty, RpcSystem};
+use distill_core::{type_uuid::TypeUuid, AssetBundle, AssetRef, AssetTypeId, AssetUuid};
 use distill_schema::{
     data, pack,
     service::asset_hub::{self, snapshot::Client as Snapshot},
@@ -99,61 +106,261 @@ impl Command<Context> for CmdPack {
     }
 
     async fn run(&self, ctx: &Context, args: Vec<&str>) -> DynResult {
-        let mut out_file = std::fs::File::create(PathBuf::from(
-            args.get(0).expect("Expected file output path"),
-        ))?;
+        let out_path = PathBuf::from(args.first().expect("Expected file output path"));
         let start = Instant::now();
         let request = ctx.snapshot.borrow().get_all_asset_metadata_request();
         let response = request.send().promise.await?;
         let response = response.get()?;
-        let assets = response.get_assets()?;
-        let mut message = capnp::message::Builder::new_default();
-        let packfile_builder = message.init_root::<pack::pack_file::Builder>();
-        let mut num_valid_entries = 0;
-        let mut num_bytes = 0;
-        for asset in assets {
-            if matches!(
-                asset.get_latest_artifact().which()?,
-                data::asset_metadata::latest_artifact::Artifact(Ok(_))
-            ) {
-                num_valid_entries += 1;
+        let mut valid_assets = Vec::new();
+        for asset in response.get_assets()? {
+            if has_artifact(&asset)? {
+                valid_assets.push(asset);
             }
         }
-        let mut packfile_entries = packfile_builder.init_entries(num_valid_entries);
-        let mut i = 0;
-        for asset in assets {
-            if let data::asset_metadata::latest_artifact::Artifact(Ok(_)) =
-                asset.get_latest_artifact().which()?
-            {
-                let path_response = {
-                    let mut path_request = ctx.snapshot.borrow().get_path_for_assets_request();
-                    let req_list = path_request.get().init_assets(1);
-                    req_list.set_with_caveats(0, asset.get_id()?)?;
-                    path_request.send().promise.await?
-                };
-                let path = path_response.get()?.get_paths()?.get(0);
-                let artifact_response = {
-                    let mut artifact_request = ctx.snapshot.borrow().get_import_artifacts_request();
-                    let req_list = artifact_request.get().init_assets(1);
-                    req_list.set_with_caveats(0, asset.get_id()?)?;
-                    artifact_request.send().promise.await?
-                };
-                let artifact = artifact_response.get()?.get_artifacts()?.get(0);
-                let mut packfile_entry = packfile_entries.reborrow().get(i);
-                packfile_entry.set_asset_metadata(asset)?;
-                packfile_entry.set_artifact(artifact)?;
-                packfile_entry.set_path(path.get_path()?);
-                num_bytes += artifact.get_data()?.len();
-                // println!("path {:?}", std::str::from_utf8(path.get_path()?).unwrap());
-                i += 1;
+        let num_bytes = write_packfile(ctx, &valid_assets, &out_path).await?;
+        let total_time = Instant::now().duration_since(start);
+        println!(
+            "packed {} assets and {} MB in {}\r",
+            valid_assets.len(),
+            num_bytes / 1_000_000,
+            total_time.as_secs_f32(),
+        );
+        Ok(())
+    }
+}
+
+pub struct CmdPackBundles;
+#[async_trait(?Send)]
+impl Command<Context> for CmdPackBundles {
+    fn desc(&self) -> &str {
+        "<path> - Pack the artifacts of each asset bundle into a separate file in a directory"
+    }
+
+    fn nargs(&self) -> usize {
+        1
+    }
+
+    async fn run(&self, ctx: &Context, args: Vec<&str>) -> DynResult {
+        let out_dir = PathBuf::from(args.first().expect("Expected directory output path"));
+        std::fs::create_dir_all(&out_dir)?;
+        let start = Instant::now();
+        let request = ctx.snapshot.borrow().get_all_asset_metadata_request();
+        let response = request.send().promise.await?;
+        let response = response.get()?;
+        let mut valid_assets = HashMap::new();
+        let mut load_deps = HashMap::new();
+        let mut bundles = Vec::new();
+        for asset in response.get_assets()? {
+            if !has_artifact(&asset)? {
+                continue;
+            }
+            let metadata = distill_schema::parse_db_metadata(&asset);
+            let artifact = metadata.artifact.expect("expected artifact metadata");
+            if artifact.type_id == AssetTypeId(AssetBundle::UUID) {
+                bundles.push(metadata.id);
+            }
+            load_deps.insert(metadata.id, artifact.load_deps);
+            valid_assets.insert(metadata.id, asset);
+        }
+        bundles.sort();
+
+        // collect the load dependencies of each bundle, and count the bundles each asset is part of
+        let mut bundle_assets = Vec::new();
+        let mut num_bundles_by_asset = HashMap::new();
+        for bundle in &bundles {
+            let mut to_visit = vec![*bundle];
+            let mut visited: HashSet<AssetUuid> = to_visit.iter().cloned().collect();
+            while let Some(uuid) = to_visit.pop() {
+                for dep in load_deps.get(&uuid).into_iter().flatten() {
+                    if let AssetRef::Uuid(dep_uuid) = dep {
+                        if visited.insert(*dep_uuid) {
+                            to_visit.push(*dep_uuid);
+                        }
+                    }
+                }
+            }
+            for uuid in &visited {
+                *num_bundles_by_asset.entry(*uuid).or_insert(0) += 1;
             }
+            bundle_assets.push(visited);
         }
-        capnp::serialize::write_message(&mut out_file, &message)?;
-        out_file.sync_all().unwrap();
+
+        // assets that are part of multiple bundles are only packed once, into shared.pack
+        let packed_assets = |filter: &dyn Fn(usize) -> bool, assets: &HashSet<AssetUuid>| {
+            let mut assets: Vec<_> = assets
+                .iter()
+                .filter(|uuid| filter(num_bundles_by_asset[uuid]))
+                .collect();
+            assets.sort();
+            assets
+                .into_iter()
+                .filter_map(|uuid| valid_assets.get(uuid).copied())
+                .collect::<Vec<_>>()
+        };
+        let mut num_bytes = 0;
+        for (bundle, assets) in bundles.iter().zip(bundle_assets.iter()) {
+            let out_path = out_dir.join(format!(
+                "{}.pack",
+                uuid::Uuid::from_bytes(bundle.0).to_hyphenated()
+            ));
+            let assets = packed_assets(&|num_bundles| num_bundles == 1, assets);
+            num_bytes += write_packfile(ctx, &assets, &out_path).await?;
+        }
+        let all_assets = num_bundles_by_asset.keys().copied().collect();
+        let shared_assets = packed_assets(&|num_bundles| num_bundles > 1, &all_assets);
+        num_bytes += write_packfile(ctx, &shared_assets, &out_dir.join("shared.pack")).await?;
         let total_time = Instant::now().duration_since(start);
         println!(
-            "packed {} assets and {} MB in {}\r",
-            num_valid_entries,
+            "packed {} bundles with {} shared assets and {} MB in {}\r",
+            bundles.len(),
+            shared_assets.len(),
+            num_bytes / 1_000_000,
+            total_time.as_secs_f32(),
+        );
+        Ok(())
+    }
+}
+
+fn has_artifact(asset: &data::asset_metadata::Reader<'_>) -> DynResult<bool> {
+    Ok(matches!(
+        asset.get_latest_artifact().which()?,
+        data::asset_metadata::latest_artifact::Artifact(Ok(_))
+    ))
+}
+
+/// Requests the path and artifact of `asset`, and sets its metadata and path in `entry`. Returns a
+/// message with the artifact as its root, to be packed into `entry` or written to its own file.
+async fn export_entry(
+    snapshot: &Snapshot,
+    asset: data::asset_metadata::Reader<'_>,
+    mut entry: pack::pack_file_entry::Builder<'_>,
+) -> DynResult<capnp::message::Builder<capnp::message::HeapAllocator>> {
+    let path_response = {
+        let mut path_request = snapshot.get_path_for_assets_request();
+        let req_list = path_request.get().init_assets(1);
+        req_list.set_with_caveats(0, asset.get_id()?)?;
+        path_request.send().promise.await?
+    };
+    let path = path_response.get()?.get_paths()?.get(0);
+    let artifact_response = {
+        let mut artifact_request = snapshot.get_import_artifacts_request();
+        let req_list = artifact_request.get().init_assets(1);
+        req_list.set_with_caveats(0, asset.get_id()?)?;
+        artifact_request.send().promise.await?
+    };
+    let artifact = artifact_response.get()?.get_artifacts()?.get(0);
+    entry.set_asset_metadata(asset)?;
+    entry.set_path(path.get_path()?);
+    let mut artifact_message = capnp::message::Builder::new_default();
+    artifact_message.set_root(artifact)?;
+    Ok(artifact_message)
+}
+
+/// Writes `message` to a temporary file that is renamed to `out_path`, so that a reader never
+/// sees a partially written file.
+fn write_message_file<A: capnp::message::Allocator>(
+    message: &capnp::message::Builder<A>,
+    out_path: &Path,
+) -> DynResult {
+    let mut tmp_name = out_path.file_name().unwrap_or_default().to_os_string();
+    tmp_name.push(".tmp");
+    let tmp_path = out_path.with_file_name(tmp_name);
+    let mut out_file = std::fs::File::create(&tmp_path)?;
+    capnp::serialize::write_message(&mut out_file, message)?;
+    out_file.sync_all()?;
+    drop(out_file);
+    std::fs::rename(&tmp_path, out_path)?;
+    Ok(())
+}
+
+/// Writes the artifacts of `assets` into a packfile at `out_path`, and returns the size of the artifact data.
+async fn write_packfile(
+    ctx: &Context,
+    assets: &[data::asset_metadata::Reader<'_>],
+    out_path: &Path,
+) -> DynResult<usize> {
+    let snapshot = ctx.snapshot.borrow().clone();
+    let mut message = capnp::message::Builder::new_default();
+    let packfile_builder = message.init_root::<pack::pack_file::Builder>();
+    let mut num_bytes = 0;
+    let mut packfile_entries = packfile_builder.init_entries(assets.len() as u32);
+    for (i, asset) in assets.iter().enumerate() {
+        let entry = packfile_entries.reborrow().get(i as u32);
+        let artifact_message = export_entry(&snapshot, *asset, entry).await?;
+        let artifact = artifact_message.get_root_as_reader::<data::artifact::Reader<'_>>()?;
+        packfile_entries
+            .reborrow()
+            .get(i as u32)
+            .set_artifact(artifact)?;
+        num_bytes += artifact.get_data()?.len();
+    }
+    write_message_file(&message, out_path)?;
+    Ok(num_bytes)
+}
+
+/// Exports the artifacts of `snapshot` into `out_dir` as loose files, in the layout that
+/// `DirectoryIO` loads. Artifact files of assets that are no longer exported are removed. Returns
+/// the number of exported assets and artifact bytes.
+pub async fn export_dir(snapshot: &Snapshot, out_dir: &Path) -> DynResult<(u32, usize)> {
+    std::fs::create_dir_all(out_dir)?;
+    let request = snapshot.get_all_asset_metadata_request();
+    let response = request.send().promise.await?;
+    let response = response.get()?;
+    let mut valid_assets = Vec::new();
+    for asset in response.get_assets()? {
+        if has_artifact(&asset)? {
+            valid_assets.push(asset);
+        }
+    }
+    let mut index_message = capnp::message::Builder::new_default();
+    let index_builder = index_message.init_root::<pack::pack_file::Builder>();
+    let mut index_entries = index_builder.init_entries(valid_assets.len() as u32);
+    let mut artifact_file_names = HashSet::new();
+    let mut num_bytes = 0;
+    for (i, asset) in valid_assets.iter().enumerate() {
+        let entry = index_entries.reborrow().get(i as u32);
+        // artifact data is stored in a separate file per asset, the index only stores metadata
+        let artifact_message = export_entry(snapshot, *asset, entry).await?;
+        let artifact = artifact_message.get_root_as_reader::<data::artifact::Reader<'_>>()?;
+        let asset_uuid = uuid::Uuid::from_slice(asset.get_id()?.get_id()?)?;
+        let file_name = format!("{}.artifact", asset_uuid.to_hyphenated());
+        write_message_file(&artifact_message, &out_dir.join(&file_name))?;
+        artifact_file_names.insert(std::ffi::OsString::from(file_name));
+        num_bytes += artifact.get_data()?.len();
+    }
+    // the index is written last so that a watching loader sees a complete set of artifacts
+    write_message_file(&index_message, &out_dir.join("index.pack"))?;
+    for entry in std::fs::read_dir(out_dir)? {
+        let path = entry?.path();
+        let is_stale = path.extension().and_then(|ext| ext.to_str()) == Some("artifact")
+            && !matches!(path.file_name(), Some(name) if artifact_file_names.contains(name));
+        if is_stale {
+            std::fs::remove_file(&path)?;
+        }
+    }
+    Ok((valid_assets.len() as u32, num_bytes))
+}
+
+pub struct CmdExportDir;
+#[async_trait(?Send)]
+impl Command<Context> for CmdExportDir {
+    fn desc(&self) -> &str {
+        "<path> - Export artifacts into a directory of loose files"
+    }
+
+    fn nargs(&self) -> usize {
+        1
+    }
+
+    async fn run(&self, ctx: &Context, args: Vec<&str>) -> DynResult {
+        let out_dir = PathBuf::from(args.first().expect("Expected directory output path"));
+        let start = Instant::now();
+        let snapshot = ctx.snapshot.borrow().clone();
+        let (num_assets, num_bytes) = export_dir(&snapshot, &out_dir).await?;
+        let total_time = Instant::now().duration_since(start);
+        println!(
+            "exported {} assets and {} MB in {}\r",
+            num_assets,
             num_bytes / 1_000_000,
             total_time.as_secs_f32(),
         );
@@ -253,6 +460,9 @@ async fn print_asset_metadata(asset: &data::asset_metadata::Reader<'_>) -> DynRe
             print!(", search_tags: [ {} ]", tags.join(", "));
         }
     }
+    if let (true, Ok(data::asset_metadata::Error(Ok(error)))) = (asset.has_error(), asset.which()) {
+        print!(", error: {:?}", error.get_text().unwrap_or(""));
+    }
     println!(" }}\r");
     Ok(())
 }
@@ -417,6 +627,146 @@ impl Command<Context> for CmdAssetsForPath {
     }
 }
 
+pub struct CmdRename;
+#[async_trait(?Send)]
+impl Command<Context> for CmdRename {
+    fn desc(&self) -> &str {
+        "<path> <new_path> - Move a source file and its .meta file, rewriting path references to it"
+    }
+
+    fn nargs(&self) -> usize {
+        2
+    }
+
+    async fn run(&self, ctx: &Context, args: Vec<&str>) -> DynResult {
+        let mut request = ctx.snapshot.borrow().rename_file_request();
+        request.get().set_from(args[0].as_bytes());
+        request.get().set_to(args[1].as_bytes());
+        let response = request.send().promise.await?;
+        let response = response.get()?;
+        let dangling_refs = response.get_dangling_refs()?;
+        for dangling_ref in dangling_refs {
+            println!(
+                "{{ source: {}, path: {}, reason: {} }}\r",
+                std::str::from_utf8(dangling_ref.get_source()?)?,
+                std::str::from_utf8(dangling_ref.get_path()?)?,
+                dangling_ref.get_reason()?,
+            );
+        }
+        println!(
+            "moved {} to {} with {} dangling path references\r",
+            args[0],
+            args[1],
+            dangling_refs.len(),
+        );
+        Ok(())
+    }
+
+    async fn autocomplete(
+        &self,
+        ctx: &Context,
+        args: Vec<&str>,
+        whitespaces_last: usize,
+    ) -> DynResult<Autocomplete> {
+        if args.len() > 1 {
+            return Ok(Autocomplete::empty());
+        }
+        autocomplete_asset_paths(ctx, args.last().copied(), whitespaces_last).await
+    }
+}
+
+pub struct CmdCheckMeta;
+#[async_trait(?Send)]
+impl Command<Context> for CmdCheckMeta {
+    fn desc(&self) -> &str {
+        "- List .meta files without a source file, and source files whose .meta file is missing"
+    }
+
+    async fn run(&self, ctx: &Context, _args: Vec<&str>) -> DynResult {
+        let request = ctx.snapshot.borrow().get_meta_file_issues_request();
+        let response = request.send().promise.await?;
+        let response = response.get()?;
+        let orphaned_metas = response.get_orphaned_metas()?;
+        for meta_path in orphaned_metas {
+            println!(
+                "{{ orphaned_meta: {} }}\r",
+                std::str::from_utf8(meta_path?)?
+            );
+        }
+        let missing_metas = response.get_missing_metas()?;
+        for missing_meta in missing_metas {
+            let assets = missing_meta
+                .get_assets()?
+                .iter()
+                .map(|asset| Ok(uuid::Uuid::from_slice(asset.get_id()?)?.to_string()))
+                .collect::<DynResult<Vec<_>>>()?;
+            println!(
+                "{{ missing_meta: {}, assets: [{}] }}\r",
+                std::str::from_utf8(missing_meta.get_path()?)?,
+                assets.join(", ")
+            );
+        }
+        println!(
+            "found {} orphaned .meta files and {} missing .meta files\r",
+            orphaned_metas.len(),
+            missing_metas.len(),
+        );
+        Ok(())
+    }
+}
+
+pub struct CmdRegenerateMeta;
+#[async_trait(?Send)]
+impl Command<Context> for CmdRegenerateMeta {
+    fn desc(&self) -> &str {
+        "<path> - Write the missing .meta file of a source file from the DB, keeping its asset IDs"
+    }
+
+    fn nargs(&self) -> usize {
+        1
+    }
+
+    async fn run(&self, ctx: &Context, args: Vec<&str>) -> DynResult {
+        let mut request = ctx.snapshot.borrow().regenerate_meta_file_request();
+        request.get().set_path(args[0].as_bytes());
+        request.send().promise.await?;
+        println!("regenerated the .meta file of {}\r", args[0]);
+        Ok(())
+    }
+
+    async fn autocomplete(
+        &self,
+        ctx: &Context,
+        args: Vec<&str>,
+        whitespaces_last: usize,
+    ) -> DynResult<Autocomplete> {
+        if args.len() > 1 {
+            return Ok(Autocomplete::empty());
+        }
+        autocomplete_asset_paths(ctx, args.last().copied(), whitespaces_last).await
+    }
+}
+
+pub struct CmdRemoveOrphanedMeta;
+#[async_trait(?Send)]
+impl Command<Context> for CmdRemoveOrphanedMeta {
+    fn desc(&self) -> &str {
+        "<meta_path> - Delete a .meta file without a source file"
+    }
+
+    fn nargs(&self) -> usize {
+        1
+    }
+
+    async fn run(&self, ctx: &Context, args: Vec<&str>) -> DynResult {
+        let mut request = ctx.snapshot.borrow().remove_orphaned_meta_file_request();
+        request.get().set_path(args[0].as_bytes());
+        request.send().promise.await?;
+        println!("removed {}\r", args[0]);
+        Ok(())
+    }
+}
+
 async fn autocomplete_asset_paths(
     ctx: &Context,
     starting_str: Option<&str>,
@@ -12,11 +12,17 @@ async fn async_main() -> Result<(), Box<dyn std::error::Error>> {
     let mut shell = Shell::new(ctx);
 
     shell.register_command("pack", CmdPack);
+    shell.register_command("pack_bundles", CmdPackBundles);
+    shell.register_command("export_dir", CmdExportDir);
     shell.register_command("show_all", CmdShowAll);
     shell.register_command("get", CmdGet);
     shell.register_command("build", CmdBuild);
     shell.register_command("path_for_asset", CmdPathForAsset);
     shell.register_command("assets_for_path", CmdAssetsForPath);
+    shell.register_command("rename", CmdRename);
+    shell.register_command("check_meta", CmdCheckMeta);
+    shell.register_command("regenerate_meta", CmdRegenerateMeta);
+    shell.register_command("remove_orphaned_meta", CmdRemoveOrphanedMeta);
 
     shell.run_repl().await
 }
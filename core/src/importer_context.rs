@@ -1,11 +1,21 @@
 use futures_core::future::BoxFuture;
 
-use crate::{AssetRef, AssetUuid};
+use crate::{AssetRef, AssetTypeId, AssetUuid};
 
 pub trait ImporterContextHandle: Send + Sync {
     fn scope<'a>(&'a self, fut: BoxFuture<'a, ()>) -> BoxFuture<'a, ()>;
 
     fn begin_serialize_asset(&mut self, asset: AssetUuid);
+    /// Returns the asset types expected by typed references registered since `begin_serialize_asset`.
+    /// Must be called before `end_serialize_asset`.
+    fn expected_types(&self) -> Vec<(AssetRef, AssetTypeId)> {
+        Vec::new()
+    }
+    /// Returns the references registered as weak dependencies since `begin_serialize_asset`.
+    /// Must be called before `end_serialize_asset`.
+    fn weak_refs(&self) -> Vec<AssetRef> {
+        Vec::new()
+    }
     /// Returns any registered dependencies
     fn end_serialize_asset(&mut self, asset: AssetUuid) -> std::collections::HashSet<AssetRef>;
     /// Resolves an AssetRef to a specific AssetUuid
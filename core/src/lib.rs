@@ -169,6 +169,8 @@ impl<'de> Deserialize<'de> for AssetTypeId {
 pub enum AssetRef {
     Uuid(AssetUuid),
     Path(std::path::PathBuf),
+    /// Refers to every asset with a matching search tag, see [`utils::tag_query_matches`].
+    Tag(String),
 }
 impl AssetRef {
     pub fn expect_uuid(&self) -> &AssetUuid {
@@ -186,6 +188,22 @@ impl AssetRef {
     pub fn is_uuid(&self) -> bool {
         matches!(self, AssetRef::Uuid(_))
     }
+
+    pub fn is_tag(&self) -> bool {
+        matches!(self, AssetRef::Tag(_))
+    }
+}
+
+/// A named group of assets that is loaded as one unit.
+///
+/// The members are load dependencies of the bundle, so a handle to the bundle is loaded once all
+/// of its members are loaded. [`AssetRef::Tag`] members are resolved to every asset with a matching
+/// search tag when the bundle is imported.
+#[cfg(all(feature = "serde-1", feature = "type_uuid"))]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, type_uuid::TypeUuid)]
+#[uuid = "5c2b3ab8-3fa3-4b6e-8a4b-0f6f3f1e9a27"]
+pub struct AssetBundle {
+    pub assets: Vec<AssetRef>,
 }
 
 #[derive(Debug, Hash, PartialEq, Copy, Clone)]
@@ -214,6 +232,9 @@ pub struct AssetMetadata {
     pub build_pipeline: Option<AssetUuid>,
     /// The latest artifact produced when importing this asset
     pub artifact: Option<ArtifactMetadata>,
+    /// Describes why the asset can not be loaded, such as a cycle in its load dependencies
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub error: Option<String>,
 }
 
 /// 64-bit hash of the inputs that would produce a given asset artifact
@@ -243,6 +264,10 @@ pub struct ArtifactMetadata {
     pub uncompressed_size: Option<u64>,
     /// The UUID of the artifact's Rust type
     pub type_id: AssetTypeId,
+    /// Weak load dependencies are referenced by this asset, but are not loaded with it and are
+    /// not kept alive by it
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub weak_deps: Vec<AssetRef>,
 }
 
 /// Provides a unique 16-byte ID for a value's type.
@@ -1,4 +1,5 @@
 use std::{
+    collections::HashSet,
     ffi::OsStr,
     hash::{Hash, Hasher},
     path::{Path, PathBuf},
@@ -57,6 +58,96 @@ where
     hasher.finish()
 }
 
+/// Returns true if one of `search_tags` matches `query`, which is either `key=value` or a bare `key`
+/// that matches the key regardless of its value.
+pub fn tag_query_matches(query: &str, search_tags: &[(String, Option<String>)]) -> bool {
+    let (key, value) = match query.find('=') {
+        Some(idx) => (&query[..idx], Some(&query[idx + 1..])),
+        None => (query, None),
+    };
+    search_tags.iter().any(|(tag_key, tag_value)| {
+        tag_key == key && (value.is_none() || tag_value.as_deref() == value)
+    })
+}
+
+/// Returns a cycle of load dependencies that starts and ends with `id`, if there is one.
+///
+/// `load_deps` returns the load dependencies of an asset.
+pub fn find_dependency_cycle(
+    id: AssetUuid,
+    mut load_deps: impl FnMut(&AssetUuid) -> Vec<AssetUuid>,
+) -> Option<Vec<AssetUuid>> {
+    let mut path = vec![id];
+    let mut visited = HashSet::new();
+    let mut to_visit = vec![load_deps(&id).into_iter()];
+    while let Some(deps) = to_visit.last_mut() {
+        match deps.next() {
+            Some(dep) if dep == id => {
+                path.push(dep);
+                return Some(path);
+            }
+            Some(dep) => {
+                if visited.insert(dep) {
+                    path.push(dep);
+                    to_visit.push(load_deps(&dep).into_iter());
+                }
+            }
+            None => {
+                to_visit.pop();
+                path.pop();
+            }
+        }
+    }
+    None
+}
+
+/// Formats a dependency cycle returned by [`find_dependency_cycle`], such as `a -> b -> a`.
+pub fn format_dependency_cycle(cycle: &[AssetUuid]) -> String {
+    cycle
+        .iter()
+        .map(|id| id.to_string())
+        .collect::<Vec<_>>()
+        .join(" -> ")
+}
+
+/// Returns true if `path` matches the glob `pattern`. `*` matches any characters except `/`, `?`
+/// matches one character except `/` and `**` matches any characters including `/`, so `**/` also
+/// matches no directory at all.
+pub fn glob_match(pattern: &str, path: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let path: Vec<char> = path.chars().collect();
+    glob_match_chars(&pattern, &path)
+}
+
+fn glob_match_chars(pattern: &[char], path: &[char]) -> bool {
+    match pattern {
+        [] => path.is_empty(),
+        ['*', '*', rest @ ..] => {
+            (rest.first() == Some(&'/') && glob_match_chars(&rest[1..], path))
+                || (0..=path.len()).any(|idx| glob_match_chars(rest, &path[idx..]))
+        }
+        ['*', rest @ ..] => {
+            for idx in 0..=path.len() {
+                if glob_match_chars(rest, &path[idx..]) {
+                    return true;
+                }
+                if path.get(idx) == Some(&'/') {
+                    break;
+                }
+            }
+            false
+        }
+        ['?', rest @ ..] => match path {
+            [c, path @ ..] if *c != '/' => glob_match_chars(rest, path),
+            _ => false,
+        },
+        [p, rest @ ..] => match path {
+            [c, path @ ..] if c == p => glob_match_chars(rest, path),
+            _ => false,
+        },
+    }
+}
+
 #[cfg(feature = "path_utils")]
 pub fn canonicalize_path(path: &Path) -> PathBuf {
     use path_slash::{PathBufExt, PathExt};
@@ -0,0 +1,45 @@
+use std::collections::HashMap;
+
+use distill_core::{utils, AssetUuid};
+
+fn graph(edges: &[(u8, u8)]) -> HashMap<AssetUuid, Vec<AssetUuid>> {
+    let mut graph: HashMap<_, Vec<_>> = HashMap::new();
+    for (from, to) in edges {
+        graph
+            .entry(AssetUuid([*from; 16]))
+            .or_default()
+            .push(AssetUuid([*to; 16]));
+    }
+    graph
+}
+
+#[test]
+fn find_dependency_cycle() {
+    let graph = graph(&[(1, 2), (2, 3), (2, 4), (4, 1), (3, 3)]);
+    let load_deps = |id: &AssetUuid| graph.get(id).cloned().unwrap_or_default();
+
+    assert_eq!(
+        utils::find_dependency_cycle(AssetUuid([1; 16]), load_deps),
+        Some(vec![
+            AssetUuid([1; 16]),
+            AssetUuid([2; 16]),
+            AssetUuid([4; 16]),
+            AssetUuid([1; 16])
+        ])
+    );
+    assert_eq!(
+        utils::find_dependency_cycle(AssetUuid([3; 16]), load_deps),
+        Some(vec![AssetUuid([3; 16]), AssetUuid([3; 16])])
+    );
+}
+
+#[test]
+fn find_dependency_cycle_without_cycle() {
+    let graph = graph(&[(1, 2), (1, 3), (2, 3)]);
+    let load_deps = |id: &AssetUuid| graph.get(id).cloned().unwrap_or_default();
+
+    assert_eq!(
+        utils::find_dependency_cycle(AssetUuid([1; 16]), load_deps),
+        None
+    );
+}
@@ -495,6 +495,131 @@ impl AssetHubSnapshotImpl {
             ))
         }
     }
+
+    async fn rename_file(
+        snapshot: Arc<SnapshotTxn>,
+        params: asset_hub::snapshot::RenameFileParams,
+        mut results: asset_hub::snapshot::RenameFileResults,
+    ) -> Result<()> {
+        let params = params.get()?;
+        let ctx = &snapshot.ctx;
+        let watch_dirs = canonical_watch_dirs(ctx);
+        let from = resolve_existing_path(&watch_dirs, params.get_from()?)?
+            .ok_or_else(|| Error::Custom("Source file does not exist in an asset dir".into()))?;
+        let root = watch_dirs
+            .iter()
+            .find(|dir| from.starts_with(dir))
+            .expect("resolved path is not in a watch dir");
+        let to = path::PathBuf::from(std::str::from_utf8(params.get_to()?)?);
+        let to = canonicalize_path(&root.join(to));
+        if !watch_dirs.iter().any(|dir| to.starts_with(dir)) {
+            return Err(Error::Custom(
+                "Cannot move a source file out of the asset dirs".into(),
+            ));
+        }
+
+        let dangling_refs = ctx.file_source.rename_source(&from, &to).await?;
+        let mut results = results.get().init_dangling_refs(dangling_refs.len() as u32);
+        for (idx, dangling_ref) in dangling_refs.iter().enumerate() {
+            let source = relative_path_string(&watch_dirs, &dangling_ref.source);
+            let mut result = results.reborrow().get(idx as u32);
+            result.set_source(source.as_bytes());
+            result.set_path(dangling_ref.path.to_string_lossy().as_bytes());
+            result.set_reason(&dangling_ref.reason);
+        }
+        Ok(())
+    }
+
+    fn get_meta_file_issues(
+        &mut self,
+        _params: asset_hub::snapshot::GetMetaFileIssuesParams,
+        mut results: asset_hub::snapshot::GetMetaFileIssuesResults,
+    ) -> Result<()> {
+        let ctx = self.txn.ctx();
+        let watch_dirs = canonical_watch_dirs(ctx);
+        let issues = ctx.file_source.check_meta_files(self.txn.txn())?;
+        let mut results_builder = results.get();
+        let mut orphaned_metas = results_builder
+            .reborrow()
+            .init_orphaned_metas(issues.orphaned_metas.len() as u32);
+        for (idx, meta_path) in issues.orphaned_metas.iter().enumerate() {
+            orphaned_metas.set(
+                idx as u32,
+                relative_path_string(&watch_dirs, meta_path).as_bytes(),
+            );
+        }
+        let mut missing_metas =
+            results_builder.init_missing_metas(issues.missing_metas.len() as u32);
+        for (idx, (path, assets)) in issues.missing_metas.iter().enumerate() {
+            let mut missing_meta = missing_metas.reborrow().get(idx as u32);
+            missing_meta.set_path(relative_path_string(&watch_dirs, path).as_bytes());
+            let mut asset_results = missing_meta.init_assets(assets.len() as u32);
+            for (idx, asset) in assets.iter().enumerate() {
+                asset_results.reborrow().get(idx as u32).set_id(&asset.0);
+            }
+        }
+        Ok(())
+    }
+
+    async fn regenerate_meta_file(
+        snapshot: Arc<SnapshotTxn>,
+        params: asset_hub::snapshot::RegenerateMetaFileParams,
+    ) -> Result<()> {
+        let params = params.get()?;
+        let ctx = &snapshot.ctx;
+        let watch_dirs = canonical_watch_dirs(ctx);
+        let path = resolve_existing_path(&watch_dirs, params.get_path()?)?
+            .ok_or_else(|| Error::Custom("Source file does not exist in an asset dir".into()))?;
+        ctx.file_source.regenerate_meta_file(&path).await
+    }
+
+    fn remove_orphaned_meta_file(
+        &mut self,
+        params: asset_hub::snapshot::RemoveOrphanedMetaFileParams,
+    ) -> Result<()> {
+        let params = params.get()?;
+        let ctx = self.txn.ctx();
+        let watch_dirs = canonical_watch_dirs(ctx);
+        let meta_path = resolve_existing_path(&watch_dirs, params.get_path()?)?
+            .ok_or_else(|| Error::Custom(".meta file does not exist in an asset dir".into()))?;
+        ctx.file_source.remove_orphaned_meta_file(&meta_path)
+    }
+}
+
+fn canonical_watch_dirs(ctx: &ServiceContext) -> Vec<path::PathBuf> {
+    ctx.file_tracker
+        .get_watch_dirs()
+        .iter()
+        .map(|dir| canonicalize_path(dir))
+        .collect()
+}
+
+/// Returns the existing file at `path` in one of the `watch_dirs`. Relative paths are relative to
+/// the watched directory that contains the file.
+fn resolve_existing_path(
+    watch_dirs: &[path::PathBuf],
+    path: &[u8],
+) -> Result<Option<path::PathBuf>> {
+    let path = path::PathBuf::from(std::str::from_utf8(path)?);
+    let path = if path.is_relative() {
+        watch_dirs
+            .iter()
+            .map(|dir| canonicalize_path(&dir.join(&path)))
+            .find(|path| path.exists())
+    } else {
+        Some(canonicalize_path(&path))
+    };
+    Ok(path.filter(|path| watch_dirs.iter().any(|dir| path.starts_with(dir))))
+}
+
+/// Returns `path` relative to the watched directory that contains it, with `/` separators.
+fn relative_path_string(watch_dirs: &[path::PathBuf], path: &path::Path) -> String {
+    watch_dirs
+        .iter()
+        .find_map(|dir| path.strip_prefix(dir).ok())
+        .unwrap_or(path)
+        .to_string_lossy()
+        .replace("\\", "/")
 }
 
 #[allow(clippy::unit_arg)]
@@ -517,6 +642,15 @@ impl asset_hub::Server for AssetHubImpl {
         let fut = AssetHubImpl::get_snapshot(self.ctx.clone(), params, results);
         Promise::from_future(async { fut.await.map_err(|e| e.into()) })
     }
+
+    fn get_daemon_info(
+        &mut self,
+        params: asset_hub::GetDaemonInfoParams,
+        results: asset_hub::GetDaemonInfoResults,
+    ) -> Promise<()> {
+        log::trace!("asset_hub::Server::get_daemon_info");
+        Promise::ok(pry!(AssetHubImpl::get_daemon_info(params, results)))
+    }
 }
 impl AssetHubImpl {
     fn register_listener(
@@ -560,6 +694,17 @@ impl AssetHubImpl {
         results.get().set_snapshot(capnp_rpc::new_client(snapshot));
         Ok(())
     }
+
+    fn get_daemon_info(
+        _params: asset_hub::GetDaemonInfoParams,
+        mut results: asset_hub::GetDaemonInfoResults,
+    ) -> Result<()> {
+        results
+            .get()
+            .init_info()
+            .set_version(distill_schema::PROTOCOL_VERSION);
+        Ok(())
+    }
 }
 
 impl AssetHubService {
@@ -715,4 +860,46 @@ impl asset_hub::snapshot::Server for AssetHubSnapshotImpl {
         let fut = AssetHubSnapshotImpl::update_asset(self.txn.clone(), params, results);
         Promise::from_future(async { fut.await.map_err(|e| e.into()) })
     }
+
+    fn rename_file(
+        &mut self,
+        params: asset_hub::snapshot::RenameFileParams,
+        results: asset_hub::snapshot::RenameFileResults,
+    ) -> Promise<()> {
+        log::trace!("asset_hub::snapshot::Server::rename_file");
+        let fut = AssetHubSnapshotImpl::rename_file(self.txn.clone(), params, results);
+        Promise::from_future(async { fut.await.map_err(|e| e.into()) })
+    }
+
+    fn get_meta_file_issues(
+        &mut self,
+        params: asset_hub::snapshot::GetMetaFileIssuesParams,
+        results: asset_hub::snapshot::GetMetaFileIssuesResults,
+    ) -> Promise<()> {
+        log::trace!("asset_hub::snapshot::Server::get_meta_file_issues");
+        Promise::ok(pry!(AssetHubSnapshotImpl::get_meta_file_issues(
+            self, params, results
+        )))
+    }
+
+    fn regenerate_meta_file(
+        &mut self,
+        params: asset_hub::snapshot::RegenerateMetaFileParams,
+        _results: asset_hub::snapshot::RegenerateMetaFileResults,
+    ) -> Promise<()> {
+        log::trace!("asset_hub::snapshot::Server::regenerate_meta_file");
+        let fut = AssetHubSnapshotImpl::regenerate_meta_file(self.txn.clone(), params);
+        Promise::from_future(async { fut.await.map_err(|e| e.into()) })
+    }
+
+    fn remove_orphaned_meta_file(
+        &mut self,
+        params: asset_hub::snapshot::RemoveOrphanedMetaFileParams,
+        _results: asset_hub::snapshot::RemoveOrphanedMetaFileResults,
+    ) -> Promise<()> {
+        log::trace!("asset_hub::snapshot::Server::remove_orphaned_meta_file");
+        Promise::ok(pry!(AssetHubSnapshotImpl::remove_orphaned_meta_file(
+            self, params
+        )))
+    }
 }
@@ -0,0 +1,269 @@
+use std::{
+    collections::HashMap,
+    fs,
+    net::SocketAddr,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use serde::Deserialize;
+
+use crate::{
+    error::{Error, Result},
+    watcher::WatchMode,
+};
+
+/// Settings of an [`AssetDaemon`](crate::AssetDaemon) that can be set in a project config file.
+///
+/// Settings that are `None` keep the value the daemon already has.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct DaemonSettings {
+    /// Directories that are watched for source files.
+    pub asset_dirs: Option<Vec<PathBuf>>,
+    /// Directory of the asset database.
+    pub db_path: Option<PathBuf>,
+    /// Address the RPC service listens on.
+    pub address: Option<SocketAddr>,
+    pub log_level: Option<log::LevelFilter>,
+    /// Maps a file extension to the name of an importer in the [`ImporterRegistry`](crate::ImporterRegistry).
+    pub importers: HashMap<String, String>,
+    /// Importers for files that match a glob pattern, which are tried in order before
+    /// `importers`.
+    pub importer_globs: Vec<ImporterGlob>,
+    /// Directories of importer plugins to load, which requires the `plugins` feature.
+    pub plugin_dirs: Option<Vec<PathBuf>>,
+    /// Rules in `.gitignore` syntax for files in the asset dirs that are not tracked, in addition
+    /// to the rules of the `.distillignore` files in the asset dirs.
+    pub ignore: Vec<String>,
+    /// How changes to source files are detected, defaults to [`WatchMode::Auto`].
+    pub watch_mode: Option<WatchMode>,
+    /// Milliseconds between scans when polling for changes.
+    pub poll_interval_ms: Option<u64>,
+    /// Maximum number of files that are checked per scan when polling for changes.
+    pub poll_scan_budget: Option<usize>,
+    /// Generate new asset IDs for source files that were copied together with their .meta files.
+    /// Otherwise the copies are only reported as import errors.
+    pub repair_duplicate_asset_ids: Option<bool>,
+}
+
+/// Uses the importer named `importer` in the [`ImporterRegistry`](crate::ImporterRegistry) for
+/// files that match `pattern`, see [`ImporterMap::insert_glob`](crate::ImporterMap::insert_glob).
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ImporterGlob {
+    pub pattern: String,
+    pub importer: String,
+}
+
+impl DaemonSettings {
+    /// Applies the settings that are set in `overrides` on top of these settings.
+    pub fn merge(&mut self, overrides: DaemonSettings) {
+        if overrides.asset_dirs.is_some() {
+            self.asset_dirs = overrides.asset_dirs;
+        }
+        if overrides.db_path.is_some() {
+            self.db_path = overrides.db_path;
+        }
+        if overrides.address.is_some() {
+            self.address = overrides.address;
+        }
+        if overrides.log_level.is_some() {
+            self.log_level = overrides.log_level;
+        }
+        self.importers.extend(overrides.importers);
+        // patterns of the overrides are tried first
+        let globs = std::mem::replace(&mut self.importer_globs, overrides.importer_globs);
+        self.importer_globs.extend(globs);
+        if overrides.plugin_dirs.is_some() {
+            self.plugin_dirs = overrides.plugin_dirs;
+        }
+        self.ignore.extend(overrides.ignore);
+        if overrides.watch_mode.is_some() {
+            self.watch_mode = overrides.watch_mode;
+        }
+        if overrides.poll_interval_ms.is_some() {
+            self.poll_interval_ms = overrides.poll_interval_ms;
+        }
+        if overrides.poll_scan_budget.is_some() {
+            self.poll_scan_budget = overrides.poll_scan_budget;
+        }
+        if overrides.repair_duplicate_asset_ids.is_some() {
+            self.repair_duplicate_asset_ids = overrides.repair_duplicate_asset_ids;
+        }
+    }
+
+    fn resolve_paths(&mut self, base: &Path) {
+        for dirs in self
+            .asset_dirs
+            .iter_mut()
+            .chain(self.plugin_dirs.iter_mut())
+        {
+            for dir in dirs.iter_mut() {
+                *dir = base.join(&dir);
+            }
+        }
+        if let Some(path) = self.db_path.as_mut() {
+            *path = base.join(&path);
+        }
+    }
+}
+
+/// A project config file for the daemon, in TOML:
+///
+/// ```toml
+/// asset_dirs = ["assets"]
+/// db_path = ".assets_db"
+/// address = "127.0.0.1:9999"
+/// log_level = "info"
+/// plugin_dirs = ["plugins"]
+/// ignore = ["*.swp", ".git/", "/tools/cache/"]
+/// watch_mode = "auto"
+/// poll_interval_ms = 2000
+/// poll_scan_budget = 10000
+/// repair_duplicate_asset_ids = false
+///
+/// [importers]
+/// ron = "ron"
+///
+/// [[importer_globs]]
+/// pattern = "terrain/*.png"
+/// importer = "heightmap"
+///
+/// [profiles.ci]
+/// db_path = "target/assets_db"
+/// log_level = "warn"
+/// ```
+///
+/// Each profile contains [`DaemonSettings`] that override the top-level settings when the
+/// profile is selected with [`DaemonConfig::settings`].
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct DaemonConfig {
+    #[serde(flatten)]
+    pub settings: DaemonSettings,
+    #[serde(default)]
+    pub profiles: HashMap<String, DaemonSettings>,
+}
+
+impl DaemonConfig {
+    /// Reads a config file. Relative paths in the file are relative to the directory of the file.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let text = fs::read_to_string(path)?;
+        let mut config: DaemonConfig = text
+            .parse()
+            .map_err(|err| Error::ConfigDeError(path.to_owned(), err))?;
+        if let Some(base) = path.parent() {
+            config.settings.resolve_paths(base);
+            for profile in config.profiles.values_mut() {
+                profile.resolve_paths(base);
+            }
+        }
+        Ok(config)
+    }
+
+    /// Returns the top-level settings, overridden by the settings of `profile` if one is given.
+    pub fn settings(&self, profile: Option<&str>) -> Result<DaemonSettings> {
+        let mut settings = self.settings.clone();
+        if let Some(name) = profile {
+            let overrides = self
+                .profiles
+                .get(name)
+                .ok_or_else(|| Error::Custom(format!("no profile named `{}` in config", name)))?;
+            settings.merge(overrides.clone());
+        }
+        Ok(settings)
+    }
+}
+
+impl FromStr for DaemonConfig {
+    type Err = toml::de::Error;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        toml::from_str(s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFIG: &str = r#"
+        asset_dirs = ["assets", "shared/assets"]
+        db_path = ".assets_db"
+        address = "127.0.0.1:9999"
+        log_level = "info"
+        ignore = ["*.swp", ".git/"]
+
+        [importers]
+        ron = "ron"
+
+        [[importer_globs]]
+        pattern = "*.prefab.ron"
+        importer = "prefab"
+
+        [profiles.ci]
+        db_path = "target/assets_db"
+        log_level = "warn"
+        ignore = ["/build/"]
+        watch_mode = "poll"
+        poll_interval_ms = 500
+
+        [profiles.ci.importers]
+        png = "image"
+
+        [[profiles.ci.importer_globs]]
+        pattern = "terrain/*.png"
+        importer = "heightmap"
+    "#;
+
+    #[test]
+    fn parse_config() {
+        let config: DaemonConfig = CONFIG.parse().unwrap();
+        let settings = config.settings(None).unwrap();
+        assert_eq!(
+            settings.asset_dirs,
+            Some(vec![
+                PathBuf::from("assets"),
+                PathBuf::from("shared/assets")
+            ])
+        );
+        assert_eq!(settings.db_path, Some(PathBuf::from(".assets_db")));
+        assert_eq!(settings.address, Some("127.0.0.1:9999".parse().unwrap()));
+        assert_eq!(settings.log_level, Some(log::LevelFilter::Info));
+        assert_eq!(settings.importers.len(), 1);
+        assert_eq!(
+            settings.importer_globs,
+            vec![ImporterGlob {
+                pattern: "*.prefab.ron".to_string(),
+                importer: "prefab".to_string(),
+            }]
+        );
+        assert!(config.settings(Some("release")).is_err());
+    }
+
+    #[test]
+    fn profile_overrides_settings() {
+        let config: DaemonConfig = CONFIG.parse().unwrap();
+        let settings = config.settings(Some("ci")).unwrap();
+        assert_eq!(settings.asset_dirs.map(|dirs| dirs.len()), Some(2));
+        assert_eq!(settings.db_path, Some(PathBuf::from("target/assets_db")));
+        assert_eq!(settings.log_level, Some(log::LevelFilter::Warn));
+        assert_eq!(
+            settings.importers.get("ron").map(String::as_str),
+            Some("ron")
+        );
+        assert_eq!(
+            settings.importers.get("png").map(String::as_str),
+            Some("image")
+        );
+        let patterns: Vec<_> = settings
+            .importer_globs
+            .iter()
+            .map(|glob| glob.pattern.as_str())
+            .collect();
+        assert_eq!(patterns, vec!["terrain/*.png", "*.prefab.ron"]);
+        assert_eq!(settings.ignore, vec!["*.swp", ".git/", "/build/"]);
+        assert_eq!(settings.watch_mode, Some(WatchMode::Poll));
+        assert_eq!(settings.poll_interval_ms, Some(500));
+    }
+}
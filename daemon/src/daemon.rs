@@ -6,34 +6,125 @@ use std::{
     sync::Arc,
     thread,
     thread::JoinHandle,
+    time::Duration,
 };
 
 use asset_hub::AssetHub;
 use asset_hub_service::AssetHubService;
+use distill_core::{utils, AssetTypeId};
 use distill_importer::{BoxedImporter, ImporterContext};
 use distill_schema::data;
 use file_asset_source::FileAssetSource;
 use tokio::sync::oneshot::{self, Receiver, Sender};
 
+#[cfg(feature = "plugins")]
+use crate::plugins::{load_importer_plugins, ImporterPlugin};
 use crate::{
-    artifact_cache::ArtifactCache, asset_hub, asset_hub_service, capnp_db::Environment,
-    error::Result, file_asset_source, file_tracker::FileTracker,
+    artifact_cache::ArtifactCache,
+    asset_hub, asset_hub_service,
+    capnp_db::Environment,
+    config::DaemonSettings,
+    error::{Error, Result},
+    file_asset_source,
+    file_tracker::FileTracker,
+    watcher::WatcherSettings,
 };
 
+/// Chooses the importer of a source file by its path.
+///
+/// Glob patterns are tried first, in the order they were inserted, then the longest extension of
+/// the file name, so `tar.gz` is preferred over `gz`. A .meta file can instead pin an importer by
+/// its type UUID, see [`ImporterMap::get_by_uuid`].
 #[derive(Default)]
-pub struct ImporterMap(HashMap<String, Box<dyn BoxedImporter>>);
+pub struct ImporterMap {
+    extensions: HashMap<String, Box<dyn BoxedImporter>>,
+    globs: Vec<(String, Box<dyn BoxedImporter>)>,
+}
 
 impl ImporterMap {
+    /// Registers `importer` for files with the extension `ext`, which may contain dots such as
+    /// `prefab.ron`.
     pub fn insert(&mut self, ext: &str, importer: Box<dyn BoxedImporter>) {
-        self.0.insert(ext.to_lowercase(), importer);
+        self.extensions.insert(ext.to_lowercase(), importer);
+    }
+
+    /// Registers `importer` for files that match the glob `pattern`, see
+    /// [`glob_match`](distill_core::utils::glob_match). Patterns are matched case-insensitively
+    /// against the end of the path, unless they start with `/`.
+    pub fn insert_glob(&mut self, pattern: &str, importer: Box<dyn BoxedImporter>) {
+        let pattern = pattern.replace('\\', "/").to_lowercase();
+        let pattern = if pattern.starts_with('/') {
+            pattern
+        } else {
+            format!("**/{}", pattern)
+        };
+        self.globs.push((pattern, importer));
     }
 
     pub fn get_by_path<'a>(&'a self, path: &Path) -> Option<&'a dyn BoxedImporter> {
-        let lower_extension = path
-            .extension()
-            .map(|s| s.to_str().unwrap().to_lowercase())
-            .unwrap_or_else(|| "".to_string());
-        self.0.get(lower_extension.as_str()).map(|i| i.as_ref())
+        let path = path.to_string_lossy().replace('\\', "/").to_lowercase();
+        if let Some((_, importer)) = self
+            .globs
+            .iter()
+            .find(|(pattern, _)| utils::glob_match(pattern, &path))
+        {
+            return Some(importer.as_ref());
+        }
+        let file_name = &path[path.rfind('/').map_or(0, |idx| idx + 1)..];
+        // a leading dot starts a hidden file name rather than an extension
+        let mut extensions = file_name
+            .match_indices('.')
+            .filter(|(idx, _)| *idx > 0)
+            .map(|(idx, _)| &file_name[idx + 1..])
+            .peekable();
+        if extensions.peek().is_none() {
+            self.extensions.get("")
+        } else {
+            extensions.find_map(|ext| self.extensions.get(ext))
+        }
+        .map(|importer| importer.as_ref())
+    }
+
+    /// Returns a registered importer with the type UUID `importer_type`.
+    pub fn get_by_uuid<'a>(&'a self, importer_type: &AssetTypeId) -> Option<&'a dyn BoxedImporter> {
+        self.globs
+            .iter()
+            .map(|(_, importer)| importer)
+            .chain(self.extensions.values())
+            .find(|importer| importer.uuid() == importer_type.0)
+            .map(|importer| importer.as_ref())
+    }
+}
+
+type ImporterFactory = Box<dyn Fn() -> Box<dyn BoxedImporter> + Send + Sync>;
+
+/// Creates importers by name, so the importer of each file extension can be chosen in a config
+/// file rather than in code, see [`DaemonSettings::importers`].
+#[derive(Default)]
+pub struct ImporterRegistry(HashMap<String, ImporterFactory>);
+
+impl ImporterRegistry {
+    pub fn register<F>(&mut self, name: &str, factory: F)
+    where
+        F: Fn() -> Box<dyn BoxedImporter> + Send + Sync + 'static,
+    {
+        self.0.insert(name.to_owned(), Box::new(factory));
+    }
+
+    pub fn with_importer<F>(mut self, name: &str, factory: F) -> Self
+    where
+        F: Fn() -> Box<dyn BoxedImporter> + Send + Sync + 'static,
+    {
+        self.register(name, factory);
+        self
+    }
+
+    pub fn create(&self, name: &str) -> Option<Box<dyn BoxedImporter>> {
+        self.0.get(name).map(|factory| factory())
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.0.keys().map(|name| name.as_str())
     }
 }
 
@@ -57,6 +148,16 @@ pub struct AssetDaemon {
     pub importers: ImporterMap,
     pub importer_contexts: Vec<Box<dyn ImporterContext>>,
     pub asset_dirs: Vec<PathBuf>,
+    /// Rules for files in the asset dirs that are not tracked, see [`DaemonSettings::ignore`].
+    pub ignore_patterns: Vec<String>,
+    /// How changes to source files are detected.
+    pub watcher_settings: WatcherSettings,
+    /// Generate new IDs for source files that were copied together with their .meta files,
+    /// instead of only reporting them.
+    pub repair_duplicate_asset_ids: bool,
+    /// Libraries of the plugins that registered importers, which are unloaded after the daemon stops.
+    #[cfg(feature = "plugins")]
+    pub plugins: Vec<ImporterPlugin>,
 }
 
 pub fn default_importer_contexts() -> Vec<Box<dyn ImporterContext + 'static>> {
@@ -71,10 +172,24 @@ pub fn default_importers() -> Vec<(&'static str, Box<dyn BoxedImporter>)> {
     let mut importers: Vec<(&'static str, Box<dyn BoxedImporter>)> = vec![];
 
     distill_importer::if_serde_importers!(
-        importers.push(("ron", Box::new(distill_importer::RonImporter::default())))
+        importers.push(("ron", Box::new(distill_importer::RonImporter)));
+        importers.push(("bundle", Box::new(distill_importer::BundleImporter)))
     );
     importers
 }
+
+/// Returns a registry of the importers that are built into the daemon, named after the file
+/// extension they are registered for by [`default_importers`].
+#[allow(unused_mut)]
+pub fn default_importer_registry() -> ImporterRegistry {
+    let mut registry = ImporterRegistry::default();
+    distill_importer::if_serde_importers!(
+        registry.register("ron", || Box::new(distill_importer::RonImporter));
+        registry.register("bundle", || Box::new(distill_importer::BundleImporter))
+    );
+    registry
+}
+
 impl Default for AssetDaemon {
     fn default() -> Self {
         let mut importer_map = ImporterMap::default();
@@ -87,6 +202,11 @@ impl Default for AssetDaemon {
             importers: importer_map,
             importer_contexts: default_importer_contexts(),
             asset_dirs: vec![PathBuf::from("assets")],
+            ignore_patterns: Vec::new(),
+            watcher_settings: WatcherSettings::default(),
+            repair_duplicate_asset_ids: false,
+            #[cfg(feature = "plugins")]
+            plugins: Vec::new(),
         }
     }
 }
@@ -117,6 +237,23 @@ impl AssetDaemon {
         self.importers.insert(ext, Box::new(importer));
     }
 
+    /// Uses `importer` for files that match the glob `pattern`, see [`ImporterMap::insert_glob`].
+    /// Patterns take precedence over extensions and are tried in the order they were added.
+    pub fn with_importer_glob<B>(mut self, pattern: &str, importer: B) -> Self
+    where
+        B: BoxedImporter + 'static,
+    {
+        self.importers.insert_glob(pattern, Box::new(importer));
+        self
+    }
+
+    pub fn add_importer_glob<B>(&mut self, pattern: &str, importer: B)
+    where
+        B: BoxedImporter + 'static,
+    {
+        self.importers.insert_glob(pattern, Box::new(importer));
+    }
+
     pub fn with_importers<B, I>(self, importers: I) -> Self
     where
         B: BoxedImporter + 'static,
@@ -165,17 +302,118 @@ impl AssetDaemon {
         self
     }
 
-    pub fn run(self) -> (JoinHandle<()>, Sender<bool>) {
+    pub fn with_ignore_patterns(mut self, patterns: Vec<String>) -> Self {
+        self.ignore_patterns = patterns;
+        self
+    }
+
+    pub fn with_watcher_settings(mut self, settings: WatcherSettings) -> Self {
+        self.watcher_settings = settings;
+        self
+    }
+
+    pub fn with_duplicate_asset_id_repair(mut self, repair: bool) -> Self {
+        self.repair_duplicate_asset_ids = repair;
+        self
+    }
+
+    /// Loads the importer plugins in `dir` and registers their importers.
+    #[cfg(feature = "plugins")]
+    pub fn with_plugin_dir<P: AsRef<Path>>(mut self, dir: P) -> Result<Self> {
+        for (plugin, importers) in load_importer_plugins(dir.as_ref())? {
+            log::info!(
+                "loaded importer plugin {} for {:?}",
+                plugin.path.display(),
+                importers.iter().map(|(ext, _)| ext).collect::<Vec<_>>()
+            );
+            for (ext, importer) in importers {
+                self.importers.insert(&ext, importer);
+            }
+            self.plugins.push(plugin);
+        }
+        Ok(self)
+    }
+
+    /// Applies the settings of a project config file, creating the configured importers with
+    /// `registry`.
+    pub fn with_settings(
+        mut self,
+        settings: &DaemonSettings,
+        registry: &ImporterRegistry,
+    ) -> Result<Self> {
+        if let Some(dirs) = &settings.asset_dirs {
+            self.asset_dirs = dirs.clone();
+        }
+        if let Some(path) = &settings.db_path {
+            self.db_dir = path.clone();
+        }
+        if let Some(address) = settings.address {
+            self.address = address;
+        }
+        self.ignore_patterns.extend(settings.ignore.iter().cloned());
+        if let Some(mode) = settings.watch_mode {
+            self.watcher_settings.mode = mode;
+        }
+        if let Some(interval) = settings.poll_interval_ms {
+            self.watcher_settings.poll_interval = Duration::from_millis(interval);
+        }
+        if let Some(budget) = settings.poll_scan_budget {
+            self.watcher_settings.poll_scan_budget = budget;
+        }
+        if let Some(repair) = settings.repair_duplicate_asset_ids {
+            self.repair_duplicate_asset_ids = repair;
+        }
+        for dir in settings.plugin_dirs.iter().flatten() {
+            #[cfg(feature = "plugins")]
+            {
+                self = self.with_plugin_dir(dir)?;
+            }
+            #[cfg(not(feature = "plugins"))]
+            return Err(Error::Custom(format!(
+                "can not load plugins in {}, the daemon was built without the `plugins` feature",
+                dir.display()
+            )));
+        }
+        for (ext, name) in &settings.importers {
+            let importer = registry.create(name).ok_or_else(|| {
+                Error::Custom(format!(
+                    "no importer named `{}` is registered for extension `{}`",
+                    name, ext
+                ))
+            })?;
+            self.importers.insert(ext, importer);
+        }
+        for glob in &settings.importer_globs {
+            let importer = registry.create(&glob.importer).ok_or_else(|| {
+                Error::Custom(format!(
+                    "no importer named `{}` is registered for pattern `{}`",
+                    glob.importer, glob.pattern
+                ))
+            })?;
+            self.importers.insert_glob(&glob.pattern, importer);
+        }
+        Ok(self)
+    }
+
+    #[allow(unused_mut)]
+    pub fn run(mut self) -> (JoinHandle<()>, Sender<bool>) {
         let (tx, rx) = oneshot::channel();
 
         let handle = thread::spawn(|| {
+            // importers from plugins may still be referenced by tasks until the runtime is dropped
+            #[cfg(feature = "plugins")]
+            let plugins = std::mem::take(&mut self.plugins);
             let rpc_runtime = tokio::runtime::Builder::new_current_thread()
                 .enable_all()
                 .build()
                 .unwrap();
             let local = tokio::task::LocalSet::new();
 
-            rpc_runtime.block_on(local.run_until(self.run_rpc_runtime(rx)))
+            rpc_runtime.block_on(local.run_until(self.run_rpc_runtime(rx)));
+            drop(local);
+            drop(rpc_runtime);
+            #[cfg(feature = "plugins")]
+            drop(plugins);
         });
 
         (handle, tx)
@@ -205,7 +443,9 @@ impl AssetDaemon {
             .expect("failed to check daemon version in asset db");
 
         let to_watch = self.asset_dirs.iter().map(|p| p.to_str().unwrap());
-        let tracker = FileTracker::new(asset_db.clone(), to_watch);
+        let tracker = FileTracker::new(asset_db.clone(), to_watch)
+            .with_ignore_patterns(self.ignore_patterns)
+            .with_watcher_settings(self.watcher_settings);
         let tracker = Arc::new(tracker);
 
         let hub = AssetHub::new(asset_db.clone()).expect("failed to create asset hub");
@@ -227,7 +467,8 @@ impl AssetDaemon {
 
         let asset_source =
             FileAssetSource::new(&tracker, &hub, &asset_db, &importers, &artifact_cache, ctxs)
-                .expect("failed to create asset source");
+                .expect("failed to create asset source")
+                .with_duplicate_asset_id_repair(self.repair_duplicate_asset_ids);
 
         let asset_source = Arc::new(asset_source);
 
@@ -259,6 +500,10 @@ impl AssetDaemon {
                     Ok(_) => {
                         log::warn!("Shutting Down!");
                         shutdown_tracker.stop().await;
+                        // the asset source stops once the tracker has dropped its listeners. Dropping
+                        // it while it imports would block on the import tasks, which can't run on
+                        // this thread.
+                        asset_source_handle.await.expect("AssetSource panicked");
                         // shutdown_service.stop().await;
                         // shutdown_asset_source.stop().await;
                         // any value on this channel means shutdown
@@ -12,6 +12,7 @@ pub enum Error {
     RonError(ron::Error),
     ErasedSerde(erased_serde::Error),
     MetaDeError(PathBuf, ron::Error),
+    ConfigDeError(PathBuf, toml::de::Error),
     SetLoggerError(log::SetLoggerError),
     UuidLength,
     RecvError,
@@ -37,6 +38,7 @@ impl std::error::Error for Error {
             Error::ErasedSerde(ref e) => Some(e),
             Error::RonError(ref e) => Some(e),
             Error::MetaDeError(_, ref e) => Some(e),
+            Error::ConfigDeError(_, ref e) => Some(e),
             Error::SetLoggerError(ref e) => Some(e),
             Error::UuidLength => None,
             Error::RecvError => None,
@@ -64,6 +66,10 @@ impl fmt::Display for Error {
                 write!(f, "metadata {} ", path.display())?;
                 e.fmt(f)
             }
+            Error::ConfigDeError(ref path, ref e) => {
+                write!(f, "config {} ", path.display())?;
+                e.fmt(f)
+            }
             Error::SetLoggerError(ref e) => e.fmt(f),
             Error::UuidLength => write!(f, "{}", self),
             Error::RecvError => write!(f, "{}", self),
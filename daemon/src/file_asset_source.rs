@@ -1,9 +1,10 @@
 use std::{
     collections::{HashMap, HashSet},
+    fs,
     path::{Path, PathBuf},
     str,
     sync::Arc,
-    time::Instant,
+    time::{Instant, SystemTime},
 };
 
 use bincode::config::Options;
@@ -12,7 +13,7 @@ use distill_core::{
     ArtifactId, AssetRef, AssetTypeId, AssetUuid, CompressionType,
 };
 use distill_importer::{
-    ArtifactMetadata, AssetMetadata, BoxedImporter, ImporterContext, SerializedAsset,
+    ArtifactMetadata, AssetMetadata, BoxedImporter, ImportOp, ImporterContext, SerializedAsset,
 };
 use distill_schema::{
     build_asset_metadata,
@@ -27,13 +28,15 @@ use rayon::prelude::*;
 use crate::{
     artifact_cache::ArtifactCache,
     asset_hub::{self, AssetHub},
-    capnp_db::{CapnpCursor, DBTransaction, Environment, MessageReader, RwTransaction},
+    capnp_db::{
+        CapnpCursor, DBTransaction, Environment, MessageReader, RoTransaction, RwTransaction,
+    },
     daemon::ImporterMap,
     error::{Error, Result},
     file_tracker::{FileState, FileTracker, FileTrackerEvent},
     source_pair_import::{
-        self, hash_file, HashedSourcePair, ImportResultMetadata, SourceMetadata, SourcePair,
-        SourcePairImport,
+        self, hash_file, AssetImportResult, HashedSourcePair, ImportResultMetadata, SourceMetadata,
+        SourcePair, SourcePairImport,
     },
 };
 
@@ -45,6 +48,9 @@ pub(crate) struct FileAssetSource {
     tables: FileAssetSourceTables,
     importers: Arc<ImporterMap>,
     importer_contexts: Arc<Vec<Box<dyn ImporterContext>>>,
+    /// Rewrite the .meta files of source files that duplicate the asset IDs of another source
+    /// file, so that they are imported with new IDs.
+    repair_duplicate_asset_ids: bool,
 }
 
 struct FileAssetSourceTables {
@@ -57,6 +63,15 @@ struct FileAssetSourceTables {
     /// Reverse index of a path reference to a list of paths to source files referencing the path
     /// Path -> PathRefs
     reverse_path_refs: lmdb::Database,
+    /// Reverse index of a source dependency to a list of paths to source files that read it
+    /// Path -> PathRefs
+    reverse_source_dependencies: lmdb::Database,
+    /// Reverse index of a tag query to a list of paths to source files referencing the tag query
+    /// Tag -> PathRefs
+    reverse_tag_refs: lmdb::Database,
+    /// Index of the assets that are flagged with a load dependency cycle error
+    /// AssetUuid -> ()
+    dependency_cycles: lmdb::Database,
 }
 
 #[derive(Debug)]
@@ -72,6 +87,26 @@ struct PairImportResultMetadata<'a> {
 
 type SerializedAssetVec = SerializedAsset<Vec<u8>>;
 
+/// A path reference to a moved source file that could not be rewritten.
+#[derive(Debug)]
+pub(crate) struct DanglingPathRef {
+    /// The source file that contains the reference.
+    pub source: PathBuf,
+    /// The reference, as written in the source file.
+    pub path: PathBuf,
+    pub reason: String,
+}
+
+/// Source files and .meta files that are not in pairs, found by
+/// [`FileAssetSource::check_meta_files`].
+#[derive(Debug, Default)]
+pub(crate) struct MetaFileIssues {
+    /// .meta files without a source file.
+    pub orphaned_metas: Vec<PathBuf>,
+    /// Source files without a .meta file, with the IDs of their assets in the DB.
+    pub missing_metas: Vec<(PathBuf, Vec<AssetUuid>)>,
+}
+
 #[cfg(feature = "parallel_hash")]
 fn hash_files<'a, T, I>(pairs: I) -> Vec<Result<HashedSourcePair>>
 where
@@ -141,6 +176,94 @@ where
         .collect()
 }
 
+fn read_source_dependencies(metadata: &source_metadata::Reader<'_>) -> Vec<PathBuf> {
+    metadata
+        .get_source_dependencies()
+        .expect("capnp: Failed to get source dependencies")
+        .iter()
+        .map(|dependency| {
+            PathBuf::from(
+                str::from_utf8(dependency.expect("capnp: Failed to read source dependency"))
+                    .expect("Failed to parse source dependency as utf8"),
+            )
+        })
+        .collect()
+}
+
+fn read_path_refs(metadata: &source_metadata::Reader<'_>) -> Vec<PathBuf> {
+    metadata
+        .get_path_refs()
+        .expect("capnp: Failed to get path refs")
+        .iter()
+        .map(|path_ref| {
+            PathBuf::from(
+                str::from_utf8(path_ref.expect("capnp: Failed to read path ref"))
+                    .expect("Failed to parse path ref as utf8"),
+            )
+        })
+        .collect()
+}
+
+/// Returns an import error for the path references of an artifact that did not resolve to an
+/// asset, which are dropped from its dependencies.
+fn unresolved_path_refs_error(artifact: &ArtifactMetadata) -> Option<String> {
+    let unresolved: Vec<String> = artifact
+        .load_deps
+        .iter()
+        .chain(artifact.build_deps.iter())
+        .filter_map(|dep| match dep {
+            AssetRef::Path(path) => Some(format!("{:?}", path)),
+            _ => None,
+        })
+        .collect();
+    if unresolved.is_empty() {
+        None
+    } else {
+        Some(format!(
+            "path references can't be resolved: {}",
+            unresolved.join(", ")
+        ))
+    }
+}
+
+/// Returns the tag queries that the assets depend on, see [`AssetRef::Tag`].
+fn collect_tag_refs<'a>(assets: impl IntoIterator<Item = &'a AssetMetadata>) -> Vec<String> {
+    let mut tag_refs = Vec::new();
+    for artifact in assets
+        .into_iter()
+        .filter_map(|asset| asset.artifact.as_ref())
+    {
+        for dep in artifact.load_deps.iter().chain(artifact.build_deps.iter()) {
+            if let AssetRef::Tag(tag) = dep {
+                if !tag_refs.contains(tag) {
+                    tag_refs.push(tag.clone());
+                }
+            }
+        }
+    }
+    tag_refs
+}
+
+fn read_tag_refs(metadata: &source_metadata::Reader<'_>) -> Vec<String> {
+    let assets: Vec<_> = metadata
+        .get_assets()
+        .expect("capnp: Failed to get assets")
+        .iter()
+        .map(|asset| parse_db_metadata(&asset))
+        .collect();
+    collect_tag_refs(&assets)
+}
+
+/// Returns when the .meta file of a source file was created, or the source file if it has no
+/// .meta file yet. Falls back to the modification time if the platform has no creation time.
+fn source_created_time(path: &Path) -> Option<SystemTime> {
+    let meta_path = utils::to_meta_path(path);
+    let metadata = fs::metadata(&meta_path)
+        .or_else(|_| fs::metadata(path))
+        .ok()?;
+    metadata.created().or_else(|_| metadata.modified()).ok()
+}
+
 fn resolve_source_path(abs_source_path: &Path, path: &Path) -> PathBuf {
     let absolute_path = if path.is_relative() {
         // TODO check from root of asset folder as well?
@@ -175,12 +298,26 @@ impl FileAssetSource {
                     .create_db(Some("asset_id_to_path"), lmdb::DatabaseFlags::default())?,
                 reverse_path_refs: db
                     .create_db(Some("reverse_path_refs"), lmdb::DatabaseFlags::default())?,
+                reverse_source_dependencies: db.create_db(
+                    Some("reverse_source_dependencies"),
+                    lmdb::DatabaseFlags::default(),
+                )?,
+                reverse_tag_refs: db
+                    .create_db(Some("reverse_tag_refs"), lmdb::DatabaseFlags::default())?,
+                dependency_cycles: db
+                    .create_db(Some("dependency_cycles"), lmdb::DatabaseFlags::default())?,
             },
             importers: importers.clone(),
             importer_contexts,
+            repair_duplicate_asset_ids: false,
         })
     }
 
+    pub fn with_duplicate_asset_id_repair(mut self, repair: bool) -> Self {
+        self.repair_duplicate_asset_ids = repair;
+        self
+    }
+
     fn put_metadata<'a>(
         &self,
         txn: &'a mut RwTransaction<'_>,
@@ -190,6 +327,16 @@ impl FileAssetSource {
     ) -> Result<Vec<AssetUuid>> {
         let metadata_assets = &result_metadata.assets;
         let mut affected_assets = Vec::new();
+        let dependencies_to_remove = self
+            .get_metadata(txn, path)
+            .map(|existing| {
+                read_source_dependencies(&existing.get().expect("capnp: Failed to read metadata"))
+            })
+            .unwrap_or_default();
+        let tag_refs_to_remove = self
+            .get_metadata(txn, path)
+            .map(|existing| read_tag_refs(&existing.get().expect("capnp: Failed to read metadata")))
+            .unwrap_or_default();
         let (assets_to_remove, path_refs_to_remove): (Vec<AssetUuid>, Vec<PathBuf>) = self
             .get_metadata(txn, path)
             .map(|existing| {
@@ -277,6 +424,28 @@ impl FileAssetSource {
                 self.add_path_ref(txn, path, &path_ref);
             }
         }
+        for tag in tag_refs_to_remove {
+            self.remove_reverse_ref(txn, self.tables.reverse_tag_refs, path, &tag);
+        }
+        for tag in collect_tag_refs(metadata_assets) {
+            self.add_reverse_ref(txn, self.tables.reverse_tag_refs, path, &tag);
+        }
+        for dependency in dependencies_to_remove {
+            self.remove_reverse_ref(
+                txn,
+                self.tables.reverse_source_dependencies,
+                path,
+                &dependency.to_string_lossy(),
+            );
+        }
+        for dependency in &result_metadata.source_dependencies {
+            self.add_reverse_ref(
+                txn,
+                self.tables.reverse_source_dependencies,
+                path,
+                &dependency.to_string_lossy(),
+            );
+        }
         let mut value_builder = capnp::message::Builder::new_default();
 
         {
@@ -307,6 +476,14 @@ impl FileAssetSource {
                     .reborrow()
                     .set(idx as u32, path_ref.to_string_lossy().as_bytes());
             }
+            let mut source_dependencies = value
+                .reborrow()
+                .init_source_dependencies(result_metadata.source_dependencies.len() as u32);
+            for (idx, dependency) in result_metadata.source_dependencies.iter().enumerate() {
+                source_dependencies
+                    .reborrow()
+                    .set(idx as u32, dependency.to_string_lossy().as_bytes());
+            }
 
             let mut assets = value.reborrow().init_assets(metadata_assets.len() as u32);
             for (idx, asset) in metadata_assets.iter().enumerate() {
@@ -356,7 +533,6 @@ impl FileAssetSource {
             .expect("db: Failed to get source metadata from path_to_metadata table")
     }
 
-    #[allow(dead_code)]
     pub fn iter_metadata<'a, V: DBTransaction<'a, T>, T: lmdb::Transaction + 'a>(
         &self,
         txn: &'a V,
@@ -374,6 +550,27 @@ impl FileAssetSource {
     }
 
     fn delete_metadata(&self, txn: &mut RwTransaction<'_>, path: &Path) -> Vec<AssetUuid> {
+        let dependencies = self
+            .get_metadata(txn, path)
+            .map(|existing| {
+                read_source_dependencies(&existing.get().expect("capnp: Failed to read metadata"))
+            })
+            .unwrap_or_default();
+        for dependency in dependencies {
+            self.remove_reverse_ref(
+                txn,
+                self.tables.reverse_source_dependencies,
+                path,
+                &dependency.to_string_lossy(),
+            );
+        }
+        let tag_refs = self
+            .get_metadata(txn, path)
+            .map(|existing| read_tag_refs(&existing.get().expect("capnp: Failed to read metadata")))
+            .unwrap_or_default();
+        for tag in tag_refs {
+            self.remove_reverse_ref(txn, self.tables.reverse_tag_refs, path, &tag);
+        }
         let to_remove: Vec<AssetUuid> = self
             .get_metadata(txn, path)
             .map(|existing| {
@@ -444,9 +641,88 @@ impl FileAssetSource {
                     None
                 }
             }
+            // Tags can match any number of assets, see `resolve_tag_ref`
+            AssetRef::Tag(_) => None,
         }
     }
 
+    /// Returns the assets with a search tag matching the `tag` query, sorted by UUID.
+    ///
+    /// `pending` contains the metadata of assets that are not yet updated in the hub, and takes
+    /// precedence over the metadata in the hub. `None` marks an asset that is being removed.
+    fn resolve_tag_ref<'a, V: DBTransaction<'a, T>, T: lmdb::Transaction + 'a>(
+        &self,
+        txn: &'a V,
+        tag: &str,
+        pending: &HashMap<AssetUuid, Option<AssetMetadata>>,
+    ) -> Vec<AssetUuid> {
+        let mut matches = Vec::new();
+        for (_, value) in self
+            .hub
+            .get_metadata_iter(txn)
+            .expect("db: failed to iterate asset metadata")
+            .capnp_iter_start()
+        {
+            let value = value.expect("db: failed to read asset metadata");
+            let metadata = value.into_typed::<data::asset_metadata::Owned>();
+            let metadata = parse_db_metadata(&metadata.get().expect("capnp: metadata read failed"));
+            if !pending.contains_key(&metadata.id)
+                && utils::tag_query_matches(tag, &metadata.search_tags)
+            {
+                matches.push(metadata.id);
+            }
+        }
+        matches.extend(
+            pending
+                .values()
+                .flatten()
+                .filter(|metadata| utils::tag_query_matches(tag, &metadata.search_tags))
+                .map(|metadata| metadata.id),
+        );
+        matches.sort();
+        matches
+    }
+
+    /// Returns an error for each typed handle in `assets` that refers to an asset of another type.
+    fn check_expected_types<'a, V: DBTransaction<'a, T>, T: lmdb::Transaction + 'a>(
+        &self,
+        txn: &'a V,
+        source_path: &Path,
+        assets: &[AssetImportResult],
+    ) -> Vec<String> {
+        let mut errors = Vec::new();
+        for asset in assets {
+            for (asset_ref, expected) in &asset.expected_types {
+                let id = match self.resolve_asset_ref(txn, source_path, asset_ref) {
+                    Some(id) => id,
+                    None => continue,
+                };
+                // Prefer the asset types of this import, since the DB may contain an older import
+                let found = match assets.iter().find(|a| a.metadata.id == id) {
+                    Some(a) => a
+                        .metadata
+                        .artifact
+                        .as_ref()
+                        .map(|artifact| artifact.type_id),
+                    None => self.hub.get_metadata(txn, &id).and_then(|metadata| {
+                        parse_db_metadata(&metadata.get().expect("capnp: metadata read failed"))
+                            .artifact
+                            .map(|artifact| artifact.type_id)
+                    }),
+                };
+                if let Some(found) = found {
+                    if found != *expected {
+                        errors.push(format!(
+                            "asset {} has a handle to asset {} of type {}, but the handle expects type {}",
+                            asset.metadata.id, id, found, expected
+                        ));
+                    }
+                }
+            }
+        }
+        errors
+    }
+
     fn put_asset_path<'a>(
         &self,
         txn: &'a mut RwTransaction<'_>,
@@ -481,11 +757,26 @@ impl FileAssetSource {
         path_ref: &Path,
     ) -> bool {
         let path_ref = resolve_source_path(source, path_ref);
-        let key_str = path_ref.to_string_lossy();
-        let key = key_str.as_bytes();
+        self.add_reverse_ref(
+            txn,
+            self.tables.reverse_path_refs,
+            source,
+            &path_ref.to_string_lossy(),
+        )
+    }
+
+    /// Adds `source` to the list of source files referencing `target` in the reverse index `table`.
+    fn add_reverse_ref(
+        &self,
+        txn: &mut RwTransaction<'_>,
+        table: lmdb::Database,
+        source: &Path,
+        target: &str,
+    ) -> bool {
+        let key = target.as_bytes();
         let existing_refs = txn
-            .get::<path_refs::Owned, &[u8]>(self.tables.reverse_path_refs, &key)
-            .expect("db: Failed to get path ref from reverse_path_refs table");
+            .get::<path_refs::Owned, &[u8]>(table, &key)
+            .expect("db: Failed to get path ref from reverse index");
         let path_ref_str = source.to_string_lossy();
         let path_ref_bytes = path_ref_str.as_bytes();
         let mut message = capnp::message::Builder::new_default();
@@ -515,7 +806,7 @@ impl FileAssetSource {
             list.init_paths(1)
         };
         paths.set(new_size - 1, &path_ref_bytes);
-        txn.put(self.tables.reverse_path_refs, &key, &message)
+        txn.put(table, &key, &message)
             .expect("lmdb: failed to put path ref");
         true
     }
@@ -525,10 +816,64 @@ impl FileAssetSource {
         txn: &'a V,
         path: &Path,
     ) -> Vec<PathBuf> {
-        let key_str = path.to_string_lossy();
-        let key = key_str.as_bytes();
-        txn.get::<path_refs::Owned, &[u8]>(self.tables.reverse_path_refs, &key)
-            .expect("db: Failed to get asset_id from asset_id_to_path table")
+        self.get_reverse_refs(txn, self.tables.reverse_path_refs, &path.to_string_lossy())
+    }
+
+    /// Returns the source files with assets that depend on the `tag` query.
+    pub fn get_tag_refs<'a, V: DBTransaction<'a, T>, T: lmdb::Transaction + 'a>(
+        &self,
+        txn: &'a V,
+        tag: &str,
+    ) -> Vec<PathBuf> {
+        self.get_reverse_refs(txn, self.tables.reverse_tag_refs, tag)
+    }
+
+    /// Returns the source files with assets that depend on a tag query matching one of the
+    /// `search_tags`.
+    fn get_tag_refs_matching<'a, V: DBTransaction<'a, T>, T: lmdb::Transaction + 'a>(
+        &self,
+        txn: &'a V,
+        search_tags: &[(String, Option<String>)],
+    ) -> Vec<PathBuf> {
+        let mut sources = Vec::new();
+        let cursor = txn
+            .open_ro_cursor(self.tables.reverse_tag_refs)
+            .expect("db: Failed to open cursor for reverse tag refs");
+        for (key, _) in cursor.capnp_iter_start() {
+            let tag = str::from_utf8(key).expect("db: Failed to read tag ref as utf8");
+            if utils::tag_query_matches(tag, search_tags) {
+                for source in self.get_tag_refs(txn, tag) {
+                    if !sources.contains(&source) {
+                        sources.push(source);
+                    }
+                }
+            }
+        }
+        sources
+    }
+
+    /// Returns the source files that read `path` as a source dependency.
+    pub fn get_source_dependents<'a, V: DBTransaction<'a, T>, T: lmdb::Transaction + 'a>(
+        &self,
+        txn: &'a V,
+        path: &Path,
+    ) -> Vec<PathBuf> {
+        self.get_reverse_refs(
+            txn,
+            self.tables.reverse_source_dependencies,
+            &path.to_string_lossy(),
+        )
+    }
+
+    fn get_reverse_refs<'a, V: DBTransaction<'a, T>, T: lmdb::Transaction + 'a>(
+        &self,
+        txn: &'a V,
+        table: lmdb::Database,
+        target: &str,
+    ) -> Vec<PathBuf> {
+        let key = target.as_bytes();
+        txn.get::<path_refs::Owned, &[u8]>(table, &key)
+            .expect("db: Failed to get path refs from reverse index")
             .map_or(Vec::new(), |path_refs_message| {
                 let path_refs_message = path_refs_message
                     .get()
@@ -552,11 +897,27 @@ impl FileAssetSource {
 
     fn remove_path_ref(&self, txn: &mut RwTransaction<'_>, source: &Path, path_ref: &Path) -> bool {
         let path_ref = resolve_source_path(source, path_ref);
-        let key_str = path_ref.to_string_lossy();
-        let key = key_str.as_bytes();
+        self.remove_reverse_ref(
+            txn,
+            self.tables.reverse_path_refs,
+            source,
+            &path_ref.to_string_lossy(),
+        )
+    }
+
+    /// Removes `source` from the list of source files referencing `target` in the reverse index
+    /// `table`.
+    fn remove_reverse_ref(
+        &self,
+        txn: &mut RwTransaction<'_>,
+        table: lmdb::Database,
+        source: &Path,
+        target: &str,
+    ) -> bool {
+        let key = target.as_bytes();
         let existing_refs = txn
-            .get::<path_refs::Owned, &[u8]>(self.tables.reverse_path_refs, &key)
-            .expect("db: Failed to get path ref from reverse_path_refs table");
+            .get::<path_refs::Owned, &[u8]>(table, &key)
+            .expect("db: Failed to get path ref from reverse index");
         if let Some(existing_refs) = existing_refs {
             let path_ref_str = source.to_string_lossy();
             let path_ref_bytes = path_ref_str.as_bytes();
@@ -576,7 +937,7 @@ impl FileAssetSource {
                 Some(remove_idx) => {
                     let new_size = existing_refs.len() - 1;
                     if new_size == 0 {
-                        txn.delete(self.tables.reverse_path_refs, &key)
+                        txn.delete(table, &key)
                             .expect("lmdb: failed to delete path ref");
                     } else {
                         let mut message = capnp::message::Builder::new_default();
@@ -592,7 +953,7 @@ impl FileAssetSource {
                                 insert_idx += 1;
                             }
                         }
-                        txn.put(self.tables.reverse_path_refs, &key, &message)
+                        txn.put(table, &key, &message)
                             .expect("db: failed to update path refs");
                     }
                     true
@@ -661,7 +1022,7 @@ impl FileAssetSource {
                     artifact
                         .build_deps
                         .iter()
-                        .filter(|dep| !dep.is_path())
+                        .filter(|dep| dep.is_uuid())
                         .map(|dep| dep.expect_uuid())
                         .cloned()
                         .collect()
@@ -675,21 +1036,40 @@ impl FileAssetSource {
                     artifact
                         .load_deps
                         .iter()
-                        .filter(|dep| !dep.is_path())
+                        .filter(|dep| dep.is_uuid())
                         .map(|dep| dep.expect_uuid())
                         .cloned()
                         .collect()
                 })
                 .unwrap_or_else(Vec::new);
+            let weak_deps = asset
+                .metadata
+                .artifact
+                .as_ref()
+                .map(|artifact| artifact.weak_deps.clone())
+                .unwrap_or_else(Vec::new);
+            let no_pending = HashMap::new();
             for unresolved_ref in asset.unresolved_build_refs.iter() {
-                if let Some(uuid) = self.resolve_asset_ref(txn, &path, &unresolved_ref) {
-                    context_set.resolve_ref(&unresolved_ref, uuid);
+                if let AssetRef::Tag(tag) = unresolved_ref {
+                    build_deps.extend(
+                        self.resolve_tag_ref(txn, tag, &no_pending)
+                            .into_iter()
+                            .filter(|uuid| *uuid != asset.metadata.id),
+                    );
+                } else if let Some(uuid) = self.resolve_asset_ref(txn, &path, unresolved_ref) {
+                    context_set.resolve_ref(unresolved_ref, uuid);
                     build_deps.push(uuid);
                 }
             }
             for unresolved_ref in asset.unresolved_load_refs.iter() {
-                if let Some(uuid) = self.resolve_asset_ref(txn, &path, &unresolved_ref) {
-                    context_set.resolve_ref(&unresolved_ref, uuid);
+                if let AssetRef::Tag(tag) = unresolved_ref {
+                    load_deps.extend(
+                        self.resolve_tag_ref(txn, tag, &no_pending)
+                            .into_iter()
+                            .filter(|uuid| *uuid != asset.metadata.id),
+                    );
+                } else if let Some(uuid) = self.resolve_asset_ref(txn, &path, unresolved_ref) {
+                    context_set.resolve_ref(unresolved_ref, uuid);
                     load_deps.push(uuid);
                 }
             }
@@ -708,7 +1088,7 @@ impl FileAssetSource {
                         import_hash,
                         load_deps.iter().chain(build_deps.iter()),
                     );
-                    let serialized_asset = crate::serialized_asset::create(
+                    let mut serialized_asset = crate::serialized_asset::create(
                         hash,
                         asset.metadata.id,
                         build_deps.into_iter().map(AssetRef::Uuid).collect(),
@@ -719,6 +1099,7 @@ impl FileAssetSource {
                         CompressionType::None,
                         scratch_buf,
                     )?;
+                    serialized_asset.metadata.weak_deps = weak_deps;
                     self.artifact_cache.insert(&mut rw_txn, &serialized_asset);
                     Ok((hash, serialized_asset))
                 })
@@ -741,18 +1122,48 @@ impl FileAssetSource {
         }
     }
 
+    /// Resolves a dependency of `asset_id` into the UUIDs it refers to. Tag queries are looked up
+    /// in `tag_matches` or else in the hub, and never resolve to the asset itself.
+    fn resolve_dependency_ref<'a, V: DBTransaction<'a, T>, T: lmdb::Transaction + 'a>(
+        &self,
+        txn: &'a V,
+        path: &Path,
+        asset_id: &AssetUuid,
+        asset_ref: &AssetRef,
+        tag_matches: &HashMap<String, Vec<AssetUuid>>,
+    ) -> Option<Vec<AssetUuid>> {
+        match asset_ref {
+            AssetRef::Tag(tag) => {
+                let matches = match tag_matches.get(tag) {
+                    Some(matches) => matches.clone(),
+                    None => self.resolve_tag_ref(txn, tag, &HashMap::new()),
+                };
+                Some(matches.into_iter().filter(|id| id != asset_id).collect())
+            }
+            _ => self
+                .resolve_asset_ref(txn, path, asset_ref)
+                .map(|id| vec![id]),
+        }
+    }
+
     fn resolve_metadata_asset_refs<'a, V: DBTransaction<'a, T>, T: lmdb::Transaction + 'a>(
         &self,
         txn: &'a V,
         path: &Path,
         asset_import_result: &AssetImportResultMetadata,
         artifact: &mut ArtifactMetadata,
+        tag_matches: &HashMap<String, Vec<AssetUuid>>,
     ) {
+        let asset_id = asset_import_result.metadata.id;
         for unresolved_build_ref in asset_import_result.unresolved_build_refs.iter() {
-            if let Some(build_ref) = self.resolve_asset_ref(txn, path, unresolved_build_ref) {
-                let uuid_ref = AssetRef::Uuid(build_ref);
-                if !artifact.build_deps.contains(&uuid_ref) {
-                    artifact.build_deps.push(uuid_ref);
+            if let Some(build_refs) =
+                self.resolve_dependency_ref(txn, path, &asset_id, unresolved_build_ref, tag_matches)
+            {
+                for build_ref in build_refs {
+                    let uuid_ref = AssetRef::Uuid(build_ref);
+                    if !artifact.build_deps.contains(&uuid_ref) {
+                        artifact.build_deps.push(uuid_ref);
+                    }
                 }
                 // remove the AssetRef that was resolved
                 let ref_idx = artifact
@@ -765,10 +1176,14 @@ impl FileAssetSource {
             }
         }
         for unresolved_load_ref in asset_import_result.unresolved_load_refs.iter() {
-            if let Some(load_ref) = self.resolve_asset_ref(txn, path, unresolved_load_ref) {
-                let uuid_ref = AssetRef::Uuid(load_ref);
-                if !artifact.load_deps.contains(&uuid_ref) {
-                    artifact.load_deps.push(uuid_ref);
+            if let Some(load_refs) =
+                self.resolve_dependency_ref(txn, path, &asset_id, unresolved_load_ref, tag_matches)
+            {
+                for load_ref in load_refs {
+                    let uuid_ref = AssetRef::Uuid(load_ref);
+                    if !artifact.load_deps.contains(&uuid_ref) {
+                        artifact.load_deps.push(uuid_ref);
+                    }
                 }
                 let ref_idx = artifact
                     .load_deps
@@ -781,12 +1196,192 @@ impl FileAssetSource {
         }
     }
 
+    /// Flags each asset in a load dependency cycle with an error that names the cycle, and clears
+    /// the error of assets that are no longer part of a cycle.
+    ///
+    /// The metadata in `affected_assets` is updated in place, and the metadata of other assets
+    /// whose error changed is returned. The flagged assets are kept in the `dependency_cycles`
+    /// table, so that they can be checked again without scanning all asset metadata.
+    fn check_dependency_cycles(
+        &self,
+        txn: &mut RwTransaction<'_>,
+        affected_assets: &mut HashMap<AssetUuid, Option<AssetMetadata>>,
+    ) -> Vec<AssetMetadata> {
+        let hub_metadata = |id: &AssetUuid| {
+            self.hub.get_metadata(&*txn, id).map(|metadata| {
+                parse_db_metadata(&metadata.get().expect("capnp: metadata read failed"))
+            })
+        };
+        let uuid_load_deps = |metadata: &AssetMetadata| -> Vec<AssetUuid> {
+            metadata
+                .artifact
+                .iter()
+                .flat_map(|artifact| artifact.load_deps.iter())
+                .filter(|dep| dep.is_uuid())
+                .map(|dep| *dep.expect_uuid())
+                .collect()
+        };
+
+        // previously flagged assets are checked again, since the cycle may have been broken
+        let flagged: Vec<AssetUuid> = txn
+            .open_ro_cursor(self.tables.dependency_cycles)
+            .expect("db: Failed to open cursor for dependency cycles")
+            .capnp_iter_start()
+            .map(|(key, _)| utils::uuid_from_slice(key).expect("invalid asset_id length"))
+            .filter(|id| !affected_assets.contains_key(id))
+            .collect();
+
+        let mut cycles = HashMap::new();
+        {
+            let load_deps = |id: &AssetUuid| match affected_assets.get(id) {
+                Some(metadata) => metadata.as_ref().map(uuid_load_deps).unwrap_or_default(),
+                None => hub_metadata(id)
+                    .map(|metadata| uuid_load_deps(&metadata))
+                    .unwrap_or_default(),
+            };
+            let changed = affected_assets
+                .iter()
+                .filter(|(_, metadata)| metadata.is_some())
+                .map(|(id, _)| *id);
+            for id in changed.chain(flagged.iter().copied()) {
+                if cycles.contains_key(&id) {
+                    continue;
+                }
+                if let Some(cycle) = utils::find_dependency_cycle(id, &load_deps) {
+                    // each asset in the cycle names the cycle starting from itself
+                    let len = cycle.len() - 1;
+                    for i in 0..len {
+                        let rotated: Vec<_> = cycle[i..len]
+                            .iter()
+                            .chain(cycle[..=i].iter())
+                            .copied()
+                            .collect();
+                        cycles.insert(cycle[i], utils::format_dependency_cycle(&rotated));
+                    }
+                }
+            }
+        }
+        let cycle_error = |id: &AssetUuid| {
+            cycles
+                .get(id)
+                .map(|cycle| format!("load dependencies form a cycle: {}", cycle))
+        };
+
+        for (id, metadata) in affected_assets.iter_mut() {
+            if let Some(metadata) = metadata {
+                metadata.error = cycle_error(id);
+                if let Some(error) = &metadata.error {
+                    error!("asset {}: {}", id, error);
+                }
+            }
+        }
+        let others: HashSet<_> = cycles
+            .keys()
+            .filter(|id| !affected_assets.contains_key(id))
+            .chain(flagged.iter())
+            .copied()
+            .collect();
+        let mut updates = Vec::new();
+        for id in others {
+            if let Some(mut metadata) = hub_metadata(&id) {
+                let error = cycle_error(&id);
+                if metadata.error != error {
+                    if let Some(error) = &error {
+                        error!("asset {}: {}", id, error);
+                    }
+                    metadata.error = error;
+                    updates.push(metadata);
+                }
+            }
+        }
+
+        for id in flagged.iter().chain(affected_assets.keys()) {
+            if !cycles.contains_key(id) {
+                txn.delete(self.tables.dependency_cycles, id)
+                    .expect("db: Failed to delete asset from dependency_cycles table");
+            }
+        }
+        for id in cycles.keys() {
+            txn.put_bytes(self.tables.dependency_cycles, id, &[])
+                .expect("db: Failed to put asset to dependency_cycles table");
+        }
+        updates
+    }
+
+    /// Drops the changes of source files with assets whose IDs are already used by another source
+    /// file, which happens when a source file is copied together with its .meta file.
+    ///
+    /// The file that used the IDs first keeps them, and the conflict is reported as an import
+    /// error of the newer file. If `repair_duplicate_asset_ids` is set, the importer state in the
+    /// .meta file of the newer file is reset, so that it is imported again with new IDs.
+    fn remove_duplicate_asset_ids(
+        &self,
+        txn: &RwTransaction<'_>,
+        changes: &mut HashMap<PathBuf, Option<PairImportResultMetadata<'_>>>,
+    ) {
+        let mut changed_paths: Vec<&PathBuf> = changes
+            .iter()
+            .filter(|(_, change)| change.is_some())
+            .map(|(path, _)| path)
+            .collect();
+        // older files claim their IDs first
+        changed_paths.sort_by_cached_key(|path| (source_created_time(path), *path));
+
+        let mut claimed_ids: HashMap<AssetUuid, &PathBuf> = HashMap::new();
+        let mut duplicates = Vec::new();
+        for path in changed_paths {
+            let metadata = changes[path].as_ref().unwrap();
+            let mut conflicts = Vec::new();
+            for asset in &metadata.assets {
+                let id = asset.metadata.id;
+                // the source that already owns the ID keeps it, unless it was deleted
+                let owner = self.get_asset_path(txn, &id).filter(|owner| {
+                    owner != path && !matches!(changes.get(owner), Some(None)) && owner.exists()
+                });
+                if let Some(owner) =
+                    owner.or_else(|| claimed_ids.get(&id).map(|owner| (*owner).clone()))
+                {
+                    conflicts.push((id, owner));
+                }
+            }
+            if conflicts.is_empty() {
+                for asset in &metadata.assets {
+                    claimed_ids.insert(asset.metadata.id, path);
+                }
+            } else {
+                duplicates.push((path.clone(), conflicts));
+            }
+        }
+
+        for (path, conflicts) in duplicates {
+            for (id, owner) in &conflicts {
+                error!(
+                    "Import error {:?}: asset {} is already defined by {:?}, the .meta file was probably copied with the source file. Delete the .meta file to import it with new IDs.",
+                    path, id, owner
+                );
+            }
+            let mut change = changes
+                .remove(&path)
+                .flatten()
+                .expect("duplicate has no change");
+            if self.repair_duplicate_asset_ids {
+                change.import_state.reset_importer_state();
+                match change.import_state.write_metadata() {
+                    Ok(()) => info!("generating new asset IDs for {:?}", path),
+                    Err(err) => error!("Failed to rewrite .meta file of {:?}: {}", path, err),
+                }
+            }
+        }
+    }
+
     fn process_metadata_changes(
         &self,
         txn: &mut RwTransaction<'_>,
-        changes: &HashMap<PathBuf, Option<PairImportResultMetadata<'_>>>,
+        changes: &mut HashMap<PathBuf, Option<PairImportResultMetadata<'_>>>,
         change_batch: &mut asset_hub::ChangeBatch,
     ) {
+        self.remove_duplicate_asset_ids(txn, changes);
+        let changes = &*changes;
         let mut affected_assets = HashMap::new();
 
         // delete metadata for deleted source pairs
@@ -841,7 +1436,27 @@ impl FileAssetSource {
             }
         }
 
-        // resolve unresolved path AssetRefs into UUIDs before updating asset metadata.
+        // tag queries are resolved against the metadata of this batch, which is not yet in the hub
+        let mut tag_matches = HashMap::new();
+        for (_, metadata) in changes.iter().filter(|(_, change)| change.is_some()) {
+            let metadata = metadata.as_ref().unwrap();
+            for asset in metadata.assets.iter() {
+                let unresolved_refs = asset
+                    .unresolved_load_refs
+                    .iter()
+                    .chain(asset.unresolved_build_refs.iter());
+                for unresolved_ref in unresolved_refs {
+                    if let AssetRef::Tag(tag) = unresolved_ref {
+                        if !tag_matches.contains_key(tag) {
+                            let matches = self.resolve_tag_ref(txn, tag, &affected_assets);
+                            tag_matches.insert(tag.clone(), matches);
+                        }
+                    }
+                }
+            }
+        }
+
+        // resolve unresolved path and tag AssetRefs into UUIDs before updating asset metadata.
         for (path, metadata) in changes.iter().filter(|(_, change)| change.is_some()) {
             let metadata = metadata.as_ref().unwrap();
             for asset in metadata.assets.iter() {
@@ -851,7 +1466,35 @@ impl FileAssetSource {
                     .as_mut()
                     .expect("asset None in affected_assets");
                 if let Some(artifact) = asset_metadata.artifact.as_mut() {
-                    self.resolve_metadata_asset_refs(txn, path, asset, artifact);
+                    self.resolve_metadata_asset_refs(txn, path, asset, artifact, &tag_matches);
+                }
+            }
+        }
+
+        let cycle_updates = self.check_dependency_cycles(txn, &mut affected_assets);
+
+        // assets that gain or lose search tags change what the tag queries of other assets match
+        let mut tag_ref_sources = Vec::new();
+        for (asset, maybe_metadata) in affected_assets.iter() {
+            let old_tags = self
+                .hub
+                .get_metadata(txn, asset)
+                .map(|metadata| {
+                    parse_db_metadata(&metadata.get().expect("capnp: metadata read failed"))
+                        .search_tags
+                })
+                .unwrap_or_default();
+            let new_tags = maybe_metadata
+                .as_ref()
+                .map(|metadata| metadata.search_tags.as_slice())
+                .unwrap_or_default();
+            if old_tags.as_slice() != new_tags {
+                for tags in [old_tags.as_slice(), new_tags].iter() {
+                    for source in self.get_tag_refs_matching(txn, tags) {
+                        if !tag_ref_sources.contains(&source) {
+                            tag_ref_sources.push(source);
+                        }
+                    }
                 }
             }
         }
@@ -871,9 +1514,15 @@ impl FileAssetSource {
                         .import_state
                         .import_hash()
                         .expect("path changed but no import hash present");
-                    // TODO set error state for unresolved path references?
-                    // this code strips out path references for now, but it will probably crash and burn when loading
+                    // unresolved path references are reported as an import error and stripped out,
+                    // unless the asset already has an error for a dependency cycle
                     if let Some(a) = asset_metadata.artifact.as_mut() {
+                        if asset_metadata.error.is_none() {
+                            asset_metadata.error = unresolved_path_refs_error(a);
+                            if let Some(error) = &asset_metadata.error {
+                                error!("asset {}: {}", asset, error);
+                            }
+                        }
                         a.load_deps = a
                             .load_deps
                             .iter()
@@ -909,86 +1558,109 @@ impl FileAssetSource {
                 }
             }
         }
+        for metadata in cycle_updates {
+            self.hub
+                .update_asset(txn, &metadata, data::AssetSource::File, change_batch)
+                .expect("hub: Failed to update asset in hub");
+        }
 
-        // update asset hashes for the reverse path refs of all changes
+        // update asset hashes for the reverse path refs of all changes and the sources whose tag
+        // queries match different assets
+        let mut ref_sources = Vec::new();
         for (path, _) in changes.iter() {
-            let reverse_path_refs = self.get_path_refs(txn, path);
-            for path_ref_source in reverse_path_refs.iter() {
-                // First, check if the path has already been processed
-                if changes.contains_key(path_ref_source) {
-                    continue;
+            for source in self.get_path_refs(txn, path) {
+                if !ref_sources.contains(&source) {
+                    ref_sources.push(source);
                 }
-                // Then we look in the database for assets affected by the change
-                let cache = DBSourceMetadataCache {
-                    txn,
-                    file_asset_source: &self,
-                    _marker: std::marker::PhantomData,
-                };
-                let mut import = SourcePairImport::new(path_ref_source.clone());
-                if !import.set_importer_from_map(&self.importers) {
-                    log::warn!("failed to set importer from map for path {:?} when updating path ref dependencies", path_ref_source);
-                } else {
-                    import.generate_source_metadata(&cache);
-                    import
-                        .get_result_metadata_from_cache(&cache)
-                        .expect("error fetching import result metadata from cache");
-                    let import_hash = import
-                        .result_metadata()
-                        .expect("expected result metadata")
-                        .import_hash
-                        .expect("expected import hash in source metadata");
-                    match import.import_result_from_cached_data() {
-                        Ok(import_result) => {
-                            for mut asset in import_result.assets {
-                                let result_metadata = AssetImportResultMetadata {
-                                    metadata: asset.metadata.clone(),
-                                    unresolved_load_refs: asset.unresolved_load_refs,
-                                    unresolved_build_refs: asset.unresolved_build_refs,
-                                };
-                                if let Some(artifact) = &mut asset.metadata.artifact {
-                                    self.resolve_metadata_asset_refs(
-                                        txn,
-                                        path_ref_source,
-                                        &result_metadata,
-                                        artifact,
-                                    );
-                                    // TODO set error state for unresolved path references?
-                                    // this code strips out path references for now, but it will probably crash and burn when loading
-                                    artifact.load_deps = artifact
+            }
+        }
+        for source in tag_ref_sources {
+            if !ref_sources.contains(&source) {
+                ref_sources.push(source);
+            }
+        }
+        for path_ref_source in ref_sources.iter() {
+            // First, check if the path has already been processed
+            if changes.contains_key(path_ref_source) {
+                continue;
+            }
+            // Then we look in the database for assets affected by the change
+            let cache = DBSourceMetadataCache {
+                txn,
+                file_asset_source: self,
+                _marker: std::marker::PhantomData,
+            };
+            let mut import = SourcePairImport::new(path_ref_source.clone());
+            if !import.set_importer_from_map(&self.importers) {
+                log::warn!("failed to set importer from map for path {:?} when updating path ref dependencies", path_ref_source);
+            } else {
+                import.generate_source_metadata(&cache);
+                import
+                    .get_result_metadata_from_cache(&cache)
+                    .expect("error fetching import result metadata from cache");
+                let import_hash = import
+                    .result_metadata()
+                    .expect("expected result metadata")
+                    .import_hash
+                    .expect("expected import hash in source metadata");
+                match import.import_result_from_cached_data() {
+                    Ok(import_result) => {
+                        for mut asset in import_result.assets {
+                            let result_metadata = AssetImportResultMetadata {
+                                metadata: asset.metadata.clone(),
+                                unresolved_load_refs: asset.unresolved_load_refs,
+                                unresolved_build_refs: asset.unresolved_build_refs,
+                            };
+                            if let Some(artifact) = &mut asset.metadata.artifact {
+                                self.resolve_metadata_asset_refs(
+                                    txn,
+                                    path_ref_source,
+                                    &result_metadata,
+                                    artifact,
+                                    &tag_matches,
+                                );
+                                // unresolved path references are reported as an import error and
+                                // stripped out
+                                if asset.metadata.error.is_none() {
+                                    asset.metadata.error = unresolved_path_refs_error(artifact);
+                                    if let Some(error) = &asset.metadata.error {
+                                        error!("asset {}: {}", asset.metadata.id, error);
+                                    }
+                                }
+                                artifact.load_deps = artifact
+                                    .load_deps
+                                    .iter()
+                                    .filter(|x| x.is_uuid())
+                                    .cloned()
+                                    .collect();
+                                artifact.build_deps = artifact
+                                    .build_deps
+                                    .iter()
+                                    .filter(|x| x.is_uuid())
+                                    .cloned()
+                                    .collect();
+                                artifact.id = ArtifactId(utils::calc_import_artifact_hash(
+                                    &asset.metadata.id,
+                                    import_hash,
+                                    artifact
                                         .load_deps
                                         .iter()
-                                        .filter(|x| x.is_uuid())
-                                        .cloned()
-                                        .collect();
-                                    artifact.build_deps = artifact
-                                        .build_deps
-                                        .iter()
-                                        .filter(|x| x.is_uuid())
-                                        .cloned()
-                                        .collect();
-                                    artifact.id = ArtifactId(utils::calc_import_artifact_hash(
-                                        &asset.metadata.id,
-                                        import_hash,
-                                        artifact
-                                            .load_deps
-                                            .iter()
-                                            .chain(artifact.build_deps.iter())
-                                            .map(|dep| dep.expect_uuid()),
-                                    ));
-                                    self.hub
-                                        .update_asset(
-                                            txn,
-                                            &asset.metadata,
-                                            data::AssetSource::File,
-                                            change_batch,
-                                        )
-                                        .expect("hub: Failed to update asset in hub");
-                                }
+                                        .chain(artifact.build_deps.iter())
+                                        .map(|dep| dep.expect_uuid()),
+                                ));
+                                self.hub
+                                    .update_asset(
+                                        txn,
+                                        &asset.metadata,
+                                        data::AssetSource::File,
+                                        change_batch,
+                                    )
+                                    .expect("hub: Failed to update asset in hub");
                             }
                         }
-                        Err(err) => {
-                            log::error!("failed to get import result from metadata when updating path ref for asset: {}", err);
-                        }
+                    }
+                    Err(err) => {
+                        log::error!("failed to get import result from metadata when updating path ref for asset: {}", err);
                     }
                 }
             }
@@ -1083,7 +1755,11 @@ impl FileAssetSource {
                 .iter()
                 .filter_map(|file_state| {
                     let metadata = self.get_metadata(&txn, &file_state.path);
-                    let importer = self.importers.get_by_path(&file_state.path);
+                    let importer = source_pair_import::importer_for_source(
+                        &self.importers,
+                        &file_state.path,
+                        source_pair_import::read_pinned_importer_type(&file_state.path).as_ref(),
+                    );
 
                     let changed = match (importer, metadata) {
                         // there's no importer, and no existing metadata.
@@ -1178,6 +1854,18 @@ impl FileAssetSource {
                 }
             }
 
+            // sources that read a changed file are imported again
+            let dependents: Vec<PathBuf> = source_meta_pairs
+                .keys()
+                .flat_map(|path| self.get_source_dependents(txn, path))
+                .collect();
+            for dependent in dependents {
+                source_meta_pairs.entry(dependent).or_insert(SourcePair {
+                    source: Option::None,
+                    meta: Option::None,
+                });
+            }
+
             for (path, pair) in source_meta_pairs.iter_mut() {
                 if pair.meta.is_none() {
                     let path = utils::to_meta_path(&path);
@@ -1239,6 +1927,21 @@ impl FileAssetSource {
 
                 if let Some((import, import_output)) = result {
                     let metadata = if let Some(mut import_output) = import_output {
+                        if let Some(source) = p.source.as_ref() {
+                            let type_errors = self.check_expected_types(
+                                &read_txn,
+                                &source.path,
+                                &import_output.assets,
+                            );
+                            if !type_errors.is_empty() {
+                                let import_op =
+                                    import_output.import_op.get_or_insert_with(ImportOp::default);
+                                for err in type_errors {
+                                    let err: Box<dyn std::error::Error + Send + Sync> = err.into();
+                                    import_op.errors.push(err);
+                                }
+                            }
+                        }
                         // TODO store reported errors and warnings in metadata
                         if let Some(import_op) = import_output.import_op {
                             for error in &import_op.errors {
@@ -1345,7 +2048,7 @@ impl FileAssetSource {
         let mut change_batch = asset_hub::ChangeBatch::new();
         let txn = txn.into_inner();
 
-        self.process_metadata_changes(txn, &metadata_changes.into_inner(), &mut change_batch);
+        self.process_metadata_changes(txn, &mut metadata_changes.into_inner(), &mut change_batch);
         self.hub
             .add_changes(txn, change_batch)
             .expect("Failed to process metadata changes")
@@ -1420,10 +2123,14 @@ impl FileAssetSource {
             log::debug!("Received file tracker event {:?}", evt);
             match evt {
                 FileTrackerEvent::Start => {
+                    let first_start = !started;
                     started = true;
                     if update || self.check_for_importer_changes().await {
                         self.handle_update().await;
                     }
+                    if first_start {
+                        self.report_meta_file_issues().await;
+                    }
                 }
                 FileTrackerEvent::Update => {
                     update = true;
@@ -1481,7 +2188,12 @@ impl FileAssetSource {
             }),
         );
         let mut change_batch = asset_hub::ChangeBatch::new();
-        self.process_metadata_changes(&mut txn, &changes, &mut change_batch);
+        self.process_metadata_changes(&mut txn, &mut changes, &mut change_batch);
+        if changes.is_empty() {
+            return Err(Error::Custom(
+                "exported assets have IDs that are used by another source file".to_string(),
+            ));
+        }
         let asset_metadata_changed = self.hub.add_changes(&mut txn, change_batch)?;
         let new_asset_metadata: Vec<AssetMetadata> = asset_ids
             .into_iter()
@@ -1505,6 +2217,249 @@ impl FileAssetSource {
         }
         Ok(new_asset_metadata)
     }
+
+    /// Regenerates the artifacts of the assets of a source file, in which path references are
+    /// replaced by the UUIDs they resolve to.
+    async fn regenerate_source_artifacts<'a, V: DBTransaction<'a, T>, T: lmdb::Transaction + 'a>(
+        &self,
+        txn: &'a V,
+        metadata: &source_metadata::Reader<'_>,
+        scratch_buf: &mut Vec<u8>,
+    ) -> Result<Vec<SerializedAssetVec>> {
+        let mut assets = Vec::new();
+        for asset in metadata.get_assets()? {
+            let id = utils::uuid_from_slice(asset.get_id()?.get_id()?).ok_or(Error::UuidLength)?;
+            let (_, artifact) = self
+                .regenerate_import_artifact(txn, &id, scratch_buf)
+                .await?;
+            assets.push(artifact);
+        }
+        Ok(assets)
+    }
+
+    /// Moves the source file `from` and its .meta file to `to`.
+    ///
+    /// Path references that resolve differently after the move, both those of `from` relative to
+    /// its own directory and those of other source files to `from`, are rewritten to UUIDs by
+    /// exporting the source files with their importers. References that can't be rewritten are
+    /// returned, and are reported as import errors once the source files are imported again.
+    pub async fn rename_source(&self, from: &Path, to: &Path) -> Result<Vec<DanglingPathRef>> {
+        if to.exists() || utils::to_meta_path(to).exists() {
+            return Err(Error::Custom(format!("{:?} already exists", to)));
+        }
+        let mut dangling_refs = Vec::new();
+        let mut own_export = None;
+        let mut exports = Vec::new();
+        {
+            let txn = self.db.ro_txn().await?;
+            let metadata = self.get_metadata(&txn, from).ok_or_else(|| {
+                Error::Custom(format!("{:?} is not an imported source file", from))
+            })?;
+            let metadata = metadata.get()?;
+            let mut scratch_buf = Vec::new();
+            // relative references of the moved file would resolve from its new directory
+            let own_refs: Vec<PathBuf> = read_path_refs(&metadata)
+                .into_iter()
+                .filter(|path_ref| {
+                    resolve_source_path(from, path_ref) != resolve_source_path(to, path_ref)
+                })
+                .collect();
+            if !own_refs.is_empty() {
+                let assets = self
+                    .regenerate_source_artifacts(&txn, &metadata, &mut scratch_buf)
+                    .await;
+                own_export = Some((own_refs, assets));
+            }
+            for dependent in self.get_path_refs(&txn, from) {
+                let metadata = match self.get_metadata(&txn, &dependent) {
+                    Some(metadata) if dependent != from => metadata,
+                    _ => continue,
+                };
+                let metadata = metadata.get()?;
+                let path_refs: Vec<PathBuf> = read_path_refs(&metadata)
+                    .into_iter()
+                    .filter(|path_ref| resolve_source_path(&dependent, path_ref) == from)
+                    .collect();
+                // regenerate the artifacts while the references still resolve, so that they refer
+                // to the UUIDs of the moved assets
+                let assets = self
+                    .regenerate_source_artifacts(&txn, &metadata, &mut scratch_buf)
+                    .await;
+                exports.push((dependent, path_refs, assets));
+            }
+        }
+
+        // the moved file is exported before it is moved, since its assets are still known by
+        // their old path
+        if let Some((path_refs, assets)) = own_export {
+            let exported = match assets {
+                Ok(assets) => self.export_source(from.to_path_buf(), assets).await,
+                Err(err) => Err(err),
+            };
+            if let Err(err) = exported {
+                for path_ref in path_refs {
+                    dangling_refs.push(DanglingPathRef {
+                        source: to.to_path_buf(),
+                        path: path_ref,
+                        reason: err.to_string(),
+                    });
+                }
+            }
+        }
+
+        if let Some(parent) = to.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::rename(from, to)?;
+        let from_meta = utils::to_meta_path(from);
+        if from_meta.exists() {
+            if let Err(err) = fs::rename(&from_meta, utils::to_meta_path(to)) {
+                // keep the source file next to its .meta file
+                fs::rename(to, from)?;
+                return Err(err.into());
+            }
+        }
+
+        for (dependent, path_refs, assets) in exports {
+            let exported = match assets {
+                Ok(assets) => self.export_source(dependent.clone(), assets).await,
+                Err(err) => Err(err),
+            };
+            if let Err(err) = exported {
+                for path_ref in path_refs {
+                    dangling_refs.push(DanglingPathRef {
+                        source: dependent.clone(),
+                        path: path_ref,
+                        reason: err.to_string(),
+                    });
+                }
+            }
+        }
+        for dangling_ref in &dangling_refs {
+            error!(
+                "Import error {:?}: path reference {:?} is dangling after moving {:?} to {:?}: {}",
+                dangling_ref.source, dangling_ref.path, from, to, dangling_ref.reason
+            );
+        }
+        Ok(dangling_refs)
+    }
+
+    /// Finds the .meta files without a source file, and the source files without a .meta file
+    /// whose asset IDs are still in the DB.
+    pub fn check_meta_files(&self, txn: &RoTransaction<'_>) -> Result<MetaFileIssues> {
+        let mut issues = MetaFileIssues::default();
+        for state in self.tracker.read_all_files(txn) {
+            if state.ty != data::FileType::File
+                || state.path.extension().and_then(|ext| ext.to_str()) != Some("meta")
+            {
+                continue;
+            }
+            let source = state.path.with_file_name(state.path.file_stem().unwrap());
+            if !source.exists() && state.path.exists() {
+                issues.orphaned_metas.push(state.path);
+            }
+        }
+        for (path, metadata) in self.iter_metadata(txn) {
+            if !path.is_file() || utils::to_meta_path(&path).exists() {
+                continue;
+            }
+            let mut assets = Vec::new();
+            for asset in metadata.get()?.get_assets()? {
+                assets.push(
+                    utils::uuid_from_slice(asset.get_id()?.get_id()?).ok_or(Error::UuidLength)?,
+                );
+            }
+            if !assets.is_empty() {
+                issues.missing_metas.push((path, assets));
+            }
+        }
+        Ok(issues)
+    }
+
+    async fn report_meta_file_issues(&self) {
+        let txn = self
+            .db
+            .ro_txn()
+            .await
+            .expect("failed to open RO transaction");
+        let issues = match self.check_meta_files(&txn) {
+            Ok(issues) => issues,
+            Err(err) => {
+                error!("Failed to check .meta files: {}", err);
+                return;
+            }
+        };
+        for meta_path in issues.orphaned_metas {
+            error!(
+                "Orphaned .meta file {:?}: the source file does not exist. Restore the source file or remove the .meta file.",
+                meta_path
+            );
+        }
+        for (path, assets) in issues.missing_metas {
+            error!(
+                "Missing .meta file for {:?}: regenerate it to keep the asset IDs {:?}",
+                path, assets
+            );
+        }
+    }
+
+    /// Writes the .meta file of the source file `path` from its metadata in the DB, which keeps
+    /// the asset IDs that were stored in the importer state.
+    pub async fn regenerate_meta_file(&self, path: &Path) -> Result<()> {
+        let meta_path = utils::to_meta_path(path);
+        if meta_path.exists() {
+            return Err(Error::Custom(format!("{:?} already exists", meta_path)));
+        }
+        if !path.is_file() {
+            return Err(Error::Custom(format!("{:?} is not a source file", path)));
+        }
+        let txn = self.db.ro_txn().await?;
+        if self.get_metadata(&txn, path).is_none() {
+            return Err(Error::Custom(format!(
+                "{:?} has no metadata in the DB",
+                path
+            )));
+        }
+        let cache = DBSourceMetadataCache {
+            txn: &txn,
+            file_asset_source: self,
+            _marker: std::marker::PhantomData,
+        };
+        let mut import = SourcePairImport::new(path.to_path_buf());
+        if !import.set_importer_from_map(&self.importers) {
+            return Err(Error::Custom(format!(
+                "no importer is registered for {:?}",
+                path
+            )));
+        }
+        import.generate_source_metadata(&cache);
+        if import.source_metadata().is_none() {
+            return Err(Error::Custom(format!(
+                "failed to restore the metadata of {:?} from the DB",
+                path
+            )));
+        }
+        import.write_metadata()
+    }
+
+    /// Deletes the .meta file `meta_path` if its source file does not exist.
+    pub fn remove_orphaned_meta_file(&self, meta_path: &Path) -> Result<()> {
+        if meta_path.extension().and_then(|ext| ext.to_str()) != Some("meta") {
+            return Err(Error::Custom(format!(
+                "{:?} is not a .meta file",
+                meta_path
+            )));
+        }
+        let source = meta_path.with_file_name(meta_path.file_stem().unwrap());
+        if source.exists() {
+            return Err(Error::Custom(format!(
+                "the source file of {:?} exists",
+                meta_path
+            )));
+        }
+        fs::remove_file(meta_path)?;
+        Ok(())
+    }
 }
 
 struct DBSourceMetadataCache<'a, 'b, V, T> {
@@ -1586,6 +2541,7 @@ where
                 importer_version,
                 importer_type,
                 assets,
+                source_dependencies: read_source_dependencies(&saved_metadata),
             }))
         } else {
             Ok(None)
@@ -34,7 +34,8 @@ use crate::{
         CapnpCursor, DBTransaction, Environment, MessageReader, RoTransaction, RwTransaction,
     },
     error::{Error, Result},
-    watcher::{self, FileEvent, FileMetadata},
+    ignore::IgnoreRules,
+    watcher::{self, FileEvent, FileMetadata, WatcherSettings},
 };
 
 #[derive(Clone)]
@@ -59,6 +60,8 @@ pub struct FileTracker {
     is_running: AtomicBool,
     stopping_event: event_listener::Event,
     watch_dirs: RwLock<Vec<PathBuf>>,
+    ignore_patterns: Vec<String>,
+    watcher_settings: WatcherSettings,
 }
 #[derive(Clone)]
 pub struct FileState {
@@ -112,22 +115,37 @@ pub fn db_file_type(t: fs::FileType) -> FileType {
 
 struct ListenersList {
     listeners: Vec<UnboundedSender<FileTrackerEvent>>,
+    /// Set once `FileTrackerEvent::Start` has been sent, so that listeners registered after the
+    /// initial scan still receive it.
+    started: bool,
 }
 
 impl ListenersList {
     fn new() -> Self {
         Self {
             listeners: Vec::new(),
+            started: false,
         }
     }
 
     fn register(&mut self, new_listener: Option<UnboundedSender<FileTrackerEvent>>) {
         if let Some(new_listener) = new_listener {
+            if self.started
+                && new_listener
+                    .unbounded_send(FileTrackerEvent::Start)
+                    .is_err()
+            {
+                debug!("Listener dropped");
+                return;
+            }
             self.listeners.push(new_listener);
         }
     }
 
     fn send_event(&mut self, event: FileTrackerEvent) {
+        if let FileTrackerEvent::Start = event {
+            self.started = true;
+        }
         self.listeners.retain(|listener| {
             match listener.unbounded_send(event) {
                 Ok(()) => {
@@ -347,6 +365,10 @@ mod events {
                         if !key.starts_with(&path_string) {
                             break;
                         }
+                        // skip siblings that share the prefix, like `dir-b` when scanning `dir`
+                        if !Path::new(key).starts_with(&scan_ctx.path) {
+                            continue;
+                        }
                         db_file_set.insert(PathBuf::from(key));
                     }
                 }
@@ -454,9 +476,24 @@ impl FileTracker {
             listener_rx: Mutex::new(Cell::new(listener_rx)),
             listener_tx,
             watch_dirs: RwLock::new(watch_dirs),
+            ignore_patterns: Vec::new(),
+            watcher_settings: WatcherSettings::default(),
         }
     }
 
+    /// Sets rules in `.gitignore` syntax for files in the watched directories that are not tracked.
+    /// They are applied in addition to the rules of the `.distillignore` files in the directories.
+    pub fn with_ignore_patterns(mut self, patterns: Vec<String>) -> Self {
+        self.ignore_patterns = patterns;
+        self
+    }
+
+    /// Sets how changes to files in the watched directories are detected.
+    pub fn with_watcher_settings(mut self, settings: WatcherSettings) -> Self {
+        self.watcher_settings = settings;
+        self
+    }
+
     pub fn make_relative_path(&self, absolute_path: &Path) -> Option<PathBuf> {
         for dir in self.get_watch_dirs() {
             let canonicalized_dir = canonicalize_path(&dir);
@@ -690,9 +727,13 @@ impl FileTracker {
         let to_watch: Vec<PathBuf> = self.get_watch_dirs();
 
         // NOTE(happens): If we can't watch the dir, we want to abort
-        let mut watcher =
-            watcher::DirWatcher::from_path_iter(to_watch.iter().map(|p| Path::new(p)), watcher_tx)
-                .expect("watcher: Failed to watch specified path");
+        let mut watcher = watcher::DirWatcher::from_path_iter(
+            to_watch.iter().map(Path::new),
+            IgnoreRules::new(&self.ignore_patterns),
+            self.watcher_settings.clone(),
+            watcher_tx,
+        )
+        .expect("watcher: Failed to watch specified path");
 
         let stop_handle = watcher.stop_handle();
         thread::spawn(move || watcher.run());
@@ -749,6 +790,8 @@ impl FileTracker {
         }
 
         listeners.send_event(FileTrackerEvent::Update);
+        // drop listeners that were never registered, so that they see the end of their channel
+        while let Some(Some(_)) = listener_rx.next().now_or_never() {}
         drop(stop_handle);
         self.is_running.store(false, Ordering::Release);
     }
@@ -775,6 +818,14 @@ pub mod tests {
     };
 
     pub async fn with_tracker<F, T>(f: F)
+    where
+        T: Future<Output = ()>,
+        F: FnOnce(Arc<FileTracker>, UnboundedReceiver<FileTrackerEvent>, PathBuf) -> T,
+    {
+        with_tracker_settings(WatcherSettings::default(), f).await
+    }
+
+    pub async fn with_tracker_settings<F, T>(settings: WatcherSettings, f: F)
     where
         T: Future<Output = ()>,
         F: FnOnce(Arc<FileTracker>, UnboundedReceiver<FileTrackerEvent>, PathBuf) -> T,
@@ -793,7 +844,7 @@ pub mod tests {
                 )
             }),
         );
-        let tracker = Arc::new(FileTracker::new(db, asset_paths));
+        let tracker = Arc::new(FileTracker::new(db, asset_paths).with_watcher_settings(settings));
         let (tx, mut rx) = unbounded();
         tracker.register_listener(tx);
 
@@ -821,6 +872,15 @@ pub mod tests {
         }
     }
 
+    /// Waits for an event, then for the events that follow it until none arrive for a while.
+    async fn expect_events(rx: &mut UnboundedReceiver<FileTrackerEvent>) {
+        expect_event(rx).await;
+        while timeout(Duration::from_millis(1000), rx.next())
+            .await
+            .is_ok()
+        {}
+    }
+
     async fn expect_no_file_state(t: &FileTracker, asset_dir: &Path, name: &str) {
         let txn = t.get_rw_txn().await;
         let canonical_path = canonicalize_path(&asset_dir.join(name));
@@ -832,6 +892,24 @@ pub mod tests {
         );
     }
 
+    /// Waits until the file state of a file exists or not, for watchers that report changes late.
+    async fn wait_for_file_state(t: &FileTracker, asset_dir: &Path, name: &str, exists: bool) {
+        let canonical_path = canonicalize_path(&asset_dir.join(name));
+        for _ in 0..100 {
+            {
+                let txn = t.get_rw_txn().await;
+                if t.get_file_state(&txn, &canonical_path).is_some() == exists {
+                    return;
+                }
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+        panic!(
+            "timed out waiting for file state of {} to exist: {}",
+            name, exists
+        );
+    }
+
     async fn expect_file_state(t: &FileTracker, asset_dir: &Path, name: &str) {
         let txn = t.get_rw_txn().await;
         let canonical_path = canonicalize_path(&asset_dir.join(name));
@@ -1084,4 +1162,101 @@ pub mod tests {
         })
         .await;
     }
+
+    #[tokio::test]
+    async fn test_ignore_file() {
+        with_tracker(|t, mut rx, asset_dir| async move {
+            tokio::fs::write(asset_dir.join(".distillignore"), "*.swp\ncache/\n")
+                .await
+                .expect("write ignore file");
+            expect_events(&mut rx).await;
+
+            add_test_file(&asset_dir, "test.txt").await;
+            tokio::fs::write(asset_dir.join(".test.txt.swp"), "swap")
+                .await
+                .expect("write swap file");
+            let cache_dir = add_test_dir(&asset_dir, "cache").await;
+            add_test_file(&cache_dir, "test.txt").await;
+            expect_events(&mut rx).await;
+
+            expect_file_state(&t, &asset_dir, "test.txt").await;
+            expect_no_file_state(&t, &asset_dir, ".test.txt.swp").await;
+            expect_no_file_state(&t, &asset_dir, "cache").await;
+            expect_no_file_state(&t, &cache_dir, "test.txt").await;
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_ignore_rules_change() {
+        with_tracker(|t, mut rx, asset_dir| async move {
+            let dir = add_test_dir(&asset_dir, "tools").await;
+            add_test_file(&dir, "test.txt").await;
+            add_test_file(&asset_dir, "test.txt").await;
+            expect_events(&mut rx).await;
+            expect_file_state(&t, &dir, "test.txt").await;
+
+            tokio::fs::write(asset_dir.join(".distillignore"), "/tools\n")
+                .await
+                .expect("write ignore file");
+            expect_events(&mut rx).await;
+            expect_no_file_state(&t, &asset_dir, "tools").await;
+            expect_no_file_state(&t, &dir, "test.txt").await;
+            expect_file_state(&t, &asset_dir, "test.txt").await;
+
+            tokio::fs::remove_file(asset_dir.join(".distillignore"))
+                .await
+                .expect("remove ignore file");
+            expect_events(&mut rx).await;
+            expect_file_state(&t, &asset_dir, "tools").await;
+            expect_file_state(&t, &dir, "test.txt").await;
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_late_listener_receives_start() {
+        with_tracker(|t, _rx, _asset_dir| async move {
+            // the first listener has already received the start event
+            let (tx, mut late_rx) = unbounded();
+            t.register_listener(tx);
+            let evt = expect_event(&mut late_rx).await;
+            assert!(
+                matches!(evt, FileTrackerEvent::Start),
+                "Received unexpected event {:?}",
+                evt
+            );
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_poll_watcher() {
+        let settings = WatcherSettings {
+            mode: watcher::WatchMode::Poll,
+            poll_interval: Duration::from_millis(50),
+            // spreads each pass over several scans
+            poll_scan_budget: 1,
+        };
+        with_tracker_settings(settings, |t, _rx, asset_dir| async move {
+            let dir = add_test_dir(&asset_dir, "testdir").await;
+            add_test_file(&dir, "test.txt").await;
+            add_test_file(&asset_dir, "test.txt").await;
+            wait_for_file_state(&t, &dir, "test.txt", true).await;
+            wait_for_file_state(&t, &asset_dir, "test.txt", true).await;
+            expect_dirty_file_state(&t, &dir, "test.txt").await;
+            clear_dirty_file_state(&t).await;
+
+            tokio::fs::write(dir.join("test.txt"), "modified")
+                .await
+                .expect("modify test file");
+            tokio::fs::remove_file(asset_dir.join("test.txt"))
+                .await
+                .expect("test file could not be deleted");
+            wait_for_file_state(&t, &asset_dir, "test.txt", false).await;
+            expect_dirty_file_state(&t, &asset_dir, "test.txt").await;
+            expect_dirty_file_state(&t, &dir, "test.txt").await;
+        })
+        .await;
+    }
 }
@@ -0,0 +1,211 @@
+use std::{
+    collections::BTreeMap,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use distill_core::utils;
+
+/// Name of the files that contain ignore rules for their directory and its subdirectories.
+pub const IGNORE_FILE_NAME: &str = ".distillignore";
+
+#[derive(Debug, Clone, PartialEq)]
+struct IgnoreRule {
+    /// Glob that is matched against the path relative to the directory of the rule.
+    pattern: String,
+    negate: bool,
+    dir_only: bool,
+}
+
+impl IgnoreRule {
+    /// Parses a line of an ignore file, which uses the syntax of `.gitignore` files.
+    fn parse(line: &str) -> Option<IgnoreRule> {
+        let line = line.trim_end();
+        if line.is_empty() || line.starts_with('#') {
+            return None;
+        }
+        let (negate, line) = match line.strip_prefix('!') {
+            Some(line) => (true, line),
+            None => (false, line.strip_prefix('\\').unwrap_or(line)),
+        };
+        let (dir_only, line) = match line.strip_suffix('/') {
+            Some(line) => (true, line),
+            None => (false, line),
+        };
+        if line.is_empty() {
+            return None;
+        }
+        // a pattern with a slash is relative to its directory, otherwise it matches at any depth
+        let pattern = if line.contains('/') {
+            line.trim_start_matches('/').to_string()
+        } else {
+            format!("**/{}", line)
+        };
+        Some(IgnoreRule {
+            pattern,
+            negate,
+            dir_only,
+        })
+    }
+}
+
+fn parse_rules<'a>(lines: impl IntoIterator<Item = &'a str>) -> Vec<IgnoreRule> {
+    lines.into_iter().filter_map(IgnoreRule::parse).collect()
+}
+
+/// Decides which paths under the watched directories are ignored, from the rules of the project
+/// config and of the `.distillignore` files in the directories.
+///
+/// Rules use the syntax of `.gitignore` files. Rules of a deeper directory take precedence, and
+/// the last matching rule in a file wins. The contents of an ignored directory are always ignored.
+#[derive(Debug, Default, Clone)]
+pub struct IgnoreRules {
+    /// Rules of the project config, which apply relative to each root directory.
+    global: Vec<IgnoreRule>,
+    roots: Vec<PathBuf>,
+    /// Rules of the ignore file in each directory.
+    dirs: BTreeMap<PathBuf, Vec<IgnoreRule>>,
+}
+
+impl IgnoreRules {
+    /// Creates rules from `patterns` that apply relative to each root directory.
+    pub fn new<S: AsRef<str>>(patterns: &[S]) -> Self {
+        IgnoreRules {
+            global: parse_rules(patterns.iter().map(|pattern| pattern.as_ref())),
+            ..Default::default()
+        }
+    }
+
+    /// Adds a directory that the rules of the project config apply to.
+    pub fn add_root(&mut self, dir: &Path) {
+        if !self.roots.iter().any(|root| root == dir) {
+            self.roots.push(dir.to_path_buf());
+        }
+    }
+
+    pub fn remove_root(&mut self, dir: &Path) {
+        self.roots.retain(|root| root != dir);
+        self.remove_dir(dir);
+    }
+
+    /// Forgets the rules of the ignore files in `dir` and its subdirectories.
+    pub fn remove_dir(&mut self, dir: &Path) {
+        self.dirs.retain(|path, _| !path.starts_with(dir));
+    }
+
+    /// Reads the ignore file of `dir`, replacing its rules. Returns true if the rules changed.
+    pub fn load_dir(&mut self, dir: &Path) -> io::Result<bool> {
+        let rules = match fs::read_to_string(dir.join(IGNORE_FILE_NAME)) {
+            Ok(text) => parse_rules(text.lines()),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Vec::new(),
+            Err(err) => return Err(err),
+        };
+        let changed = if rules.is_empty() {
+            self.dirs.remove(dir).is_some()
+        } else {
+            self.dirs.insert(dir.to_path_buf(), rules.clone()).as_ref() != Some(&rules)
+        };
+        Ok(changed)
+    }
+
+    /// Returns true if `path` or one of its parent directories is ignored.
+    pub fn is_ignored(&self, path: &Path, is_dir: bool) -> bool {
+        if self.global.is_empty() && self.dirs.is_empty() {
+            return false;
+        }
+        let root = match self.roots.iter().find(|root| path.starts_with(root)) {
+            Some(root) => root,
+            None => return self.matches(path, is_dir),
+        };
+        let mut parent = root.clone();
+        let relative = path.strip_prefix(root).expect("path is in root");
+        let mut components = relative.components().peekable();
+        while let Some(component) = components.next() {
+            parent.push(component);
+            let is_last = components.peek().is_none();
+            if self.matches(&parent, !is_last || is_dir) {
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Returns true if the last rule that matches `path` ignores it.
+    fn matches(&self, path: &Path, is_dir: bool) -> bool {
+        let mut ignored = false;
+        let mut check = |base: &Path, rules: &[IgnoreRule]| {
+            // rules only apply below the directory they are defined in
+            let relative = match path.strip_prefix(base) {
+                Ok(relative) if relative.as_os_str().is_empty() => return,
+                Ok(relative) => relative.to_string_lossy().replace('\\', "/"),
+                Err(_) => return,
+            };
+            for rule in rules {
+                if (!rule.dir_only || is_dir) && utils::glob_match(&rule.pattern, &relative) {
+                    ignored = !rule.negate;
+                }
+            }
+        };
+        for root in &self.roots {
+            check(root, &self.global);
+        }
+        // parent directories sort before their subdirectories, so deeper rules are checked last
+        for (dir, rules) in &self.dirs {
+            check(dir, rules);
+        }
+        ignored
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules(global: &[&str], dirs: &[(&str, &str)]) -> IgnoreRules {
+        let mut rules = IgnoreRules::new(global);
+        rules.add_root(Path::new("/assets"));
+        for (dir, text) in dirs {
+            rules
+                .dirs
+                .insert(PathBuf::from(dir), parse_rules(text.lines()));
+        }
+        rules
+    }
+
+    #[test]
+    fn ignore_patterns() {
+        let rules = rules(
+            &["*.swp", ".git/", "/build", "# comment", "textures/*.psd"],
+            &[],
+        );
+        let ignored = |path: &str, is_dir: bool| rules.is_ignored(Path::new(path), is_dir);
+        assert!(ignored("/assets/.hero.txt.swp", false));
+        assert!(ignored("/assets/levels/.level.swp", false));
+        assert!(ignored("/assets/.git", true));
+        assert!(ignored("/assets/.git/objects/ab", false));
+        assert!(!ignored("/assets/.git", false));
+        assert!(ignored("/assets/build", true));
+        assert!(!ignored("/assets/levels/build", true));
+        assert!(ignored("/assets/textures/wall.psd", false));
+        assert!(!ignored("/assets/props/textures/wall.psd", false));
+        assert!(!ignored("/assets/hero.txt", false));
+    }
+
+    #[test]
+    fn ignore_files_take_precedence() {
+        let rules = rules(
+            &["*.log"],
+            &[
+                ("/assets/tools", "cache/\n!keep.log\n"),
+                ("/assets/tools/cache", "!*"),
+            ],
+        );
+        let ignored = |path: &str, is_dir: bool| rules.is_ignored(Path::new(path), is_dir);
+        assert!(ignored("/assets/build.log", false));
+        assert!(!ignored("/assets/tools/keep.log", false));
+        assert!(ignored("/assets/tools/other.log", false));
+        // files in an ignored directory can't be included again
+        assert!(ignored("/assets/tools/cache/data.bin", false));
+        assert!(!ignored("/assets/cache/data.bin", false));
+    }
+}
@@ -6,18 +6,31 @@ mod artifact_cache;
 mod asset_hub;
 mod asset_hub_service;
 mod capnp_db;
+mod config;
 mod daemon;
 mod error;
 mod file_asset_source;
 mod file_tracker;
+mod ignore;
+#[cfg(feature = "plugins")]
+mod plugins;
 mod scope;
 mod serialized_asset;
 mod source_pair_import;
 mod watcher;
 
+#[cfg(feature = "plugins")]
+pub use crate::plugins::{
+    load_importer_plugin, load_importer_plugins, ImporterPlugin, PluginImporters,
+};
 pub use crate::{
-    daemon::{default_importer_contexts, default_importers, AssetDaemon, ImporterMap},
+    config::{DaemonConfig, DaemonSettings, ImporterGlob},
+    daemon::{
+        default_importer_contexts, default_importer_registry, default_importers, AssetDaemon,
+        ImporterMap, ImporterRegistry,
+    },
     error::{Error, Result},
+    watcher::{WatchMode, WatcherSettings},
 };
 
 #[cfg(debug_assertions)]
@@ -47,21 +60,23 @@ mod simple_logger {
 #[cfg(not(feature = "pretty_log"))]
 static LOGGER: simple_logger::SimpleLogger = simple_logger::SimpleLogger;
 
-#[cfg(not(feature = "pretty_log"))]
+/// Initializes logging with the level in the `RUST_LOG` environment variable, if it is set.
 pub fn init_logging() -> Result<()> {
     let rust_log = std::env::var("RUST_LOG").unwrap_or_else(|_| "".to_string());
     let log_level = <log::LevelFilter as std::str::FromStr>::from_str(&rust_log)
         .unwrap_or(DEFAULT_LOGGING_LEVEL);
+    init_logging_with_level(log_level)
+}
+
+#[cfg(not(feature = "pretty_log"))]
+pub fn init_logging_with_level(log_level: log::LevelFilter) -> Result<()> {
     log::set_logger(&LOGGER)
         .map(|()| log::set_max_level(log_level))
         .map_err(Error::SetLoggerError)
 }
 #[cfg(feature = "pretty_log")]
-pub fn init_logging() -> Result<()> {
+pub fn init_logging_with_level(log_level: log::LevelFilter) -> Result<()> {
     use chrono::Local;
-    let rust_log = std::env::var("RUST_LOG").unwrap_or_else(|_| "".to_string());
-    let log_level = <log::LevelFilter as std::str::FromStr>::from_str(&rust_log)
-        .unwrap_or(DEFAULT_LOGGING_LEVEL);
     fern::Dispatch::new()
         .format(|out, message, record| {
             out.finish(format_args!(
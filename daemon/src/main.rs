@@ -0,0 +1,167 @@
+use std::path::{Path, PathBuf};
+
+use distill_daemon::{
+    default_importer_registry, init_logging, init_logging_with_level, AssetDaemon, DaemonConfig,
+    DaemonSettings, Error, ImporterGlob, Result,
+};
+
+const DEFAULT_CONFIG_PATH: &str = "distill.toml";
+
+const USAGE: &str = "Usage: distill-daemon [options]
+
+Options:
+    --config <path>         Project config file, defaults to `distill.toml` if it exists
+    --profile <name>        Profile of the config file to apply
+    --asset-dir <path>      Directory to watch for source files, can be repeated
+    --db-path <path>        Directory of the asset database
+    --address <address>     Address to listen on, such as `127.0.0.1:9999`
+    --log-level <level>     One of `off`, `error`, `warn`, `info`, `debug` or `trace`
+    --importer <ext=name>   Use the registered importer `name` for files with extension `ext`
+    --importer-glob <pattern=name>
+                            Use the registered importer `name` for files that match `pattern`,
+                            can be repeated and takes precedence over `--importer`
+    --plugin-dir <path>     Directory of importer plugins to load, can be repeated
+    --watch-mode <mode>     One of `auto`, `native` or `poll`, `auto` falls back to polling
+                            when the native file watcher fails or receives no events
+    --poll-interval-ms <ms> Milliseconds between scans when polling for changes
+    --repair-duplicate-ids  Generate new asset IDs for source files that were copied together
+                            with their .meta files
+    --ignore <pattern>      Don't track files in the asset dirs that match the `.gitignore`
+                            style `pattern`, can be repeated
+    --list-importers        Print the names of the registered importers and exit
+    --help                  Print this message and exit";
+
+/// Options given on the command line, which take precedence over the config file.
+#[derive(Default)]
+struct Args {
+    config: Option<PathBuf>,
+    profile: Option<String>,
+    list_importers: bool,
+    help: bool,
+    overrides: DaemonSettings,
+}
+
+fn parse_args(args: impl IntoIterator<Item = String>) -> Result<Args> {
+    let mut parsed = Args::default();
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .ok_or_else(|| Error::Custom(format!("missing value for `{}`", arg)))
+        };
+        let invalid =
+            |value: &str| Error::Custom(format!("invalid value for `{}`: {}", arg, value));
+        match arg.as_str() {
+            "--config" => parsed.config = Some(PathBuf::from(value()?)),
+            "--profile" => parsed.profile = Some(value()?),
+            "--asset-dir" => parsed
+                .overrides
+                .asset_dirs
+                .get_or_insert_with(Vec::new)
+                .push(PathBuf::from(value()?)),
+            "--db-path" => parsed.overrides.db_path = Some(PathBuf::from(value()?)),
+            "--address" => {
+                let value = value()?;
+                parsed.overrides.address = Some(value.parse().map_err(|_| invalid(&value))?);
+            }
+            "--log-level" => {
+                let value = value()?;
+                parsed.overrides.log_level = Some(value.parse().map_err(|_| invalid(&value))?);
+            }
+            "--importer" => {
+                let value = value()?;
+                let (ext, name) = value.split_once('=').ok_or_else(|| invalid(&value))?;
+                parsed
+                    .overrides
+                    .importers
+                    .insert(ext.to_owned(), name.to_owned());
+            }
+            "--importer-glob" => {
+                let value = value()?;
+                let (pattern, name) = value.rsplit_once('=').ok_or_else(|| invalid(&value))?;
+                parsed.overrides.importer_globs.push(ImporterGlob {
+                    pattern: pattern.to_owned(),
+                    importer: name.to_owned(),
+                });
+            }
+            "--plugin-dir" => parsed
+                .overrides
+                .plugin_dirs
+                .get_or_insert_with(Vec::new)
+                .push(PathBuf::from(value()?)),
+            "--watch-mode" => parsed.overrides.watch_mode = Some(value()?.parse()?),
+            "--poll-interval-ms" => {
+                let value = value()?;
+                parsed.overrides.poll_interval_ms =
+                    Some(value.parse().map_err(|_| invalid(&value))?);
+            }
+            "--repair-duplicate-ids" => parsed.overrides.repair_duplicate_asset_ids = Some(true),
+            "--ignore" => parsed.overrides.ignore.push(value()?),
+            "--list-importers" => parsed.list_importers = true,
+            "--help" | "-h" => parsed.help = true,
+            _ => return Err(Error::Custom(format!("unknown argument `{}`", arg))),
+        }
+    }
+    Ok(parsed)
+}
+
+fn load_settings(args: &Args) -> Result<DaemonSettings> {
+    let config = match &args.config {
+        Some(path) => Some(DaemonConfig::load(path)?),
+        None if Path::new(DEFAULT_CONFIG_PATH).exists() => {
+            Some(DaemonConfig::load(DEFAULT_CONFIG_PATH)?)
+        }
+        None => None,
+    };
+    let mut settings = match config {
+        Some(config) => config.settings(args.profile.as_deref())?,
+        None if args.profile.is_some() => {
+            return Err(Error::Custom(
+                "`--profile` requires a config file".to_string(),
+            ))
+        }
+        None => DaemonSettings::default(),
+    };
+    settings.merge(args.overrides.clone());
+    Ok(settings)
+}
+
+fn main() {
+    if let Err(err) = run() {
+        eprintln!("error: {}", err);
+        std::process::exit(1);
+    }
+}
+
+fn run() -> Result<()> {
+    let args = parse_args(std::env::args().skip(1))?;
+    if args.help {
+        println!("{}", USAGE);
+        return Ok(());
+    }
+    let registry = default_importer_registry();
+    if args.list_importers {
+        let mut names: Vec<_> = registry.names().collect();
+        names.sort_unstable();
+        for name in names {
+            println!("{}", name);
+        }
+        return Ok(());
+    }
+
+    let settings = load_settings(&args)?;
+    match settings.log_level {
+        Some(level) => init_logging_with_level(level)?,
+        None => init_logging()?,
+    }
+    let daemon = AssetDaemon::default().with_settings(&settings, &registry)?;
+    log::info!(
+        "watching {:?}, database at {:?}",
+        daemon.asset_dirs,
+        daemon.db_dir
+    );
+    let (handle, _shutdown) = daemon.run();
+    handle
+        .join()
+        .map_err(|_| Error::Custom("daemon thread panicked".to_string()))
+}
@@ -0,0 +1,67 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use distill_importer::{
+    BoxedImporter, ImporterPluginDeclaration, ImporterRegistrar, PLUGIN_DECLARATION_SYMBOL,
+};
+use libloading::Library;
+
+use crate::error::{Error, Result};
+
+/// Importers registered by a plugin, with the extensions they were registered for.
+pub type PluginImporters = Vec<(String, Box<dyn BoxedImporter>)>;
+
+/// A loaded importer plugin. The library stays loaded for as long as this is alive, so it must
+/// outlive the importers it registered.
+pub struct ImporterPlugin {
+    pub path: PathBuf,
+    _library: Library,
+}
+
+/// Loads the importer plugin at `path`, returning the importers it registers with their
+/// extensions. Fails if the plugin was built for a different version of the plugin interface,
+/// the compiler or `distill-importer`.
+pub fn load_importer_plugin(path: &Path) -> Result<(ImporterPlugin, PluginImporters)> {
+    let plugin_error =
+        |err: &dyn std::fmt::Display| Error::Custom(format!("plugin {}: {}", path.display(), err));
+    // SAFETY: loading a library runs its initializers, which is inherent to loading plugins.
+    let library = unsafe { Library::new(path) }.map_err(|err| plugin_error(&err))?;
+    let importers = unsafe {
+        let declaration = library
+            .get::<*const ImporterPluginDeclaration>(PLUGIN_DECLARATION_SYMBOL)
+            .map_err(|err| plugin_error(&err))?;
+        let declaration = &**declaration;
+        declaration
+            .check_compatible()
+            .map_err(|err| plugin_error(&err))?;
+        let mut registrar = ImporterRegistrar::default();
+        (declaration.register)(&mut registrar);
+        registrar.into_importers()
+    };
+    Ok((
+        ImporterPlugin {
+            path: path.to_owned(),
+            _library: library,
+        },
+        importers,
+    ))
+}
+
+/// Loads every shared library in `dir` as an importer plugin, see [`load_importer_plugin`].
+pub fn load_importer_plugins(dir: &Path) -> Result<Vec<(ImporterPlugin, PluginImporters)>> {
+    let mut paths = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().and_then(|ext| ext.to_str()) == Some(std::env::consts::DLL_EXTENSION) {
+            paths.push(path);
+        }
+    }
+    // load in a stable order, so the same plugin wins when two register an extension
+    paths.sort();
+    paths
+        .iter()
+        .map(|path| load_importer_plugin(path))
+        .collect()
+}
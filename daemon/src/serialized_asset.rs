@@ -33,6 +33,7 @@ pub fn create(
             uncompressed_size: Some(size as u64),
             compressed_size: Some(asset_buf.len() as u64),
             type_id: AssetTypeId(value.uuid()),
+            weak_deps: Vec::new(),
         },
         data: asset_buf,
     })
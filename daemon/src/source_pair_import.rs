@@ -15,7 +15,7 @@ use distill_importer::{
 };
 use distill_schema::data;
 use futures::future::{BoxFuture, Future};
-use log::{debug, error};
+use log::{debug, error, warn};
 use serde::{Deserialize, Serialize};
 use tokio::{fs::File, io::AsyncReadExt};
 
@@ -41,6 +41,10 @@ pub struct ImportResultMetadata {
     pub importer_type: AssetTypeId,
     /// Metadata of assets resulting from importing the source file.
     pub assets: Vec<AssetMetadata>,
+    /// Other files that were read when importing the source file, see
+    /// [`ImportOp::read_source_dependency`].
+    #[serde(default)]
+    pub source_dependencies: Vec<PathBuf>,
 }
 // Only files get Some(hash)
 #[derive(Clone, Debug)]
@@ -68,6 +72,8 @@ pub(crate) struct AssetImportResult {
     pub unresolved_build_refs: Vec<AssetRef>,
     pub asset: Option<Box<dyn SerdeObj>>,
     pub serialized_asset: Option<SerializedAsset<Vec<u8>>>,
+    /// Asset types expected by typed handles in the asset, checked once references can be resolved.
+    pub expected_types: Vec<(AssetRef, AssetTypeId)>,
 }
 
 impl AssetImportResult {
@@ -88,6 +94,8 @@ impl AssetImportResult {
 pub(crate) struct SourcePairImport<'a> {
     source: PathBuf,
     importer: Option<&'a dyn BoxedImporter>,
+    /// The importer pinned by the .meta file, which is kept when the .meta file is rewritten.
+    importer_type: Option<AssetTypeId>,
     importer_contexts: Option<&'a [Box<dyn ImporterContext>]>,
     source_hash: Option<u64>,
     #[allow(dead_code)]
@@ -149,6 +157,20 @@ impl ImporterContextHandleSet {
         }
     }
 
+    pub fn expected_types(&self) -> Vec<(AssetRef, AssetTypeId)> {
+        self.0
+            .iter()
+            .flat_map(|handle| handle.expected_types())
+            .collect()
+    }
+
+    pub fn weak_refs(&self) -> Vec<AssetRef> {
+        self.0
+            .iter()
+            .flat_map(|handle| handle.weak_refs())
+            .collect()
+    }
+
     pub fn end_serialize_asset(&mut self, id: AssetUuid) -> HashSet<AssetRef> {
         let mut deps = HashSet::new();
         for handle in self.0.iter_mut() {
@@ -200,7 +222,8 @@ impl<'a> SourcePairImport<'a> {
 
     /// Returns true if an appropriate importer was found, otherwise false.
     pub fn set_importer_from_map(&mut self, importers: &'a ImporterMap) -> bool {
-        self.importer = importers.get_by_path(&self.source);
+        self.importer_type = read_pinned_importer_type(&self.source);
+        self.importer = importer_for_source(importers, &self.source, self.importer_type.as_ref());
         self.importer.is_some()
     }
 
@@ -215,12 +238,13 @@ impl<'a> SourcePairImport<'a> {
             if meta_file.version != SOURCEMETADATA_VERSION {
                 return Ok(true);
             }
-            if cached_result.importer_version
-                != self
-                    .importer
-                    .expect("need importer to determine if source import is required")
-                    .version()
-            {
+            let importer = self
+                .importer
+                .expect("need importer to determine if source import is required");
+            if cached_result.importer_version != importer.version() {
+                return Ok(true);
+            }
+            if cached_result.importer_type.0 != importer.uuid() {
                 return Ok(true);
             }
             if cached_result.import_hash.is_none() {
@@ -232,6 +256,7 @@ impl<'a> SourcePairImport<'a> {
                     meta_file.importer_state.as_ref(),
                     cached_result.importer_version,
                     cached_result.importer_type.0,
+                    &cached_result.source_dependencies,
                     scratch_buf,
                 )?);
             }
@@ -247,6 +272,7 @@ impl<'a> SourcePairImport<'a> {
         state: &dyn SerdeObj,
         importer_version: u32,
         importer_type: [u8; 16],
+        source_dependencies: &[PathBuf],
         scratch_buf: &mut Vec<u8>,
     ) -> Result<u64> {
         let mut hasher = ::std::collections::hash_map::DefaultHasher::new();
@@ -261,6 +287,10 @@ impl<'a> SourcePairImport<'a> {
             .hash(&mut hasher);
         importer_version.hash(&mut hasher);
         importer_type.hash(&mut hasher);
+        for dependency in source_dependencies {
+            dependency.hash(&mut hasher);
+            hash_source_dependency(dependency).hash(&mut hasher);
+        }
         Ok(hasher.finish())
     }
 
@@ -298,6 +328,7 @@ impl<'a> SourcePairImport<'a> {
 
         let mut default_metadata = SourceMetadata {
             version: SOURCEMETADATA_VERSION,
+            importer_type: self.importer_type,
             importer_options: importer.default_options(),
             importer_state: importer.default_state(),
         };
@@ -366,6 +397,7 @@ impl<'a> SourcePairImport<'a> {
                     unresolved_build_refs: unresolved_build_refs.into_iter().collect(),
                     asset: None,
                     serialized_asset: None,
+                    expected_types: Vec::new(),
                 });
             }
         }
@@ -388,11 +420,25 @@ impl<'a> SourcePairImport<'a> {
         mut ctx: ImporterContextHandleSet,
     ) -> Result<PairImportResult> {
         let mut imported_assets = Vec::new();
+        // exports don't report source dependencies, so keep the ones of the last import
+        let source_dependencies = match op.as_ref() {
+            Some(op) => op
+                .source_dependencies
+                .iter()
+                .map(|path| utils::canonicalize_path(path))
+                .collect(),
+            None => self
+                .result_metadata
+                .as_ref()
+                .map(|result| result.source_dependencies.clone())
+                .unwrap_or_default(),
+        };
         let import_hash = self.calc_import_hash(
             options.as_ref(),
             state.as_ref(),
             importer.version(),
             importer.uuid(),
+            &source_dependencies,
             scratch_buf,
         )?;
         self.import_hash = Some(import_hash);
@@ -430,6 +476,8 @@ impl<'a> SourcePairImport<'a> {
                 .await;
 
             let (mut asset, serialized_asset) = scope_result?;
+            let expected_types = ctx.expected_types();
+            let weak_refs = ctx.weak_refs();
             let serde_refs = ctx.end_serialize_asset(asset.id);
             // TODO implement build pipeline execution
             // let build_pipeline = metadata
@@ -442,10 +490,8 @@ impl<'a> SourcePairImport<'a> {
             let mut load_deps = HashSet::new();
             for load_dep in serde_refs.iter().chain(asset.load_deps.iter()) {
                 // check insert return value to prevent duplicates in unresolved_load_refs
-                if load_deps.insert(load_dep.clone()) {
-                    if let AssetRef::Path(path) = load_dep {
-                        unresolved_load_refs.push(AssetRef::Path(path.clone()));
-                    }
+                if load_deps.insert(load_dep.clone()) && !load_dep.is_uuid() {
+                    unresolved_load_refs.push(load_dep.clone());
                 }
             }
             let mut unresolved_build_refs = Vec::new();
@@ -455,12 +501,17 @@ impl<'a> SourcePairImport<'a> {
                 .chain(asset.build_deps.iter().cloned())
             {
                 // check insert return value to prevent duplicates in unresolved_build_refs
-                if build_deps.insert(build_dep.clone()) {
-                    if let AssetRef::Path(path) = build_dep {
-                        unresolved_build_refs.push(AssetRef::Path(path));
-                    }
+                if build_deps.insert(build_dep.clone()) && !build_dep.is_uuid() {
+                    unresolved_build_refs.push(build_dep);
                 }
             }
+            // Weak references that are also load dependencies are already kept alive
+            let mut weak_deps: Vec<_> = weak_refs
+                .into_iter()
+                .filter(|dep| dep.is_uuid() && !load_deps.contains(dep))
+                .collect();
+            weak_deps.sort_unstable();
+            weak_deps.dedup();
             asset.load_deps = load_deps.into_iter().collect();
             asset.build_deps = build_deps.into_iter().collect();
             imported_assets.push(AssetImportResult {
@@ -490,17 +541,21 @@ impl<'a> SourcePairImport<'a> {
                         compressed_size: serialized_asset.metadata.compressed_size,
                         uncompressed_size: serialized_asset.metadata.uncompressed_size,
                         type_id: AssetTypeId(asset.asset_data.uuid()),
+                        weak_deps,
                     }),
                     build_pipeline: asset.build_pipeline,
+                    error: None,
                 },
                 unresolved_load_refs,
                 unresolved_build_refs,
                 asset: Some(asset.asset_data),
                 serialized_asset: Some(serialized_asset),
+                expected_types,
             });
         }
         self.source_metadata = Some(SourceMetadata {
             version: SOURCEMETADATA_VERSION,
+            importer_type: self.importer_type,
             importer_options: options,
             importer_state: state,
         });
@@ -509,6 +564,7 @@ impl<'a> SourcePairImport<'a> {
             import_hash: Some(import_hash),
             importer_version: importer.version(),
             importer_type: AssetTypeId(importer.uuid()),
+            source_dependencies,
         });
 
         Ok(PairImportResult {
@@ -534,13 +590,13 @@ impl<'a> SourcePairImport<'a> {
         let mut ctx = Self::get_importer_context_set(self.importer_contexts);
 
         let source = &self.source;
-        use tokio_util::compat::*;
         let exported = ctx
             .scope(async move {
-                let f = File::open(source).await?;
-                importer
+                // export to memory first, so the source file is left intact if the export fails
+                let mut output = futures::io::Cursor::new(Vec::new());
+                let exported = importer
                     .export_boxed(
-                        &mut f.compat(),
+                        &mut output,
                         metadata.importer_options,
                         metadata.importer_state,
                         assets
@@ -548,7 +604,9 @@ impl<'a> SourcePairImport<'a> {
                             .map(|asset| ExportAsset { asset })
                             .collect(),
                     )
-                    .await
+                    .await?;
+                tokio::fs::write(source, output.into_inner()).await?;
+                Ok::<_, distill_importer::Error>(exported)
             })
             .await?;
         self.hash_source(); // hash source to get a hash of the exported data
@@ -588,7 +646,7 @@ impl<'a> SourcePairImport<'a> {
 
         let source = &self.source;
 
-        let mut import_op = ImportOp::default();
+        let mut import_op = ImportOp::for_source(source.clone());
         let import_op_ref = &mut import_op;
         let imported = ctx
             .scope(async move {
@@ -639,6 +697,17 @@ impl<'a> SourcePairImport<'a> {
         Ok(result)
     }
 
+    /// Replaces the importer state with the default state of the importer, which makes the
+    /// importer generate new asset IDs when the source is imported again.
+    pub fn reset_importer_state(&mut self) {
+        let importer = self
+            .importer
+            .expect("cannot reset importer state without an importer");
+        if let Some(metadata) = self.source_metadata.as_mut() {
+            metadata.importer_state = importer.default_state();
+        }
+    }
+
     pub fn write_metadata(&self) -> Result<()> {
         let serialized_metadata = ron::ser::to_string_pretty(
             self.source_metadata
@@ -654,6 +723,47 @@ impl<'a> SourcePairImport<'a> {
     }
 }
 
+/// The part of a .meta file that is read before the importer of its source file is known.
+#[derive(Deserialize)]
+struct PinnedImporterType {
+    #[serde(default)]
+    importer_type: Option<AssetTypeId>,
+}
+
+/// Returns the importer type pinned by the .meta file of `source`, if there is one.
+pub(crate) fn read_pinned_importer_type(source: &Path) -> Option<AssetTypeId> {
+    let meta = fs::read_to_string(utils::to_meta_path(source)).ok()?;
+    match ron::de::from_str::<PinnedImporterType>(&meta) {
+        Ok(pinned) => pinned.importer_type,
+        Err(err) => {
+            debug!("failed to read importer type of {:?}: {}", source, err);
+            None
+        }
+    }
+}
+
+/// Returns the importer of `source`, which is the importer with the type `importer_type` if it is
+/// pinned, or else the importer registered for its path.
+pub(crate) fn importer_for_source<'a>(
+    importers: &'a ImporterMap,
+    source: &Path,
+    importer_type: Option<&AssetTypeId>,
+) -> Option<&'a dyn BoxedImporter> {
+    match importer_type {
+        Some(importer_type) => {
+            let importer = importers.get_by_uuid(importer_type);
+            if importer.is_none() {
+                error!(
+                    "no importer with type {} is registered, which is pinned by the .meta file of {:?}",
+                    importer_type, source
+                );
+            }
+            importer
+        }
+        None => importers.get_by_path(source),
+    }
+}
+
 pub(crate) async fn import_pair<'a, C: SourceMetadataCache>(
     metadata_cache: &C,
     importer_map: &'a ImporterMap,
@@ -694,21 +804,14 @@ pub(crate) async fn import_pair<'a, C: SourceMetadataCache>(
             meta: None,
             source: None,
             ..
-        } => {
-            if let HashedSourcePair {
-                source: Some(state),
-                ..
-            } = original_pair
-            {
-                debug!("deleted pair {}", state.path.to_string_lossy());
-            } else if let HashedSourcePair {
-                meta: Some(state), ..
-            } = original_pair
-            {
+        } => match original_pair.source.or(original_pair.meta) {
+            Some(state) => {
                 debug!("deleted pair {}", state.path.to_string_lossy());
+                // an import without output removes the metadata of the source file
+                Ok(Some((SourcePairImport::new(state.path), None)))
             }
-            Ok(None)
-        }
+            None => Ok(None),
+        },
         // Source file with metadata
         HashedSourcePair {
             meta: Some(_meta),
@@ -746,6 +849,19 @@ pub(crate) async fn import_pair<'a, C: SourceMetadataCache>(
             ..
         } => {
             debug!("file without meta {}", source.path.to_string_lossy());
+            // a .meta file that went missing is not recreated here, but reported by
+            // `FileAssetSource::check_meta_files` so it can be regenerated with its asset IDs
+            let has_assets = matches!(
+                metadata_cache.get_cached_metadata(&source.path)?,
+                Some(metadata) if !metadata.assets.is_empty()
+            );
+            if has_assets {
+                warn!(
+                    "{:?} has assets in the DB but no .meta file: not importing it until the .meta file is regenerated",
+                    source.path
+                );
+                return Ok(None);
+            }
             let mut import = SourcePairImport::new(source.path);
             import.set_source_hash(hash);
             import.set_importer_contexts(importer_contexts);
@@ -816,12 +932,14 @@ pub(crate) async fn import_pair<'a, C: SourceMetadataCache>(
             source: None,
             ..
         } => {
-            debug!(
-                "meta file without source file {}",
-                meta.path.to_string_lossy()
+            // the .meta file is kept, so that the asset IDs are preserved if the source file is
+            // restored, for example when switching branches in version control
+            error!(
+                "Orphaned .meta file {:?}: the source file does not exist. Restore the source file or remove the .meta file.",
+                meta.path
             );
-            fs::remove_file(&meta.path)?;
-            Ok(None)
+            let source = meta.path.with_file_name(meta.path.file_stem().unwrap());
+            Ok(Some((SourcePairImport::new(source), None)))
         }
         _ => {
             debug!("Unknown case for {:?}", pair);
@@ -923,6 +1041,18 @@ pub(crate) async fn export_pair<'a, C: SourceMetadataCache>(
     }
 }
 
+/// Returns the hash of a source dependency, or `None` if it can't be read.
+fn hash_source_dependency(path: &Path) -> Option<u64> {
+    let state = FileState {
+        path: path.to_path_buf(),
+        state: data::FileState::Exists,
+        last_modified: 0,
+        length: 0,
+        ty: data::FileType::None,
+    };
+    hash_file(&state).ok().and_then(|(_, hash)| hash)
+}
+
 pub(crate) fn hash_file(state: &FileState) -> Result<(FileState, Option<u64>)> {
     let metadata = match fs::metadata(&state.path) {
         Err(e) => return Err(Error::IO(e)),
@@ -1,25 +1,110 @@
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     fs, io,
     path::{Path, PathBuf},
+    str::FromStr,
+    sync::mpsc::RecvTimeoutError,
     sync::mpsc::{channel, Receiver, Sender},
-    time::{Duration, UNIX_EPOCH},
+    time::{Duration, Instant, UNIX_EPOCH},
 };
 
 use distill_core::utils::canonicalize_path;
 use notify::{watcher, DebouncedEvent, RecommendedWatcher, RecursiveMode, Watcher};
+use serde::Deserialize;
 use tokio::sync::mpsc::UnboundedSender;
 
-use crate::error::{Error, Result};
+use crate::{
+    error::{Error, Result},
+    ignore::{IgnoreRules, IGNORE_FILE_NAME},
+};
+
+/// Name of the file that is written to each root directory in [`WatchMode::Auto`] to check that
+/// the native watcher receives events. It is never reported as a file event.
+const PROBE_FILE_NAME: &str = ".distill-watch-probe";
+
+/// Time to wait for the event of a probe file before falling back to polling.
+const PROBE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// How a [`DirWatcher`] finds out about changes to files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WatchMode {
+    /// Uses the native backend of the platform, and falls back to polling if the backend fails,
+    /// runs out of watches or receives no event for a probe file written to each root directory.
+    Auto,
+    /// Only uses the native backend of the platform.
+    Native,
+    /// Periodically scans the directories and compares the modification time and length of files
+    /// to the ones stored by the [`FileTracker`](crate::file_tracker::FileTracker). This works on
+    /// network shares and container mounts where the native backend receives no events.
+    Poll,
+}
+
+impl FromStr for WatchMode {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "auto" => Ok(WatchMode::Auto),
+            "native" => Ok(WatchMode::Native),
+            "poll" => Ok(WatchMode::Poll),
+            _ => Err(Error::Custom(format!("unknown watch mode `{}`", s))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct WatcherSettings {
+    pub mode: WatchMode,
+    /// Time between scans when polling.
+    pub poll_interval: Duration,
+    /// Maximum number of files that are checked per scan when polling. A pass over all
+    /// directories is spread over several scans if there are more files.
+    pub poll_scan_budget: usize,
+}
+
+impl Default for WatcherSettings {
+    fn default() -> Self {
+        WatcherSettings {
+            mode: WatchMode::Auto,
+            poll_interval: Duration::from_secs(2),
+            poll_scan_budget: 10_000,
+        }
+    }
+}
+
+/// Progress of a polling pass over the root directories.
+#[derive(Default)]
+struct PollState {
+    /// Root directories that are not yet scanned in this pass.
+    roots: Vec<PathBuf>,
+    /// The root directory that is being scanned and its directories that are left to scan.
+    scanning: Option<(PathBuf, Vec<PathBuf>)>,
+}
+
+/// Probe files that were written to check the native watcher, and the time to stop waiting for
+/// their events.
+struct Probe {
+    pending: Vec<PathBuf>,
+    deadline: Instant,
+}
 
 /// The purpose of DirWatcher is to provide enough information to
 /// determine which files may be candidates for going through the asset import process.
 /// It handles updating watches for directories behind symlinks and scans directories on create/delete.
+/// Each directory is watched separately so that ignored directories are never watched or scanned.
 pub struct DirWatcher {
-    watcher: RecommendedWatcher,
+    /// The native watcher, which is `None` when polling.
+    watcher: Option<RecommendedWatcher>,
+    settings: WatcherSettings,
+    poll: PollState,
+    probe: Option<Probe>,
     symlink_map: HashMap<PathBuf, PathBuf>,
     watch_refs: HashMap<PathBuf, i32>,
     dirs: Vec<PathBuf>,
+    /// Watched directories below the root directories in `dirs`.
+    subdirs: HashSet<PathBuf>,
+    ignore: IgnoreRules,
     rx: Receiver<DebouncedEvent>,
     tx: Sender<DebouncedEvent>,
     asset_tx: UnboundedSender<FileEvent>,
@@ -64,17 +149,75 @@ pub(crate) fn file_metadata(metadata: &fs::Metadata) -> FileMetadata {
     }
 }
 
+/// Returns true if `path` is a directory, without following symlinks.
+fn is_dir(path: &Path) -> bool {
+    fs::symlink_metadata(path).is_ok_and(|metadata| metadata.is_dir())
+}
+
+/// Returns an event for a created or modified file, or `None` if it no longer exists.
+fn updated_event(path: PathBuf) -> Result<Option<FileEvent>> {
+    let metadata_result = match fs::metadata(&path) {
+        Err(ref e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(ref e) if e.kind() == io::ErrorKind::PermissionDenied => {
+            log::warn!(
+                "Permission denied when fetching metadata for create event {:?}",
+                path,
+            );
+            Ok(None)
+        }
+        Err(e) => Err(Error::IO(e)),
+        Ok(metadata) => Ok(Some(FileEvent::Updated(
+            path.clone(),
+            file_metadata(&metadata),
+        ))),
+    };
+    match metadata_result {
+        Ok(None) => {
+            if let Ok(metadata) = fs::symlink_metadata(&path) {
+                Ok(Some(FileEvent::Updated(path, file_metadata(&metadata))))
+            } else {
+                Ok(None)
+            }
+        }
+        result => result,
+    }
+}
+
 impl DirWatcher {
-    pub fn from_path_iter<'a, T>(paths: T, chan: UnboundedSender<FileEvent>) -> Result<DirWatcher>
+    pub fn from_path_iter<'a, T>(
+        paths: T,
+        ignore: IgnoreRules,
+        settings: WatcherSettings,
+        chan: UnboundedSender<FileEvent>,
+    ) -> Result<DirWatcher>
     where
         T: IntoIterator<Item = &'a Path>,
     {
         let (tx, rx) = channel();
+        let native_watcher = match settings.mode {
+            WatchMode::Poll => None,
+            WatchMode::Native => Some(watcher(tx.clone(), Duration::from_millis(300))?),
+            WatchMode::Auto => match watcher(tx.clone(), Duration::from_millis(300)) {
+                Ok(watcher) => Some(watcher),
+                Err(err) => {
+                    log::warn!(
+                        "Failed to create a native file watcher, polling for changes instead: {}",
+                        err
+                    );
+                    None
+                }
+            },
+        };
         let mut asset_watcher = DirWatcher {
-            watcher: watcher(tx.clone(), Duration::from_millis(300))?,
+            watcher: native_watcher,
+            settings,
+            poll: PollState::default(),
+            probe: None,
             symlink_map: HashMap::new(),
             watch_refs: HashMap::new(),
             dirs: Vec::new(),
+            subdirs: HashSet::new(),
+            ignore,
             rx,
             tx,
             asset_tx: chan,
@@ -119,6 +262,14 @@ impl DirWatcher {
     where
         F: Fn(PathBuf) -> DebouncedEvent,
     {
+        if let Err(err) = self.ignore.load_dir(dir) {
+            log::warn!(
+                "Failed to read {} in {}: {}",
+                IGNORE_FILE_NAME,
+                dir.to_string_lossy(),
+                err
+            );
+        }
         match fs::read_dir(dir) {
             Err(ref e) if e.kind() == io::ErrorKind::NotFound => {}
             Err(e) => return Err(Error::IO(e)),
@@ -128,10 +279,6 @@ impl DirWatcher {
                         Err(ref e) if e.kind() == io::ErrorKind::NotFound => continue,
                         Err(e) => return Err(Error::IO(e)),
                         Ok(entry) => {
-                            let evt = self.handle_notify_event(evt_create(entry.path()), true)?;
-                            if let Some(evt) = evt {
-                                self.asset_tx.send(evt).map_err(|_| Error::SendError)?;
-                            }
                             let metadata;
                             match entry.metadata() {
                                 Err(ref e) if e.kind() == io::ErrorKind::NotFound => continue,
@@ -139,7 +286,24 @@ impl DirWatcher {
                                 Ok(m) => metadata = m,
                             }
                             let is_dir = metadata.is_dir();
+                            let path = canonicalize_path(&entry.path());
+                            if self.ignore.is_ignored(&path, is_dir) {
+                                // the rules may have changed since the directory was watched
+                                self.unwatch_subdirs(&path);
+                                continue;
+                            }
+                            let evt = evt_create(entry.path());
+                            let is_remove = matches!(evt, DebouncedEvent::Remove(_));
+                            let evt = self.handle_notify_event(evt, true)?;
+                            if let Some(evt) = evt {
+                                self.asset_tx.send(evt).map_err(|_| Error::SendError)?;
+                            }
                             if is_dir {
+                                if is_remove {
+                                    self.unwatch_subdirs(&path);
+                                } else {
+                                    self.watch_subdir(&path)?;
+                                }
                                 self.scan_directory_recurse(&entry.path(), evt_create)?;
                             }
                         }
@@ -158,9 +322,30 @@ impl DirWatcher {
                     .expect("Failed to send file error event. Ironic...");
             }
         }
+        if self.settings.mode == WatchMode::Auto && self.watcher.is_some() {
+            self.start_probe();
+        }
 
         loop {
-            match self.rx.recv() {
+            let event = match (&self.watcher, &self.probe) {
+                (Some(_), Some(probe)) => self
+                    .rx
+                    .recv_timeout(probe.deadline.saturating_duration_since(Instant::now())),
+                (Some(_), None) => self.rx.recv().map_err(|_| RecvTimeoutError::Disconnected),
+                (None, _) => self.rx.recv_timeout(self.settings.poll_interval),
+            };
+            if let Ok(event) = &event {
+                self.check_probe_event(event);
+            }
+            match event {
+                Err(RecvTimeoutError::Timeout) if self.watcher.is_some() => self.probe_timed_out(),
+                Err(RecvTimeoutError::Timeout) => {
+                    if let Err(err) = self.poll() {
+                        self.asset_tx
+                            .send(FileEvent::FileError(err))
+                            .expect("Failed to send file error event");
+                    }
+                }
                 Ok(event) => match self.handle_notify_event(event, false) {
                     Ok(maybe_event) => {
                         if let Some(evt) = maybe_event {
@@ -202,7 +387,8 @@ impl DirWatcher {
         let refs = *self.watch_refs.get(path).unwrap_or(&0);
         match refs {
             0 => {
-                self.watcher.watch(path, RecursiveMode::Recursive)?;
+                self.watch_native(path)?;
+                self.ignore.add_root(path);
                 self.dirs.push(path.to_path_buf());
                 self.watch_refs.insert(path.to_path_buf(), 1);
                 return Ok(true);
@@ -220,7 +406,9 @@ impl DirWatcher {
     fn unwatch(&mut self, path: &Path) -> Result<bool> {
         let refs = *self.watch_refs.get(path).unwrap_or(&0);
         if refs == 1 {
-            self.watcher.unwatch(path)?;
+            if let (Some(watcher), false) = (&mut self.watcher, self.subdirs.contains(path)) {
+                watcher.unwatch(path)?;
+            }
             for i in 0..self.dirs.len() {
                 if *path == self.dirs[i] {
                     self.dirs.remove(i);
@@ -228,6 +416,7 @@ impl DirWatcher {
                 }
             }
             self.watch_refs.remove(path);
+            self.ignore.remove_root(path);
             return Ok(true);
         } else if refs > 0 {
             self.watch_refs
@@ -237,6 +426,276 @@ impl DirWatcher {
         Ok(false)
     }
 
+    /// Watches a directory with the native watcher. In [`WatchMode::Auto`], falls back to
+    /// polling if the directory can't be watched, for example because the inotify watch limit
+    /// is reached.
+    fn watch_native(&mut self, path: &Path) -> Result<()> {
+        let watcher = match &mut self.watcher {
+            Some(watcher) => watcher,
+            None if path.is_dir() => return Ok(()),
+            None => return Err(Error::Notify(notify::Error::PathNotFound)),
+        };
+        match watcher.watch(path, RecursiveMode::NonRecursive) {
+            Ok(()) => Ok(()),
+            Err(notify::Error::PathNotFound) => Err(Error::Notify(notify::Error::PathNotFound)),
+            Err(notify::Error::Io(err)) if err.kind() == io::ErrorKind::NotFound => {
+                Err(Error::Notify(notify::Error::Io(err)))
+            }
+            Err(err) if self.settings.mode == WatchMode::Auto => {
+                let reason = match &err {
+                    // ENOSPC is returned by inotify when the watch limit is reached
+                    notify::Error::Io(io_err)
+                        if cfg!(target_os = "linux") && io_err.raw_os_error() == Some(28) =>
+                    {
+                        "the inotify watch limit was reached, see fs.inotify.max_user_watches"
+                            .to_string()
+                    }
+                    err => err.to_string(),
+                };
+                log::warn!(
+                    "Failed to watch {}, polling for changes instead: {}",
+                    path.to_string_lossy(),
+                    reason
+                );
+                // dropping the native watcher removes its watches
+                self.watcher = None;
+                self.subdirs.clear();
+                self.finish_probe();
+                Ok(())
+            }
+            Err(err) => Err(Error::Notify(err)),
+        }
+    }
+
+    /// Writes a probe file to each root directory, to check that the native watcher receives their
+    /// events. File systems such as network shares and container mounts accept watches, but never
+    /// report changes.
+    fn start_probe(&mut self) {
+        let mut pending = Vec::new();
+        for dir in &self.dirs {
+            let path = dir.join(PROBE_FILE_NAME);
+            match fs::write(&path, "") {
+                Ok(()) => pending.push(path),
+                // the directory can't be probed, but there are no changes to miss if it can't be
+                // written to
+                Err(err) => log::debug!(
+                    "Failed to write probe file {}: {}",
+                    path.to_string_lossy(),
+                    err
+                ),
+            }
+        }
+        if !pending.is_empty() {
+            self.probe = Some(Probe {
+                pending,
+                deadline: Instant::now() + PROBE_TIMEOUT,
+            });
+        }
+    }
+
+    /// Finishes the probe once events for all probe files have been received.
+    fn check_probe_event(&mut self, event: &DebouncedEvent) {
+        let probe = match &mut self.probe {
+            Some(probe) => probe,
+            None => return,
+        };
+        if let DebouncedEvent::NoticeWrite(path)
+        | DebouncedEvent::Create(path)
+        | DebouncedEvent::Write(path) = event
+        {
+            probe.pending.retain(|probe_path| probe_path != path);
+        }
+        if probe.pending.is_empty() {
+            self.finish_probe();
+        }
+    }
+
+    /// Falls back to polling, since the native watcher did not receive the events of some probe
+    /// files in time.
+    fn probe_timed_out(&mut self) {
+        if let Some(probe) = &self.probe {
+            let dirs: Vec<_> = probe
+                .pending
+                .iter()
+                .filter_map(|path| path.parent())
+                .map(|dir| dir.to_string_lossy())
+                .collect();
+            log::warn!(
+                "No file events were received for {} within {:?}, polling for changes instead",
+                dirs.join(", "),
+                PROBE_TIMEOUT
+            );
+        }
+        // dropping the native watcher removes its watches
+        self.watcher = None;
+        self.subdirs.clear();
+        self.finish_probe();
+    }
+
+    fn finish_probe(&mut self) {
+        for dir in &self.dirs {
+            let _ = fs::remove_file(dir.join(PROBE_FILE_NAME));
+        }
+        self.probe = None;
+    }
+
+    fn is_probe_file(path: &Path) -> bool {
+        path.file_name().is_some_and(|name| name == PROBE_FILE_NAME)
+    }
+
+    /// Watches a directory below one of the root directories.
+    fn watch_subdir(&mut self, path: &Path) -> Result<()> {
+        if self.watcher.is_none()
+            || self.subdirs.contains(path)
+            || self.watch_refs.contains_key(path)
+        {
+            return Ok(());
+        }
+        match self.watch_native(path) {
+            Ok(()) => {}
+            // the directory was removed before it could be watched
+            Err(Error::Notify(notify::Error::PathNotFound)) => return Ok(()),
+            Err(Error::Notify(notify::Error::Io(err))) if err.kind() == io::ErrorKind::NotFound => {
+                return Ok(())
+            }
+            Err(err) => return Err(err),
+        }
+        if self.watcher.is_some() {
+            self.subdirs.insert(path.to_path_buf());
+        }
+        Ok(())
+    }
+
+    /// Scans the directories for changes, continuing the current pass over the root directories
+    /// until the scan budget is used up.
+    ///
+    /// Every file is reported as updated, and the [`FileTracker`](crate::file_tracker::FileTracker)
+    /// compares it to the stored state. Removed files are detected at the end of the pass of
+    /// each root directory.
+    fn poll(&mut self) -> Result<()> {
+        if self.poll.roots.is_empty() && self.poll.scanning.is_none() {
+            self.poll.roots = self.dirs.iter().rev().cloned().collect();
+        }
+        let mut budget = self.settings.poll_scan_budget;
+        while budget > 0 {
+            let (root, pending) = match &mut self.poll.scanning {
+                Some(scanning) => scanning,
+                None => match self.poll.roots.pop() {
+                    Some(root) => {
+                        self.asset_tx
+                            .send(FileEvent::ScanStart(root.clone()))
+                            .map_err(|_| Error::SendError)?;
+                        self.poll.scanning.insert((root.clone(), vec![root]))
+                    }
+                    None => break,
+                },
+            };
+            let dir = match pending.pop() {
+                Some(dir) => dir,
+                None => {
+                    let root = root.clone();
+                    self.poll.scanning = None;
+                    self.asset_tx
+                        .send(FileEvent::ScanEnd(root, self.dirs.clone()))
+                        .map_err(|_| Error::SendError)?;
+                    continue;
+                }
+            };
+            budget = budget.saturating_sub(self.poll_directory(&dir)?);
+        }
+        Ok(())
+    }
+
+    /// Reports the files in `dir` as updated and queues its subdirectories for the current
+    /// polling pass. Returns the number of files that were checked.
+    fn poll_directory(&mut self, dir: &Path) -> Result<usize> {
+        if let Err(err) = self.ignore.load_dir(dir) {
+            log::warn!(
+                "Failed to read {} in {}: {}",
+                IGNORE_FILE_NAME,
+                dir.to_string_lossy(),
+                err
+            );
+        }
+        let entries = match fs::read_dir(dir) {
+            Err(ref e) if e.kind() == io::ErrorKind::NotFound => return Ok(0),
+            Err(e) => return Err(Error::IO(e)),
+            Ok(entries) => entries,
+        };
+        let mut checked = 0;
+        for entry in entries {
+            let entry = match entry {
+                Err(ref e) if e.kind() == io::ErrorKind::NotFound => continue,
+                Err(e) => return Err(Error::IO(e)),
+                Ok(entry) => entry,
+            };
+            let file_type = match entry.file_type() {
+                Err(ref e) if e.kind() == io::ErrorKind::NotFound => continue,
+                Err(e) => return Err(Error::IO(e)),
+                Ok(file_type) => file_type,
+            };
+            let path = canonicalize_path(&entry.path());
+            if self.ignore.is_ignored(&path, file_type.is_dir()) || Self::is_probe_file(&path) {
+                continue;
+            }
+            checked += 1;
+            if file_type.is_symlink() && !self.symlink_map.contains_key(&path) {
+                self.handle_updated_symlink(None, Some(&path))?;
+            }
+            if let Some(evt) = updated_event(path.clone())? {
+                self.asset_tx.send(evt).map_err(|_| Error::SendError)?;
+            }
+            if file_type.is_dir() {
+                if let Some((_, pending)) = &mut self.poll.scanning {
+                    pending.push(path);
+                }
+            }
+        }
+        Ok(checked)
+    }
+
+    /// Stops watching `path` and the directories below it, unless they are root directories.
+    fn unwatch_subdirs(&mut self, path: &Path) {
+        let to_unwatch: Vec<PathBuf> = self
+            .subdirs
+            .iter()
+            .filter(|dir| dir.starts_with(path))
+            .cloned()
+            .collect();
+        for dir in to_unwatch {
+            self.subdirs.remove(&dir);
+            if let (Some(watcher), false) = (&mut self.watcher, self.watch_refs.contains_key(&dir))
+            {
+                // the watch is already gone if the directory was removed
+                let _ = watcher.unwatch(&dir);
+            }
+        }
+        self.ignore.remove_dir(path);
+    }
+
+    /// Reloads the rules of the ignore file in `dir` and rescans it if they changed, which adds
+    /// files that are no longer ignored and removes files that are now ignored.
+    fn handle_updated_ignore_file(&mut self, dir: &Path) -> Result<()> {
+        match self.ignore.load_dir(dir) {
+            Ok(true) => self.scan_directory(dir, &|p| DebouncedEvent::Create(p)),
+            Ok(false) => Ok(()),
+            Err(err) => {
+                log::warn!(
+                    "Failed to read {} in {}: {}",
+                    IGNORE_FILE_NAME,
+                    dir.to_string_lossy(),
+                    err
+                );
+                Ok(())
+            }
+        }
+    }
+
+    fn is_ignore_file(path: &Path) -> bool {
+        path.file_name()
+            .is_some_and(|name| name == IGNORE_FILE_NAME)
+    }
+
     fn handle_updated_symlink(
         &mut self,
         src: Option<&PathBuf>,
@@ -303,40 +762,64 @@ impl DirWatcher {
         event: DebouncedEvent,
         is_scanning: bool,
     ) -> Result<Option<FileEvent>> {
+        match &event {
+            DebouncedEvent::Create(path)
+            | DebouncedEvent::Write(path)
+            | DebouncedEvent::Remove(path)
+                if Self::is_probe_file(path) =>
+            {
+                return Ok(None)
+            }
+            _ => {}
+        }
         match event {
             DebouncedEvent::Create(path) | DebouncedEvent::Write(path) => {
                 let path = canonicalize_path(&path);
+                let is_dir = is_dir(&path);
+                if self.ignore.is_ignored(&path, is_dir) {
+                    return Ok(None);
+                }
                 self.handle_updated_symlink(Option::None, Some(&path))?;
-                let metadata_result = match fs::metadata(&path) {
-                    Err(ref e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
-                    Err(ref e) if e.kind() == io::ErrorKind::PermissionDenied => {
-                        log::warn!(
-                            "Permission denied when fetching metadata for create event {:?}",
-                            path,
-                        );
-                        Ok(None)
-                    }
-                    Err(e) => Err(Error::IO(e)),
-                    Ok(metadata) => Ok(Some(FileEvent::Updated(
-                        path.clone(),
-                        file_metadata(&metadata),
-                    ))),
-                };
-                match metadata_result {
-                    Ok(None) => {
-                        if let Ok(metadata) = fs::symlink_metadata(&path) {
-                            Ok(Some(FileEvent::Updated(path, file_metadata(&metadata))))
-                        } else {
-                            Ok(None)
+                if !is_scanning {
+                    if Self::is_ignore_file(&path) {
+                        if let Some(dir) = path.parent() {
+                            self.handle_updated_ignore_file(dir)?;
                         }
+                    } else if is_dir && !self.subdirs.contains(&path) {
+                        // files may have been created before the directory was watched
+                        self.watch_subdir(&path)?;
+                        self.scan_directory_recurse(&path, &|p| DebouncedEvent::Create(p))?;
                     }
-                    result => result,
                 }
+                updated_event(path)
             }
             DebouncedEvent::Rename(src, dest) => {
                 let src = canonicalize_path(&src);
                 let dest = canonicalize_path(&dest);
+                let src_is_dir = self.subdirs.contains(&src);
+                let dest_is_dir = is_dir(&dest);
+                match (
+                    self.ignore.is_ignored(&src, src_is_dir),
+                    self.ignore.is_ignored(&dest, dest_is_dir),
+                ) {
+                    (true, true) => return Ok(None),
+                    (true, false) => {
+                        return self.handle_notify_event(DebouncedEvent::Create(dest), is_scanning)
+                    }
+                    (false, true) => {
+                        return self.handle_notify_event(DebouncedEvent::Remove(src), is_scanning)
+                    }
+                    (false, false) => {}
+                }
                 self.handle_updated_symlink(Some(&src), Some(&dest))?;
+                if !is_scanning {
+                    self.unwatch_subdirs(&src);
+                    if Self::is_ignore_file(&src) || Self::is_ignore_file(&dest) {
+                        for dir in src.parent().into_iter().chain(dest.parent()) {
+                            self.handle_updated_ignore_file(dir)?;
+                        }
+                    }
+                }
                 match fs::metadata(&dest) {
                     Err(ref e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
                     Err(ref e) if e.kind() == io::ErrorKind::PermissionDenied => {
@@ -350,6 +833,7 @@ impl DirWatcher {
                     Err(e) => Err(Error::IO(e)),
                     Ok(metadata) => {
                         if metadata.is_dir() && !is_scanning {
+                            self.watch_subdir(&dest)?;
                             self.scan_directory(&dest, &|p| {
                                 let replaced = canonicalize_path(&src.join(
                                     p.strip_prefix(&dest).expect("Failed to strip prefix dir"),
@@ -367,7 +851,18 @@ impl DirWatcher {
             }
             DebouncedEvent::Remove(path) => {
                 let path = canonicalize_path(&path);
+                if self.ignore.is_ignored(&path, self.subdirs.contains(&path)) {
+                    return Ok(None);
+                }
                 self.handle_updated_symlink(Some(&path), Option::None)?;
+                if !is_scanning {
+                    self.unwatch_subdirs(&path);
+                    if Self::is_ignore_file(&path) {
+                        if let Some(dir) = path.parent() {
+                            self.handle_updated_ignore_file(dir)?;
+                        }
+                    }
+                }
                 Ok(Some(FileEvent::Removed(path)))
             }
             DebouncedEvent::Rescan => Err(Error::RescanRequired),
@@ -385,3 +880,61 @@ impl Drop for StopHandle {
         ));
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use tokio::sync::mpsc::unbounded_channel;
+
+    use super::*;
+
+    #[test]
+    fn auto_mode_polls_when_probe_receives_no_events() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = canonicalize_path(dir.path());
+        let (tx, mut rx) = unbounded_channel();
+        let settings = WatcherSettings {
+            poll_interval: Duration::from_millis(50),
+            ..WatcherSettings::default()
+        };
+        let mut watcher = DirWatcher::from_path_iter(
+            std::iter::once(root.as_path()),
+            IgnoreRules::new::<&str>(&[]),
+            settings,
+            tx,
+        )
+        .unwrap();
+        // the directory stays watched, but no events are received, as on a network share
+        watcher.watcher.as_mut().unwrap().unwatch(&root).unwrap();
+        let stop_handle = watcher.stop_handle();
+        let thread = std::thread::spawn(move || {
+            watcher.run();
+            watcher.watcher.is_none()
+        });
+
+        // the probe file is written after the initial scan and removed when the probe times out
+        let deadline = Instant::now() + PROBE_TIMEOUT + Duration::from_secs(5);
+        for probed in [false, true] {
+            while root.join(PROBE_FILE_NAME).exists() == probed && Instant::now() < deadline {
+                std::thread::sleep(Duration::from_millis(10));
+            }
+        }
+        assert!(!root.join(PROBE_FILE_NAME).exists());
+
+        fs::write(root.join("test.txt"), "test").unwrap();
+        let mut updated = false;
+        while !updated && Instant::now() < deadline {
+            match rx.try_recv() {
+                Ok(FileEvent::Updated(path, _)) => {
+                    assert!(!DirWatcher::is_probe_file(&path));
+                    updated = path == root.join("test.txt");
+                }
+                Ok(_) => {}
+                Err(_) => std::thread::sleep(Duration::from_millis(10)),
+            }
+        }
+        assert!(updated);
+
+        drop(stop_handle);
+        assert!(thread.join().unwrap());
+    }
+}
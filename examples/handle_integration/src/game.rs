@@ -4,10 +4,10 @@ use distill::loader::{
     crossbeam_channel::{unbounded, Receiver},
     handle::{self, AssetHandle, Handle, RefOp, WeakHandle},
     storage::{DefaultIndirectionResolver, IndirectIdentifier, LoadStatus},
-    Loader, RpcIO,
+    GenericAssetStorage, Loader, RpcIO,
 };
 
-use crate::{custom_asset::BigPerf, image::Image, storage::GenericAssetStorage};
+use crate::{custom_asset::BigPerf, image::Image};
 
 struct Game {
     storage: GenericAssetStorage,
@@ -29,7 +29,7 @@ pub fn run() {
     // let file_reader = distill::loader::packfile_io::PackfileReader::new(file).unwrap();
     // let mut loader = Loader::new(Box::new(file_reader));
     let game = Game {
-        storage: GenericAssetStorage::new(tx.clone(), loader.indirection_table()),
+        storage: GenericAssetStorage::new((*tx).clone(), loader.indirection_table()),
     };
     game.storage.add_storage::<Image>();
     game.storage.add_storage::<BigPerf>();
@@ -1,7 +1,6 @@
 mod custom_asset;
 mod game;
 mod image;
-mod storage;
 
 use std::path::PathBuf;
 
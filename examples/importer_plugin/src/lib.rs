@@ -0,0 +1,62 @@
+//! An importer plugin for the daemon. Build it with `cargo build -p importer_plugin` and start
+//! the daemon with `--plugin-dir` set to a directory containing the built library, to import
+//! `.shout` files as uppercase strings.
+use std::io::Read;
+
+use distill::core::{type_uuid, type_uuid::TypeUuid, AssetUuid};
+use distill_importer::{
+    ImportOp, ImportedAsset, Importer, ImporterRegistrar, ImporterValue, Result,
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(TypeUuid, Serialize, Deserialize, Default)]
+#[uuid = "5a1f3b9e-0f4e-4d2c-8b51-3c3f2a9d7e10"]
+pub struct ShoutState(Option<AssetUuid>);
+
+#[derive(TypeUuid)]
+#[uuid = "b0e6d3a2-6c1f-4e8a-9d47-2f5b8c1a0e93"]
+pub struct ShoutImporter;
+
+impl Importer for ShoutImporter {
+    type Options = ();
+    type State = ShoutState;
+
+    fn version_static() -> u32 {
+        1
+    }
+
+    fn version(&self) -> u32 {
+        Self::version_static()
+    }
+
+    fn import(
+        &self,
+        _op: &mut ImportOp,
+        source: &mut dyn Read,
+        _options: &Self::Options,
+        state: &mut Self::State,
+    ) -> Result<ImporterValue> {
+        let id = state
+            .0
+            .unwrap_or_else(|| AssetUuid(*uuid::Uuid::new_v4().as_bytes()));
+        *state = ShoutState(Some(id));
+        let mut text = String::new();
+        source.read_to_string(&mut text)?;
+        Ok(ImporterValue {
+            assets: vec![ImportedAsset {
+                id,
+                search_tags: vec![],
+                build_deps: vec![],
+                load_deps: vec![],
+                build_pipeline: None,
+                asset_data: Box::new(text.to_uppercase()),
+            }],
+        })
+    }
+}
+
+fn register(registrar: &mut ImporterRegistrar) {
+    registrar.register_importer("shout", Box::new(ShoutImporter));
+}
+
+distill_importer::declare_importer_plugin!(register);
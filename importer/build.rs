@@ -0,0 +1,23 @@
+use std::{env, process::Command};
+
+fn main() {
+    // Importer plugins are only compatible with a daemon built by the same compiler
+    let rustc = env::var("RUSTC").unwrap_or_else(|_| "rustc".to_string());
+    let version = Command::new(rustc)
+        .arg("--version")
+        .output()
+        .ok()
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .unwrap_or_default();
+    println!("cargo:rustc-env=DISTILL_RUSTC_VERSION={}", version.trim());
+    // features change the layout of types shared with plugins, such as `Error`
+    let mut features: Vec<_> = env::vars()
+        .filter_map(|(key, _)| key.strip_prefix("CARGO_FEATURE_").map(|f| f.to_lowercase()))
+        .collect();
+    features.sort();
+    println!(
+        "cargo:rustc-env=DISTILL_IMPORTER_FEATURES={}",
+        features.join(",")
+    );
+    println!("cargo:rerun-if-env-changed=RUSTC");
+}
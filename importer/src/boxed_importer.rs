@@ -1,4 +1,4 @@
-use distill_core::TypeUuidDynamic;
+use distill_core::{AssetTypeId, TypeUuidDynamic};
 use erased_serde::Deserializer;
 use futures::{future::BoxFuture, AsyncRead, AsyncWrite};
 use serde::{Deserialize, Serialize};
@@ -14,6 +14,11 @@ pub const SOURCEMETADATA_VERSION: u32 = 2;
 pub struct SourceMetadata<Options: 'static, State: 'static> {
     /// Metadata struct version
     pub version: u32,
+    /// The [`TypeUuidDynamic::uuid`] of the importer to use for the source file, which takes
+    /// precedence over the importer registered for its path. The options and state must be reset
+    /// when it is changed to an importer with different types.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub importer_type: Option<AssetTypeId>,
     /// The [`crate::Importer::Options`] used to import the source file.
     pub importer_options: Options,
     /// The [`crate::Importer::State`] generated when importing the source file.
@@ -159,6 +164,7 @@ where
         let metadata = erased_serde::deserialize::<SourceMetadata<O, S>>(deserializer)?;
         Ok(SourceMetadata {
             version: metadata.version,
+            importer_type: metadata.importer_type,
             importer_options: Box::new(metadata.importer_options),
             importer_state: Box::new(metadata.importer_state),
         })
@@ -0,0 +1,108 @@
+use std::io::Read;
+
+use distill_core::{type_uuid, type_uuid::TypeUuid, AssetBundle, AssetUuid};
+use ron::de::from_reader;
+use serde::{Deserialize, Serialize};
+
+use crate::{ImportOp, ImportedAsset, Importer, ImporterValue, Result};
+
+#[derive(Default, Deserialize, Serialize, TypeUuid, Clone, Copy)]
+#[uuid = "2b7b90ac-4542-492a-a2ec-bbd94e089828"]
+pub struct BundleImporterOptions {}
+
+/// A simple state for Importer to retain the same UUID between imports
+#[derive(Default, Deserialize, Serialize, TypeUuid)]
+#[uuid = "338447b5-e663-4a3b-bb8d-e88a9a9ee498"]
+pub struct BundleImporterState {
+    pub id: Option<AssetUuid>,
+}
+
+/// Imports a RON manifest of an [`AssetBundle`], such as
+/// `(assets: [Uuid("..."), Path("trees.txt"), Tag("level=1")])`.
+///
+/// All members of the bundle become load dependencies of the imported asset.
+#[derive(Default, TypeUuid)]
+#[uuid = "1b509192-995e-4c74-aadb-64763e52b47c"]
+pub struct BundleImporter;
+
+impl Importer for BundleImporter {
+    type Options = BundleImporterOptions;
+    type State = BundleImporterState;
+
+    fn version_static() -> u32 {
+        1
+    }
+
+    fn version(&self) -> u32 {
+        Self::version_static()
+    }
+
+    fn import(
+        &self,
+        _op: &mut ImportOp,
+        source: &mut dyn Read,
+        _: &Self::Options,
+        state: &mut Self::State,
+    ) -> Result<ImporterValue> {
+        if state.id.is_none() {
+            state.id = Some(AssetUuid(*uuid::Uuid::new_v4().as_bytes()));
+        }
+        let bundle: AssetBundle = from_reader(source)?;
+
+        Ok(ImporterValue {
+            assets: vec![ImportedAsset {
+                id: state.id.expect("AssetUuid not generated"),
+                search_tags: Vec::new(),
+                build_deps: Vec::new(),
+                load_deps: bundle.assets.clone(),
+                asset_data: Box::new(bundle),
+                build_pipeline: None,
+            }],
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use distill_core::AssetRef;
+
+    use super::*;
+    use crate::BoxedImporter;
+
+    #[test]
+    fn bundle_importer_test() {
+        let importer: Box<dyn BoxedImporter> = Box::new(BundleImporter);
+
+        let mut manifest = "(
+                              assets: [
+                                  Uuid(\"36fb2083-7195-4583-8af9-0965f10ae60d\"),
+                                  Path(\"trees.txt\"),
+                                  Tag(\"level=1\"),
+                              ],
+                            )"
+        .as_bytes();
+
+        let mut import_op = ImportOp::default();
+        let result = futures::executor::block_on(importer.import_boxed(
+            &mut import_op,
+            &mut manifest,
+            Box::new(BundleImporterOptions {}),
+            Box::new(BundleImporterState { id: None }),
+        ))
+        .unwrap();
+        let asset = result.value.assets.into_iter().next().unwrap();
+        let expected = vec![
+            AssetRef::Uuid("36fb2083-7195-4583-8af9-0965f10ae60d".into()),
+            AssetRef::Path(PathBuf::from("trees.txt")),
+            AssetRef::Tag("level=1".to_string()),
+        ];
+
+        assert_eq!(asset.load_deps, expected);
+        match asset.asset_data.any().downcast_ref::<AssetBundle>() {
+            Some(bundle) => assert_eq!(bundle.assets, expected),
+            None => panic!("Expected serde_obj to be downcast to `AssetBundle`."),
+        }
+    }
+}
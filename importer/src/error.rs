@@ -8,8 +8,10 @@ pub enum Error {
     IoError(std::io::Error),
     Uuid(uuid::Error),
     ErasedSerde(erased_serde::Error),
-    #[cfg(feature = "serde_importers")]
+    #[cfg(any(feature = "serde_importers", feature = "process_importer"))]
     RonDe(ron::de::Error),
+    #[cfg(feature = "process_importer")]
+    Bincode(Box<bincode::ErrorKind>),
     Boxed(Box<dyn std::error::Error + Send>),
     ExportUnsupported,
     Custom(String),
@@ -21,8 +23,10 @@ impl std::error::Error for Error {
             Error::IoError(ref e) => Some(e),
             Error::Uuid(ref e) => Some(e),
             Error::ErasedSerde(ref e) => Some(e),
-            #[cfg(feature = "serde_importers")]
+            #[cfg(any(feature = "serde_importers", feature = "process_importer"))]
             Error::RonDe(ref e) => Some(e),
+            #[cfg(feature = "process_importer")]
+            Error::Bincode(ref e) => Some(e),
             Error::Boxed(ref e) => e.source(),
             Error::ExportUnsupported => None,
             Error::Custom(_) => None,
@@ -36,8 +40,10 @@ impl fmt::Display for Error {
             Error::Uuid(ref e) => e.fmt(f),
             Error::IoError(ref e) => e.fmt(f),
             Error::ErasedSerde(ref e) => e.fmt(f),
-            #[cfg(feature = "serde_importers")]
+            #[cfg(any(feature = "serde_importers", feature = "process_importer"))]
             Error::RonDe(ref e) => e.fmt(f),
+            #[cfg(feature = "process_importer")]
+            Error::Bincode(ref e) => e.fmt(f),
             Error::Boxed(ref e) => e.fmt(f),
             Error::ExportUnsupported => write!(f, "{:?}", self),
             Error::Custom(ref e) => write!(f, "{}", e),
@@ -62,13 +68,20 @@ impl From<erased_serde::Error> for Error {
     }
 }
 
-#[cfg(feature = "serde_importers")]
+#[cfg(any(feature = "serde_importers", feature = "process_importer"))]
 impl From<ron::de::Error> for Error {
     fn from(err: ron::de::Error) -> Error {
         Error::RonDe(err)
     }
 }
 
+#[cfg(feature = "process_importer")]
+impl From<Box<bincode::ErrorKind>> for Error {
+    fn from(err: Box<bincode::ErrorKind>) -> Error {
+        Error::Bincode(err)
+    }
+}
+
 impl From<Box<dyn std::error::Error + Send>> for Error {
     fn from(err: Box<dyn std::error::Error + Send>) -> Error {
         Error::Boxed(err)
@@ -1,11 +1,19 @@
 mod boxed_importer;
 mod error;
+mod plugin;
 mod serde_obj;
 mod serialized_asset;
 
+#[cfg(feature = "serde_importers")]
+mod bundle_importer;
+#[cfg(feature = "process_importer")]
+mod process_importer;
 #[cfg(feature = "serde_importers")]
 mod ron_importer;
-use std::io::{Read, Write};
+use std::{
+    io::{Read, Write},
+    path::{Path, PathBuf},
+};
 
 pub use distill_core::{
     importer_context::{ImporterContext, ImporterContextHandle},
@@ -20,6 +28,12 @@ use serde::Serialize;
 
 pub use self::error::{Error, Result};
 #[cfg(feature = "serde_importers")]
+pub use crate::bundle_importer::{BundleImporter, BundleImporterOptions, BundleImporterState};
+#[cfg(feature = "process_importer")]
+pub use crate::process_importer::{
+    run_importer_process, ProcessImporter, ProcessValue, IMPORTER_HOST_ENV,
+};
+#[cfg(feature = "serde_importers")]
 pub use crate::ron_importer::{RonImporter, RonImporterOptions, RonImporterState};
 #[doc(hidden)]
 #[cfg(feature = "serde_importers")]
@@ -27,7 +41,13 @@ pub use crate::serde_obj::typetag;
 #[cfg(feature = "serde_importers")]
 pub use crate::serde_obj::SerdeImportable;
 pub use crate::{
-    boxed_importer::{BoxedImporter, SourceMetadata, SOURCEMETADATA_VERSION},
+    boxed_importer::{
+        BoxedExportInputs, BoxedImporter, BoxedImporterValue, SourceMetadata,
+        SOURCEMETADATA_VERSION,
+    },
+    plugin::{
+        ImporterPluginDeclaration, ImporterRegistrar, PLUGIN_ABI_VERSION, PLUGIN_DECLARATION_SYMBOL,
+    },
     serde_obj::{IntoSerdeObj, SerdeObj},
     serialized_asset::SerializedAsset,
 };
@@ -36,9 +56,43 @@ pub use crate::{
 pub struct ImportOp {
     pub errors: Vec<Box<dyn std::error::Error + Send + 'static>>,
     pub warnings: Vec<Box<dyn std::error::Error + Send + 'static>>,
+    /// Files other than the source file that affect the import, see
+    /// [`ImportOp::read_source_dependency`].
+    pub source_dependencies: Vec<PathBuf>,
+    source_path: Option<PathBuf>,
 }
 
 impl ImportOp {
+    /// Creates an `ImportOp` for importing the source file at `source_path`.
+    pub fn for_source(source_path: PathBuf) -> Self {
+        ImportOp {
+            source_path: Some(source_path),
+            ..Default::default()
+        }
+    }
+
+    /// Path of the source file being imported, if it is known.
+    pub fn source_path(&self) -> Option<&Path> {
+        self.source_path.as_deref()
+    }
+
+    /// Reads a file that the import depends on, such as an include or a sidecar file. A relative
+    /// `path` is relative to the directory of the source file.
+    ///
+    /// The file is recorded as a source dependency even if it can't be read, and the source file
+    /// is imported again when it changes.
+    pub fn read_source_dependency<P: AsRef<Path>>(&mut self, path: P) -> std::io::Result<Vec<u8>> {
+        let path = match self.source_path.as_ref().and_then(|source| source.parent()) {
+            Some(dir) => dir.join(path),
+            None => path.as_ref().to_path_buf(),
+        };
+        let contents = std::fs::read(&path);
+        if !self.source_dependencies.contains(&path) {
+            self.source_dependencies.push(path);
+        }
+        contents
+    }
+
     pub fn error<T: Into<Box<dyn std::error::Error + Send + 'static>>>(&mut self, err: T) {
         self.errors.push(err.into());
     }
@@ -188,7 +242,7 @@ impl<T: Importer + Sync> AsyncImporter for T {
         Box::pin(async move {
             let mut write_buf = Vec::new();
             let result = <T as Importer>::export(self, &mut write_buf, options, state, assets)?;
-            output.write(&write_buf).await?;
+            output.write_all(&write_buf).await?;
             Ok(result)
         })
     }
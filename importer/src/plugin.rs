@@ -0,0 +1,152 @@
+use std::{ffi::CStr, os::raw::c_char};
+
+use crate::{BoxedImporter, Error, Result};
+
+/// Version of the plugin interface. Changes whenever [`ImporterPluginDeclaration`] or
+/// [`ImporterRegistrar`] change in an incompatible way.
+pub const PLUGIN_ABI_VERSION: u32 = 1;
+
+/// Name of the static [`ImporterPluginDeclaration`] exported by a plugin, see
+/// [`declare_importer_plugin`](crate::declare_importer_plugin).
+pub const PLUGIN_DECLARATION_SYMBOL: &[u8] = b"DISTILL_IMPORTER_PLUGIN\0";
+
+const RUSTC_VERSION: &str = concat!(env!("DISTILL_RUSTC_VERSION"), "\0");
+const DISTILL_VERSION: &str = concat!(
+    env!("CARGO_PKG_VERSION"),
+    " [",
+    env!("DISTILL_IMPORTER_FEATURES"),
+    "]\0"
+);
+
+/// Collects the importers of a plugin when it is loaded.
+///
+/// `SerdeImportable` types are registered with the copy of `distill-importer` that is linked into
+/// the plugin, so a plugin that defines them should also register a `RonImporter` for the
+/// extension of their files.
+#[derive(Default)]
+pub struct ImporterRegistrar {
+    importers: Vec<(String, Box<dyn BoxedImporter>)>,
+}
+
+impl ImporterRegistrar {
+    /// Registers `importer` for files with the extension `ext`.
+    pub fn register_importer(&mut self, ext: &str, importer: Box<dyn BoxedImporter>) {
+        self.importers.push((ext.to_lowercase(), importer));
+    }
+
+    pub fn into_importers(self) -> Vec<(String, Box<dyn BoxedImporter>)> {
+        self.importers
+    }
+}
+
+/// Entry point of an importer plugin, exported as `DISTILL_IMPORTER_PLUGIN` by
+/// [`declare_importer_plugin`](crate::declare_importer_plugin).
+///
+/// Only `abi_version` is read before the versions are checked, so its offset must not change.
+#[repr(C)]
+pub struct ImporterPluginDeclaration {
+    pub abi_version: u32,
+    /// Version of the compiler that built the plugin, as a nul-terminated string.
+    pub rustc_version: *const c_char,
+    /// Version and features of `distill-importer` the plugin was built with, as a nul-terminated
+    /// string.
+    pub distill_version: *const c_char,
+    pub register: unsafe extern "C" fn(&mut ImporterRegistrar),
+}
+
+// The pointers refer to static strings
+unsafe impl Sync for ImporterPluginDeclaration {}
+
+impl ImporterPluginDeclaration {
+    pub const fn new(register: unsafe extern "C" fn(&mut ImporterRegistrar)) -> Self {
+        ImporterPluginDeclaration {
+            abi_version: PLUGIN_ABI_VERSION,
+            rustc_version: RUSTC_VERSION.as_ptr() as *const c_char,
+            distill_version: DISTILL_VERSION.as_ptr() as *const c_char,
+            register,
+        }
+    }
+
+    /// Returns an error describing the mismatch if the plugin was not built with the same
+    /// compiler and version and features of `distill-importer` as the host.
+    ///
+    /// # Safety
+    ///
+    /// The declaration must come from a plugin with the same `abi_version` as the host, or the
+    /// version strings must be valid.
+    pub unsafe fn check_compatible(&self) -> Result<()> {
+        if self.abi_version != PLUGIN_ABI_VERSION {
+            return Err(Error::Custom(format!(
+                "plugin ABI version {} does not match the daemon's ABI version {}",
+                self.abi_version, PLUGIN_ABI_VERSION
+            )));
+        }
+        let check = |name: &str, plugin: *const c_char, host: &str| {
+            let plugin = CStr::from_ptr(plugin).to_string_lossy();
+            let host = host.trim_end_matches('\0');
+            if plugin == host {
+                Ok(())
+            } else {
+                Err(Error::Custom(format!(
+                    "plugin was built with {} `{}`, but the daemon was built with `{}`",
+                    name, plugin, host
+                )))
+            }
+        };
+        check("rustc", self.rustc_version, RUSTC_VERSION)?;
+        check("distill-importer", self.distill_version, DISTILL_VERSION)
+    }
+}
+
+/// Exports the entry point of an importer plugin built as a `cdylib`. The function is called with
+/// an [`ImporterRegistrar`] when the daemon loads the plugin.
+///
+/// ```ignore
+/// fn register(registrar: &mut ImporterRegistrar) {
+///     registrar.register_importer("fbx", Box::new(FbxImporter));
+/// }
+///
+/// distill_importer::declare_importer_plugin!(register);
+/// ```
+#[macro_export]
+macro_rules! declare_importer_plugin {
+    ($register:path) => {
+        #[doc(hidden)]
+        #[no_mangle]
+        pub static DISTILL_IMPORTER_PLUGIN: $crate::ImporterPluginDeclaration = {
+            unsafe extern "C" fn __distill_register_importers(
+                registrar: &mut $crate::ImporterRegistrar,
+            ) {
+                $register(registrar)
+            }
+            $crate::ImporterPluginDeclaration::new(__distill_register_importers)
+        };
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    unsafe extern "C" fn register(_: &mut ImporterRegistrar) {}
+
+    #[test]
+    fn plugin_version_mismatch() {
+        let declaration = ImporterPluginDeclaration::new(register);
+        unsafe { declaration.check_compatible() }.unwrap();
+
+        let declaration = ImporterPluginDeclaration {
+            abi_version: PLUGIN_ABI_VERSION + 1,
+            ..ImporterPluginDeclaration::new(register)
+        };
+        let err = unsafe { declaration.check_compatible() }.unwrap_err();
+        assert!(err.to_string().contains("ABI version"));
+
+        let declaration = ImporterPluginDeclaration {
+            rustc_version: b"rustc 1.0.0\0".as_ptr() as *const c_char,
+            ..ImporterPluginDeclaration::new(register)
+        };
+        let err = unsafe { declaration.check_compatible() }.unwrap_err();
+        assert!(err.to_string().contains("rustc 1.0.0"));
+    }
+}
@@ -0,0 +1,796 @@
+use std::{
+    io::{self, Read, Write},
+    net::{TcpListener, TcpStream},
+    num::NonZeroUsize,
+    path::{Path, PathBuf},
+    process::{Child, Command, Stdio},
+    sync::{Arc, Condvar, Mutex},
+    time::{Duration, Instant},
+};
+
+use distill_core::{type_uuid, AssetRef, AssetUuid, TypeUuidDynamic};
+use erased_serde::Deserializer;
+use futures::{channel::oneshot, future::BoxFuture, AsyncRead, AsyncReadExt, AsyncWrite};
+use serde::{
+    de::DeserializeOwned,
+    ser::{SerializeMap, SerializeTuple, Serializer},
+    Deserialize, Serialize,
+};
+
+use crate::{
+    BoxedExportInputs, BoxedImporter, BoxedImporterValue, Error, ExportAsset, ImportOp,
+    ImportedAsset, Importer, ImporterValue, Result, SerdeObj, SourceMetadata,
+};
+
+/// Environment variable that contains the address an importer process connects to, see
+/// [`run_importer_process`].
+pub const IMPORTER_HOST_ENV: &str = "DISTILL_IMPORTER_HOST";
+
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(60);
+
+#[derive(Serialize, Deserialize)]
+enum Request {
+    /// Options and state are RON text.
+    Import {
+        source: Vec<u8>,
+        /// Path of the source file, which source dependencies are relative to.
+        source_path: Option<PathBuf>,
+        options: String,
+        state: String,
+    },
+}
+
+#[derive(Serialize, Deserialize)]
+enum Response {
+    /// Sent by the importer process once it connects to the host.
+    Hello {
+        importer_type: [u8; 16],
+        version: u32,
+        default_options: String,
+        default_state: String,
+    },
+    Imported {
+        assets: Vec<ProcessAsset>,
+        state: String,
+        errors: Vec<String>,
+        warnings: Vec<String>,
+        source_dependencies: Vec<PathBuf>,
+    },
+    Error(String),
+}
+
+#[derive(Serialize, Deserialize)]
+struct ProcessAsset {
+    id: AssetUuid,
+    search_tags: Vec<(String, Option<String>)>,
+    build_deps: Vec<AssetRef>,
+    load_deps: Vec<AssetRef>,
+    build_pipeline: Option<AssetUuid>,
+    /// The asset data serialized with bincode by the importer process.
+    asset_data: ProcessAssetData,
+}
+
+/// Asset data that was serialized with bincode by an importer process.
+///
+/// Serializes to the same bincode as the original asset, so the daemon can store it without
+/// knowing the asset's type.
+#[derive(Serialize, Deserialize)]
+struct ProcessAssetData {
+    type_id: [u8; 16],
+    data: Vec<u8>,
+}
+
+/// Writes the data as a tuple of bytes, which bincode writes without a length prefix.
+struct RawBytes<'a>(&'a [u8]);
+
+impl Serialize for RawBytes<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        let mut tuple = serializer.serialize_tuple(self.0.len())?;
+        for byte in self.0 {
+            tuple.serialize_element(byte)?;
+        }
+        tuple.end()
+    }
+}
+
+/// The data of a [`ProcessAssetData`] as an [`ImportedAsset::asset_data`].
+struct SerializedAssetData(ProcessAssetData);
+
+impl Serialize for SerializedAssetData {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        RawBytes(&self.0.data).serialize(serializer)
+    }
+}
+
+impl TypeUuidDynamic for SerializedAssetData {
+    fn uuid(&self) -> type_uuid::Bytes {
+        self.0.type_id
+    }
+}
+
+/// Options or state of a [`ProcessImporter`], which does not know the types used by the importer
+/// process.
+///
+/// Enums with data can not be represented, since their variant names are lost when read from RON.
+#[derive(Clone, Deserialize)]
+#[serde(transparent)]
+pub struct ProcessValue(pub ron::Value);
+
+impl Serialize for ProcessValue {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        SerializeValue(&self.0).serialize(serializer)
+    }
+}
+
+/// Serializes a map as a map, where `ron::Value` wraps it in a tuple that reads back as a sequence.
+struct SerializeValue<'a>(&'a ron::Value);
+
+impl Serialize for SerializeValue<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        match self.0 {
+            ron::Value::Map(map) => {
+                let mut serialize_map = serializer.serialize_map(Some(map.len()))?;
+                for (key, value) in map.iter() {
+                    serialize_map.serialize_entry(&SerializeValue(key), &SerializeValue(value))?;
+                }
+                serialize_map.end()
+            }
+            ron::Value::Option(Some(value)) => serializer.serialize_some(&SerializeValue(value)),
+            ron::Value::Seq(values) => serializer.collect_seq(values.iter().map(SerializeValue)),
+            value => value.serialize(serializer),
+        }
+    }
+}
+
+impl Default for ProcessValue {
+    fn default() -> Self {
+        ProcessValue(ron::Value::Unit)
+    }
+}
+
+impl TypeUuidDynamic for ProcessValue {
+    fn uuid(&self) -> type_uuid::Bytes {
+        // 7d1b3f35-4bc3-4c41-b1b5-0c5a5c5c1e21
+        [
+            0x7d, 0x1b, 0x3f, 0x35, 0x4b, 0xc3, 0x4c, 0x41, 0xb1, 0xb5, 0x0c, 0x5a, 0x5c, 0x5c,
+            0x1e, 0x21,
+        ]
+    }
+}
+
+impl ProcessValue {
+    fn from_ron(text: &str) -> Result<Self> {
+        Ok(ProcessValue(ron::de::from_str(text)?))
+    }
+
+    fn to_ron(&self) -> Result<String> {
+        Ok(ron::ser::to_string(self)?)
+    }
+}
+
+fn write_message<T: Serialize>(stream: &mut impl Write, message: &T) -> Result<()> {
+    let data = bincode::serialize(message)?;
+    stream.write_all(&(data.len() as u32).to_le_bytes())?;
+    stream.write_all(&data)?;
+    stream.flush()?;
+    Ok(())
+}
+
+fn read_message<T: DeserializeOwned>(stream: &mut impl Read) -> Result<T> {
+    let mut len = [0; 4];
+    stream.read_exact(&mut len)?;
+    let mut data = vec![0; u32::from_le_bytes(len) as usize];
+    stream.read_exact(&mut data)?;
+    Ok(bincode::deserialize(&data)?)
+}
+
+struct ImporterProcess {
+    child: Child,
+    stream: TcpStream,
+}
+
+impl ImporterProcess {
+    fn spawn(program: &PathBuf, args: &[String], timeout: Duration) -> Result<Self> {
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        listener.set_nonblocking(true)?;
+        let mut child = Command::new(program)
+            .args(args)
+            .env(IMPORTER_HOST_ENV, listener.local_addr()?.to_string())
+            .stdin(Stdio::null())
+            .spawn()?;
+        let deadline = Instant::now() + timeout;
+        let stream = loop {
+            match listener.accept() {
+                Ok((stream, _)) => break stream,
+                Err(err) if err.kind() == io::ErrorKind::WouldBlock => {}
+                Err(err) => {
+                    let _ = child.kill();
+                    return Err(err.into());
+                }
+            }
+            if let Some(status) = child.try_wait()? {
+                return Err(Error::Custom(format!(
+                    "importer process {} exited before connecting: {}",
+                    program.display(),
+                    status
+                )));
+            }
+            if Instant::now() > deadline {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(Error::Custom(format!(
+                    "importer process {} did not connect within {:?}",
+                    program.display(),
+                    timeout
+                )));
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        };
+        stream.set_nonblocking(false)?;
+        stream.set_read_timeout(Some(timeout))?;
+        stream.set_write_timeout(Some(timeout))?;
+        Ok(ImporterProcess { child, stream })
+    }
+
+    /// Spawns the process and reads the handshake it sends when it connects.
+    fn start(program: &PathBuf, args: &[String], timeout: Duration) -> Result<(Self, ProcessInfo)> {
+        let mut process = Self::spawn(program, args, timeout)?;
+        match ProcessInfo::read(&mut process.stream) {
+            Ok(info) => Ok((process, info)),
+            Err(err) => {
+                process.kill();
+                Err(err)
+            }
+        }
+    }
+
+    fn kill(mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+struct ProcessInfo {
+    importer_type: [u8; 16],
+    version: u32,
+    default_options: ProcessValue,
+    default_state: ProcessValue,
+}
+
+impl ProcessInfo {
+    fn read(stream: &mut TcpStream) -> Result<Self> {
+        match read_message(stream)? {
+            Response::Hello {
+                importer_type,
+                version,
+                default_options,
+                default_state,
+            } => Ok(ProcessInfo {
+                importer_type,
+                version,
+                default_options: ProcessValue::from_ron(&default_options)?,
+                default_state: ProcessValue::from_ron(&default_state)?,
+            }),
+            _ => Err(Error::Custom(
+                "importer process did not start with a handshake".to_string(),
+            )),
+        }
+    }
+}
+
+struct Processes {
+    idle: Vec<ImporterProcess>,
+    /// Number of processes that are running an import or starting.
+    busy: usize,
+}
+
+/// The importer processes of a [`ProcessImporter`], one for each import that runs at the same time.
+struct ProcessPool {
+    program: PathBuf,
+    args: Vec<String>,
+    timeout: Duration,
+    importer_type: [u8; 16],
+    version: u32,
+    max_processes: usize,
+    processes: Mutex<Processes>,
+    process_released: Condvar,
+}
+
+impl ProcessPool {
+    /// Takes an idle process, or starts one if fewer than `max_processes` are busy. Waits for a
+    /// process to be released otherwise.
+    fn take(&self) -> Result<ImporterProcess> {
+        let mut processes = self.processes.lock().unwrap();
+        loop {
+            if let Some(process) = processes.idle.pop() {
+                processes.busy += 1;
+                return Ok(process);
+            }
+            if processes.busy < self.max_processes {
+                processes.busy += 1;
+                break;
+            }
+            processes = self.process_released.wait(processes).unwrap();
+        }
+        drop(processes);
+        let started = self.start();
+        if started.is_err() {
+            self.release(None);
+        }
+        started
+    }
+
+    fn release(&self, process: Option<ImporterProcess>) {
+        let mut processes = self.processes.lock().unwrap();
+        processes.busy -= 1;
+        processes.idle.extend(process);
+        self.process_released.notify_one();
+    }
+
+    /// Starts a process, which must still run the importer that the [`ProcessImporter`] was
+    /// created with, since the type and version of an importer can not change.
+    fn start(&self) -> Result<ImporterProcess> {
+        log::info!("starting importer process {}", self.program.display());
+        let (process, info) = ImporterProcess::start(&self.program, &self.args, self.timeout)?;
+        if info.importer_type != self.importer_type || info.version != self.version {
+            process.kill();
+            return Err(Error::Custom(format!(
+                "importer process {} changed its importer type or version after it was first started",
+                self.program.display()
+            )));
+        }
+        Ok(process)
+    }
+
+    fn request(&self, request: &Request) -> Result<Response> {
+        let mut process = self.take()?;
+        let response = write_message(&mut process.stream, request)
+            .and_then(|_| read_message(&mut process.stream));
+        match response {
+            Ok(response) => {
+                self.release(Some(process));
+                Ok(response)
+            }
+            Err(err) => {
+                // the process crashed or hangs, so a later request starts a new one
+                log::error!(
+                    "importer process {} failed: {}",
+                    self.program.display(),
+                    err
+                );
+                process.kill();
+                self.release(None);
+                Err(err)
+            }
+        }
+    }
+}
+
+impl Drop for ProcessPool {
+    fn drop(&mut self) {
+        for process in self.processes.get_mut().unwrap().idle.drain(..) {
+            process.kill();
+        }
+    }
+}
+
+/// Runs imports in a separate importer process, so an importer that crashes or hangs does not
+/// take down the daemon.
+///
+/// The importer process is an executable that calls [`run_importer_process`]. It is spawned when
+/// the `ProcessImporter` is created, and spawned again for the next import if it exits or does not
+/// respond within the timeout. Imports that run at the same time are sent to separate processes,
+/// up to one for each CPU. The importer type and version are those of the importer in the
+/// process.
+pub struct ProcessImporter {
+    info: ProcessInfo,
+    pool: Arc<ProcessPool>,
+}
+
+impl ProcessImporter {
+    /// Spawns the importer process `program` with `args`, failing if it does not connect.
+    pub fn new<P: Into<PathBuf>>(program: P, args: Vec<String>) -> Result<Self> {
+        Self::with_timeout(program, args, DEFAULT_TIMEOUT)
+    }
+
+    /// Like [`ProcessImporter::new`], with the time to wait for the process to respond before
+    /// it is restarted.
+    pub fn with_timeout<P: Into<PathBuf>>(
+        program: P,
+        args: Vec<String>,
+        timeout: Duration,
+    ) -> Result<Self> {
+        let program = program.into();
+        let (process, info) = ImporterProcess::start(&program, &args, timeout)?;
+        let pool = ProcessPool {
+            program,
+            args,
+            timeout,
+            importer_type: info.importer_type,
+            version: info.version,
+            max_processes: std::thread::available_parallelism().map_or(1, NonZeroUsize::get),
+            processes: Mutex::new(Processes {
+                idle: vec![process],
+                busy: 0,
+            }),
+            process_released: Condvar::new(),
+        };
+        Ok(ProcessImporter {
+            info,
+            pool: Arc::new(pool),
+        })
+    }
+
+    /// Sends the request from a separate thread, since it blocks until the process responds.
+    async fn request(&self, request: Request) -> Result<Response> {
+        let pool = self.pool.clone();
+        let (tx, rx) = oneshot::channel();
+        std::thread::Builder::new()
+            .name("importer process request".to_string())
+            .spawn(move || {
+                let _ = tx.send(pool.request(&request));
+            })?;
+        rx.await
+            .map_err(|_| Error::Custom("importer process request was dropped".to_string()))?
+    }
+}
+
+impl TypeUuidDynamic for ProcessImporter {
+    fn uuid(&self) -> type_uuid::Bytes {
+        self.info.importer_type
+    }
+}
+
+fn downcast_value(value: &dyn SerdeObj) -> &ProcessValue {
+    value
+        .any()
+        .downcast_ref::<ProcessValue>()
+        .expect("Failed to downcast ProcessImporter options or state")
+}
+
+impl BoxedImporter for ProcessImporter {
+    fn import_boxed<'a>(
+        &'a self,
+        op: &'a mut ImportOp,
+        source: &'a mut (dyn AsyncRead + Unpin + Send + Sync),
+        options: Box<dyn SerdeObj>,
+        state: Box<dyn SerdeObj>,
+    ) -> BoxFuture<'a, Result<BoxedImporterValue>> {
+        Box::pin(async move {
+            let mut bytes = Vec::new();
+            source.read_to_end(&mut bytes).await?;
+            let request = Request::Import {
+                source: bytes,
+                source_path: op.source_path().map(Path::to_path_buf),
+                options: downcast_value(options.as_ref()).to_ron()?,
+                state: downcast_value(state.as_ref()).to_ron()?,
+            };
+            match self.request(request).await? {
+                Response::Imported {
+                    assets,
+                    state,
+                    errors,
+                    warnings,
+                    source_dependencies,
+                } => {
+                    for error in errors {
+                        op.errors.push(Box::new(Error::Custom(error)));
+                    }
+                    for warning in warnings {
+                        op.warnings.push(Box::new(Error::Custom(warning)));
+                    }
+                    op.source_dependencies.extend(source_dependencies);
+                    let assets = assets
+                        .into_iter()
+                        .map(|asset| ImportedAsset {
+                            id: asset.id,
+                            search_tags: asset.search_tags,
+                            build_deps: asset.build_deps,
+                            load_deps: asset.load_deps,
+                            build_pipeline: asset.build_pipeline,
+                            asset_data: Box::new(SerializedAssetData(asset.asset_data)),
+                        })
+                        .collect();
+                    Ok(BoxedImporterValue {
+                        value: ImporterValue { assets },
+                        options,
+                        state: Box::new(ProcessValue::from_ron(&state)?),
+                    })
+                }
+                Response::Error(err) => Err(Error::Custom(err)),
+                Response::Hello { .. } => Err(Error::Custom(
+                    "unexpected handshake from importer process".to_string(),
+                )),
+            }
+        })
+    }
+
+    fn export_boxed<'a>(
+        &'a self,
+        _output: &'a mut (dyn AsyncWrite + Unpin + Send + Sync),
+        _options: Box<dyn SerdeObj>,
+        _state: Box<dyn SerdeObj>,
+        _assets: Vec<ExportAsset>,
+    ) -> BoxFuture<'a, Result<BoxedExportInputs>> {
+        Box::pin(async move { Err(Error::ExportUnsupported) })
+    }
+
+    fn default_options(&self) -> Box<dyn SerdeObj> {
+        Box::new(self.info.default_options.clone())
+    }
+
+    fn default_state(&self) -> Box<dyn SerdeObj> {
+        Box::new(self.info.default_state.clone())
+    }
+
+    fn version(&self) -> u32 {
+        self.info.version
+    }
+
+    fn deserialize_metadata(
+        &self,
+        deserializer: &mut dyn Deserializer,
+    ) -> Result<SourceMetadata<Box<dyn SerdeObj>, Box<dyn SerdeObj>>> {
+        let metadata =
+            erased_serde::deserialize::<SourceMetadata<ProcessValue, ProcessValue>>(deserializer)?;
+        Ok(SourceMetadata {
+            version: metadata.version,
+            importer_type: metadata.importer_type,
+            importer_options: Box::new(metadata.importer_options),
+            importer_state: Box::new(metadata.importer_state),
+        })
+    }
+
+    fn deserialize_options(
+        &self,
+        deserializer: &mut dyn Deserializer,
+    ) -> Result<Box<dyn SerdeObj>> {
+        Ok(Box::new(erased_serde::deserialize::<ProcessValue>(
+            deserializer,
+        )?))
+    }
+
+    fn deserialize_state(&self, deserializer: &mut dyn Deserializer) -> Result<Box<dyn SerdeObj>> {
+        Ok(Box::new(erased_serde::deserialize::<ProcessValue>(
+            deserializer,
+        )?))
+    }
+}
+
+fn import_in_process<T>(
+    importer: &T,
+    source: &[u8],
+    source_path: Option<PathBuf>,
+    options: &str,
+    state: &str,
+) -> Result<Response>
+where
+    T: Importer,
+    T::Options: DeserializeOwned,
+    T::State: DeserializeOwned,
+{
+    let options: T::Options = ron::de::from_str::<ron::Value>(options)?.into_rust()?;
+    let mut state: T::State = ron::de::from_str::<ron::Value>(state)?.into_rust()?;
+    let mut op = match source_path {
+        Some(path) => ImportOp::for_source(path),
+        None => ImportOp::default(),
+    };
+    let value = importer.import(&mut op, &mut &source[..], &options, &mut state)?;
+    let assets = value
+        .assets
+        .into_iter()
+        .map(|asset| {
+            Ok(ProcessAsset {
+                id: asset.id,
+                search_tags: asset.search_tags,
+                build_deps: asset.build_deps,
+                load_deps: asset.load_deps,
+                build_pipeline: asset.build_pipeline,
+                asset_data: ProcessAssetData {
+                    type_id: asset.asset_data.uuid(),
+                    data: bincode::serialize(asset.asset_data.as_ref())?,
+                },
+            })
+        })
+        .collect::<Result<_>>()?;
+    Ok(Response::Imported {
+        assets,
+        state: ron::ser::to_string(&state)?,
+        errors: op.errors.iter().map(|err| err.to_string()).collect(),
+        warnings: op.warnings.iter().map(|err| err.to_string()).collect(),
+        source_dependencies: op.source_dependencies,
+    })
+}
+
+/// Serves imports with `importer` for the [`ProcessImporter`] that spawned this process, until
+/// the host disconnects.
+///
+/// Call this from the `main` of an importer executable. Communication with the host does not use
+/// stdin or stdout, so the importer is free to use them.
+pub fn run_importer_process<T>(importer: T) -> Result<()>
+where
+    T: Importer + TypeUuidDynamic,
+    T::Options: Serialize + DeserializeOwned + Default,
+    T::State: DeserializeOwned + Default,
+{
+    let address = std::env::var(IMPORTER_HOST_ENV)
+        .map_err(|_| Error::Custom(format!("{} is not set", IMPORTER_HOST_ENV)))?;
+    let mut stream = TcpStream::connect(address)?;
+    write_message(
+        &mut stream,
+        &Response::Hello {
+            importer_type: importer.uuid(),
+            version: importer.version(),
+            default_options: ron::ser::to_string(&T::Options::default())?,
+            default_state: ron::ser::to_string(&T::State::default())?,
+        },
+    )?;
+    loop {
+        let request = match read_message(&mut stream) {
+            Ok(request) => request,
+            Err(Error::IoError(err)) if err.kind() == io::ErrorKind::UnexpectedEof => {
+                return Ok(());
+            }
+            Err(err) => return Err(err),
+        };
+        let response = match request {
+            Request::Import {
+                source,
+                source_path,
+                options,
+                state,
+            } => import_in_process(&importer, &source, source_path, &options, &state)
+                .unwrap_or_else(|err| Response::Error(err.to_string())),
+        };
+        write_message(&mut stream, &response)?;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use distill_core::type_uuid::TypeUuid;
+
+    use super::*;
+
+    #[derive(TypeUuid)]
+    #[uuid = "0a9b6c4e-2f5c-4f7e-9a34-6d3c7b1f0e52"]
+    struct UppercaseImporter;
+
+    #[derive(Default, Serialize, Deserialize)]
+    struct UppercaseState {
+        id: Option<AssetUuid>,
+    }
+
+    impl Importer for UppercaseImporter {
+        type Options = ();
+        type State = UppercaseState;
+
+        fn version_static() -> u32 {
+            3
+        }
+
+        fn version(&self) -> u32 {
+            Self::version_static()
+        }
+
+        fn import(
+            &self,
+            op: &mut ImportOp,
+            source: &mut dyn Read,
+            _: &Self::Options,
+            state: &mut Self::State,
+        ) -> Result<ImporterValue> {
+            let mut text = String::new();
+            source.read_to_string(&mut text)?;
+            if text == "crash" {
+                std::process::abort();
+            }
+            if text == "slow" {
+                std::thread::sleep(Duration::from_millis(500));
+            }
+            let id = *state.id.get_or_insert_with(|| op.new_asset_uuid());
+            Ok(ImporterValue {
+                assets: vec![ImportedAsset {
+                    id,
+                    search_tags: Vec::new(),
+                    build_deps: Vec::new(),
+                    load_deps: Vec::new(),
+                    build_pipeline: None,
+                    asset_data: Box::new(text.to_uppercase()),
+                }],
+            })
+        }
+    }
+
+    /// Runs the importer when this test binary is spawned as an importer process.
+    #[test]
+    fn importer_process() {
+        if std::env::var(IMPORTER_HOST_ENV).is_ok() {
+            run_importer_process(UppercaseImporter).unwrap();
+        }
+    }
+
+    fn spawn_importer() -> ProcessImporter {
+        let args = vec![
+            "process_importer::tests::importer_process".to_string(),
+            "--exact".to_string(),
+        ];
+        ProcessImporter::with_timeout(
+            std::env::current_exe().unwrap(),
+            args,
+            Duration::from_secs(30),
+        )
+        .unwrap()
+    }
+
+    fn import(
+        importer: &ProcessImporter,
+        source: &str,
+        state: Box<dyn SerdeObj>,
+    ) -> Result<BoxedImporterValue> {
+        let mut op = ImportOp::default();
+        futures::executor::block_on(importer.import_boxed(
+            &mut op,
+            &mut source.as_bytes(),
+            importer.default_options(),
+            state,
+        ))
+    }
+
+    #[test]
+    fn process_importer_import() {
+        let importer = spawn_importer();
+        assert_eq!(BoxedImporter::version(&importer), 3);
+        assert_eq!(importer.uuid(), UppercaseImporter::UUID);
+
+        let result = import(&importer, "hello", importer.default_state()).unwrap();
+        let asset = &result.value.assets[0];
+        assert_eq!(asset.asset_data.uuid(), String::UUID);
+        assert_eq!(
+            bincode::serialize(asset.asset_data.as_ref()).unwrap(),
+            bincode::serialize("HELLO").unwrap()
+        );
+
+        // the returned state is passed to the process for the next import
+        let id = asset.id;
+        let result = import(&importer, "again", result.state).unwrap();
+        assert_eq!(result.value.assets[0].id, id);
+    }
+
+    #[test]
+    fn process_importer_restarts_after_crash() {
+        let importer = spawn_importer();
+        assert!(import(&importer, "crash", importer.default_state()).is_err());
+        let result = import(&importer, "hello", importer.default_state()).unwrap();
+        assert_eq!(result.value.assets.len(), 1);
+    }
+
+    #[test]
+    fn process_importer_checks_restarted_process() {
+        let mut importer = spawn_importer();
+        Arc::get_mut(&mut importer.pool).unwrap().version = 4;
+        assert!(import(&importer, "crash", importer.default_state()).is_err());
+        // the restarted process still has version 3
+        let err = import(&importer, "hello", importer.default_state())
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("version"), "{}", err);
+    }
+
+    #[test]
+    fn process_importer_runs_imports_in_parallel() {
+        let mut importer = spawn_importer();
+        Arc::get_mut(&mut importer.pool).unwrap().max_processes = 2;
+        let importer = &importer;
+        std::thread::scope(|scope| {
+            let imports: Vec<_> = (0..2)
+                .map(|_| scope.spawn(|| import(importer, "slow", importer.default_state())))
+                .collect();
+            for import in imports {
+                assert!(import.join().unwrap().is_ok());
+            }
+        });
+        // the second import did not wait for the process of the first one
+        assert_eq!(importer.pool.processes.lock().unwrap().idle.len(), 2);
+    }
+}
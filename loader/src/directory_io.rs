@@ -0,0 +1,344 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    path::{Path, PathBuf},
+    sync::{Arc, RwLock},
+    time::{Duration, SystemTime},
+};
+
+use capnp::serialize::SliceSegments;
+use distill_core::{utils::canonicalize_path, AssetMetadata, AssetRef, AssetUuid};
+use distill_schema::{data::artifact, pack::pack_file};
+use instant::Instant;
+
+use crate::{
+    io::{self, DataRequest, LoaderIO, MetadataRequest, MetadataRequestResult, ResolveRequest},
+    loader::LoaderState,
+};
+
+/// File name of the index in an exported artifact directory.
+pub const INDEX_FILE_NAME: &str = "index.pack";
+/// File extension of artifact files in an exported artifact directory.
+pub const ARTIFACT_FILE_EXTENSION: &str = "artifact";
+
+/// Returns the path of the artifact file for `asset_id` within an exported artifact directory.
+pub fn artifact_file_path(root: &Path, asset_id: &AssetUuid) -> PathBuf {
+    root.join(format!(
+        "{}.{}",
+        uuid::Uuid::from_bytes(asset_id.0).to_hyphenated(),
+        ARTIFACT_FILE_EXTENSION
+    ))
+}
+
+fn read_message<'a>(
+    mut slice: &'a [u8],
+) -> capnp::Result<capnp::message::Reader<SliceSegments<'a>>> {
+    let mut options = capnp::message::ReaderOptions::new();
+    options.traversal_limit_in_words(Some(1 << 31));
+    capnp::serialize::read_message_from_flat_slice(&mut slice, options)
+}
+
+#[derive(Default)]
+struct DirectoryIndex {
+    assets: HashMap<AssetUuid, (PathBuf, AssetMetadata)>,
+    assets_by_path: HashMap<PathBuf, Vec<AssetUuid>>,
+}
+
+impl DirectoryIndex {
+    fn read(root: &Path) -> capnp::Result<Self> {
+        let bytes = fs::read(root.join(INDEX_FILE_NAME))?;
+        let message = read_message(&bytes)?;
+        let reader = message.get_root::<pack_file::Reader<'_>>()?;
+        let mut index = DirectoryIndex::default();
+        for entry in reader.get_entries()? {
+            let asset_metadata = distill_schema::parse_db_metadata(&entry.get_asset_metadata()?);
+            let path = canonicalize_path(&PathBuf::from(std::str::from_utf8(entry.get_path()?)?));
+            index
+                .assets_by_path
+                .entry(path.clone())
+                .or_default()
+                .push(asset_metadata.id);
+            index
+                .assets
+                .insert(asset_metadata.id, (path, asset_metadata));
+        }
+        log::debug!(
+            "Loaded {} asset entries from {:?}",
+            index.assets.len(),
+            root
+        );
+        Ok(index)
+    }
+}
+
+struct DirectoryIOInner {
+    root: PathBuf,
+    index: RwLock<DirectoryIndex>,
+    runtime: tokio::runtime::Runtime,
+}
+
+struct DirectoryWatchState {
+    interval: Duration,
+    last_scan: Instant,
+    modified_times: HashMap<PathBuf, SystemTime>,
+}
+
+/// An implementation of [`LoaderIO`] which reads artifacts from a directory of loose files.
+///
+/// The directory contains an index file named [`INDEX_FILE_NAME`], which is a packfile without
+/// artifact data, and one `<asset uuid>.artifact` file per asset containing a serialized artifact.
+/// This layout can be exported from a running daemon using the `export_dir` CLI command.
+pub struct DirectoryIO {
+    inner: Arc<DirectoryIOInner>,
+    watch: Option<DirectoryWatchState>,
+}
+
+impl DirectoryIO {
+    /// Creates a `DirectoryIO` which reads the index and artifacts from `root`.
+    pub fn new(root: impl Into<PathBuf>) -> capnp::Result<Self> {
+        let root = root.into();
+        let index = DirectoryIndex::read(&root)?;
+        let runtime = tokio::runtime::Builder::new_multi_thread().build()?;
+        Ok(DirectoryIO {
+            inner: Arc::new(DirectoryIOInner {
+                root,
+                index: RwLock::new(index),
+                runtime,
+            }),
+            watch: None,
+        })
+    }
+
+    /// Polls the directory for changes every `interval`, invalidating any changed assets and paths.
+    pub fn with_watch_interval(mut self, interval: Duration) -> Self {
+        self.watch = Some(DirectoryWatchState {
+            interval,
+            last_scan: Instant::now(),
+            modified_times: scan_modified_times(&self.inner.root),
+        });
+        self
+    }
+
+    fn check_for_changes(&mut self, loader: &LoaderState) {
+        let watch = match self.watch.as_mut() {
+            Some(watch) if watch.last_scan.elapsed() >= watch.interval => watch,
+            _ => return,
+        };
+        watch.last_scan = Instant::now();
+        let modified_times = scan_modified_times(&self.inner.root);
+        let changed_files: Vec<_> = modified_times
+            .iter()
+            .filter(|(path, time)| watch.modified_times.get(*path) != Some(*time))
+            .map(|(path, _)| path.clone())
+            .chain(
+                watch
+                    .modified_times
+                    .keys()
+                    .filter(|path| !modified_times.contains_key(*path))
+                    .cloned(),
+            )
+            .collect();
+        watch.modified_times = modified_times;
+        if changed_files.is_empty() {
+            return;
+        }
+
+        let mut changed_assets = HashSet::new();
+        let mut changed_paths = HashSet::new();
+        let index_path = self.inner.root.join(INDEX_FILE_NAME);
+        if changed_files.contains(&index_path) {
+            match DirectoryIndex::read(&self.inner.root) {
+                Ok(new_index) => {
+                    let mut index = self.inner.index.write().unwrap();
+                    for (id, (path, metadata)) in &new_index.assets {
+                        let existing = index.assets.get(id);
+                        if existing.map(|(_, m)| m.artifact.as_ref().map(|a| a.id.0))
+                            != Some(metadata.artifact.as_ref().map(|a| a.id.0))
+                        {
+                            changed_assets.insert(*id);
+                        }
+                        if existing.map(|(p, _)| p) != Some(path) {
+                            changed_paths.insert(path.clone());
+                            if let Some((old_path, _)) = existing {
+                                changed_paths.insert(old_path.clone());
+                            }
+                        }
+                    }
+                    for (id, (path, _)) in &index.assets {
+                        if !new_index.assets.contains_key(id) {
+                            changed_assets.insert(*id);
+                            changed_paths.insert(path.clone());
+                        }
+                    }
+                    *index = new_index;
+                }
+                Err(err) => {
+                    log::error!("Failed to reload index in {:?}: {}", self.inner.root, err);
+                }
+            }
+        }
+        for path in &changed_files {
+            if path.extension().and_then(|ext| ext.to_str()) != Some(ARTIFACT_FILE_EXTENSION) {
+                continue;
+            }
+            if let Some(id) = path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .and_then(|stem| uuid::Uuid::parse_str(stem).ok())
+            {
+                changed_assets.insert(AssetUuid(*id.as_bytes()));
+            }
+        }
+
+        log::trace!(
+            "DirectoryIO detected changes to {} assets and {} paths",
+            changed_assets.len(),
+            changed_paths.len()
+        );
+        loader.invalidate_assets(&changed_assets.into_iter().collect::<Vec<_>>());
+        loader.invalidate_paths(&changed_paths.into_iter().collect::<Vec<_>>());
+    }
+}
+
+fn scan_modified_times(root: &Path) -> HashMap<PathBuf, SystemTime> {
+    let mut modified_times = HashMap::new();
+    match fs::read_dir(root) {
+        Ok(entries) => {
+            for entry in entries.flatten() {
+                if let Ok(modified) = entry.metadata().and_then(|m| m.modified()) {
+                    modified_times.insert(entry.path(), modified);
+                }
+            }
+        }
+        Err(err) => log::error!("Failed to scan {:?} for changes: {}", root, err),
+    }
+    modified_times
+}
+
+impl DirectoryIOInner {
+    fn get_asset_metadata_with_dependencies_impl(
+        &self,
+        request: &MetadataRequest,
+    ) -> capnp::Result<Vec<MetadataRequestResult>> {
+        let index = self.index.read().unwrap();
+        let mut to_visit = request.requested_assets().cloned().collect::<Vec<_>>();
+        let mut visited: HashSet<AssetUuid> = to_visit.iter().cloned().collect();
+        let mut metadata = Vec::new();
+        while let Some(uuid) = to_visit.pop() {
+            if let Some((_, asset_metadata)) = index.assets.get(&uuid) {
+                let artifact_metadata = match asset_metadata.artifact.as_ref() {
+                    Some(artifact_metadata) => artifact_metadata.clone(),
+                    None => continue,
+                };
+                for dep in &artifact_metadata.load_deps {
+                    if let AssetRef::Uuid(dep_uuid) = dep {
+                        if visited.insert(*dep_uuid) {
+                            to_visit.push(*dep_uuid);
+                        }
+                    }
+                }
+                metadata.push(MetadataRequestResult {
+                    artifact_metadata,
+                    asset_metadata: if request.include_asset_metadata() {
+                        Some(asset_metadata.clone())
+                    } else {
+                        None
+                    },
+                });
+            }
+        }
+        Ok(metadata)
+    }
+
+    fn get_artifact_impl(&self, request: &DataRequest) -> capnp::Result<Vec<u8>> {
+        let path = artifact_file_path(&self.root, &request.asset_id);
+        let bytes = fs::read(&path)?;
+        let message = read_message(&bytes)?;
+        let artifact = message.get_root::<artifact::Reader<'_>>()?;
+        let artifact_metadata = distill_schema::parse_artifact_metadata(&artifact.get_metadata()?);
+        if artifact_metadata.asset_id != request.asset_id {
+            return Err(capnp::Error::failed(format!(
+                "Artifact file {:?} contains asset {:?}, expected {:?}",
+                path, artifact_metadata.asset_id, request.asset_id
+            )));
+        }
+        if artifact_metadata.id.0 != request.artifact_id.0 {
+            return Err(capnp::Error::failed(format!(
+                "Artifact file {:?} contains artifact {:?}, expected {:?}",
+                path, artifact_metadata.id, request.artifact_id
+            )));
+        }
+        Ok(Vec::from(artifact.get_data()?))
+    }
+
+    fn get_asset_candidates_impl(
+        &self,
+        request: &ResolveRequest,
+    ) -> capnp::Result<Vec<(PathBuf, Vec<AssetMetadata>)>> {
+        let index = self.index.read().unwrap();
+        let path = match request.identifier().as_tag() {
+            None => canonicalize_path(&PathBuf::from(request.identifier().path())),
+            Some(_) => {
+                return Ok(io::tag_candidates(
+                    request.identifier(),
+                    index.assets.values().cloned(),
+                ))
+            }
+        };
+        if let Some(ids) = index.assets_by_path.get(&path) {
+            let metadata = ids
+                .iter()
+                .filter_map(|id| index.assets.get(id).map(|(_, m)| m.clone()))
+                .collect();
+            Ok(vec![(path, metadata)])
+        } else {
+            // The identifier is re-resolved when a file is created at the path
+            Ok(Vec::new())
+        }
+    }
+}
+
+impl LoaderIO for DirectoryIO {
+    fn get_asset_metadata_with_dependencies(&mut self, request: MetadataRequest) {
+        let _guard = self.inner.runtime.enter();
+        let inner = self.inner.clone();
+        tokio::spawn(async move {
+            match inner.get_asset_metadata_with_dependencies_impl(&request) {
+                Ok(data) => request.complete(data),
+                Err(err) => request.error(err),
+            }
+        });
+    }
+
+    fn get_asset_candidates(&mut self, requests: Vec<ResolveRequest>) {
+        let _guard = self.inner.runtime.enter();
+        for request in requests {
+            let inner = self.inner.clone();
+            tokio::spawn(async move {
+                match inner.get_asset_candidates_impl(&request) {
+                    Ok(data) => request.complete(data),
+                    Err(err) => request.error(err),
+                }
+            });
+        }
+    }
+
+    fn get_artifacts(&mut self, requests: Vec<DataRequest>) {
+        let _guard = self.inner.runtime.enter();
+        for request in requests {
+            let inner = self.inner.clone();
+            tokio::task::spawn_blocking(move || match inner.get_artifact_impl(&request) {
+                Ok(data) => request.complete(data),
+                Err(err) => request.error(err),
+            });
+        }
+    }
+
+    fn tick(&mut self, loader: &mut LoaderState) {
+        self.check_for_changes(loader);
+    }
+
+    fn with_runtime(&self, f: &mut dyn FnMut(&tokio::runtime::Runtime)) {
+        f(&self.inner.runtime);
+    }
+}
@@ -0,0 +1,497 @@
+use std::{
+    collections::{HashMap, HashSet},
+    error::Error,
+    path::PathBuf,
+};
+
+use crossbeam_channel::{unbounded, Receiver, Sender};
+use distill_core::{AssetMetadata, AssetUuid};
+
+use crate::{
+    io::{DataRequest, LoaderIO, MetadataRequest, MetadataRequestResult, ResolveRequest},
+    loader::LoaderState,
+    storage::IndirectIdentifier,
+    LoadHandle, Result,
+};
+
+type DataResponse = (Result<Vec<u8>>, LoadHandle, u32);
+type MetadataResponse = (
+    Result<Vec<MetadataRequestResult>>,
+    HashMap<AssetUuid, (LoadHandle, u32)>,
+);
+type ResolveResponse = (
+    Result<Vec<(PathBuf, Vec<AssetMetadata>)>>,
+    IndirectIdentifier,
+    LoadHandle,
+);
+
+/// A data request that has been forwarded to `source`, along with the original request from the loader.
+struct PendingData {
+    request: DataRequest,
+    source: usize,
+}
+
+/// A resolve request that has been forwarded to `source`, along with the original request from the loader.
+struct PendingResolve {
+    request: ResolveRequest,
+    source: usize,
+}
+
+/// A metadata request from the loader that may have been split over multiple sources.
+struct PendingMetadata {
+    request: MetadataRequest,
+    results: Vec<MetadataRequestResult>,
+    last_error: Option<String>,
+    /// Sub-requests that have not completed yet, the source they were sent to and the assets
+    /// they requested.
+    in_flight: Vec<InFlightMetadata>,
+}
+
+type InFlightMetadata = (
+    usize,
+    HashMap<AssetUuid, (LoadHandle, u32)>,
+    Receiver<MetadataResponse>,
+);
+
+/// An implementation of [`LoaderIO`] which chains multiple sources together.
+///
+/// Each request is sent to the first source, and is forwarded to the next source in order
+/// if the source fails to provide the requested data. For example, a [`PackfileReader`](crate::PackfileReader)
+/// can serve shipped content while an [`RpcIO`](crate::RpcIO) serves anything that is missing from the packfile.
+///
+/// When a source reports a change to an asset or path during [`LoaderIO::tick`], that source takes
+/// precedence over the sources before it for any subsequent requests of that asset or path.
+/// This makes sure hot reloaded data is not shadowed by stale data in an earlier source.
+pub struct FallbackIO {
+    sources: Vec<Box<dyn LoaderIO>>,
+    preferred_asset_sources: HashMap<AssetUuid, usize>,
+    preferred_path_sources: HashMap<PathBuf, usize>,
+    data_tx: Sender<DataResponse>,
+    data_rx: Receiver<DataResponse>,
+    pending_data: HashMap<(LoadHandle, u32), PendingData>,
+    resolve_tx: Sender<ResolveResponse>,
+    resolve_rx: Receiver<ResolveResponse>,
+    pending_resolves: HashMap<LoadHandle, PendingResolve>,
+    pending_metadata: Vec<PendingMetadata>,
+}
+
+impl FallbackIO {
+    /// Creates a `FallbackIO` which tries `sources` in order.
+    ///
+    /// The first source provides the runtime for [`LoaderIO::with_runtime`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `sources` is empty.
+    pub fn new(sources: Vec<Box<dyn LoaderIO>>) -> FallbackIO {
+        assert!(
+            !sources.is_empty(),
+            "FallbackIO requires at least one source"
+        );
+        let (data_tx, data_rx) = unbounded();
+        let (resolve_tx, resolve_rx) = unbounded();
+        FallbackIO {
+            sources,
+            preferred_asset_sources: HashMap::new(),
+            preferred_path_sources: HashMap::new(),
+            data_tx,
+            data_rx,
+            pending_data: HashMap::new(),
+            resolve_tx,
+            resolve_rx,
+            pending_resolves: HashMap::new(),
+            pending_metadata: Vec::new(),
+        }
+    }
+
+    fn first_source_for_asset(&self, asset_id: &AssetUuid) -> usize {
+        self.preferred_asset_sources
+            .get(asset_id)
+            .copied()
+            .unwrap_or(0)
+    }
+
+    fn first_source_for_path(&self, path: &str) -> usize {
+        self.preferred_path_sources
+            .get(&normalize_path(path))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    fn send_data_request(&mut self, request: DataRequest, source: usize) {
+        let request_data = match request.request_data {
+            Some(request_data) => request_data,
+            None => return,
+        };
+        let forwarded = DataRequest {
+            tx: self.data_tx.clone(),
+            asset_id: request.asset_id,
+            artifact_id: request.artifact_id,
+            request_data: Some(request_data),
+        };
+        self.pending_data
+            .insert(request_data, PendingData { request, source });
+        self.sources[source].get_artifacts(vec![forwarded]);
+    }
+
+    fn send_resolve_request(&mut self, request: ResolveRequest, source: usize) {
+        let id = match request.id.as_ref() {
+            Some(id) => id.clone(),
+            None => return,
+        };
+        let forwarded = ResolveRequest {
+            tx: self.resolve_tx.clone(),
+            id: Some(id.clone()),
+        };
+        self.pending_resolves
+            .insert(id.1, PendingResolve { request, source });
+        self.sources[source].get_asset_candidates(vec![forwarded]);
+    }
+
+    fn send_metadata_request(
+        &mut self,
+        requests: HashMap<AssetUuid, (LoadHandle, u32)>,
+        include_asset_metadata: bool,
+        source: usize,
+    ) -> InFlightMetadata {
+        let (tx, rx) = unbounded();
+        self.sources[source].get_asset_metadata_with_dependencies(MetadataRequest {
+            tx,
+            requests: Some(requests.clone()),
+            include_asset_metadata,
+        });
+        (source, requests, rx)
+    }
+
+    fn process_data_responses(&mut self) {
+        while let Ok((result, handle, version)) = self.data_rx.try_recv() {
+            let pending = match self.pending_data.remove(&(handle, version)) {
+                Some(pending) => pending,
+                None => continue,
+            };
+            match result {
+                Ok(data) => pending.request.complete(data),
+                Err(err) => {
+                    let next_source = pending.source + 1;
+                    if next_source < self.sources.len() {
+                        log::trace!(
+                            "FallbackIO data request for {:?} failed in source {}: {}",
+                            pending.request.asset_id,
+                            pending.source,
+                            err
+                        );
+                        self.send_data_request(pending.request, next_source);
+                    } else {
+                        pending.request.error(FallbackError(err.to_string()));
+                    }
+                }
+            }
+        }
+    }
+
+    fn process_resolve_responses(&mut self) {
+        while let Ok((result, _id, handle)) = self.resolve_rx.try_recv() {
+            let pending = match self.pending_resolves.remove(&handle) {
+                Some(pending) => pending,
+                None => continue,
+            };
+            let next_source = pending.source + 1;
+            let has_next_source = next_source < self.sources.len();
+            match result {
+                Ok(candidates)
+                    if has_next_source
+                        && candidates.iter().all(|(_, assets)| assets.is_empty()) =>
+                {
+                    self.send_resolve_request(pending.request, next_source);
+                }
+                Ok(candidates) => pending.request.complete(candidates),
+                Err(_) if has_next_source => {
+                    self.send_resolve_request(pending.request, next_source);
+                }
+                Err(err) => pending.request.error(FallbackError(err.to_string())),
+            }
+        }
+    }
+
+    fn process_metadata_responses(&mut self) {
+        let mut pending_metadata = std::mem::take(&mut self.pending_metadata);
+        for pending in &mut pending_metadata {
+            let in_flight = std::mem::take(&mut pending.in_flight);
+            for (source, requests, rx) in in_flight {
+                let result = match rx.try_recv() {
+                    Ok((result, _)) => result.map_err(|err| err.to_string()),
+                    Err(crossbeam_channel::TryRecvError::Empty) => {
+                        pending.in_flight.push((source, requests, rx));
+                        continue;
+                    }
+                    // the source dropped the request without responding
+                    Err(crossbeam_channel::TryRecvError::Disconnected) => {
+                        Err(format!("source {} dropped the metadata request", source))
+                    }
+                };
+                let mut missing = requests;
+                match result {
+                    Ok(results) => {
+                        let mut received: HashSet<AssetUuid> = pending
+                            .results
+                            .iter()
+                            .map(|r| r.artifact_metadata.asset_id)
+                            .collect();
+                        for result in results {
+                            let asset_id = result.artifact_metadata.asset_id;
+                            missing.remove(&asset_id);
+                            if received.insert(asset_id) {
+                                pending.results.push(result);
+                            }
+                        }
+                    }
+                    Err(err) => {
+                        log::trace!(
+                            "FallbackIO metadata request failed in source {}: {}",
+                            source,
+                            err
+                        );
+                        pending.last_error = Some(err);
+                    }
+                }
+                let next_source = source + 1;
+                if !missing.is_empty() && next_source < self.sources.len() {
+                    let in_flight = self.send_metadata_request(
+                        missing,
+                        pending.request.include_asset_metadata,
+                        next_source,
+                    );
+                    pending.in_flight.push(in_flight);
+                }
+            }
+        }
+        for pending in pending_metadata {
+            if !pending.in_flight.is_empty() {
+                self.pending_metadata.push(pending);
+                continue;
+            }
+            match pending.last_error {
+                Some(err) if pending.results.is_empty() => {
+                    pending.request.error(FallbackError(err))
+                }
+                _ => pending.request.complete(pending.results),
+            }
+        }
+    }
+}
+
+impl LoaderIO for FallbackIO {
+    fn get_asset_metadata_with_dependencies(&mut self, mut request: MetadataRequest) {
+        let requests = match request.requests.take() {
+            Some(requests) => requests,
+            None => return,
+        };
+        // group the requested assets by the first source they should be requested from
+        let mut requests_by_source: HashMap<usize, HashMap<AssetUuid, (LoadHandle, u32)>> =
+            HashMap::new();
+        for (asset_id, request_data) in &requests {
+            requests_by_source
+                .entry(self.first_source_for_asset(asset_id))
+                .or_default()
+                .insert(*asset_id, *request_data);
+        }
+        let include_asset_metadata = request.include_asset_metadata;
+        request.requests = Some(requests);
+        let mut in_flight = Vec::new();
+        for (source, requests) in requests_by_source {
+            in_flight.push(self.send_metadata_request(requests, include_asset_metadata, source));
+        }
+        self.pending_metadata.push(PendingMetadata {
+            request,
+            results: Vec::new(),
+            last_error: None,
+            in_flight,
+        });
+    }
+
+    fn get_asset_candidates(&mut self, requests: Vec<ResolveRequest>) {
+        for request in requests {
+            let id = request.identifier();
+            let source = if id.as_tag().is_some() {
+                0
+            } else {
+                self.first_source_for_path(id.path())
+            };
+            self.send_resolve_request(request, source);
+        }
+    }
+
+    fn get_artifacts(&mut self, requests: Vec<DataRequest>) {
+        for request in requests {
+            let source = self.first_source_for_asset(&request.asset_id);
+            self.send_data_request(request, source);
+        }
+    }
+
+    fn tick(&mut self, loader: &mut LoaderState) {
+        // Invalidations that were queued before this tick are not attributed to any source.
+        let mut invalidated_assets = loader.take_invalidated_assets();
+        let mut invalidated_paths = loader.take_invalidated_paths();
+        for (idx, source) in self.sources.iter_mut().enumerate() {
+            source.tick(loader);
+            for asset in loader.take_invalidated_assets() {
+                self.preferred_asset_sources.insert(asset, idx);
+                invalidated_assets.push(asset);
+            }
+            for path in loader.take_invalidated_paths() {
+                self.preferred_path_sources
+                    .insert(normalize_path(&path.to_string_lossy()), idx);
+                invalidated_paths.push(path);
+            }
+        }
+        loader.invalidate_assets(&invalidated_assets);
+        loader.invalidate_paths(&invalidated_paths);
+
+        self.process_metadata_responses();
+        self.process_resolve_responses();
+        self.process_data_responses();
+    }
+
+    fn with_runtime(&self, f: &mut dyn FnMut(&tokio::runtime::Runtime)) {
+        self.sources[0].with_runtime(f)
+    }
+}
+
+fn normalize_path(path: &str) -> PathBuf {
+    distill_core::utils::canonicalize_path(&PathBuf::from(path))
+}
+
+/// Returned to the loader when every source failed to serve a request.
+#[derive(Debug)]
+struct FallbackError(String);
+impl std::fmt::Display for FallbackError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "all sources failed, last error: {}", self.0)
+    }
+}
+impl Error for FallbackError {}
+
+#[cfg(test)]
+mod tests {
+    use distill_core::ArtifactMetadata;
+
+    use super::*;
+
+    /// How a [`MockIO`] responds to metadata requests.
+    enum MockResponse {
+        /// Returns the metadata of the requested assets that are in the list.
+        Serve(Vec<AssetUuid>),
+        Fail,
+        /// Drops the request without responding.
+        Drop,
+    }
+
+    struct MockIO(MockResponse);
+
+    impl LoaderIO for MockIO {
+        fn get_asset_metadata_with_dependencies(&mut self, mut request: MetadataRequest) {
+            match &self.0 {
+                MockResponse::Serve(assets) => {
+                    let results = request
+                        .requested_assets()
+                        .filter(|asset_id| assets.contains(asset_id))
+                        .map(|asset_id| MetadataRequestResult {
+                            artifact_metadata: ArtifactMetadata {
+                                asset_id: *asset_id,
+                                ..Default::default()
+                            },
+                            asset_metadata: None,
+                        })
+                        .collect();
+                    request.complete(results);
+                }
+                MockResponse::Fail => request.error(FallbackError("mock failure".to_string())),
+                MockResponse::Drop => {
+                    request.requests.take();
+                }
+            }
+        }
+
+        fn get_asset_candidates(&mut self, _requests: Vec<ResolveRequest>) {}
+
+        fn get_artifacts(&mut self, _requests: Vec<DataRequest>) {}
+
+        fn tick(&mut self, _loader: &mut LoaderState) {}
+
+        fn with_runtime(&self, _f: &mut dyn FnMut(&tokio::runtime::Runtime)) {}
+    }
+
+    fn fallback_io(responses: Vec<MockResponse>) -> FallbackIO {
+        FallbackIO::new(
+            responses
+                .into_iter()
+                .map(|response| Box::new(MockIO(response)) as Box<dyn LoaderIO>)
+                .collect(),
+        )
+    }
+
+    /// Requests the metadata of `assets`, and returns the assets in the response.
+    fn request_metadata(io: &mut FallbackIO, assets: &[AssetUuid]) -> Result<HashSet<AssetUuid>> {
+        let (tx, rx) = unbounded();
+        let requests = assets
+            .iter()
+            .enumerate()
+            .map(|(idx, asset_id)| (*asset_id, (LoadHandle(idx as u64), 0)))
+            .collect();
+        io.get_asset_metadata_with_dependencies(MetadataRequest {
+            tx,
+            requests: Some(requests),
+            include_asset_metadata: false,
+        });
+        // the mock sources respond immediately, so each pass forwards to at most one more source
+        for _ in 0..io.sources.len() {
+            io.process_metadata_responses();
+        }
+        let (result, _) = rx.try_recv().expect("metadata request was not completed");
+        result.map(|results| {
+            results
+                .iter()
+                .map(|result| result.artifact_metadata.asset_id)
+                .collect()
+        })
+    }
+
+    #[test]
+    fn test_metadata_falls_back_when_source_drops_request() {
+        let assets = [AssetUuid([1; 16]), AssetUuid([2; 16])];
+        let mut io = fallback_io(vec![
+            MockResponse::Drop,
+            MockResponse::Serve(assets.to_vec()),
+        ]);
+        let received = request_metadata(&mut io, &assets).unwrap();
+        assert_eq!(received, assets.iter().copied().collect());
+    }
+
+    #[test]
+    fn test_metadata_falls_back_when_source_fails() {
+        let assets = [AssetUuid([1; 16])];
+        let mut io = fallback_io(vec![
+            MockResponse::Fail,
+            MockResponse::Serve(assets.to_vec()),
+        ]);
+        let received = request_metadata(&mut io, &assets).unwrap();
+        assert_eq!(received, assets.iter().copied().collect());
+    }
+
+    #[test]
+    fn test_metadata_missing_assets_are_requested_from_next_source() {
+        let (first, second) = (AssetUuid([1; 16]), AssetUuid([2; 16]));
+        let mut io = fallback_io(vec![
+            MockResponse::Serve(vec![first]),
+            MockResponse::Serve(vec![second]),
+        ]);
+        let received = request_metadata(&mut io, &[first, second]).unwrap();
+        assert_eq!(received, [first, second].iter().copied().collect());
+    }
+
+    #[test]
+    fn test_metadata_errors_when_all_sources_fail() {
+        let mut io = fallback_io(vec![MockResponse::Fail, MockResponse::Drop]);
+        assert!(request_metadata(&mut io, &[AssetUuid([1; 16])]).is_err());
+    }
+}
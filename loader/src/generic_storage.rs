@@ -0,0 +1,590 @@
+use std::{
+    any::Any,
+    collections::HashMap,
+    error::Error,
+    fmt,
+    marker::PhantomData,
+    sync::{Arc, Mutex, RwLock},
+    thread::JoinHandle,
+};
+
+use crossbeam_channel::{unbounded, Sender};
+use distill_core::{type_uuid::TypeUuid, AssetBundle};
+use serde::de::DeserializeOwned;
+
+use crate::{
+    handle::{AssetHandle, RefOp, SerdeContext, TypedAssetStorage},
+    storage::{AssetLoadOp, AssetStorage, IndirectionTable, LoaderInfoProvider},
+    AssetTypeId, LoadHandle, Result,
+};
+
+/// Converts the artifact data of an asset into the asset type `A`.
+///
+/// Deserialization runs within a [`SerdeContext`], so [`Handle`](crate::handle::Handle)s in the
+/// asset data are deserialized with their references tracked. When the storage has decode threads,
+/// the context only contains the asset's load dependencies, see [`LoaderInfoProvider::dependency_snapshot`].
+pub trait AssetDeserializer<A>: Send + Sync {
+    fn deserialize(&self, data: &[u8]) -> Result<A>;
+}
+
+impl<A, F: Fn(&[u8]) -> Result<A> + Send + Sync> AssetDeserializer<A> for F {
+    fn deserialize(&self, data: &[u8]) -> Result<A> {
+        self(data)
+    }
+}
+
+/// Deserializes assets from the `bincode` format that the daemon serializes artifacts with.
+pub struct BincodeDeserializer<A>(PhantomData<fn() -> A>);
+
+impl<A> Default for BincodeDeserializer<A> {
+    fn default() -> Self {
+        BincodeDeserializer(PhantomData)
+    }
+}
+
+impl<A: DeserializeOwned> AssetDeserializer<A> for BincodeDeserializer<A> {
+    fn deserialize(&self, data: &[u8]) -> Result<A> {
+        bincode::deserialize(data).map_err(|err| -> Box<dyn Error + Send> { err })
+    }
+}
+
+/// Called after an asset of type `A` has been deserialized, before it becomes visible.
+///
+/// When the storage has decode threads, the hook is called on a decode thread.
+/// The hook is responsible for signalling completion through `load_op`. This allows finishing the
+/// load asynchronously, for example after uploading data to the GPU, by holding on to the
+/// [`AssetLoadOp`] until the work is done.
+pub trait AssetLoadHook<A>: Send + Sync {
+    fn on_load(&self, asset: &mut A, load_handle: LoadHandle, load_op: AssetLoadOp, version: u32);
+}
+
+impl<A, F: Fn(&mut A, LoadHandle, AssetLoadOp, u32) + Send + Sync> AssetLoadHook<A> for F {
+    fn on_load(&self, asset: &mut A, load_handle: LoadHandle, load_op: AssetLoadOp, version: u32) {
+        self(asset, load_handle, load_op, version)
+    }
+}
+
+struct AssetState<A> {
+    version: u32,
+    // Boxed so that the address of the asset is stable while it is moved between maps.
+    asset: Box<A>,
+}
+
+/// A decoded asset waiting to be committed. It is locked while the load hook runs, and taken
+/// out when the version is committed.
+type UncommittedAsset<A> = Arc<Mutex<Option<Box<A>>>>;
+
+type DecodeJob = Box<dyn FnOnce() + Send>;
+
+/// Worker threads that deserialize assets outside of [`Loader::process`](crate::Loader::process).
+struct DecodePool {
+    job_tx: Option<Sender<DecodeJob>>,
+    threads: Vec<JoinHandle<()>>,
+}
+
+impl DecodePool {
+    fn new(num_threads: usize) -> Self {
+        let (job_tx, job_rx) = unbounded::<DecodeJob>();
+        let threads = (0..num_threads)
+            .map(|idx| {
+                let job_rx = job_rx.clone();
+                std::thread::Builder::new()
+                    .name(format!("distill-decode-{}", idx))
+                    .spawn(move || {
+                        while let Ok(job) = job_rx.recv() {
+                            job();
+                        }
+                    })
+                    .expect("failed to spawn decode thread")
+            })
+            .collect();
+        Self {
+            job_tx: Some(job_tx),
+            threads,
+        }
+    }
+
+    fn spawn(&self, job: DecodeJob) {
+        let _ = self.job_tx.as_ref().unwrap().send(job);
+    }
+}
+
+impl Drop for DecodePool {
+    fn drop(&mut self) {
+        // Disconnecting the channel stops the threads once the remaining jobs are done.
+        self.job_tx = None;
+        for thread in self.threads.drain(..) {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// The part of a per-type storage that is shared with decode jobs.
+struct Decoder<A> {
+    deserializer: Box<dyn AssetDeserializer<A>>,
+    load_hook: RwLock<Option<Box<dyn AssetLoadHook<A>>>>,
+    uncommitted: Mutex<HashMap<(LoadHandle, u32), UncommittedAsset<A>>>,
+}
+
+impl<A> Decoder<A> {
+    fn decode(
+        &self,
+        loader_info: &dyn LoaderInfoProvider,
+        refop_sender: &Sender<RefOp>,
+        data: &[u8],
+        load_handle: LoadHandle,
+        load_op: AssetLoadOp,
+        version: u32,
+    ) {
+        let result = futures_executor::block_on(SerdeContext::with(
+            loader_info,
+            refop_sender.clone(),
+            async { self.deserializer.deserialize(data) },
+        ));
+        let asset = match result {
+            Ok(asset) => Arc::new(Mutex::new(Some(Box::new(asset)))),
+            Err(err) => {
+                load_op.error(StorageError(format!(
+                    "failed to deserialize asset: {}",
+                    err
+                )));
+                return;
+            }
+        };
+        // The uncommitted asset must be present before the loader can see the completed load op,
+        // which may happen before the hook returns when decoding on another thread. Committing
+        // waits for the lock on the asset, which is held until the hook returns.
+        let mut asset_guard = asset.lock().unwrap();
+        self.uncommitted
+            .lock()
+            .unwrap()
+            .insert((load_handle, version), asset.clone());
+        let asset = asset_guard.as_mut().unwrap();
+        match &*self.load_hook.read().unwrap() {
+            Some(load_hook) => load_hook.on_load(asset, load_handle, load_op, version),
+            None => load_op.complete(),
+        }
+        drop(asset_guard);
+        log::trace!("{} bytes loaded for {:?}", data.len(), load_handle);
+    }
+}
+
+struct Storage<A> {
+    decoder: Arc<Decoder<A>>,
+    assets: RwLock<HashMap<LoadHandle, AssetState<A>>>,
+    /// Assets that were replaced or freed while references to them may still exist.
+    freed: Mutex<Vec<Box<A>>>,
+}
+
+impl<A> Storage<A> {
+    fn get_asset_with_version(&self, load_handle: LoadHandle) -> Option<(*const A, u32)> {
+        self.assets
+            .read()
+            .unwrap()
+            .get(&load_handle)
+            .map(|state| (&*state.asset as *const A, state.version))
+    }
+}
+
+trait TypedStorage: Any + Send + Sync {
+    fn as_any(&self) -> &dyn Any;
+
+    #[allow(clippy::too_many_arguments)]
+    fn update_asset(
+        &self,
+        loader_info: &dyn LoaderInfoProvider,
+        refop_sender: &Sender<RefOp>,
+        decode_pool: Option<&DecodePool>,
+        data: Vec<u8>,
+        load_handle: LoadHandle,
+        load_op: AssetLoadOp,
+        version: u32,
+    );
+
+    fn commit_asset_version(&self, load_handle: LoadHandle, version: u32);
+
+    fn free(&self, load_handle: LoadHandle, version: u32);
+
+    fn drop_freed_assets(&mut self);
+}
+
+impl<A: Send + Sync + 'static> TypedStorage for Storage<A> {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn update_asset(
+        &self,
+        loader_info: &dyn LoaderInfoProvider,
+        refop_sender: &Sender<RefOp>,
+        decode_pool: Option<&DecodePool>,
+        data: Vec<u8>,
+        load_handle: LoadHandle,
+        load_op: AssetLoadOp,
+        version: u32,
+    ) {
+        match decode_pool {
+            Some(decode_pool) => {
+                let loader_info = loader_info.dependency_snapshot(load_handle);
+                let refop_sender = refop_sender.clone();
+                let decoder = self.decoder.clone();
+                decode_pool.spawn(Box::new(move || {
+                    decoder.decode(
+                        &loader_info,
+                        &refop_sender,
+                        &data,
+                        load_handle,
+                        load_op,
+                        version,
+                    )
+                }));
+            }
+            None => self.decoder.decode(
+                loader_info,
+                refop_sender,
+                &data,
+                load_handle,
+                load_op,
+                version,
+            ),
+        }
+    }
+
+    fn commit_asset_version(&self, load_handle: LoadHandle, version: u32) {
+        let asset = self
+            .decoder
+            .uncommitted
+            .lock()
+            .unwrap()
+            .remove(&(load_handle, version))
+            .expect("asset not present when committing");
+        let asset = asset.lock().unwrap().take().expect("asset committed twice");
+        let state = AssetState { version, asset };
+        if let Some(previous) = self.assets.write().unwrap().insert(load_handle, state) {
+            self.freed.lock().unwrap().push(previous.asset);
+        }
+    }
+
+    fn free(&self, load_handle: LoadHandle, version: u32) {
+        self.decoder
+            .uncommitted
+            .lock()
+            .unwrap()
+            .remove(&(load_handle, version));
+        let mut assets = self.assets.write().unwrap();
+        if assets.get(&load_handle).map(|state| state.version) == Some(version) {
+            let state = assets.remove(&load_handle).unwrap();
+            self.freed.lock().unwrap().push(state.asset);
+        }
+    }
+
+    fn drop_freed_assets(&mut self) {
+        self.freed.get_mut().unwrap().clear();
+    }
+}
+
+/// A thread-safe [`AssetStorage`] which stores assets of every registered asset type.
+///
+/// Asset types are registered using [`GenericAssetStorage::add_storage`], or
+/// [`GenericAssetStorage::add_storage_with_deserializer`] for assets that are not serialized with
+/// `bincode`. Loaded assets are accessed through [`TypedAssetStorage`], for example using
+/// [`AssetHandle::asset`].
+///
+/// By default, assets are deserialized in [`Loader::process`](crate::Loader::process). Use
+/// [`GenericAssetStorage::with_decode_threads`] to deserialize assets on worker threads instead.
+///
+/// Since references returned by [`TypedAssetStorage::get`] borrow the storage, assets that are
+/// replaced by a reload or freed by the loader are kept alive until
+/// [`GenericAssetStorage::drop_freed_assets`] is called.
+pub struct GenericAssetStorage {
+    storages: RwLock<HashMap<AssetTypeId, Box<dyn TypedStorage>>>,
+    refop_sender: Sender<RefOp>,
+    indirection_table: IndirectionTable,
+    decode_pool: Option<DecodePool>,
+}
+
+impl GenericAssetStorage {
+    /// Creates a storage where only [`AssetBundle`] is registered, so that handles to bundles load.
+    ///
+    /// # Parameters
+    ///
+    /// * `refop_sender`: Receives reference count changes of handles in deserialized assets.
+    /// * `indirection_table`: Used to resolve indirect handles, see [`Loader::indirection_table`](crate::Loader::indirection_table).
+    pub fn new(refop_sender: Sender<RefOp>, indirection_table: IndirectionTable) -> Self {
+        let storage = Self {
+            storages: RwLock::new(HashMap::new()),
+            refop_sender,
+            indirection_table,
+            decode_pool: None,
+        };
+        storage.add_storage::<AssetBundle>();
+        storage
+    }
+
+    /// Deserializes assets on `num_threads` worker threads instead of in [`Loader::process`](crate::Loader::process).
+    ///
+    /// Decoded assets are committed by the loader in the first `process` after they complete.
+    /// Combine with [`Loader::set_max_update_bytes_per_process`](crate::Loader::set_max_update_bytes_per_process)
+    /// to bound the amount of work that is queued per frame.
+    pub fn with_decode_threads(mut self, num_threads: usize) -> Self {
+        self.decode_pool = if num_threads > 0 {
+            Some(DecodePool::new(num_threads))
+        } else {
+            None
+        };
+        self
+    }
+
+    /// Registers storage for assets of type `A`, deserialized with [`BincodeDeserializer`].
+    pub fn add_storage<A>(&self)
+    where
+        A: TypeUuid + DeserializeOwned + Send + Sync + 'static,
+    {
+        self.add_storage_with_deserializer::<A, _>(BincodeDeserializer::default());
+    }
+
+    /// Registers storage for assets of type `A`, deserialized with `deserializer`.
+    pub fn add_storage_with_deserializer<A, D>(&self, deserializer: D)
+    where
+        A: TypeUuid + Send + Sync + 'static,
+        D: AssetDeserializer<A> + 'static,
+    {
+        let storage = Storage::<A> {
+            decoder: Arc::new(Decoder {
+                deserializer: Box::new(deserializer),
+                load_hook: RwLock::new(None),
+                uncommitted: Mutex::new(HashMap::new()),
+            }),
+            assets: RwLock::new(HashMap::new()),
+            freed: Mutex::new(Vec::new()),
+        };
+        let previous = self
+            .storages
+            .write()
+            .unwrap()
+            .insert(AssetTypeId(A::UUID), Box::new(storage));
+        assert!(
+            previous.is_none(),
+            "storage for asset type {:?} registered twice",
+            AssetTypeId(A::UUID)
+        );
+    }
+
+    /// Sets the hook that is called after an asset of type `A` has been deserialized.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `A` has not been registered with [`GenericAssetStorage::add_storage`].
+    pub fn set_load_hook<A, H>(&self, load_hook: H)
+    where
+        A: TypeUuid + Send + Sync + 'static,
+        H: AssetLoadHook<A> + 'static,
+    {
+        let storages = self.storages.read().unwrap();
+        let storage = storages
+            .get(&AssetTypeId(A::UUID))
+            .expect("asset type not registered")
+            .as_any()
+            .downcast_ref::<Storage<A>>()
+            .expect("failed to downcast");
+        *storage.decoder.load_hook.write().unwrap() = Some(Box::new(load_hook));
+    }
+    /// Drops assets that have been replaced or freed since the last call.
+    ///
+    /// Requires exclusive access to guarantee that no references to the dropped assets remain.
+    pub fn drop_freed_assets(&mut self) {
+        for storage in self.storages.get_mut().unwrap().values_mut() {
+            storage.drop_freed_assets();
+        }
+    }
+
+    fn resolve_handle<T: AssetHandle>(&self, handle: &T) -> Option<LoadHandle> {
+        let load_handle = handle.load_handle();
+        if load_handle.is_indirect() {
+            self.indirection_table.resolve(load_handle)
+        } else {
+            Some(load_handle)
+        }
+    }
+}
+
+impl<A: TypeUuid + Send + Sync + 'static> TypedAssetStorage<A> for GenericAssetStorage {
+    fn get<T: AssetHandle>(&self, handle: &T) -> Option<&A> {
+        self.get_asset_with_version(handle).map(|(asset, _)| asset)
+    }
+
+    fn get_version<T: AssetHandle>(&self, handle: &T) -> Option<u32> {
+        self.get_asset_with_version(handle)
+            .map(|(_, version): (&A, u32)| version)
+    }
+
+    fn get_asset_with_version<T: AssetHandle>(&self, handle: &T) -> Option<(&A, u32)> {
+        let load_handle = self.resolve_handle(handle)?;
+        let storages = self.storages.read().unwrap();
+        let (asset, version) = storages
+            .get(&AssetTypeId(A::UUID))
+            .expect("asset type not registered")
+            .as_any()
+            .downcast_ref::<Storage<A>>()
+            .expect("failed to downcast")
+            .get_asset_with_version(load_handle)?;
+        // SAFETY: Assets are boxed and only dropped in `drop_freed_assets`, which requires
+        // exclusive access to the storage, so the asset outlives the borrow of `self`.
+        Some((unsafe { &*asset }, version))
+    }
+}
+
+impl AssetStorage for GenericAssetStorage {
+    fn update_asset(
+        &self,
+        loader_info: &dyn LoaderInfoProvider,
+        asset_type_id: &AssetTypeId,
+        data: Vec<u8>,
+        load_handle: LoadHandle,
+        load_op: AssetLoadOp,
+        version: u32,
+    ) -> std::result::Result<(), Box<dyn Error + Send + 'static>> {
+        match self.storages.read().unwrap().get(asset_type_id) {
+            Some(storage) => storage.update_asset(
+                loader_info,
+                &self.refop_sender,
+                self.decode_pool.as_ref(),
+                data,
+                load_handle,
+                load_op,
+                version,
+            ),
+            None => load_op.error(StorageError(format!(
+                "asset type {:?} is not registered",
+                asset_type_id
+            ))),
+        }
+        Ok(())
+    }
+
+    fn commit_asset_version(
+        &self,
+        asset_type_id: &AssetTypeId,
+        load_handle: LoadHandle,
+        version: u32,
+    ) {
+        self.storages
+            .read()
+            .unwrap()
+            .get(asset_type_id)
+            .expect("asset type not registered")
+            .commit_asset_version(load_handle, version)
+    }
+
+    fn free(&self, asset_type_id: &AssetTypeId, load_handle: LoadHandle, version: u32) {
+        self.storages
+            .read()
+            .unwrap()
+            .get(asset_type_id)
+            .expect("asset type not registered")
+            .free(load_handle, version)
+    }
+}
+
+/// Reported through the [`AssetLoadOp`] when an asset could not be stored.
+#[derive(Debug)]
+struct StorageError(String);
+impl fmt::Display for StorageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+impl Error for StorageError {}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crossbeam_channel::Receiver;
+
+    use super::*;
+    use crate::storage::{AtomicHandleAllocator, HandleOp, LoaderInfoSnapshot};
+
+    struct TestAsset(Vec<u8>);
+
+    impl TypeUuid for TestAsset {
+        const UUID: [u8; 16] = [7; 16];
+    }
+
+    struct TestHandle(LoadHandle);
+
+    impl AssetHandle for TestHandle {
+        fn load_handle(&self) -> LoadHandle {
+            self.0
+        }
+    }
+
+    const TIMEOUT: Duration = Duration::from_secs(10);
+
+    fn expect_complete(op_rx: &Receiver<HandleOp>) -> (LoadHandle, u32) {
+        match op_rx.recv_timeout(TIMEOUT).expect("load did not complete") {
+            HandleOp::Complete(handle, version) => (handle, version),
+            HandleOp::Error(handle, version, err) => {
+                panic!("load of {:?} version {} failed: {}", handle, version, err)
+            }
+            HandleOp::Drop(handle, version) => {
+                panic!("load op of {:?} version {} dropped", handle, version)
+            }
+        }
+    }
+
+    #[test]
+    fn concurrent_versions_of_an_asset() {
+        let (refop_tx, _refop_rx) = unbounded();
+        let indirection_table = IndirectionTable::new(Arc::new(AtomicHandleAllocator::default()));
+        let storage = GenericAssetStorage::new(refop_tx, indirection_table).with_decode_threads(2);
+        storage.add_storage_with_deserializer::<TestAsset, _>(|data: &[u8]| {
+            Ok(TestAsset(data.to_vec()))
+        });
+        // The hook of version 1 only completes after version 2 has been decoded and completed.
+        let (decoded_tx, decoded_rx) = unbounded();
+        let decoded_rx = Mutex::new(decoded_rx);
+        storage.set_load_hook::<TestAsset, _>(
+            move |_: &mut TestAsset, _: LoadHandle, load_op: AssetLoadOp, version: u32| {
+                if version == 1 {
+                    decoded_rx
+                        .lock()
+                        .unwrap()
+                        .recv_timeout(TIMEOUT)
+                        .expect("version 2 was not decoded while version 1 was loading");
+                    load_op.complete();
+                } else {
+                    load_op.complete();
+                    decoded_tx.send(()).unwrap();
+                }
+            },
+        );
+
+        let asset_type = AssetTypeId(TestAsset::UUID);
+        let handle = LoadHandle(1);
+        let (op_tx, op_rx) = unbounded();
+        for version in 1..=2 {
+            storage
+                .update_asset(
+                    &LoaderInfoSnapshot::default(),
+                    &asset_type,
+                    vec![version as u8],
+                    handle,
+                    AssetLoadOp::new(op_tx.clone(), handle, version),
+                    version,
+                )
+                .unwrap();
+        }
+
+        // Versions are committed in the order that they complete, as the loader does.
+        for expected_version in [2, 1].iter() {
+            let (completed_handle, version) = expect_complete(&op_rx);
+            assert_eq!((completed_handle, version), (handle, *expected_version));
+            storage.commit_asset_version(&asset_type, handle, version);
+            let (asset, committed_version): (&TestAsset, u32) =
+                TypedAssetStorage::get_asset_with_version(&storage, &TestHandle(handle)).unwrap();
+            assert_eq!(committed_version, version);
+            assert_eq!(asset.0, vec![version as u8]);
+        }
+    }
+}
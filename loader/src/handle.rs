@@ -3,6 +3,7 @@ use std::{
     fmt::Debug,
     hash::Hash,
     marker::PhantomData,
+    path::PathBuf,
     sync::{
         atomic::{AtomicU64, Ordering},
         Arc, Mutex, RwLock,
@@ -10,6 +11,7 @@ use std::{
 };
 
 use crossbeam_channel::{unbounded, Receiver, Sender};
+use distill_core::type_uuid::TypeUuid;
 use futures_core::future::{BoxFuture, Future};
 use serde::{
     de::{self, Deserialize, Visitor},
@@ -17,8 +19,8 @@ use serde::{
 };
 
 use crate::{
-    storage::{LoadStatus, LoaderInfoProvider},
-    AssetRef, AssetUuid, LoadHandle, Loader,
+    storage::{LoadStatus, LoaderInfoProvider, LoaderInfoSnapshot},
+    AssetRef, AssetTypeId, AssetUuid, LoadHandle, Loader,
 };
 
 /// Operations on an asset reference.
@@ -27,6 +29,8 @@ pub enum RefOp {
     Decrease(LoadHandle),
     Increase(LoadHandle),
     IncreaseUuid(AssetUuid),
+    /// Registers the asset type that a typed handle expects, see [`Loader::expect_asset_type`].
+    ExpectType(LoadHandle, AssetTypeId),
 }
 
 pub fn process_ref_ops(loader: &Loader, rx: &Receiver<RefOp>) {
@@ -40,6 +44,9 @@ pub fn process_ref_ops(loader: &Loader, rx: &Receiver<RefOp>) {
             Ok(RefOp::IncreaseUuid(uuid)) => {
                 loader.add_ref(uuid);
             }
+            Ok(RefOp::ExpectType(handle, asset_type)) => {
+                loader.expect_asset_type(handle, asset_type);
+            }
         }
     }
 }
@@ -160,9 +167,36 @@ impl<T: ?Sized> From<GenericHandle> for Handle<T> {
     }
 }
 
-impl<T> Handle<T> {
+/// The bound on the asset type of a [`Handle`]. With the `type_checked_handles` feature, which is
+/// enabled by default, it is implemented for types with a [`TypeUuid`] so the loader can check the
+/// type of the referenced asset. Without the feature it is implemented for all types.
+pub trait HandleAssetType {
+    /// The asset type that handles to `Self` expect, if it is checked.
+    fn expected_type() -> Option<AssetTypeId>;
+}
+
+#[cfg(feature = "type_checked_handles")]
+impl<T: TypeUuid> HandleAssetType for T {
+    fn expected_type() -> Option<AssetTypeId> {
+        Some(AssetTypeId(T::UUID))
+    }
+}
+
+#[cfg(not(feature = "type_checked_handles"))]
+impl<T> HandleAssetType for T {
+    fn expected_type() -> Option<AssetTypeId> {
+        None
+    }
+}
+
+impl<T: HandleAssetType> Handle<T> {
     /// Creates a new handle with `HandleRefType::Strong`
+    ///
+    /// The loader is notified of the asset type that is expected, see [`Loader::expect_asset_type`].
     pub fn new(chan: Sender<RefOp>, handle: LoadHandle) -> Self {
+        if let Some(asset_type) = T::expected_type() {
+            let _ = chan.send(RefOp::ExpectType(handle, asset_type));
+        }
         Self {
             handle_ref: HandleRef {
                 id: handle,
@@ -171,7 +205,9 @@ impl<T> Handle<T> {
             marker: PhantomData,
         }
     }
+}
 
+impl<T> Handle<T> {
     /// Creates a new handle with `HandleRefType::Internal`
     pub(crate) fn new_internal(chan: Sender<RefOp>, handle: LoadHandle) -> Self {
         Self {
@@ -188,6 +224,23 @@ impl<T> Handle<T> {
     }
 }
 
+impl<T: TypeUuid + HandleAssetType> Handle<T> {
+    /// Upgrades a [`WeakHandle`] into a strong handle, if the asset is still loaded and is of type `T`.
+    ///
+    /// # Parameters
+    ///
+    /// * `weak`: Handle to upgrade.
+    /// * `loader`: Loader that is loading the asset.
+    /// * `chan`: Channel used by the new handle to release its reference, see [`process_ref_ops`].
+    pub fn upgrade(weak: &WeakHandle, loader: &Loader, chan: &Sender<RefOp>) -> Option<Self> {
+        if loader.get_asset_type(weak.id) != Some(AssetTypeId(T::UUID)) {
+            return None;
+        }
+        weak.add_ref_if_loaded(loader)
+            .then(|| Handle::new(chan.clone(), weak.id))
+    }
+}
+
 impl<T> AssetHandle for Handle<T> {
     fn load_handle(&self) -> LoadHandle {
         self.handle_ref.load_handle()
@@ -255,6 +308,28 @@ impl WeakHandle {
     pub fn new(handle: LoadHandle) -> Self {
         WeakHandle { id: handle }
     }
+
+    /// Upgrades this handle into a strong handle, if the asset is still loaded.
+    ///
+    /// # Parameters
+    ///
+    /// * `loader`: Loader that is loading the asset.
+    /// * `chan`: Channel used by the new handle to release its reference, see [`process_ref_ops`].
+    pub fn upgrade(&self, loader: &Loader, chan: &Sender<RefOp>) -> Option<GenericHandle> {
+        self.add_ref_if_loaded(loader)
+            .then(|| GenericHandle::new(chan.clone(), self.id))
+    }
+
+    fn add_ref_if_loaded(&self, loader: &Loader) -> bool {
+        // `Loader::process` takes `&mut self`, so the asset cannot be unloaded before the
+        // reference is added.
+        if let LoadStatus::Loaded = loader.get_load_status(self.id) {
+            loader.add_ref_handle(self.id);
+            true
+        } else {
+            false
+        }
+    }
 }
 
 impl AssetHandle for WeakHandle {
@@ -308,6 +383,8 @@ struct DummySerdeContextMaps {
 
 struct DummySerdeContextCurrent {
     current_serde_dependencies: HashSet<AssetRef>,
+    current_serde_weak_dependencies: HashSet<AssetRef>,
+    current_serde_expected_types: HashMap<AssetRef, AssetTypeId>,
     current_serde_asset: Option<AssetUuid>,
 }
 
@@ -321,12 +398,40 @@ impl DummySerdeContext {
             }),
             current: Mutex::new(DummySerdeContextCurrent {
                 current_serde_dependencies: HashSet::new(),
+                current_serde_weak_dependencies: HashSet::new(),
+                current_serde_expected_types: HashMap::new(),
                 current_serde_asset: None,
             }),
             ref_sender: tx,
             handle_gen: AtomicU64::new(1),
         }
     }
+
+    fn record_dependency(&self, load: LoadHandle, weak: bool) -> Option<AssetUuid> {
+        let maps = self.maps.read().unwrap();
+        let maybe_asset = maps.load_to_uuid.get(&load).cloned();
+        if let Some(asset_ref) = maybe_asset.as_ref() {
+            let mut current = self.current.lock().unwrap();
+            if let Some(ref current_serde_id) = current.current_serde_asset {
+                if AssetRef::Uuid(*current_serde_id) != *asset_ref
+                    && *asset_ref != AssetRef::Uuid(AssetUuid::default())
+                {
+                    if weak {
+                        current
+                            .current_serde_weak_dependencies
+                            .insert(asset_ref.clone());
+                    } else {
+                        current.current_serde_dependencies.insert(asset_ref.clone());
+                    }
+                }
+            }
+        }
+        if let Some(AssetRef::Uuid(uuid)) = maybe_asset {
+            Some(uuid)
+        } else {
+            None
+        }
+    }
 }
 
 impl LoaderInfoProvider for DummySerdeContext {
@@ -348,23 +453,53 @@ impl LoaderInfoProvider for DummySerdeContext {
     }
 
     fn get_asset_id(&self, load: LoadHandle) -> Option<AssetUuid> {
+        self.record_dependency(load, false)
+    }
+
+    fn get_weak_asset_id(&self, load: LoadHandle) -> Option<AssetUuid> {
+        self.record_dependency(load, true)
+    }
+
+    fn get_asset_path(&self, load: LoadHandle) -> Option<PathBuf> {
         let maps = self.maps.read().unwrap();
-        let maybe_asset = maps.load_to_uuid.get(&load).cloned();
-        if let Some(asset_ref) = maybe_asset.as_ref() {
+        match maps.load_to_uuid.get(&load) {
+            Some(AssetRef::Path(path)) => Some(path.clone()),
+            _ => None,
+        }
+    }
+
+    fn get_asset_tag(&self, load: LoadHandle) -> Option<String> {
+        let maps = self.maps.read().unwrap();
+        match maps.load_to_uuid.get(&load) {
+            Some(AssetRef::Tag(tag)) => Some(tag.clone()),
+            _ => None,
+        }
+    }
+
+    fn check_asset_type(&self, load: LoadHandle, expected: AssetTypeId) -> Result<(), String> {
+        // The types of referenced assets are not known during import, so record the expected
+        // type for the daemon to check once the asset is serialized.
+        let maps = self.maps.read().unwrap();
+        if let Some(asset_ref) = maps.load_to_uuid.get(&load) {
             let mut current = self.current.lock().unwrap();
-            if let Some(ref current_serde_id) = current.current_serde_asset {
-                if AssetRef::Uuid(*current_serde_id) != *asset_ref
-                    && *asset_ref != AssetRef::Uuid(AssetUuid::default())
-                {
-                    current.current_serde_dependencies.insert(asset_ref.clone());
-                }
+            if current.current_serde_asset.is_some() {
+                current
+                    .current_serde_expected_types
+                    .insert(asset_ref.clone(), expected);
             }
         }
-        if let Some(AssetRef::Uuid(uuid)) = maybe_asset {
-            Some(uuid)
-        } else {
-            None
+        Ok(())
+    }
+
+    fn dependency_snapshot(&self, _load: LoadHandle) -> LoaderInfoSnapshot {
+        let maps = self.maps.read().unwrap();
+        let mut snapshot = LoaderInfoSnapshot::default();
+        for (asset_ref, handle) in &maps.uuid_to_load {
+            if let AssetRef::Uuid(uuid) = asset_ref {
+                snapshot.insert(*uuid, *handle);
+            }
         }
+        snapshot
     }
 }
 struct DummySerdeContextHandle {
@@ -396,6 +531,24 @@ impl<'a> distill_core::importer_context::ImporterContextHandle for DummySerdeCon
         current.current_serde_asset = Some(asset);
     }
 
+    fn expected_types(&self) -> Vec<(AssetRef, AssetTypeId)> {
+        let current = self.dummy.current.lock().unwrap();
+        current
+            .current_serde_expected_types
+            .iter()
+            .map(|(asset_ref, asset_type)| (asset_ref.clone(), *asset_type))
+            .collect()
+    }
+
+    fn weak_refs(&self) -> Vec<AssetRef> {
+        let current = self.dummy.current.lock().unwrap();
+        current
+            .current_serde_weak_dependencies
+            .iter()
+            .cloned()
+            .collect()
+    }
+
     /// Finish gathering dependencies for an asset
     fn end_serialize_asset(&mut self, _asset: AssetUuid) -> HashSet<AssetRef> {
         let mut current = self.dummy.current.lock().unwrap();
@@ -403,6 +556,8 @@ impl<'a> distill_core::importer_context::ImporterContextHandle for DummySerdeCon
             panic!("end_serialize_asset when current_serde_asset is not set");
         }
         current.current_serde_asset = None;
+        current.current_serde_expected_types.clear();
+        current.current_serde_weak_dependencies.clear();
         std::mem::replace(&mut current.current_serde_dependencies, HashSet::new())
     }
 }
@@ -416,26 +571,63 @@ impl distill_core::importer_context::ImporterContext for HandleSerdeContextProvi
     }
 }
 
-fn serialize_handle<S>(load: LoadHandle, serializer: S) -> Result<S::Ok, S::Error>
+fn serialize_handle<S>(
+    load: LoadHandle,
+    expected_type: Option<AssetTypeId>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
 where
     S: Serializer,
 {
     SerdeContext::with_active(|loader, _| {
-        use ser::SerializeSeq;
+        use ser::Error;
         let uuid: AssetUuid = loader.get_asset_id(load).unwrap_or_default();
-        let mut seq = serializer.serialize_seq(Some(uuid.0.len()))?;
-        for element in &uuid.0 {
-            seq.serialize_element(element)?;
+        if let Some(expected_type) = expected_type {
+            if uuid != AssetUuid::default() {
+                loader
+                    .check_asset_type(load, expected_type)
+                    .map_err(|err| S::Error::custom(format!("handle to {}: {}", uuid, err)))?;
+            }
         }
-        seq.end()
+        serialize_asset_ref(loader, load, uuid, serializer)
     })
 }
-impl<T> Serialize for Handle<T> {
+/// Human-readable formats get the path or the `tag:` prefixed tag query the handle was created
+/// from, or the hyphenated UUID.
+/// Other formats get the UUID as a sequence of 16 bytes.
+fn serialize_asset_ref<S>(
+    loader: &dyn LoaderInfoProvider,
+    load: LoadHandle,
+    uuid: AssetUuid,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    use ser::SerializeSeq;
+    if serializer.is_human_readable() {
+        return if let Some(path) = loader.get_asset_path(load) {
+            serializer.serialize_str(&path.to_string_lossy())
+        } else if let Some(tag) = loader.get_asset_tag(load) {
+            serializer.collect_str(&format_args!("{}{}", TAG_REF_PREFIX, tag))
+        } else {
+            serializer.collect_str(&uuid)
+        };
+    }
+    let mut seq = serializer.serialize_seq(Some(uuid.0.len()))?;
+    for element in &uuid.0 {
+        seq.serialize_element(element)?;
+    }
+    seq.end()
+}
+/// Serializing fails if the referenced asset is known to not be of type `T`, and the expected type
+/// is recorded for the daemon to check during import, see [`HandleAssetType`].
+impl<T: HandleAssetType> Serialize for Handle<T> {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: Serializer,
     {
-        serialize_handle(self.handle_ref.id, serializer)
+        serialize_handle(self.handle_ref.id, T::expected_type(), serializer)
     }
 }
 impl Serialize for GenericHandle {
@@ -443,7 +635,20 @@ impl Serialize for GenericHandle {
     where
         S: Serializer,
     {
-        serialize_handle(self.handle_ref.id, serializer)
+        serialize_handle(self.handle_ref.id, None, serializer)
+    }
+}
+/// Serializing a `WeakHandle` records a weak load dependency, so the referenced asset is not
+/// loaded with the asset that contains the handle.
+impl Serialize for WeakHandle {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        SerdeContext::with_active(|loader, _| {
+            let uuid: AssetUuid = loader.get_weak_asset_id(self.id).unwrap_or_default();
+            serialize_asset_ref(loader, self.id, uuid, serializer)
+        })
     }
 }
 
@@ -460,23 +665,54 @@ fn get_handle_ref(asset_ref: AssetRef) -> (LoadHandle, Sender<RefOp>) {
     })
 }
 
-impl<'de, T> Deserialize<'de> for Handle<T> {
+fn deserialize_handle<'de, D>(
+    deserializer: D,
+    expected_type: Option<AssetTypeId>,
+) -> Result<(LoadHandle, Sender<RefOp>), D::Error>
+where
+    D: de::Deserializer<'de>,
+{
+    use de::Error;
+    let asset_ref = if deserializer.is_human_readable() {
+        deserializer.deserialize_any(AssetRefVisitor)?
+    } else {
+        deserializer.deserialize_seq(AssetRefVisitor)?
+    };
+    let (handle, sender) = get_handle_ref(asset_ref.clone());
+    if let Some(expected_type) = expected_type {
+        if handle != LoadHandle(0) {
+            SerdeContext::with_active(|loader, _| loader.check_asset_type(handle, expected_type))
+                .map_err(|err| D::Error::custom(format!("handle to {:?}: {}", asset_ref, err)))?;
+        }
+    }
+    Ok((handle, sender))
+}
+
+/// Deserializing fails if the referenced asset is known to not be of type `T`, see
+/// [`HandleAssetType`].
+impl<'de, T: HandleAssetType> Deserialize<'de> for Handle<T> {
     fn deserialize<D>(deserializer: D) -> Result<Handle<T>, D::Error>
     where
         D: de::Deserializer<'de>,
     {
-        let asset_ref = if deserializer.is_human_readable() {
-            deserializer.deserialize_any(AssetRefVisitor)?
-        } else {
-            deserializer.deserialize_seq(AssetRefVisitor)?
-        };
-        let (handle, sender) = get_handle_ref(asset_ref);
+        let (handle, sender) = deserialize_handle(deserializer, T::expected_type())?;
         Ok(Handle::new_internal(sender, handle))
     }
 }
 
 impl<'de> Deserialize<'de> for GenericHandle {
     fn deserialize<D>(deserializer: D) -> Result<GenericHandle, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        let (handle, sender) = deserialize_handle(deserializer, None)?;
+        Ok(GenericHandle::new_internal(sender, handle))
+    }
+}
+
+/// A deserialized `WeakHandle` is null if the referenced asset was not loaded at the time.
+impl<'de> Deserialize<'de> for WeakHandle {
+    fn deserialize<D>(deserializer: D) -> Result<WeakHandle, D::Error>
     where
         D: de::Deserializer<'de>,
     {
@@ -485,18 +721,26 @@ impl<'de> Deserialize<'de> for GenericHandle {
         } else {
             deserializer.deserialize_seq(AssetRefVisitor)?
         };
-        let (handle, sender) = get_handle_ref(asset_ref);
-        Ok(GenericHandle::new_internal(sender, handle))
+        let handle = if asset_ref == AssetRef::Uuid(AssetUuid::default()) {
+            LoadHandle(0)
+        } else {
+            SerdeContext::with_active(|loader, _| loader.get_load_handle(&asset_ref))
+                .unwrap_or(LoadHandle(0))
+        };
+        Ok(WeakHandle::new(handle))
     }
 }
 
+/// Prefix of tag query references in human-readable formats, for example `tag:name=PlayerSword`.
+const TAG_REF_PREFIX: &str = "tag:";
+
 struct AssetRefVisitor;
 
 impl<'de> Visitor<'de> for AssetRefVisitor {
     type Value = AssetRef;
 
     fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        formatter.write_str("an array of 16 u8")
+        formatter.write_str("an array of 16 u8, a UUID string, an asset path or a tag query")
     }
 
     fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
@@ -535,6 +779,9 @@ impl<'de> Visitor<'de> for AssetRefVisitor {
         E: de::Error,
     {
         use std::str::FromStr;
+        if let Some(tag) = v.strip_prefix(TAG_REF_PREFIX) {
+            return Ok(AssetRef::Tag(tag.to_string()));
+        }
         match std::path::PathBuf::from_str(v) {
             Ok(path) => {
                 if let Ok(uuid) = uuid::Uuid::parse_str(&path.to_string_lossy()) {
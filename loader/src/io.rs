@@ -1,4 +1,7 @@
-use std::{collections::HashMap, path::PathBuf};
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::PathBuf,
+};
 
 use crossbeam_channel::Sender;
 use distill_core::{ArtifactId, ArtifactMetadata, AssetMetadata, AssetUuid};
@@ -99,6 +102,22 @@ impl std::fmt::Display for RequestDropError {
 }
 impl std::error::Error for RequestDropError {}
 
+/// Returns the candidates for an identifier without a path, which are the assets that match its
+/// tag, grouped by path in path order.
+#[cfg(any(feature = "rpc_io", feature = "packfile_io", feature = "directory_io"))]
+pub(crate) fn tag_candidates(
+    id: &IndirectIdentifier,
+    assets: impl IntoIterator<Item = (PathBuf, AssetMetadata)>,
+) -> Vec<(PathBuf, Vec<AssetMetadata>)> {
+    let mut by_path: BTreeMap<PathBuf, Vec<AssetMetadata>> = BTreeMap::new();
+    for (path, asset) in assets {
+        if id.matches_tags(&asset.search_tags) {
+            by_path.entry(path).or_default().push(asset);
+        }
+    }
+    by_path.into_iter().collect()
+}
+
 pub struct MetadataRequestResult {
     pub artifact_metadata: ArtifactMetadata,
     pub asset_metadata: Option<AssetMetadata>,
@@ -1,5 +1,16 @@
 #![warn(rust_2018_idioms, rust_2018_compatibility)]
 
+/// *feature:* `directory_io`. `DirectoryIO` is an implementation of [`LoaderIO`](crate::io::LoaderIO) which reads
+/// artifacts from a directory of loose files exported by the CLI.
+#[cfg(feature = "directory_io")]
+pub mod directory_io;
+/// [`FallbackIO`](crate::fallback_io::FallbackIO) chains multiple [`LoaderIO`](crate::io::LoaderIO) implementations,
+/// trying each in order until one can serve a request.
+pub mod fallback_io;
+/// *feature:* `generic_storage`. [`GenericAssetStorage`](crate::generic_storage::GenericAssetStorage) is a thread-safe
+/// [`AssetStorage`](crate::storage::AssetStorage) for any registered asset type, for engines that do not need their own storage.
+#[cfg(feature = "generic_storage")]
+pub mod generic_storage;
 /// *feature:* `handle`. Handles provide automatic reference counting of assets, similar to [Rc](`std::rc::Rc`).
 #[cfg(feature = "handle")]
 pub mod handle;
@@ -9,6 +20,8 @@ pub mod io;
 pub mod loader;
 #[cfg(feature = "packfile_io")]
 pub mod packfile_io;
+#[cfg(feature = "rpc_io")]
+mod rpc_cache;
 /// *feature:* `rpc_io`. `RpcIO` is an implementation of [`LoaderIO`](crate::io::LoaderIO) which communicates with `distill_daemon`
 /// to load and hot reload assets. Intended for development workflows.
 #[cfg(feature = "rpc_io")]
@@ -17,7 +30,12 @@ pub mod rpc_io;
 pub mod storage;
 
 pub use crossbeam_channel;
+#[cfg(feature = "directory_io")]
+pub use directory_io::DirectoryIO;
 pub use distill_core::{AssetRef, AssetTypeId, AssetUuid};
+pub use fallback_io::FallbackIO;
+#[cfg(feature = "generic_storage")]
+pub use generic_storage::GenericAssetStorage;
 pub use loader::Loader;
 #[cfg(feature = "packfile_io")]
 pub use packfile_io::PackfileReader;
@@ -1,15 +1,21 @@
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{HashMap, HashSet, VecDeque},
+    error::Error,
+    fmt,
     path::PathBuf,
     sync::{
-        atomic::{AtomicUsize, Ordering},
+        atomic::{AtomicBool, AtomicUsize, Ordering},
         Arc,
     },
+    time::Duration,
 };
 
 use crossbeam_channel::{unbounded, Receiver, Sender};
 use dashmap::DashMap;
-use distill_core::{ArtifactMetadata, AssetMetadata, AssetRef, AssetTypeId, AssetUuid};
+use distill_core::{
+    utils::{self, canonicalize_path},
+    ArtifactMetadata, AssetMetadata, AssetRef, AssetTypeId, AssetUuid,
+};
 use instant::Instant;
 use log::error;
 
@@ -18,8 +24,8 @@ use crate::{
     io::{DataRequest, LoaderIO, MetadataRequest, MetadataRequestResult, ResolveRequest},
     storage::{
         AssetLoadOp, AssetStorage, AtomicHandleAllocator, HandleAllocator, HandleOp,
-        IndirectIdentifier, IndirectionResolver, IndirectionTable, LoadHandle, LoadInfo,
-        LoadStatus, LoaderInfoProvider,
+        IndirectIdentifier, IndirectResolution, IndirectionResolver, IndirectionTable, LoadHandle,
+        LoadInfo, LoadStatus, LoaderInfoProvider, LoaderInfoSnapshot,
     },
     Result,
 };
@@ -70,8 +76,91 @@ struct IndirectLoad {
     resolved_uuid: Option<AssetUuid>,
     refs: AtomicUsize,
     pending_reresolve: bool,
+    /// Assets that matched the identifier when it was last found to be ambiguous.
+    ambiguous: Vec<AssetUuid>,
+}
+
+/// Describes why an asset failed to load.
+#[derive(Debug, Clone, PartialEq)]
+pub enum LoadFailure {
+    /// The asset does not exist in the [`LoaderIO`].
+    DoesNotExist,
+    /// Fetching or loading the asset failed with the contained error.
+    Error(String),
+    /// The asset did not finish loading before the timeout passed to [`Loader::load_blocking`].
+    TimedOut,
+    /// The asset's type does not match the type expected by a typed handle, see [`Loader::expect_asset_type`].
+    TypeMismatch {
+        expected: AssetTypeId,
+        found: AssetTypeId,
+    },
+    /// Multiple assets match an indirect identifier, see [`IndirectResolution::Ambiguous`].
+    Ambiguous(Vec<AssetUuid>),
+    /// The asset's load dependencies form a cycle, starting and ending at the asset.
+    DependencyCycle(Vec<AssetUuid>),
+}
+
+impl fmt::Display for LoadFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LoadFailure::DoesNotExist => write!(f, "asset does not exist"),
+            LoadFailure::Error(err) => write!(f, "{}", err),
+            LoadFailure::TimedOut => write!(f, "timed out"),
+            LoadFailure::TypeMismatch { expected, found } => write!(
+                f,
+                "expected asset of type {}, but the asset has type {}",
+                expected, found
+            ),
+            LoadFailure::Ambiguous(candidates) => write!(
+                f,
+                "identifier is ambiguous, matching assets {}",
+                candidates
+                    .iter()
+                    .map(|id| id.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            LoadFailure::DependencyCycle(cycle) => write!(
+                f,
+                "load dependencies form a cycle: {}",
+                utils::format_dependency_cycle(cycle)
+            ),
+        }
+    }
+}
+
+/// An asset that could not be loaded by [`Loader::load_blocking`].
+#[derive(Debug, Clone)]
+pub struct LoadBlockingError {
+    /// Load dependencies leading from the requested asset to the asset that failed to load.
+    /// The first element is the requested asset, and the last element is the asset that failed.
+    pub dependency_chain: Vec<AssetUuid>,
+    /// Why the last asset in `dependency_chain` failed to load.
+    pub failure: LoadFailure,
+}
+
+impl fmt::Display for LoadBlockingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let chain = self
+            .dependency_chain
+            .iter()
+            .map(|id| id.to_string())
+            .collect::<Vec<_>>()
+            .join(" -> ");
+        write!(f, "failed to load {}: {}", chain, self.failure)
+    }
 }
 
+impl Error for LoadBlockingError {}
+
+/// Time to wait between calls to [`Loader::process`] in [`Loader::load_blocking`].
+#[cfg(not(target_arch = "wasm32"))]
+const LOAD_BLOCKING_POLL_INTERVAL: Duration = Duration::from_millis(1);
+
+/// Time a load waits for its dependencies before it is checked for a dependency cycle, and between
+/// further checks while it keeps waiting.
+const DEPENDENCY_CYCLE_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
 #[derive(Debug, Clone)]
 struct AssetVersionLoad {
     state: LoadState,
@@ -80,11 +169,32 @@ struct AssetVersionLoad {
     asset_type: Option<AssetTypeId>,
     auto_commit: bool,
     version: u32,
+    /// The last failure while loading this version, cleared when the version leaves the state it failed in.
+    error: Option<LoadFailure>,
+}
+
+impl AssetVersionLoad {
+    /// Moves the version to `state`, clearing the failure of the previous state unless the
+    /// version is only retrying the request that failed.
+    fn set_state(&mut self, state: LoadState) {
+        let retrying = matches!(
+            (self.state, state),
+            (LoadState::WaitingForMetadata, LoadState::RequestingMetadata)
+                | (LoadState::RequestingMetadata, LoadState::WaitingForMetadata)
+                | (LoadState::WaitingForData, LoadState::RequestingData)
+                | (LoadState::RequestingData, LoadState::WaitingForData)
+        );
+        if self.state != state && !retrying {
+            self.error = None;
+        }
+        self.state = state;
+    }
 }
 #[derive(Debug)]
 struct AssetLoad {
     asset_id: AssetUuid,
     last_state_change_instant: Instant,
+    last_cycle_check_instant: Instant,
     refs: AtomicUsize,
     versions: Vec<AssetVersionLoad>,
     version_counter: u32,
@@ -107,15 +217,23 @@ pub struct LoaderState {
     op_rx: Receiver<HandleOp>,
     invalidate_tx: Sender<AssetUuid>,
     invalidate_rx: Receiver<AssetUuid>,
-    #[cfg(feature = "invalidate_path")]
     invalidate_path_tx: Sender<PathBuf>,
-    #[cfg(feature = "invalidate_path")]
     invalidate_path_rx: Receiver<PathBuf>,
     pending_reloads: Vec<PendingReload>,
     indirect_states: DashMap<LoadHandle, IndirectLoad>,
     indirect_to_load: DashMap<IndirectIdentifier, LoadHandle>,
     indirect_table: IndirectionTable,
     responses: IORequestChannels,
+    /// Maximum number of artifact bytes passed to [`AssetStorage::update_asset`] per `process`.
+    max_update_bytes_per_process: Option<usize>,
+    /// Artifact data that did not fit in `max_update_bytes_per_process`, which is handled before
+    /// newer responses in the next `process`.
+    deferred_data_responses: VecDeque<(Vec<u8>, LoadHandle, u32)>,
+    /// Asset types that typed handles expect their load handles to resolve to.
+    expected_types: DashMap<LoadHandle, AssetTypeId>,
+    /// Set when a version starts waiting for the dependencies in its newly arrived metadata, which
+    /// may complete a cycle of load dependencies.
+    dependencies_changed: AtomicBool,
 }
 
 #[allow(clippy::type_complexity)]
@@ -145,13 +263,15 @@ struct IORequestChannels {
 struct AssetLoadResult {
     new_state: LoadState,
     asset_type: Option<AssetTypeId>,
+    error: Option<LoadFailure>,
 }
 
 impl AssetLoadResult {
-    pub fn from_state(new_state: LoadState) -> Self {
+    pub fn from_error(new_state: LoadState, error: String) -> Self {
         Self {
             new_state,
             asset_type: None,
+            error: Some(LoadFailure::Error(error)),
         }
     }
 }
@@ -177,6 +297,7 @@ impl LoaderState {
                     resolved_uuid: None,
                     refs: AtomicUsize::new(0),
                     pending_reresolve: false,
+                    ambiguous: Vec::new(),
                 },
             );
             self.indirect_to_load.insert(id, new_handle);
@@ -205,9 +326,11 @@ impl LoaderState {
                         asset_metadata: None,
                         state: LoadState::None,
                         version: 1,
+                        error: None,
                     }],
                     version_counter: 1,
                     last_state_change_instant: Instant::now(),
+                    last_cycle_check_instant: Instant::now(),
                     refs: AtomicUsize::new(0),
                     pending_reload: false,
                 },
@@ -227,7 +350,10 @@ impl LoaderState {
         if handle.is_indirect() {
             let state = self.indirect_states.get(&handle).unwrap();
             if let Some(uuid) = state.resolved_uuid {
-                self.add_refs(uuid, 1);
+                // The load may have been removed while unreferenced, so keep the table current
+                let uuid_handle = self.add_refs(uuid, 1);
+                self.indirect_table
+                    .update(Some((handle, Some(uuid_handle))));
             }
             state.refs.fetch_add(1, Ordering::Relaxed);
         } else {
@@ -255,7 +381,31 @@ impl LoaderState {
             .unwrap_or(None)
     }
 
+    /// Returns the asset type in the metadata of the newest version of the load that has metadata.
+    fn get_metadata_asset_type(&self, load: LoadHandle) -> Option<AssetTypeId> {
+        self.load_states.get(&load).and_then(|load| {
+            load.versions
+                .iter()
+                .rev()
+                .find_map(|version| version.metadata.as_ref().map(|m| m.type_id))
+        })
+    }
+
+    /// Returns a failure if the asset of `load` is known to not have the type expected by `handle`.
+    fn get_type_mismatch(&self, handle: LoadHandle, load: LoadHandle) -> Option<LoadFailure> {
+        let expected = *self.expected_types.get(&handle)?;
+        let found = self.get_metadata_asset_type(load)?;
+        if expected != found {
+            Some(LoadFailure::TypeMismatch { expected, found })
+        } else {
+            None
+        }
+    }
+
     fn get_load_info(&self, load: LoadHandle) -> Option<LoadInfo> {
+        if !self.handle_allocator.is_valid(load) {
+            return None;
+        }
         let load = if load.is_indirect() {
             self.indirect_table.resolve(load)?
         } else {
@@ -329,6 +479,7 @@ impl LoaderState {
 
     fn process_load_states(&self, asset_storage: &dyn AssetStorage) {
         let mut to_remove = Vec::new();
+        let dependencies_changed = self.dependencies_changed.swap(false, Ordering::Relaxed);
         let keys: Vec<_> = self.load_states.iter().map(|x| *x.key()).collect();
 
         for key in keys {
@@ -338,7 +489,12 @@ impl LoaderState {
             let load = entry.value_mut();
 
             let has_refs = load.refs.load(Ordering::Relaxed) > 0;
-            if !has_refs && load.versions.is_empty() {
+            if !has_refs
+                && load
+                    .versions
+                    .iter()
+                    .all(|v| matches!(v.state, LoadState::None))
+            {
                 to_remove.push(key);
             } else {
                 if has_refs && load.pending_reload {
@@ -359,11 +515,20 @@ impl LoaderState {
                             auto_commit: false,
                             state: LoadState::None,
                             version: new_version,
+                            error: None,
                         });
                         load.pending_reload = false;
                     }
                 }
                 let last_state_change_instant = load.last_state_change_instant;
+                // loads that wait without changing state are checked for cycles periodically, in
+                // case a cycle formed without new metadata arriving
+                let stalled = last_state_change_instant.elapsed()
+                    >= DEPENDENCY_CYCLE_CHECK_INTERVAL
+                    && load.last_cycle_check_instant.elapsed() >= DEPENDENCY_CYCLE_CHECK_INTERVAL;
+                if stalled {
+                    load.last_cycle_check_instant = Instant::now();
+                }
                 let mut versions = load.versions.clone();
                 // make sure we drop the lock before we start processing the state
                 drop(entry);
@@ -393,10 +558,20 @@ impl LoaderState {
                             LoadState::None
                         }
                         LoadState::WaitingForMetadata => {
-                            if version_load.metadata.is_some() {
-                                LoadState::RequestDependencies
-                            } else {
-                                LoadState::WaitingForMetadata
+                            let expected_type = self.expected_types.get(&key).map(|t| *t);
+                            match (&version_load.metadata, expected_type) {
+                                (Some(metadata), Some(expected))
+                                    if metadata.type_id != expected =>
+                                {
+                                    // Don't load an asset of the wrong type for the handles referring to it.
+                                    version_load.error = Some(LoadFailure::TypeMismatch {
+                                        expected,
+                                        found: metadata.type_id,
+                                    });
+                                    LoadState::WaitingForMetadata
+                                }
+                                (Some(_), _) => LoadState::RequestDependencies,
+                                (None, _) => LoadState::WaitingForMetadata,
                             }
                         }
                         LoadState::RequestingMetadata => LoadState::RequestingMetadata,
@@ -410,10 +585,12 @@ impl LoaderState {
                                 }
                             }
 
+                            self.dependencies_changed.store(true, Ordering::Relaxed);
                             LoadState::WaitingForDependencies
                         }
                         LoadState::WaitingForDependencies => {
                             let asset_metadata = version_load.metadata.as_ref().unwrap();
+                            let asset_id = asset_metadata.asset_id;
 
                             // Ensure dependencies are loaded by engine before continuing to load this asset.
                             let asset_dependencies_committed =
@@ -443,6 +620,19 @@ impl LoaderState {
                             if asset_dependencies_committed {
                                 LoadState::WaitingForData
                             } else {
+                                // An asset in a cycle would wait for its dependencies forever.
+                                if version_load.error.is_none() && (dependencies_changed || stalled)
+                                {
+                                    if let Some(cycle) = self.find_dependency_cycle(asset_id) {
+                                        error!(
+                                            "asset {}: load dependencies form a cycle: {}",
+                                            asset_id,
+                                            utils::format_dependency_cycle(&cycle)
+                                        );
+                                        version_load.error =
+                                            Some(LoadFailure::DependencyCycle(cycle));
+                                    }
+                                }
                                 LoadState::WaitingForDependencies
                             }
                         }
@@ -493,7 +683,7 @@ impl LoaderState {
                         state_change = true;
                         log_new_state = Some(new_state);
                         log_old_state = Some(version_load.state);
-                        version_load.state = new_state;
+                        version_load.set_state(new_state);
                     }
                 }
                 let mut entry = self.load_states.get_mut(&key).unwrap();
@@ -542,20 +732,25 @@ impl LoaderState {
             }
             */
         }
-        for _i in to_remove {
-            // TODO: This will reset the version counter because it's stored in the AssetLoad.
-            // Is this a problem? Should we guarantee that users never see the same version twice, ever?
-            // Should we store version counters separately?
-            //     let load_state = load_states.remove(&i);
-            //     if let Some((_, load_state)) = load_state {
-            //         uuid_to_load.remove(&load_state.asset_id);
-            //     }
+        for handle in to_remove {
+            // Removing the load resets its version counter, but a new load is given a new handle,
+            // so a (handle, version) pair is never seen twice.
+            if let Some((_, load)) = self
+                .load_states
+                .remove_if(&handle, |_, load| load.refs.load(Ordering::Relaxed) == 0)
+            {
+                self.uuid_to_load.remove(&load.asset_id);
+                self.expected_types.remove(&handle);
+                self.handle_allocator.free(handle);
+            }
         }
     }
 
     fn process_metadata_requests(&self, io: &mut dyn LoaderIO) {
         while let Ok(mut response) = self.responses.metadata_rx.try_recv() {
             let request_data = &mut response.1;
+            // assets remaining in `request_data` after a successful response do not exist
+            let mut failure = LoadFailure::DoesNotExist;
             match response.0 {
                 Ok(metadata_list) => {
                     for metadata in metadata_list {
@@ -586,7 +781,7 @@ impl LoaderState {
                             version_load.metadata = Some(metadata.artifact_metadata);
                             version_load.asset_metadata = metadata.asset_metadata;
                             if let LoadState::RequestingMetadata = version_load.state {
-                                version_load.state = LoadState::RequestDependencies
+                                version_load.set_state(LoadState::RequestDependencies)
                             }
                         } else if request_data.is_some() {
                             load.version_counter += 1;
@@ -598,12 +793,14 @@ impl LoaderState {
                                 asset_metadata: metadata.asset_metadata,
                                 state: LoadState::None,
                                 version: new_version,
+                                error: None,
                             });
                         }
                     }
                 }
                 Err(err) => {
                     error!("metadata request failed: {}", err);
+                    failure = LoadFailure::Error(format!("metadata request failed: {}", err));
                 }
             }
             for (handle, version) in request_data.values() {
@@ -617,7 +814,8 @@ impl LoaderState {
                     .find(|v| v.version == *version)
                     .expect("load in metadata request but not in load.versions");
                 if let LoadState::RequestingMetadata = version_load.state {
-                    version_load.state = LoadState::WaitingForMetadata
+                    version_load.set_state(LoadState::WaitingForMetadata);
+                    version_load.error = Some(failure.clone());
                 }
             }
         }
@@ -627,7 +825,7 @@ impl LoaderState {
             let load = entry.value_mut();
             for version_load in &mut load.versions {
                 if let LoadState::WaitingForMetadata = version_load.state {
-                    version_load.state = LoadState::RequestingMetadata;
+                    version_load.set_state(LoadState::RequestingMetadata);
                     assets_to_request.insert(load.asset_id, (handle, version_load.version));
                 }
             }
@@ -641,8 +839,26 @@ impl LoaderState {
         }
     }
 
-    fn process_data_requests(&self, storage: &dyn AssetStorage, io: &mut dyn LoaderIO) {
-        while let Ok(response) = self.responses.data_rx.try_recv() {
+    fn process_data_requests(&mut self, storage: &dyn AssetStorage, io: &mut dyn LoaderIO) {
+        let mut updated_bytes = 0;
+        while let Some(response) = self
+            .deferred_data_responses
+            .pop_front()
+            .map(|(data, handle, version)| (Ok(data), handle, version))
+            .or_else(|| self.responses.data_rx.try_recv().ok())
+        {
+            if let (Ok(data), Some(max_bytes)) = (&response.0, self.max_update_bytes_per_process) {
+                // Always update at least one asset to make progress, even if it exceeds the budget.
+                if updated_bytes > 0 && updated_bytes + data.len() > max_bytes {
+                    // Keep the response to be handled first in the next `process`.
+                    if let (Ok(data), handle, version) = response {
+                        self.deferred_data_responses
+                            .push_front((data, handle, version));
+                    }
+                    break;
+                }
+                updated_bytes += data.len();
+            }
             let result = response.0;
             let handle = response.1;
             let version = response.2;
@@ -677,11 +893,15 @@ impl LoaderState {
                             "AssetStorage implementor error when updating asset {:?}: {}",
                             asset_id, storage_error
                         );
-                        AssetLoadResult::from_state(LoadState::WaitingForData)
+                        AssetLoadResult::from_error(
+                            LoadState::WaitingForData,
+                            format!("failed to update asset in storage: {}", storage_error),
+                        )
                     } else {
                         AssetLoadResult {
                             asset_type: Some(artifact_type),
                             new_state: LoadState::LoadingAsset,
+                            error: None,
                         }
                     }
                 }
@@ -690,7 +910,10 @@ impl LoaderState {
                         "asset data request failed for asset {:?}: {}",
                         load.asset_id, err
                     );
-                    AssetLoadResult::from_state(LoadState::WaitingForMetadata)
+                    AssetLoadResult::from_error(
+                        LoadState::WaitingForMetadata,
+                        format!("asset data request failed: {}", err),
+                    )
                 }
             };
             let mut load = self
@@ -702,10 +925,13 @@ impl LoaderState {
                 .iter_mut()
                 .find(|v| v.version == version)
                 .expect("load version did not exist when data request completed");
-            version_load.state = load_result.new_state;
+            version_load.set_state(load_result.new_state);
             if let Some(asset_type) = load_result.asset_type {
                 version_load.asset_type = Some(asset_type);
             }
+            if load_result.error.is_some() {
+                version_load.error = load_result.error;
+            }
         }
         let mut assets_to_request = Vec::new();
         for mut load in self.load_states.iter_mut() {
@@ -717,7 +943,7 @@ impl LoaderState {
                 .iter_mut()
                 .find(|v| matches!(v.state, LoadState::WaitingForData))
             {
-                version_load.state = LoadState::RequestingData;
+                version_load.set_state(LoadState::RequestingData);
                 let artifact_id = version_load.metadata.as_ref().unwrap().id;
                 assets_to_request.push(DataRequest {
                     tx: self.responses.data_tx.clone(),
@@ -735,9 +961,23 @@ impl LoaderState {
     fn process_load_ops(&self, asset_storage: &dyn AssetStorage) {
         while let Ok(op) = self.op_rx.try_recv() {
             match op {
-                HandleOp::Error(_handle, _version, err) => {
-                    log::error!("load error {}", err);
-                    panic!("load error {}", err);
+                HandleOp::Error(handle, version, err) => {
+                    log::error!(
+                        "load error for handle {:?} version {}: {}",
+                        handle,
+                        version,
+                        err
+                    );
+                    let mut load = self
+                        .load_states
+                        .get_mut(&handle)
+                        .expect("load op failed but load state does not exist");
+                    let load_version = load
+                        .versions
+                        .iter_mut()
+                        .find(|v| v.version == version)
+                        .expect("load op failed but version not found in load");
+                    load_version.error = Some(LoadFailure::Error(err.to_string()));
                 }
                 HandleOp::Complete(handle, version) => {
                     log::debug!("completed load for handle {:?} version {}", handle, version);
@@ -753,7 +993,7 @@ impl LoaderState {
                     if load_version.auto_commit {
                         commit_asset(handle, load.value_mut(), version, asset_storage);
                     } else {
-                        load_version.state = LoadState::LoadedUncommitted;
+                        load_version.set_state(LoadState::LoadedUncommitted);
                     }
                 }
                 HandleOp::Drop(handle, version) => {
@@ -771,23 +1011,23 @@ impl LoaderState {
         }
     }
 
-    #[cfg(feature = "invalidate_path")]
+    /// Marks indirect handles for re-resolution when files at their paths are created, changed or removed.
     fn process_path_changes(&mut self) {
         let mut changes = HashSet::new();
         while let Ok(path) = self.invalidate_path_rx.try_recv() {
             log::trace!("process_path_changes invalidate_path_rx path: {:?}", path);
-            changes.insert(path);
+            changes.insert(canonicalize_path(&path));
+        }
+        if changes.is_empty() {
+            return;
         }
         for entry in self.indirect_to_load.iter() {
-            let indirect_id = entry.key();
-            let handle = entry.value();
-            let cleaned_path =
-                distill_core::utils::canonicalize_path(&PathBuf::from(indirect_id.path()));
-            for change in &changes {
-                if change == &cleaned_path {
-                    if let Some(mut indirect) = self.indirect_states.get_mut(&handle) {
-                        indirect.pending_reresolve = true;
-                    }
+            // Identifiers without a path match assets at any path
+            let affected = entry.key().as_tag().is_some()
+                || changes.contains(&canonicalize_path(&PathBuf::from(entry.key().path())));
+            if affected {
+                if let Some(mut indirect) = self.indirect_states.get_mut(entry.value()) {
+                    indirect.pending_reresolve = true;
                 }
             }
         }
@@ -899,6 +1139,7 @@ impl LoaderState {
     }
 
     fn process_resolve_requests(&self, io: &mut dyn LoaderIO, resolver: &dyn IndirectionResolver) {
+        let mut updates = Vec::new();
         while let Ok(response) = self.responses.resolve_rx.try_recv() {
             let result = response.0;
             let id = response.1;
@@ -910,7 +1151,21 @@ impl LoaderState {
             match result {
                 Ok(candidates) => {
                     let num_refs = state.refs.load(Ordering::Relaxed);
-                    let new_uuid = resolver.resolve(&id, candidates);
+                    let new_uuid = match resolver.try_resolve(&id, candidates) {
+                        IndirectResolution::Resolved(uuid) => {
+                            state.ambiguous.clear();
+                            Some(uuid)
+                        }
+                        IndirectResolution::Ambiguous(candidates) => {
+                            error!("{:?} is ambiguous, matching assets {:?}", id, candidates);
+                            state.ambiguous = candidates;
+                            None
+                        }
+                        IndirectResolution::NotFound => {
+                            state.ambiguous.clear();
+                            None
+                        }
+                    };
                     if let Some(existing_uuid) = state.resolved_uuid {
                         let uuid_handle = self.get_or_insert(existing_uuid);
                         self.remove_refs(uuid_handle, num_refs);
@@ -918,9 +1173,9 @@ impl LoaderState {
                     if let Some(new_uuid) = new_uuid {
                         let uuid_handle = self.get_or_insert(new_uuid);
                         self.add_refs(new_uuid, num_refs);
-                        self.indirect_table.0.insert(load_handle, uuid_handle);
+                        updates.push((load_handle, Some(uuid_handle)));
                     } else {
-                        self.indirect_table.0.remove(&load_handle);
+                        updates.push((load_handle, None));
                     }
                     state.resolved_uuid = new_uuid;
                     state.state = IndirectHandleState::Resolved;
@@ -931,6 +1186,8 @@ impl LoaderState {
                 }
             }
         }
+        // Apply all resolutions at once, so the table never mixes old and new resolutions
+        self.indirect_table.update(updates);
         let mut assets_to_request = Vec::new();
         for mut load in self.indirect_states.iter_mut() {
             if let IndirectHandleState::WaitingForMetadata = load.state {
@@ -946,13 +1203,120 @@ impl LoaderState {
         }
     }
 
+    /// Searches the load dependencies of `root` breadth-first for an asset that failed to load,
+    /// returning the chain of dependencies from `root` to the failed asset.
+    ///
+    /// Assets that do not exist are only reported when `timed_out` is set, since they may still be
+    /// created by the [`LoaderIO`], for example when the daemon has not finished importing yet.
+    /// When `timed_out` is set and no failure is found, returns the deepest asset that has not
+    /// finished loading, as that is the asset `root` is waiting for.
+    fn find_load_failure(
+        &self,
+        root: AssetUuid,
+        timed_out: bool,
+    ) -> Option<(Vec<AssetUuid>, LoadFailure)> {
+        let mut parents = HashMap::new();
+        let mut to_visit = VecDeque::new();
+        to_visit.push_back(root);
+        parents.insert(root, root);
+        let mut pending = None;
+        let chain_to = |parents: &HashMap<AssetUuid, AssetUuid>, mut id: AssetUuid| {
+            let mut chain = vec![id];
+            while id != root {
+                id = parents[&id];
+                chain.push(id);
+            }
+            chain.reverse();
+            chain
+        };
+        while let Some(id) = to_visit.pop_front() {
+            let handle = self.uuid_to_load.get(&id).map(|h| *h);
+            let load = handle.and_then(|handle| self.load_states.get(&handle));
+            let version = load
+                .as_ref()
+                .and_then(|load| load.versions.iter().max_by_key(|v| v.version).cloned());
+            drop(load);
+            let version = match version {
+                Some(version) => version,
+                None => {
+                    pending = Some(id);
+                    continue;
+                }
+            };
+            match version.error {
+                Some(LoadFailure::DoesNotExist) if !timed_out => {}
+                Some(error) => return Some((chain_to(&parents, id), error)),
+                None => {}
+            }
+            if matches!(
+                version.state,
+                LoadState::Loaded | LoadState::LoadedUncommitted
+            ) {
+                continue;
+            }
+            pending = Some(id);
+            for dep in version.metadata.iter().flat_map(|m| m.load_deps.iter()) {
+                if let AssetRef::Uuid(dep) = dep {
+                    if !parents.contains_key(dep) {
+                        parents.insert(*dep, id);
+                        to_visit.push_back(*dep);
+                    }
+                }
+            }
+        }
+        if timed_out {
+            let id = pending.unwrap_or(root);
+            Some((chain_to(&parents, id), LoadFailure::TimedOut))
+        } else {
+            None
+        }
+    }
+
+    /// Finds a cycle of load dependencies through `id` among the assets that have not finished
+    /// loading, returning the cycle starting and ending at `id`.
+    fn find_dependency_cycle(&self, id: AssetUuid) -> Option<Vec<AssetUuid>> {
+        utils::find_dependency_cycle(id, |id| {
+            let handle = self.uuid_to_load.get(id).map(|h| *h);
+            let load = handle.and_then(|handle| self.load_states.get(&handle));
+            load.and_then(|load| {
+                let version = load.versions.iter().max_by_key(|v| v.version)?;
+                if matches!(
+                    version.state,
+                    LoadState::Loaded | LoadState::LoadedUncommitted
+                ) {
+                    return None;
+                }
+                version.metadata.as_ref().map(|metadata| {
+                    metadata
+                        .load_deps
+                        .iter()
+                        .filter_map(|dep| match dep {
+                            AssetRef::Uuid(dep) => Some(*dep),
+                            _ => None,
+                        })
+                        .collect()
+                })
+            })
+            .unwrap_or_default()
+        })
+    }
+
+    /// Drains asset invalidations that have not been processed yet.
+    pub(crate) fn take_invalidated_assets(&self) -> Vec<AssetUuid> {
+        self.invalidate_rx.try_iter().collect()
+    }
+
+    /// Drains path invalidations that have not been processed yet.
+    pub(crate) fn take_invalidated_paths(&self) -> Vec<PathBuf> {
+        self.invalidate_path_rx.try_iter().collect()
+    }
+
     pub fn invalidate_assets(&self, assets: &[AssetUuid]) {
         for asset in assets {
             let _ = self.invalidate_tx.send(*asset);
         }
     }
 
-    #[cfg(feature = "invalidate_path")]
     pub fn invalidate_paths(&self, paths: &[PathBuf]) {
         for path in paths {
             let _ = self.invalidate_path_tx.send(path.clone());
@@ -968,12 +1332,87 @@ pub struct Loader {
 
 impl LoaderInfoProvider for LoaderState {
     fn get_load_handle(&self, id: &AssetRef) -> Option<LoadHandle> {
-        self.uuid_to_load.get(id.expect_uuid()).map(|l| *l)
+        match id {
+            AssetRef::Uuid(uuid) => self.uuid_to_load.get(uuid).map(|l| *l),
+            // Path references only occur in human-readable data, and are loaded indirectly
+            AssetRef::Path(path) => Some(self.get_or_insert_indirect(IndirectIdentifier::Path(
+                path.to_string_lossy().into_owned(),
+            ))),
+            AssetRef::Tag(tag) => {
+                Some(self.get_or_insert_indirect(IndirectIdentifier::Tag(tag.clone())))
+            }
+        }
     }
 
     fn get_asset_id(&self, load: LoadHandle) -> Option<AssetUuid> {
+        if !self.handle_allocator.is_valid(load) {
+            return None;
+        }
+        if load.is_indirect() {
+            return self.indirect_states.get(&load)?.resolved_uuid;
+        }
         self.load_states.get(&load).map(|l| l.asset_id)
     }
+
+    fn get_asset_path(&self, load: LoadHandle) -> Option<PathBuf> {
+        if load.is_indirect() {
+            self.indirect_states
+                .get(&load)
+                .filter(|state| state.id.as_tag().is_none())
+                .map(|state| PathBuf::from(state.id.path()))
+        } else {
+            None
+        }
+    }
+
+    fn get_asset_tag(&self, load: LoadHandle) -> Option<String> {
+        if load.is_indirect() {
+            self.indirect_states
+                .get(&load)
+                .and_then(|state| match &state.id {
+                    IndirectIdentifier::Tag(tag) => Some(tag.clone()),
+                    _ => None,
+                })
+        } else {
+            None
+        }
+    }
+
+    fn check_asset_type(
+        &self,
+        load: LoadHandle,
+        expected: AssetTypeId,
+    ) -> std::result::Result<(), String> {
+        match self.get_metadata_asset_type(load) {
+            Some(found) if found != expected => {
+                Err(LoadFailure::TypeMismatch { expected, found }.to_string())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn dependency_snapshot(&self, load: LoadHandle) -> LoaderInfoSnapshot {
+        let mut snapshot = LoaderInfoSnapshot::default();
+        if let Some(asset_load) = self.load_states.get(&load) {
+            snapshot.insert(asset_load.asset_id, load);
+            let deps = asset_load
+                .versions
+                .iter()
+                .filter_map(|v| v.metadata.as_ref())
+                .flat_map(|metadata| metadata.load_deps.iter().chain(&metadata.weak_deps));
+            for dep in deps {
+                if let AssetRef::Uuid(uuid) = dep {
+                    if let Some(dep_load) = self.uuid_to_load.get(uuid) {
+                        snapshot.insert(*uuid, *dep_load);
+                        if let Some(asset_type) = self.get_metadata_asset_type(*dep_load) {
+                            snapshot.insert_asset_type(*dep_load, asset_type);
+                        }
+                    }
+                }
+            }
+        }
+        snapshot
+    }
 }
 
 impl Loader {
@@ -987,28 +1426,25 @@ impl Loader {
     ) -> Loader {
         let (op_tx, op_rx) = unbounded();
         let (invalidate_tx, invalidate_rx) = unbounded();
-        #[cfg(feature = "invalidate_path")]
         let (invalidate_path_tx, invalidate_path_rx) = unbounded();
         let (metadata_tx, metadata_rx) = unbounded();
         let (data_tx, data_rx) = unbounded();
         let (resolve_tx, resolve_rx) = unbounded();
         Loader {
             data: LoaderState {
-                handle_allocator,
+                handle_allocator: handle_allocator.clone(),
                 load_states: DashMap::default(),
                 uuid_to_load: DashMap::default(),
                 op_rx,
                 op_tx,
                 invalidate_rx,
                 invalidate_tx,
-                #[cfg(feature = "invalidate_path")]
                 invalidate_path_rx,
-                #[cfg(feature = "invalidate_path")]
                 invalidate_path_tx,
                 pending_reloads: Vec::new(),
                 indirect_states: DashMap::new(),
                 indirect_to_load: DashMap::new(),
-                indirect_table: IndirectionTable(Arc::new(DashMap::new())),
+                indirect_table: IndirectionTable::new(handle_allocator),
                 responses: IORequestChannels {
                     data_rx,
                     data_tx,
@@ -1017,6 +1453,10 @@ impl Loader {
                     resolve_rx,
                     resolve_tx,
                 },
+                max_update_bytes_per_process: None,
+                deferred_data_responses: VecDeque::new(),
+                expected_types: DashMap::new(),
+                dependencies_changed: AtomicBool::new(false),
             },
             io,
         }
@@ -1066,19 +1506,35 @@ impl Loader {
 
     /// Returns the asset load status.
     ///
+    /// An asset whose load failed in a way that is retried is still [`LoadStatus::Loading`], see
+    /// [`Loader::get_load_failure`] for the failure.
+    ///
     /// # Parameters
     ///
-    /// * `load`: ID allocated by `Loader` to track loading of the asset.
-    pub fn get_load_status(&self, load: LoadHandle) -> LoadStatus {
-        let load = if load.is_indirect() {
-            if let Some(load) = self.data.indirect_table.resolve(load) {
+    /// * `handle`: ID allocated by `Loader` to track loading of the asset.
+    pub fn get_load_status(&self, handle: LoadHandle) -> LoadStatus {
+        if !self.data.handle_allocator.is_valid(handle) {
+            return LoadStatus::NotRequested;
+        }
+        let load = if handle.is_indirect() {
+            if let Some(load) = self.data.indirect_table.resolve(handle) {
                 load
             } else {
-                return LoadStatus::Unresolved;
+                return match self.data.indirect_states.get(&handle) {
+                    Some(state) if !state.ambiguous.is_empty() => LoadStatus::Error(
+                        LoadFailure::Ambiguous(state.ambiguous.clone())
+                            .to_string()
+                            .into(),
+                    ),
+                    _ => LoadStatus::Unresolved,
+                };
             }
         } else {
-            load
+            handle
         };
+        if let Some(failure) = self.data.get_type_mismatch(handle, load) {
+            return LoadStatus::Error(failure.to_string().into());
+        }
         if let Some(load) = self.data.load_states.get(&load) {
             let version = load.versions.iter().max_by_key(|v| v.version);
             version
@@ -1092,7 +1548,13 @@ impl Loader {
                     }
                     LoadState::Loaded => LoadStatus::Loaded,
                     LoadState::UnloadRequested | LoadState::Unloading => LoadStatus::Unloading,
-                    _ => LoadStatus::Loading,
+                    _ => match &v.error {
+                        // the asset would wait for its dependencies forever
+                        Some(err @ LoadFailure::DependencyCycle(_)) => {
+                            LoadStatus::Error(err.to_string().into())
+                        }
+                        _ => LoadStatus::Loading,
+                    },
                 })
                 .unwrap_or(LoadStatus::NotRequested)
         } else {
@@ -1100,6 +1562,29 @@ impl Loader {
         }
     }
 
+    /// Returns the last failure while loading the newest version of an asset, if it has not
+    /// loaded since.
+    ///
+    /// # Parameters
+    ///
+    /// * `handle`: ID allocated by `Loader` to track loading of the asset.
+    pub fn get_load_failure(&self, handle: LoadHandle) -> Option<LoadFailure> {
+        if !self.data.handle_allocator.is_valid(handle) {
+            return None;
+        }
+        let load = if handle.is_indirect() {
+            self.data.indirect_table.resolve(handle)?
+        } else {
+            handle
+        };
+        if let Some(failure) = self.data.get_type_mismatch(handle, load) {
+            return Some(failure);
+        }
+        let load = self.data.load_states.get(&load)?;
+        let version = load.versions.iter().max_by_key(|v| v.version)?;
+        version.error.clone()
+    }
+
     pub fn add_ref_handle(&self, handle: LoadHandle) {
         self.data.add_ref_handle(handle, 1);
     }
@@ -1175,6 +1660,86 @@ impl Loader {
         Ok(())
     }
 
+    /// Loads the provided assets, calling [`Loader::process`] until every asset has either loaded
+    /// or failed to load, or until `timeout` has passed.
+    ///
+    /// Returns one result per requested asset, in the same order as `ids`. A reference is kept
+    /// for each asset that loaded successfully, which must be released using [`Loader::remove_ref`].
+    ///
+    /// An asset fails as soon as it or one of its dependencies reports an error, or when it has not
+    /// finished loading before `timeout`. Assets that do not exist are retried until `timeout`,
+    /// as they may not have been imported yet. The returned [`LoadBlockingError`] contains the chain
+    /// of dependencies leading to the asset that caused the failure.
+    ///
+    /// # Parameters
+    ///
+    /// * `ids`: UUIDs of the assets to load.
+    /// * `asset_storage`: Storage for all assets of all asset types.
+    /// * `resolver`: Resolver for indirect handles that are encountered while loading.
+    /// * `timeout`: Maximum time to wait for the assets to load.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn load_blocking(
+        &mut self,
+        ids: &[AssetUuid],
+        asset_storage: &dyn AssetStorage,
+        resolver: &dyn IndirectionResolver,
+        timeout: Duration,
+    ) -> Result<Vec<std::result::Result<LoadHandle, LoadBlockingError>>> {
+        let start = Instant::now();
+        let handles: Vec<_> = ids.iter().map(|id| self.add_ref(*id)).collect();
+        let mut results = vec![None; ids.len()];
+        loop {
+            self.process(asset_storage, resolver)?;
+            let timed_out = start.elapsed() >= timeout;
+            for (idx, (id, handle)) in ids.iter().zip(&handles).enumerate() {
+                if results[idx].is_some() {
+                    continue;
+                }
+                if let LoadStatus::Loaded = self.get_load_status(*handle) {
+                    results[idx] = Some(Ok(*handle));
+                } else if let Some((dependency_chain, failure)) =
+                    self.data.find_load_failure(*id, timed_out)
+                {
+                    self.remove_ref(*handle);
+                    results[idx] = Some(Err(LoadBlockingError {
+                        dependency_chain,
+                        failure,
+                    }));
+                }
+            }
+            if results.iter().all(Option::is_some) {
+                break;
+            }
+            std::thread::sleep(LOAD_BLOCKING_POLL_INTERVAL);
+        }
+        Ok(results.into_iter().map(Option::unwrap).collect())
+    }
+
+    /// Registers the asset type that `handle` is expected to resolve to, for example by a
+    /// [`Handle`](crate::handle::Handle). This is called automatically when a typed handle is created, unless
+    /// the `type_checked_handles` feature is disabled.
+    ///
+    /// If the asset turns out to have a different type, [`Loader::get_load_status`] returns
+    /// [`LoadStatus::Error`] for the handle instead of loading the asset.
+    ///
+    /// # Parameters
+    ///
+    /// * `handle`: Direct or indirect handle of the asset.
+    /// * `asset_type`: Expected type of the asset.
+    pub fn expect_asset_type(&self, handle: LoadHandle, asset_type: AssetTypeId) {
+        self.data.expected_types.insert(handle, asset_type);
+    }
+
+    /// Limits the number of artifact bytes that are passed to [`AssetStorage::update_asset`] in
+    /// a single call to [`Loader::process`]. Data that exceeds the limit is passed to the storage in
+    /// subsequent calls, spreading the cost of deserialization over multiple frames.
+    ///
+    /// At least one asset is updated per call, regardless of its size. `None` removes the limit,
+    /// which is the default.
+    pub fn set_max_update_bytes_per_process(&mut self, max_bytes: Option<usize>) {
+        self.data.max_update_bytes_per_process = max_bytes;
+    }
+
     /// Returns a reference to the loader's [`IndirectionTable`].
     ///
     /// When a user fetches an asset by LoadHandle, implementors of [`AssetStorage`]
@@ -1220,11 +1785,243 @@ fn commit_asset(
         .as_ref()
         .expect("in LoadingAsset state but asset_type is None");
     asset_storage.commit_asset_version(asset_type, handle, version_load.version);
-    version_load.state = LoadState::Loaded;
+    version_load.set_state(LoadState::Loaded);
     for version_load in load.versions.iter_mut() {
         if version_load.version != version {
             assert_eq!(LoadState::Loaded, version_load.state);
-            version_load.state = LoadState::UnloadRequested;
+            version_load.set_state(LoadState::UnloadRequested);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::DefaultIndirectionResolver;
+
+    fn failed_version(state: LoadState) -> AssetVersionLoad {
+        AssetVersionLoad {
+            state,
+            metadata: None,
+            asset_metadata: None,
+            asset_type: None,
+            auto_commit: true,
+            version: 1,
+            error: Some(LoadFailure::Error("failed".to_string())),
+        }
+    }
+
+    #[test]
+    fn error_is_kept_while_retrying() {
+        let mut version_load = failed_version(LoadState::WaitingForMetadata);
+        version_load.set_state(LoadState::RequestingMetadata);
+        assert!(version_load.error.is_some());
+        version_load.set_state(LoadState::WaitingForMetadata);
+        assert!(version_load.error.is_some());
+
+        let mut version_load = failed_version(LoadState::WaitingForData);
+        version_load.set_state(LoadState::RequestingData);
+        assert!(version_load.error.is_some());
+    }
+
+    #[test]
+    fn error_is_cleared_when_leaving_the_failed_state() {
+        let transitions = [
+            (
+                LoadState::RequestingMetadata,
+                LoadState::RequestDependencies,
+            ),
+            (
+                LoadState::WaitingForMetadata,
+                LoadState::RequestDependencies,
+            ),
+            (LoadState::WaitingForDependencies, LoadState::WaitingForData),
+            (LoadState::RequestingData, LoadState::LoadingAsset),
+            (LoadState::RequestingData, LoadState::WaitingForMetadata),
+            (LoadState::LoadingAsset, LoadState::LoadedUncommitted),
+            (LoadState::LoadingAsset, LoadState::Loaded),
+        ];
+        for (from, to) in transitions.iter() {
+            let mut version_load = failed_version(*from);
+            version_load.set_state(*to);
+            assert!(
+                version_load.error.is_none(),
+                "error kept in {:?} => {:?}",
+                from,
+                to
+            );
+        }
+
+        // staying in the failed state keeps the error
+        let mut version_load = failed_version(LoadState::WaitingForDependencies);
+        version_load.set_state(LoadState::WaitingForDependencies);
+        assert!(version_load.error.is_some());
+    }
+
+    /// Serves the artifact metadata of a fixed set of assets.
+    struct MetadataIO(Vec<ArtifactMetadata>);
+
+    impl LoaderIO for MetadataIO {
+        fn get_asset_metadata_with_dependencies(&mut self, request: MetadataRequest) {
+            let results = request
+                .requested_assets()
+                .filter_map(|asset_id| self.0.iter().find(|a| a.asset_id == *asset_id))
+                .map(|artifact_metadata| MetadataRequestResult {
+                    artifact_metadata: artifact_metadata.clone(),
+                    asset_metadata: None,
+                })
+                .collect();
+            request.complete(results);
+        }
+
+        fn get_asset_candidates(&mut self, _requests: Vec<ResolveRequest>) {}
+
+        fn get_artifacts(&mut self, _requests: Vec<DataRequest>) {}
+
+        fn tick(&mut self, _loader: &mut LoaderState) {}
+
+        fn with_runtime(&self, _f: &mut dyn FnMut(&tokio::runtime::Runtime)) {}
+    }
+
+    struct NoStorage;
+
+    impl AssetStorage for NoStorage {
+        fn update_asset(
+            &self,
+            _loader_info: &dyn LoaderInfoProvider,
+            _asset_type_id: &AssetTypeId,
+            _data: Vec<u8>,
+            _load_handle: LoadHandle,
+            _load_op: AssetLoadOp,
+            _version: u32,
+        ) -> std::result::Result<(), Box<dyn Error + Send + 'static>> {
+            Ok(())
+        }
+
+        fn commit_asset_version(&self, _: &AssetTypeId, _: LoadHandle, _: u32) {}
+
+        fn free(&self, _: &AssetTypeId, _: LoadHandle, _: u32) {}
+    }
+
+    #[test]
+    fn dependency_cycle_is_found_when_dependencies_arrive() {
+        let artifact = |id: u8, dep: u8| ArtifactMetadata {
+            asset_id: AssetUuid([id; 16]),
+            load_deps: vec![AssetRef::Uuid(AssetUuid([dep; 16]))],
+            ..Default::default()
+        };
+        let mut loader = Loader::new(Box::new(MetadataIO(vec![artifact(1, 2), artifact(2, 1)])));
+        let handle = loader.add_ref(AssetUuid([1; 16]));
+        // well within DEPENDENCY_CYCLE_CHECK_INTERVAL, so the cycle is not found by a stall check
+        for _ in 0..10 {
+            loader
+                .process(&NoStorage, &DefaultIndirectionResolver)
+                .unwrap();
+        }
+        match loader.get_load_status(handle) {
+            LoadStatus::Error(err) => {
+                assert!(err.to_string().contains("cycle"), "{}", err)
+            }
+            status => panic!("unexpected status {:?}", status),
+        }
+        // nothing is checked again until dependencies change or the load stalls
+        assert!(!loader.data.dependencies_changed.load(Ordering::Relaxed));
+    }
+
+    #[test]
+    fn missing_asset_is_loading_with_a_failure() {
+        let mut loader = Loader::new(Box::new(MetadataIO(Vec::new())));
+        let handle = loader.add_ref(AssetUuid([1; 16]));
+        for _ in 0..10 {
+            loader
+                .process(&NoStorage, &DefaultIndirectionResolver)
+                .unwrap();
+        }
+        // the metadata is requested again, so the load has not failed for good
+        assert!(matches!(
+            loader.get_load_status(handle),
+            LoadStatus::Loading
+        ));
+        assert_eq!(
+            loader.get_load_failure(handle),
+            Some(LoadFailure::DoesNotExist)
+        );
+    }
+
+    /// Serves the artifact metadata of `MetadataIO`, and one byte of data for each artifact.
+    struct DataIO {
+        metadata: MetadataIO,
+        requested: Arc<std::sync::Mutex<Vec<AssetUuid>>>,
+    }
+
+    impl LoaderIO for DataIO {
+        fn get_asset_metadata_with_dependencies(&mut self, request: MetadataRequest) {
+            self.metadata.get_asset_metadata_with_dependencies(request)
+        }
+
+        fn get_asset_candidates(&mut self, _requests: Vec<ResolveRequest>) {}
+
+        fn get_artifacts(&mut self, requests: Vec<DataRequest>) {
+            for request in requests {
+                self.requested.lock().unwrap().push(request.asset_id());
+                request.complete(vec![0]);
+            }
+        }
+
+        fn tick(&mut self, _loader: &mut LoaderState) {}
+
+        fn with_runtime(&self, _f: &mut dyn FnMut(&tokio::runtime::Runtime)) {}
+    }
+
+    /// Records the assets in the order they are updated.
+    #[derive(Default)]
+    struct UpdateOrderStorage(std::sync::Mutex<Vec<AssetUuid>>);
+
+    impl AssetStorage for UpdateOrderStorage {
+        fn update_asset(
+            &self,
+            loader_info: &dyn LoaderInfoProvider,
+            _asset_type_id: &AssetTypeId,
+            _data: Vec<u8>,
+            load_handle: LoadHandle,
+            load_op: AssetLoadOp,
+            _version: u32,
+        ) -> std::result::Result<(), Box<dyn Error + Send + 'static>> {
+            let asset_id = loader_info.get_asset_id(load_handle).unwrap();
+            self.0.lock().unwrap().push(asset_id);
+            load_op.complete();
+            Ok(())
+        }
+
+        fn commit_asset_version(&self, _: &AssetTypeId, _: LoadHandle, _: u32) {}
+
+        fn free(&self, _: &AssetTypeId, _: LoadHandle, _: u32) {}
+    }
+
+    #[test]
+    fn data_over_the_update_budget_keeps_its_order() {
+        let artifact = |id: u8| ArtifactMetadata {
+            asset_id: AssetUuid([id; 16]),
+            ..Default::default()
+        };
+        let requested = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let mut loader = Loader::new(Box::new(DataIO {
+            metadata: MetadataIO(vec![artifact(1), artifact(2), artifact(3)]),
+            requested: requested.clone(),
+        }));
+        // one artifact is updated per `process`
+        loader.set_max_update_bytes_per_process(Some(1));
+        for id in 1..=3 {
+            loader.add_ref(AssetUuid([id; 16]));
+        }
+        let storage = UpdateOrderStorage::default();
+        for _ in 0..10 {
+            loader
+                .process(&storage, &DefaultIndirectionResolver)
+                .unwrap();
         }
+        let requested = requested.lock().unwrap();
+        assert_eq!(requested.len(), 3);
+        assert_eq!(*storage.0.lock().unwrap(), *requested);
     }
 }
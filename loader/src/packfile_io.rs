@@ -14,7 +14,7 @@ use thread_local::ThreadLocal;
 use memmap::{Mmap, MmapOptions};
 
 use crate::{
-    io::{DataRequest, LoaderIO, MetadataRequest, MetadataRequestResult, ResolveRequest},
+    io::{self, DataRequest, LoaderIO, MetadataRequest, MetadataRequestResult, ResolveRequest},
     loader::LoaderState,
 };
 
@@ -241,10 +241,25 @@ impl PackfileReaderInner {
     ) -> capnp::Result<Vec<(std::path::PathBuf, Vec<AssetMetadata>)>> {
         let reader = self.reader.get_reader()?;
         let entries = reader.get_entries()?;
-        if let Some(indices) = self.assets_by_path.get(request.identifier().path()) {
+        let path = match request.identifier().as_tag() {
+            None => request.identifier().path(),
+            Some(_) => {
+                let mut assets = Vec::new();
+                for (path, indices) in &self.assets_by_path {
+                    for idx in indices {
+                        let entry = entries.get(*idx);
+                        let asset_metadata =
+                            distill_schema::parse_db_metadata(&entry.get_asset_metadata()?);
+                        assets.push((std::path::PathBuf::from(path), asset_metadata));
+                    }
+                }
+                return Ok(io::tag_candidates(request.identifier(), assets));
+            }
+        };
+        if let Some(indices) = self.assets_by_path.get(path) {
             let mut metadata = Vec::with_capacity(indices.len());
             // TODO canonicalize the requested path
-            let path = std::path::PathBuf::from(request.identifier().path().replace("\\", "/"));
+            let path = std::path::PathBuf::from(path.replace("\\", "/"));
             for idx in indices {
                 let entry = entries.get(*idx);
                 let asset_metadata =
@@ -253,10 +268,7 @@ impl PackfileReaderInner {
             }
             Ok(vec![(path, metadata)])
         } else {
-            Err(capnp::Error::failed(format!(
-                "Identifier {:?} not found in packfile",
-                request.identifier()
-            )))
+            Ok(Vec::new())
         }
     }
 }
@@ -0,0 +1,421 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    path::{Path, PathBuf},
+    sync::Mutex,
+    time::Duration,
+};
+
+use distill_core::{ArtifactId, AssetMetadata, AssetRef, AssetUuid};
+use distill_schema::{data, pack::pack_file};
+use instant::Instant;
+
+use crate::{
+    io::{self, MetadataRequestResult},
+    storage::IndirectIdentifier,
+};
+
+const INDEX_FILE_NAME: &str = "index.pack";
+const LATEST_CHANGE_FILE_NAME: &str = "latest_change";
+const ARTIFACTS_DIR_NAME: &str = "artifacts";
+/// Minimum time between writes of the index, as metadata tends to arrive over many consecutive ticks while loading.
+const FLUSH_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Default)]
+struct RpcCacheState {
+    assets: HashMap<AssetUuid, AssetMetadata>,
+    asset_paths: HashMap<AssetUuid, PathBuf>,
+    latest_change: Option<u64>,
+    dirty: bool,
+    last_flush: Option<Instant>,
+}
+
+/// Local cache of metadata and artifacts received by [`RpcIO`](crate::rpc_io::RpcIO), used to serve
+/// requests while the daemon is unreachable.
+///
+/// Artifacts are stored as one file per [`ArtifactId`], while asset metadata and paths are stored in
+/// an index which is periodically rewritten by [`RpcCache::flush`], and when the cache is dropped.
+pub(crate) struct RpcCache {
+    dir: PathBuf,
+    state: Mutex<RpcCacheState>,
+}
+
+impl RpcCache {
+    pub(crate) fn open(dir: PathBuf) -> std::io::Result<Self> {
+        fs::create_dir_all(dir.join(ARTIFACTS_DIR_NAME))?;
+        let mut state = RpcCacheState::default();
+        match fs::read(dir.join(INDEX_FILE_NAME)) {
+            Ok(bytes) => {
+                if let Err(err) = read_index(&bytes, &mut state) {
+                    log::error!("Failed to read RpcIO cache index in {:?}: {}", dir, err);
+                    state = RpcCacheState::default();
+                }
+            }
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+            Err(err) => return Err(err),
+        }
+        // The change number is only valid if the index it belongs to was read successfully.
+        if !state.assets.is_empty() {
+            if let Ok(bytes) = fs::read(dir.join(LATEST_CHANGE_FILE_NAME)) {
+                if bytes.len() == 8 {
+                    state.latest_change =
+                        Some(u64::from_le_bytes(distill_core::utils::make_array(&bytes)));
+                }
+            }
+        }
+        log::debug!(
+            "Opened RpcIO cache in {:?} with {} assets",
+            dir,
+            state.assets.len()
+        );
+        Ok(RpcCache {
+            dir,
+            state: Mutex::new(state),
+        })
+    }
+
+    fn artifact_path(&self, artifact_id: ArtifactId) -> PathBuf {
+        self.dir
+            .join(ARTIFACTS_DIR_NAME)
+            .join(format!("{:016x}", artifact_id.0))
+    }
+
+    /// Returns the latest change number of the daemon's asset change log that the cache has seen.
+    pub(crate) fn latest_change(&self) -> Option<u64> {
+        self.state.lock().unwrap().latest_change
+    }
+
+    pub(crate) fn set_latest_change(&self, latest_change: u64) {
+        let mut state = self.state.lock().unwrap();
+        if state.latest_change != Some(latest_change) {
+            state.latest_change = Some(latest_change);
+            state.dirty = true;
+        }
+    }
+
+    /// Returns the IDs of all assets in the cache.
+    pub(crate) fn asset_ids(&self) -> Vec<AssetUuid> {
+        self.state.lock().unwrap().assets.keys().cloned().collect()
+    }
+
+    pub(crate) fn insert_metadata(&self, results: &[MetadataRequestResult]) {
+        let mut state = self.state.lock().unwrap();
+        for result in results {
+            let metadata = result
+                .asset_metadata
+                .clone()
+                .unwrap_or_else(|| AssetMetadata {
+                    id: result.artifact_metadata.asset_id,
+                    artifact: Some(result.artifact_metadata.clone()),
+                    ..Default::default()
+                });
+            state.assets.insert(metadata.id, metadata);
+        }
+        state.dirty = true;
+    }
+
+    pub(crate) fn insert_candidates(&self, candidates: &[(PathBuf, Vec<AssetMetadata>)]) {
+        let mut state = self.state.lock().unwrap();
+        for (path, assets) in candidates {
+            for asset in assets {
+                // Tag candidates have an empty path when the daemon does not know the asset's path
+                if !path.as_os_str().is_empty() {
+                    state.asset_paths.insert(asset.id, path.clone());
+                }
+                state.assets.insert(asset.id, asset.clone());
+            }
+        }
+        state.dirty = true;
+    }
+
+    pub(crate) fn insert_artifact(&self, artifact_id: ArtifactId, data: &[u8]) {
+        let path = self.artifact_path(artifact_id);
+        if path.exists() {
+            return;
+        }
+        if let Err(err) = write_file(&path, data) {
+            log::error!(
+                "Failed to write artifact to RpcIO cache {:?}: {}",
+                path,
+                err
+            );
+        }
+    }
+
+    /// Returns the cached metadata for the requested assets and their load dependencies,
+    /// along with the assets that were not found in the cache.
+    pub(crate) fn get_metadata_with_dependencies<'a>(
+        &self,
+        requested: impl Iterator<Item = &'a AssetUuid>,
+        include_asset_metadata: bool,
+    ) -> (Vec<MetadataRequestResult>, Vec<AssetUuid>) {
+        let state = self.state.lock().unwrap();
+        let mut to_visit: Vec<_> = requested.cloned().collect();
+        let mut visited: HashSet<AssetUuid> = to_visit.iter().cloned().collect();
+        let mut missing = Vec::new();
+        let mut results = Vec::new();
+        while let Some(uuid) = to_visit.pop() {
+            let metadata = match state.assets.get(&uuid) {
+                Some(metadata) if metadata.artifact.is_some() => metadata,
+                _ => {
+                    missing.push(uuid);
+                    continue;
+                }
+            };
+            let artifact_metadata = metadata.artifact.clone().unwrap();
+            for dep in &artifact_metadata.load_deps {
+                if let AssetRef::Uuid(dep_uuid) = dep {
+                    if visited.insert(*dep_uuid) {
+                        to_visit.push(*dep_uuid);
+                    }
+                }
+            }
+            results.push(MetadataRequestResult {
+                artifact_metadata,
+                asset_metadata: if include_asset_metadata {
+                    Some(metadata.clone())
+                } else {
+                    None
+                },
+            });
+        }
+        (results, missing)
+    }
+
+    pub(crate) fn get_artifact(&self, artifact_id: ArtifactId) -> Option<Vec<u8>> {
+        fs::read(self.artifact_path(artifact_id)).ok()
+    }
+
+    pub(crate) fn get_candidates(&self, path: &str) -> Option<Vec<(PathBuf, Vec<AssetMetadata>)>> {
+        let state = self.state.lock().unwrap();
+        let path = Path::new(path);
+        let assets: Vec<_> = state
+            .asset_paths
+            .iter()
+            .filter(|(_, asset_path)| asset_path.as_path() == path)
+            .filter_map(|(id, _)| state.assets.get(id).cloned())
+            .collect();
+        if assets.is_empty() {
+            None
+        } else {
+            Some(vec![(path.to_path_buf(), assets)])
+        }
+    }
+
+    pub(crate) fn get_tag_candidates(
+        &self,
+        id: &IndirectIdentifier,
+    ) -> Option<Vec<(PathBuf, Vec<AssetMetadata>)>> {
+        let state = self.state.lock().unwrap();
+        let assets = state.asset_paths.iter().filter_map(|(asset_id, path)| {
+            state
+                .assets
+                .get(asset_id)
+                .map(|asset| (path.clone(), asset.clone()))
+        });
+        let candidates = io::tag_candidates(id, assets);
+        if candidates.is_empty() {
+            None
+        } else {
+            Some(candidates)
+        }
+    }
+
+    /// Writes the cache index to disk if it has changed and was not written recently.
+    pub(crate) fn flush(&self) {
+        let mut state = self.state.lock().unwrap();
+        if state.dirty
+            && state
+                .last_flush
+                .is_none_or(|last_flush| last_flush.elapsed() >= FLUSH_INTERVAL)
+        {
+            self.write_index(&mut state);
+        }
+    }
+
+    fn write_index(&self, state: &mut RpcCacheState) {
+        state.last_flush = Some(Instant::now());
+        let mut message = capnp::message::Builder::new_default();
+        {
+            let index = message.init_root::<pack_file::Builder<'_>>();
+            let mut entries = index.init_entries(state.assets.len() as u32);
+            for (idx, (id, metadata)) in state.assets.iter().enumerate() {
+                let mut entry = entries.reborrow().get(idx as u32);
+                distill_schema::build_asset_metadata(
+                    metadata,
+                    &mut entry.reborrow().init_asset_metadata(),
+                    data::AssetSource::File,
+                );
+                if let Some(path) = state.asset_paths.get(id) {
+                    entry.set_path(path.to_string_lossy().as_bytes());
+                }
+            }
+        }
+        let mut bytes = Vec::new();
+        let result = capnp::serialize::write_message(&mut bytes, &message)
+            .map_err(std::io::Error::other)
+            .and_then(|_| write_file(&self.dir.join(INDEX_FILE_NAME), &bytes))
+            .and_then(|_| match state.latest_change {
+                Some(latest_change) => write_file(
+                    &self.dir.join(LATEST_CHANGE_FILE_NAME),
+                    &latest_change.to_le_bytes(),
+                ),
+                None => Ok(()),
+            });
+        match result {
+            Ok(()) => state.dirty = false,
+            Err(err) => log::error!(
+                "Failed to write RpcIO cache index in {:?}: {}",
+                self.dir,
+                err
+            ),
+        }
+    }
+}
+
+impl Drop for RpcCache {
+    fn drop(&mut self) {
+        let state = self.state.get_mut().unwrap();
+        if state.dirty {
+            let mut state = std::mem::take(state);
+            self.write_index(&mut state);
+        }
+    }
+}
+
+/// Writes to a temporary file first and renames it into place, to avoid leaving partially
+/// written files in the cache.
+fn write_file(path: &Path, data: &[u8]) -> std::io::Result<()> {
+    let tmp_path = path.with_extension("tmp");
+    fs::write(&tmp_path, data).and_then(|_| fs::rename(&tmp_path, path))
+}
+
+fn read_index(mut bytes: &[u8], state: &mut RpcCacheState) -> capnp::Result<()> {
+    let mut options = capnp::message::ReaderOptions::new();
+    options.traversal_limit_in_words(Some(1 << 31));
+    let message = capnp::serialize::read_message_from_flat_slice(&mut bytes, options)?;
+    let reader = message.get_root::<pack_file::Reader<'_>>()?;
+    for entry in reader.get_entries()? {
+        let metadata = distill_schema::parse_db_metadata(&entry.get_asset_metadata()?);
+        let path = entry.get_path()?;
+        if !path.is_empty() {
+            state
+                .asset_paths
+                .insert(metadata.id, PathBuf::from(std::str::from_utf8(path)?));
+        }
+        state.assets.insert(metadata.id, metadata);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use distill_core::ArtifactMetadata;
+
+    use super::*;
+
+    fn asset(id: u8, load_deps: Vec<AssetUuid>) -> AssetMetadata {
+        AssetMetadata {
+            id: AssetUuid([id; 16]),
+            artifact: Some(ArtifactMetadata {
+                id: ArtifactId(id as u64),
+                asset_id: AssetUuid([id; 16]),
+                load_deps: load_deps.into_iter().map(AssetRef::Uuid).collect(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    fn metadata_result(asset: &AssetMetadata) -> MetadataRequestResult {
+        MetadataRequestResult {
+            artifact_metadata: asset.artifact.clone().unwrap(),
+            asset_metadata: Some(asset.clone()),
+        }
+    }
+
+    fn sorted_ids(results: &[MetadataRequestResult]) -> Vec<AssetUuid> {
+        let mut ids: Vec<_> = results
+            .iter()
+            .map(|result| result.artifact_metadata.asset_id)
+            .collect();
+        ids.sort();
+        ids
+    }
+
+    #[test]
+    fn persists_across_reopen() {
+        let dir = tempfile::tempdir().unwrap();
+        let a = asset(1, vec![AssetUuid([2; 16])]);
+        let b = asset(2, vec![]);
+        {
+            let cache = RpcCache::open(dir.path().to_path_buf()).unwrap();
+            cache.insert_metadata(&[metadata_result(&a)]);
+            cache.insert_candidates(&[(PathBuf::from("b.txt"), vec![b.clone()])]);
+            cache.insert_artifact(ArtifactId(1), b"artifact a");
+            cache.set_latest_change(7);
+        }
+        // temporary files are renamed into place
+        let leftovers: Vec<_> = fs::read_dir(dir.path())
+            .unwrap()
+            .chain(fs::read_dir(dir.path().join(ARTIFACTS_DIR_NAME)).unwrap())
+            .map(|entry| entry.unwrap().path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "tmp"))
+            .collect();
+        assert!(leftovers.is_empty(), "{:?}", leftovers);
+
+        let cache = RpcCache::open(dir.path().to_path_buf()).unwrap();
+        assert_eq!(cache.latest_change(), Some(7));
+        let (results, missing) = cache.get_metadata_with_dependencies([a.id].iter(), true);
+        assert_eq!(sorted_ids(&results), vec![a.id, b.id]);
+        assert!(missing.is_empty());
+        assert_eq!(
+            cache.get_artifact(ArtifactId(1)).as_deref(),
+            Some(&b"artifact a"[..])
+        );
+        assert!(cache.get_artifact(ArtifactId(2)).is_none());
+        let candidates = cache.get_candidates("b.txt").unwrap();
+        assert_eq!(candidates[0].0, PathBuf::from("b.txt"));
+        assert_eq!(candidates[0].1[0].id, b.id);
+    }
+
+    #[test]
+    fn reports_missing_dependencies() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = RpcCache::open(dir.path().to_path_buf()).unwrap();
+        let a = asset(1, vec![AssetUuid([2; 16])]);
+        cache.insert_metadata(&[metadata_result(&a)]);
+
+        let (results, mut missing) =
+            cache.get_metadata_with_dependencies([a.id, AssetUuid([3; 16])].iter(), false);
+        assert_eq!(sorted_ids(&results), vec![a.id]);
+        assert!(results[0].asset_metadata.is_none());
+        missing.sort();
+        assert_eq!(missing, vec![AssetUuid([2; 16]), AssetUuid([3; 16])]);
+    }
+
+    #[test]
+    fn skips_empty_candidate_paths() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = RpcCache::open(dir.path().to_path_buf()).unwrap();
+        let a = asset(1, vec![]);
+        cache.insert_candidates(&[(PathBuf::new(), vec![a.clone()])]);
+
+        assert!(cache.get_candidates("").is_none());
+        let (results, missing) = cache.get_metadata_with_dependencies([a.id].iter(), true);
+        assert_eq!(sorted_ids(&results), vec![a.id]);
+        assert!(missing.is_empty());
+    }
+
+    #[test]
+    fn ignores_corrupt_index() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join(INDEX_FILE_NAME), b"not an index").unwrap();
+        fs::write(dir.path().join(LATEST_CHANGE_FILE_NAME), 7u64.to_le_bytes()).unwrap();
+
+        let cache = RpcCache::open(dir.path().to_path_buf()).unwrap();
+        assert!(cache.asset_ids().is_empty());
+        // the change number of an unreadable index would skip reloading changed assets
+        assert_eq!(cache.latest_change(), None);
+    }
+}
@@ -1,4 +1,11 @@
-use std::{error::Error, path::PathBuf, sync::Mutex};
+use std::{
+    collections::HashMap,
+    error::Error,
+    fmt,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
 
 use capnp::message::ReaderOptions;
 use capnp_rpc::{pry, rpc_twoparty_capnp, twoparty, RpcSystem};
@@ -6,6 +13,7 @@ use crossbeam_channel::{unbounded, Receiver, Sender};
 use distill_core::{utils, AssetMetadata, AssetUuid};
 use distill_schema::{data::asset_change_event, parse_db_metadata, service::asset_hub};
 use futures_util::AsyncReadExt;
+use instant::Instant;
 use tokio::{
     net::TcpStream,
     runtime::{Builder, Runtime},
@@ -13,16 +21,71 @@ use tokio::{
 };
 
 use crate::{
-    io::{DataRequest, LoaderIO, MetadataRequest, MetadataRequestResult, ResolveRequest},
+    io::{self, DataRequest, LoaderIO, MetadataRequest, MetadataRequestResult, ResolveRequest},
     loader::LoaderState,
+    rpc_cache::RpcCache,
 };
 
 type Promise<T> = capnp::capability::Promise<T, capnp::Error>;
 
+/// Delay before the first reconnection attempt after a connection failure.
+const MIN_RECONNECT_DELAY: Duration = Duration::from_millis(250);
+/// Upper bound for the delay between reconnection attempts, which doubles after every failure.
+const MAX_RECONNECT_DELAY: Duration = Duration::from_secs(10);
+
+/// Status of the connection between an [`RpcIO`] and the daemon, as returned by [`RpcIO::connection_status`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConnectionStatus {
+    /// No connection attempt has been made yet.
+    Disconnected,
+    /// A connection attempt is in progress.
+    Connecting,
+    /// Connected to a compatible daemon.
+    Connected,
+    /// The last connection attempt failed or the connection was lost.
+    /// Another attempt is made once `retry_in` has elapsed.
+    Error { error: String, retry_in: Duration },
+    /// The daemon uses a different protocol version than this loader.
+    /// Connection attempts continue in case a compatible daemon is started.
+    IncompatibleDaemon {
+        daemon_version: u32,
+        loader_version: u32,
+        retry_in: Duration,
+    },
+}
+
+/// Error returned when connecting to a daemon with a different [`PROTOCOL_VERSION`](distill_schema::PROTOCOL_VERSION).
+#[derive(Debug)]
+pub struct IncompatibleDaemonError {
+    /// Protocol version of the daemon, `0` for daemons that predate `getDaemonInfo`.
+    pub daemon_version: u32,
+    pub loader_version: u32,
+}
+
+impl fmt::Display for IncompatibleDaemonError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (too_old, outdated) = if self.daemon_version < self.loader_version {
+            ("daemon is too old", "daemon")
+        } else {
+            ("daemon is too new", "loader")
+        };
+        write!(
+            f,
+            "{} (daemon protocol version {}, loader protocol version {}), \
+             update the {} so that both are built from the same version of distill",
+            too_old, self.daemon_version, self.loader_version, outdated
+        )
+    }
+}
+
+impl Error for IncompatibleDaemonError {}
+
 /// a connection to the capnp provided rpc and an event receiver for SnapshotChange events
 struct RpcConnection {
     snapshot: asset_hub::snapshot::Client,
     snapshot_rx: Receiver<SnapshotChange>,
+    /// completed when the rpc system shuts down, i.e. when the connection is lost
+    disconnected_rx: oneshot::Receiver<()>,
 }
 
 /// an event which represents change to the assets
@@ -32,6 +95,11 @@ struct SnapshotChange {
     deleted_assets: Vec<AssetUuid>,
     changed_paths: Vec<PathBuf>,
     deleted_paths: Vec<PathBuf>,
+    /// The latest change number in the daemon's asset change log when this change was produced
+    latest_change: u64,
+    /// Set when the daemon's change log does not contain the changes since the last seen change,
+    /// meaning that any previously received data may be out of date.
+    invalidate_all: bool,
 }
 
 enum InternalConnectionState {
@@ -46,6 +114,10 @@ struct RpcRuntime {
     runtime: Runtime,
     local: tokio::task::LocalSet,
     connection: InternalConnectionState,
+    /// delay until the next connection attempt after the current one fails
+    reconnect_delay: Duration,
+    /// earliest time for the next connection attempt while in the error state
+    next_connect_attempt: Instant,
 }
 
 // While capnp_rpc does not impl Send or Sync, in our usage of the API there can only be one thread
@@ -55,7 +127,76 @@ struct RpcRuntime {
 unsafe impl Send for RpcRuntime {}
 
 impl RpcRuntime {
-    fn check_asset_changes(&mut self, loader: &LoaderState) {
+    fn set_connection_error(&mut self, err: Box<dyn Error>) {
+        log::error!(
+            "Error connecting RpcIO: {}, retrying in {:?}",
+            err,
+            self.reconnect_delay
+        );
+        self.next_connect_attempt = Instant::now() + self.reconnect_delay;
+        self.reconnect_delay = (self.reconnect_delay * 2).min(MAX_RECONNECT_DELAY);
+        self.connection = InternalConnectionState::Error(err);
+    }
+
+    fn update_connection_state(&mut self) {
+        match std::mem::replace(&mut self.connection, InternalConnectionState::None) {
+            InternalConnectionState::Connecting(mut pending_connection) => {
+                match pending_connection.try_recv() {
+                    Ok(Ok(conn)) => {
+                        log::info!("RpcIO connected to daemon");
+                        self.reconnect_delay = MIN_RECONNECT_DELAY;
+                        self.connection = InternalConnectionState::Connected(conn);
+                    }
+                    Ok(Err(err)) => self.set_connection_error(err),
+                    Err(oneshot::error::TryRecvError::Closed) => {
+                        self.set_connection_error(Box::new(oneshot::error::TryRecvError::Closed))
+                    }
+                    Err(oneshot::error::TryRecvError::Empty) => {
+                        self.connection = InternalConnectionState::Connecting(pending_connection)
+                    }
+                }
+            }
+            InternalConnectionState::Connected(mut conn) => match conn.disconnected_rx.try_recv() {
+                Err(oneshot::error::TryRecvError::Empty) => {
+                    self.connection = InternalConnectionState::Connected(conn)
+                }
+                _ => self.set_connection_error("connection to daemon lost".into()),
+            },
+            c => self.connection = c,
+        }
+    }
+
+    fn connection_status(&self) -> ConnectionStatus {
+        let retry_in = || {
+            let now = Instant::now();
+            if self.next_connect_attempt > now {
+                self.next_connect_attempt - now
+            } else {
+                Duration::from_secs(0)
+            }
+        };
+        match &self.connection {
+            InternalConnectionState::None => ConnectionStatus::Disconnected,
+            InternalConnectionState::Connecting(_) => ConnectionStatus::Connecting,
+            InternalConnectionState::Connected(_) => ConnectionStatus::Connected,
+            InternalConnectionState::Error(err) => {
+                if let Some(err) = err.downcast_ref::<IncompatibleDaemonError>() {
+                    ConnectionStatus::IncompatibleDaemon {
+                        daemon_version: err.daemon_version,
+                        loader_version: err.loader_version,
+                        retry_in: retry_in(),
+                    }
+                } else {
+                    ConnectionStatus::Error {
+                        error: err.to_string(),
+                        retry_in: retry_in(),
+                    }
+                }
+            }
+        }
+    }
+
+    fn check_asset_changes(&mut self, loader: &LoaderState, cache: Option<&RpcCache>) {
         self.connection =
             match std::mem::replace(&mut self.connection, InternalConnectionState::None) {
                 InternalConnectionState::Connected(mut conn) => {
@@ -77,6 +218,15 @@ impl RpcRuntime {
                             );
                             changed_assets.push(asset);
                         }
+                        if let Some(cache) = cache {
+                            if change.invalidate_all {
+                                log::info!(
+                                    "Daemon change log was reset, reloading all cached assets"
+                                );
+                                changed_assets.extend(cache.asset_ids());
+                            }
+                            cache.set_latest_change(change.latest_change);
+                        }
                         loader.invalidate_assets(&changed_assets);
                         let mut changed_paths = Vec::new();
                         for path in change.changed_paths {
@@ -93,7 +243,7 @@ impl RpcRuntime {
             };
     }
 
-    fn connect(&mut self, connect_string: String) {
+    fn connect(&mut self, connect_string: String, last_change: Option<u64>) {
         match self.connection {
             InternalConnectionState::Connected(_) | InternalConnectionState::Connecting(_) => {
                 panic!("Trying to connect while already connected or connecting")
@@ -126,9 +276,30 @@ impl RpcRuntime {
 
                 let hub: asset_hub::Client = rpc_system.bootstrap(rpc_twoparty_capnp::Side::Server);
 
-                let _disconnector = rpc_system.get_disconnector();
+                let disconnector = rpc_system.get_disconnector();
+
+                let (disconnected_tx, disconnected_rx) = oneshot::channel();
+                tokio::task::spawn_local(async move {
+                    if let Err(err) = rpc_system.await {
+                        log::debug!("RpcIO rpc system shut down: {}", err);
+                    }
+                    let _ = disconnected_tx.send(());
+                });
 
-                tokio::task::spawn_local(rpc_system);
+                log::trace!("Requesting daemon info..");
+                let daemon_version = match hub.get_daemon_info_request().send().promise.await {
+                    Ok(response) => response.get()?.get_info()?.get_version(),
+                    // daemons from before the protocol was versioned do not implement getDaemonInfo
+                    Err(err) if err.kind == capnp::ErrorKind::Unimplemented => 0,
+                    Err(err) => return Err(Box::new(err) as Box<dyn Error>),
+                };
+                if daemon_version != distill_schema::PROTOCOL_VERSION {
+                    let _ = disconnector.await;
+                    return Err(Box::new(IncompatibleDaemonError {
+                        daemon_version,
+                        loader_version: distill_schema::PROTOCOL_VERSION,
+                    }) as Box<dyn Error>);
+                }
 
                 log::trace!("Requesting RPC snapshot..");
                 let response = hub.get_snapshot_request().send().promise.await?;
@@ -138,7 +309,7 @@ impl RpcRuntime {
                 let (snapshot_tx, snapshot_rx) = unbounded();
                 let listener: asset_hub::listener::Client = capnp_rpc::new_client(ListenerImpl {
                     snapshot_channel: snapshot_tx,
-                    snapshot_change: None,
+                    snapshot_change: last_change,
                 });
 
                 let mut request = hub.register_listener_request();
@@ -146,6 +317,7 @@ impl RpcRuntime {
                 let rpc_conn = request.send().promise.await.map(|_| RpcConnection {
                     snapshot,
                     snapshot_rx,
+                    disconnected_rx,
                 })?;
                 log::trace!("Registered listener, done connecting RPC loader.");
 
@@ -163,6 +335,7 @@ pub struct RpcIO {
     connect_string: String,
     runtime: Mutex<RpcRuntime>,
     requests: QueuedRequests,
+    cache: Option<Arc<RpcCache>>,
 }
 
 #[derive(Default)]
@@ -186,74 +359,74 @@ impl RpcIO {
                 runtime: Builder::new_current_thread().enable_all().build()?,
                 local: tokio::task::LocalSet::new(),
                 connection: InternalConnectionState::None,
+                reconnect_delay: MIN_RECONNECT_DELAY,
+                next_connect_attempt: Instant::now(),
             }),
             requests: Default::default(),
+            cache: None,
         })
     }
+
+    /// Returns the current status of the connection to the daemon.
+    pub fn connection_status(&self) -> ConnectionStatus {
+        self.runtime.lock().unwrap().connection_status()
+    }
+
+    /// Persists metadata and artifacts received from the daemon in `cache_dir`.
+    ///
+    /// While the daemon is unreachable, requests are served from the cache. When the connection is
+    /// (re-)established, assets that changed since the last change seen by the cache are reloaded.
+    pub fn with_cache_dir(mut self, cache_dir: impl Into<PathBuf>) -> std::io::Result<Self> {
+        self.cache = Some(Arc::new(RpcCache::open(cache_dir.into())?));
+        Ok(self)
+    }
 }
 
 impl LoaderIO for RpcIO {
     fn get_asset_metadata_with_dependencies(&mut self, request: MetadataRequest) {
         self.requests.metadata_requests.push(request);
         let mut runtime = self.runtime.lock().unwrap();
-        process_requests(&mut runtime, &mut self.requests);
+        process_requests(&mut runtime, &mut self.requests, self.cache.as_ref());
     }
 
     fn get_asset_candidates(&mut self, requests: Vec<ResolveRequest>) {
         self.requests.resolve_requests.extend(requests);
         let mut runtime = self.runtime.lock().unwrap();
-        process_requests(&mut runtime, &mut self.requests);
+        process_requests(&mut runtime, &mut self.requests, self.cache.as_ref());
     }
 
     fn get_artifacts(&mut self, requests: Vec<DataRequest>) {
         self.requests.data_requests.extend(requests);
         let mut runtime = self.runtime.lock().unwrap();
-        process_requests(&mut runtime, &mut self.requests);
+        process_requests(&mut runtime, &mut self.requests, self.cache.as_ref());
     }
 
     fn tick(&mut self, loader: &mut LoaderState) {
         let mut runtime = self.runtime.lock().unwrap();
 
+        let last_change = self.cache.as_ref().and_then(|cache| cache.latest_change());
         match &runtime.connection {
-            InternalConnectionState::Error(err) => {
-                log::error!("Error connecting RpcIO: {}", err);
-                runtime.connect(self.connect_string.clone());
+            InternalConnectionState::Error(_) if Instant::now() >= runtime.next_connect_attempt => {
+                runtime.connect(self.connect_string.clone(), last_change);
             }
             InternalConnectionState::None => {
-                runtime.connect(self.connect_string.clone());
+                runtime.connect(self.connect_string.clone(), last_change);
             }
             _ => {}
         };
 
-        process_requests(&mut runtime, &mut self.requests);
-
-        runtime.connection =
-            match std::mem::replace(&mut runtime.connection, InternalConnectionState::None) {
-                // update connection state
-                InternalConnectionState::Connecting(mut pending_connection) => {
-                    match pending_connection.try_recv() {
-                        Ok(connection_result) => match connection_result {
-                            Ok(conn) => InternalConnectionState::Connected(conn),
-                            Err(err) => InternalConnectionState::Error(err),
-                        },
-                        Err(oneshot::error::TryRecvError::Closed) => {
-                            InternalConnectionState::Error(Box::new(
-                                oneshot::error::TryRecvError::Closed,
-                            ))
-                        }
-                        Err(oneshot::error::TryRecvError::Empty) => {
-                            InternalConnectionState::Connecting(pending_connection)
-                        }
-                    }
-                }
-                c => c,
-            };
+        process_requests(&mut runtime, &mut self.requests, self.cache.as_ref());
+
+        runtime.update_connection_state();
 
         runtime
             .local
             .block_on(&runtime.runtime, tokio::task::yield_now());
 
-        runtime.check_asset_changes(loader);
+        runtime.check_asset_changes(loader, self.cache.as_deref());
+        if let Some(cache) = &self.cache {
+            cache.flush();
+        }
     }
 
     fn with_runtime(&self, f: &mut dyn FnMut(&tokio::runtime::Runtime)) {
@@ -309,6 +482,9 @@ async fn do_resolve_request(
     resolve: &ResolveRequest,
     snapshot: &asset_hub::snapshot::Client,
 ) -> Result<Vec<(PathBuf, Vec<AssetMetadata>)>, capnp::Error> {
+    if resolve.identifier().as_tag().is_some() {
+        return do_resolve_tag_request(resolve, snapshot).await;
+    }
     let path = resolve.identifier().path();
     // get asset IDs at path
     let mut request = snapshot.get_assets_for_paths_request();
@@ -337,14 +513,59 @@ async fn do_resolve_request(
     Ok(results)
 }
 
-fn process_requests(runtime: &mut RpcRuntime, requests: &mut QueuedRequests) {
+async fn do_resolve_tag_request(
+    resolve: &ResolveRequest,
+    snapshot: &asset_hub::snapshot::Client,
+) -> Result<Vec<(PathBuf, Vec<AssetMetadata>)>, capnp::Error> {
+    let request = snapshot.get_all_asset_metadata_request();
+    let response = request.send().promise.await?;
+    let reader = response.get()?;
+    let assets = reader
+        .get_assets()?
+        .into_iter()
+        .map(|a| parse_db_metadata(&a))
+        .filter(|asset| resolve.identifier().matches_tags(&asset.search_tags))
+        .collect::<Vec<_>>();
+    if assets.is_empty() {
+        return Ok(Vec::new());
+    }
+    // get the paths of the matching assets
+    let mut request = snapshot.get_path_for_assets_request();
+    let mut asset_ids = request.get().init_assets(assets.len() as u32);
+    for (idx, asset) in assets.iter().enumerate() {
+        asset_ids.reborrow().get(idx as u32).set_id(&asset.id.0);
+    }
+    let response = request.send().promise.await?;
+    let reader = response.get()?;
+    let mut paths = HashMap::new();
+    for asset_path in reader.get_paths()? {
+        let id = utils::make_array(asset_path.get_id()?.get_id()?);
+        let path = PathBuf::from(std::str::from_utf8(asset_path.get_path()?)?);
+        paths.insert(AssetUuid(id), path);
+    }
+    let assets = assets.into_iter().map(|asset| {
+        let path = paths.remove(&asset.id).unwrap_or_default();
+        (path, asset)
+    });
+    Ok(io::tag_candidates(resolve.identifier(), assets))
+}
+
+fn process_requests(
+    runtime: &mut RpcRuntime,
+    requests: &mut QueuedRequests,
+    cache: Option<&Arc<RpcCache>>,
+) {
     if let InternalConnectionState::Connected(connection) = &runtime.connection {
         let len = requests.data_requests.len();
         for asset in requests.data_requests.drain(0..len) {
             let snapshot = connection.snapshot.clone();
+            let cache = cache.cloned();
             runtime.local.spawn_local(async move {
                 match do_import_artifact_request(&asset, &snapshot).await {
                     Ok(data) => {
+                        if let Some(cache) = cache {
+                            cache.insert_artifact(asset.artifact_id(), &data);
+                        }
                         asset.complete(data);
                     }
                     Err(e) => {
@@ -357,9 +578,13 @@ fn process_requests(runtime: &mut RpcRuntime, requests: &mut QueuedRequests) {
         let len = requests.metadata_requests.len();
         for m in requests.metadata_requests.drain(0..len) {
             let snapshot = connection.snapshot.clone();
+            let cache = cache.cloned();
             runtime.local.spawn_local(async move {
                 match do_metadata_request(&m, &snapshot).await {
                     Ok(data) => {
+                        if let Some(cache) = cache {
+                            cache.insert_metadata(&data);
+                        }
                         m.complete(data);
                     }
                     Err(e) => {
@@ -372,9 +597,13 @@ fn process_requests(runtime: &mut RpcRuntime, requests: &mut QueuedRequests) {
         let len = requests.resolve_requests.len();
         for m in requests.resolve_requests.drain(0..len) {
             let snapshot = connection.snapshot.clone();
+            let cache = cache.cloned();
             runtime.local.spawn_local(async move {
                 match do_resolve_request(&m, &snapshot).await {
                     Ok(data) => {
+                        if let Some(cache) = cache {
+                            cache.insert_candidates(&data);
+                        }
                         m.complete(data);
                     }
                     Err(e) => {
@@ -383,7 +612,63 @@ fn process_requests(runtime: &mut RpcRuntime, requests: &mut QueuedRequests) {
                 }
             });
         }
+    } else if let Some(cache) = cache {
+        process_requests_from_cache(cache, requests);
+    }
+}
+
+/// Serves queued requests from the cache while disconnected.
+/// Requests that can not be served from the cache remain queued until the daemon is reachable.
+fn process_requests_from_cache(cache: &RpcCache, requests: &mut QueuedRequests) {
+    let mut uncached_data_requests = Vec::new();
+    for request in requests.data_requests.drain(..) {
+        match cache.get_artifact(request.artifact_id()) {
+            Some(data) => request.complete(data),
+            None => uncached_data_requests.push(request),
+        }
+    }
+    requests.data_requests = uncached_data_requests;
+
+    let mut uncached_metadata_requests = Vec::new();
+    for mut request in requests.metadata_requests.drain(..) {
+        let (results, missing) = cache.get_metadata_with_dependencies(
+            request.requested_assets(),
+            request.include_asset_metadata(),
+        );
+        if results.is_empty() {
+            uncached_metadata_requests.push(request);
+            continue;
+        }
+        // split out the assets that were not in the cache into a new request
+        let mut cached_requests = request.requests.take().unwrap();
+        let uncached_requests: HashMap<_, _> = missing
+            .iter()
+            .filter_map(|id| cached_requests.remove_entry(id))
+            .collect();
+        if !uncached_requests.is_empty() {
+            uncached_metadata_requests.push(MetadataRequest {
+                tx: request.tx.clone(),
+                requests: Some(uncached_requests),
+                include_asset_metadata: request.include_asset_metadata,
+            });
+        }
+        request.requests = Some(cached_requests);
+        request.complete(results);
+    }
+    requests.metadata_requests = uncached_metadata_requests;
+
+    let mut uncached_resolve_requests = Vec::new();
+    for request in requests.resolve_requests.drain(..) {
+        let candidates = match request.identifier().as_tag() {
+            None => cache.get_candidates(request.identifier().path()),
+            Some(_) => cache.get_tag_candidates(request.identifier()),
+        };
+        match candidates {
+            Some(candidates) => request.complete(candidates),
+            None => uncached_resolve_requests.push(request),
+        }
     }
+    requests.resolve_requests = uncached_resolve_requests;
 }
 
 struct ListenerImpl {
@@ -402,13 +687,29 @@ impl asset_hub::listener::Server for ListenerImpl {
             "ListenerImpl::update self.snapshot_change: {:?}",
             self.snapshot_change
         );
-        if let Some(change_num) = self.snapshot_change {
+        let latest_change = params.get_latest_change();
+        if let Some(change_num) = self.snapshot_change.filter(|n| *n > latest_change) {
+            // The change log is older than the last change we have seen, so the daemon's database
+            // has been reset and the changes since `change_num` can not be retrieved.
+            log::warn!(
+                "Daemon change log is at {} but change {} was previously seen",
+                latest_change,
+                change_num
+            );
+            let _ = self.snapshot_channel.try_send(SnapshotChange {
+                snapshot,
+                changed_assets: Vec::new(),
+                deleted_assets: Vec::new(),
+                changed_paths: Vec::new(),
+                deleted_paths: Vec::new(),
+                latest_change,
+                invalidate_all: true,
+            });
+        } else if let Some(change_num) = self.snapshot_change {
             let channel = self.snapshot_channel.clone();
             let mut request = snapshot.get_asset_changes_request();
             request.get().set_start(change_num);
-            request
-                .get()
-                .set_count(params.get_latest_change() - change_num);
+            request.get().set_count(latest_change - change_num);
             return Promise::from_future(async move {
                 let response = request.send().promise.await?;
                 let response = response.get()?;
@@ -451,6 +752,8 @@ impl asset_hub::listener::Server for ListenerImpl {
                         deleted_assets,
                         changed_paths,
                         deleted_paths,
+                        latest_change,
+                        invalidate_all: false,
                     })
                     .map_err(|_| capnp::Error::failed("Could not send SnapshotChange".into()))
             });
@@ -461,9 +764,274 @@ impl asset_hub::listener::Server for ListenerImpl {
                 deleted_assets: Vec::new(),
                 changed_paths: Vec::new(),
                 deleted_paths: Vec::new(),
+                latest_change,
+                invalidate_all: false,
             });
         }
-        self.snapshot_change = Some(params.get_latest_change());
+        self.snapshot_change = Some(latest_change);
         Promise::ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{storage::IndirectIdentifier, LoadHandle};
+
+    /// Serves an [`asset_hub`] that reports `version` from `getDaemonInfo`, or does not implement
+    /// it if `version` is `None`. Returns the address it listens on.
+    fn spawn_mock_daemon(version: Option<u32>) -> String {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        listener.set_nonblocking(true).unwrap();
+        std::thread::spawn(move || {
+            let runtime = Builder::new_current_thread().enable_all().build().unwrap();
+            let local = tokio::task::LocalSet::new();
+            local.block_on(&runtime, async move {
+                let listener = tokio::net::TcpListener::from_std(listener).unwrap();
+                loop {
+                    let (stream, _) = listener.accept().await.unwrap();
+                    use tokio_util::compat::*;
+                    let (reader, writer) = stream.compat().split();
+                    let network = twoparty::VatNetwork::new(
+                        reader,
+                        writer,
+                        rpc_twoparty_capnp::Side::Server,
+                        Default::default(),
+                    );
+                    let hub: asset_hub::Client = capnp_rpc::new_client(MockHub { version });
+                    let rpc_system = RpcSystem::new(Box::new(network), Some(hub.client));
+                    tokio::task::spawn_local(async move {
+                        let _ = rpc_system.await;
+                    });
+                }
+            });
+        });
+        address
+    }
+
+    struct MockHub {
+        version: Option<u32>,
+    }
+
+    impl asset_hub::Server for MockHub {
+        fn register_listener(
+            &mut self,
+            _params: asset_hub::RegisterListenerParams,
+            _results: asset_hub::RegisterListenerResults,
+        ) -> Promise<()> {
+            Promise::ok(())
+        }
+
+        fn get_snapshot(
+            &mut self,
+            _params: asset_hub::GetSnapshotParams,
+            mut results: asset_hub::GetSnapshotResults,
+        ) -> Promise<()> {
+            results
+                .get()
+                .set_snapshot(capnp_rpc::new_client(MockSnapshot::default()));
+            Promise::ok(())
+        }
+
+        fn get_daemon_info(
+            &mut self,
+            _params: asset_hub::GetDaemonInfoParams,
+            mut results: asset_hub::GetDaemonInfoResults,
+        ) -> Promise<()> {
+            match self.version {
+                Some(version) => {
+                    results.get().init_info().set_version(version);
+                    Promise::ok(())
+                }
+                None => Promise::err(capnp::Error::unimplemented(
+                    "method not implemented".to_string(),
+                )),
+            }
+        }
+    }
+
+    #[derive(Default)]
+    struct MockSnapshot {
+        assets: Vec<AssetMetadata>,
+        paths: HashMap<AssetUuid, &'static str>,
+    }
+
+    impl asset_hub::snapshot::Server for MockSnapshot {
+        fn get_all_asset_metadata(
+            &mut self,
+            _params: asset_hub::snapshot::GetAllAssetMetadataParams,
+            mut results: asset_hub::snapshot::GetAllAssetMetadataResults,
+        ) -> Promise<()> {
+            let mut assets = results.get().init_assets(self.assets.len() as u32);
+            for (idx, asset) in self.assets.iter().enumerate() {
+                distill_schema::build_asset_metadata(
+                    asset,
+                    &mut assets.reborrow().get(idx as u32),
+                    distill_schema::data::AssetSource::File,
+                );
+            }
+            Promise::ok(())
+        }
+
+        fn get_path_for_assets(
+            &mut self,
+            params: asset_hub::snapshot::GetPathForAssetsParams,
+            mut results: asset_hub::snapshot::GetPathForAssetsResults,
+        ) -> Promise<()> {
+            let mut paths = Vec::new();
+            for id in pry!(pry!(params.get()).get_assets()) {
+                let id = AssetUuid(utils::make_array(pry!(id.get_id())));
+                if let Some(path) = self.paths.get(&id) {
+                    paths.push((id, *path));
+                }
+            }
+            let mut results = results.get().init_paths(paths.len() as u32);
+            for (idx, (id, path)) in paths.into_iter().enumerate() {
+                let mut result = results.reborrow().get(idx as u32);
+                result.reborrow().init_id().set_id(&id.0);
+                result.set_path(path.as_bytes());
+            }
+            Promise::ok(())
+        }
+    }
+
+    /// Starts connecting to `address` and runs the runtime until the attempt has finished.
+    fn connect(io: &RpcIO, address: &str) -> ConnectionStatus {
+        let mut runtime = io.runtime.lock().unwrap();
+        runtime.connect(address.to_string(), None);
+        assert_eq!(runtime.connection_status(), ConnectionStatus::Connecting);
+        let start = Instant::now();
+        while runtime.connection_status() == ConnectionStatus::Connecting {
+            assert!(
+                start.elapsed() < Duration::from_secs(10),
+                "connection attempt did not finish"
+            );
+            runtime
+                .local
+                .block_on(&runtime.runtime, tokio::task::yield_now());
+            runtime.update_connection_state();
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        runtime.connection_status()
+    }
+
+    fn connection_error(io: &RpcIO) -> String {
+        match &io.runtime.lock().unwrap().connection {
+            InternalConnectionState::Error(err) => err.to_string(),
+            _ => panic!("connection is not in the error state"),
+        }
+    }
+
+    fn unused_address() -> String {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        listener.local_addr().unwrap().to_string()
+    }
+
+    #[test]
+    fn reconnect_backoff() {
+        let io = RpcIO::new(String::new()).unwrap();
+        assert_eq!(io.connection_status(), ConnectionStatus::Disconnected);
+
+        let address = unused_address();
+        match connect(&io, &address) {
+            ConnectionStatus::Error { retry_in, .. } => assert!(retry_in <= MIN_RECONNECT_DELAY),
+            status => panic!("unexpected status {:?}", status),
+        }
+        match connect(&io, &address) {
+            ConnectionStatus::Error { retry_in, .. } => {
+                assert!(retry_in > MIN_RECONNECT_DELAY && retry_in <= MIN_RECONNECT_DELAY * 2)
+            }
+            status => panic!("unexpected status {:?}", status),
+        }
+
+        // the delay doubles up to the maximum
+        let mut runtime = io.runtime.lock().unwrap();
+        for _ in 0..10 {
+            runtime.set_connection_error("connection refused".into());
+        }
+        assert_eq!(runtime.reconnect_delay, MAX_RECONNECT_DELAY);
+        drop(runtime);
+
+        // and is reset by a successful connection
+        let address = spawn_mock_daemon(Some(distill_schema::PROTOCOL_VERSION));
+        assert_eq!(connect(&io, &address), ConnectionStatus::Connected);
+        assert_eq!(
+            io.runtime.lock().unwrap().reconnect_delay,
+            MIN_RECONNECT_DELAY
+        );
+    }
+
+    #[test]
+    fn resolve_tag_request_uses_asset_paths() {
+        let asset = |id: u8, name: &str| AssetMetadata {
+            id: AssetUuid([id; 16]),
+            search_tags: vec![("name".to_string(), Some(name.to_string()))],
+            ..Default::default()
+        };
+        let snapshot: asset_hub::snapshot::Client = capnp_rpc::new_client(MockSnapshot {
+            assets: vec![asset(1, "Sword"), asset(2, "Shield"), asset(3, "Sword")],
+            paths: vec![(AssetUuid([1; 16]), "weapons/sword.ron")]
+                .into_iter()
+                .collect(),
+        });
+        let (tx, _rx) = unbounded();
+        let request = ResolveRequest {
+            tx,
+            id: Some((IndirectIdentifier::Tag("name=Sword".into()), LoadHandle(0))),
+        };
+
+        let runtime = Builder::new_current_thread().enable_all().build().unwrap();
+        let candidates = tokio::task::LocalSet::new()
+            .block_on(&runtime, do_resolve_tag_request(&request, &snapshot))
+            .unwrap();
+        let candidates: Vec<_> = candidates
+            .iter()
+            .map(|(path, assets)| {
+                (
+                    path.clone(),
+                    assets.iter().map(|a| a.id).collect::<Vec<_>>(),
+                )
+            })
+            .collect();
+        // assets the daemon has no path for are reported with an empty path
+        assert_eq!(
+            candidates,
+            vec![
+                (PathBuf::new(), vec![AssetUuid([3; 16])]),
+                (PathBuf::from("weapons/sword.ron"), vec![AssetUuid([1; 16])]),
+            ]
+        );
+    }
+
+    #[test]
+    fn incompatible_daemon_version() {
+        let io = RpcIO::new(String::new()).unwrap();
+        let address = spawn_mock_daemon(Some(distill_schema::PROTOCOL_VERSION + 1));
+        match connect(&io, &address) {
+            ConnectionStatus::IncompatibleDaemon {
+                daemon_version,
+                loader_version,
+                ..
+            } => {
+                assert_eq!(daemon_version, distill_schema::PROTOCOL_VERSION + 1);
+                assert_eq!(loader_version, distill_schema::PROTOCOL_VERSION);
+            }
+            status => panic!("unexpected status {:?}", status),
+        }
+        assert!(connection_error(&io).starts_with("daemon is too new"));
+    }
+
+    #[test]
+    fn daemon_without_daemon_info() {
+        let io = RpcIO::new(String::new()).unwrap();
+        let address = spawn_mock_daemon(None);
+        match connect(&io, &address) {
+            ConnectionStatus::IncompatibleDaemon { daemon_version, .. } => {
+                assert_eq!(daemon_version, 0)
+            }
+            status => panic!("unexpected status {:?}", status),
+        }
+        assert!(connection_error(&io).starts_with("daemon is too old"));
+    }
+}
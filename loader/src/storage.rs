@@ -1,16 +1,19 @@
 use std::{
+    collections::HashMap,
+    convert::TryFrom,
     error::Error,
     path::PathBuf,
     sync::{
         atomic::{AtomicU64, Ordering},
-        Arc,
+        Arc, Mutex, RwLock,
     },
 };
 
-use crossbeam_channel::Sender;
-use dashmap::DashMap;
+use crossbeam_channel::{unbounded, Receiver, Sender};
 use distill_core::{AssetMetadata, AssetRef, AssetTypeId, AssetUuid};
 
+use crate::loader::LoadFailure;
+
 /// Loading ID allocated by [`Loader`](crate::loader::Loader) to track loading of a particular asset
 /// or an indirect reference to an asset.
 #[derive(Copy, Clone, PartialEq, Eq, Debug, Hash)]
@@ -191,6 +194,121 @@ pub trait LoaderInfoProvider: Send + Sync {
     ///
     /// * `load_handle`: ID allocated by [`Loader`](crate::loader::Loader) to track loading of the asset.
     fn get_asset_id(&self, load: LoadHandle) -> Option<AssetUuid>;
+
+    /// Returns the AssetUUID for the given LoadHandle of a [`WeakHandle`](crate::handle::WeakHandle), if present.
+    ///
+    /// Unlike [`LoaderInfoProvider::get_asset_id`], the reference is recorded as a weak load
+    /// dependency when an asset is serialized during import.
+    ///
+    /// # Parameters
+    ///
+    /// * `load_handle`: ID allocated by [`Loader`](crate::loader::Loader) to track loading of the asset.
+    fn get_weak_asset_id(&self, load: LoadHandle) -> Option<AssetUuid> {
+        self.get_asset_id(load)
+    }
+
+    /// Returns the path that the given LoadHandle was created from, if it refers to an asset by
+    /// path instead of by UUID. Used to round-trip path references through human-readable formats.
+    /// The default implementation returns `None`.
+    ///
+    /// # Parameters
+    ///
+    /// * `load_handle`: ID allocated by [`Loader`](crate::loader::Loader) to track loading of the asset.
+    fn get_asset_path(&self, _load: LoadHandle) -> Option<PathBuf> {
+        None
+    }
+
+    /// Returns the tag query that the given LoadHandle was created from, if it refers to assets by
+    /// tag instead of by UUID or path. Used to round-trip tag references through human-readable
+    /// formats. The default implementation returns `None`.
+    ///
+    /// # Parameters
+    ///
+    /// * `load_handle`: ID allocated by [`Loader`](crate::loader::Loader) to track loading of the asset.
+    fn get_asset_tag(&self, _load: LoadHandle) -> Option<String> {
+        None
+    }
+
+    /// Checks that the asset referenced by a typed [`Handle`](crate::handle::Handle) has the expected type.
+    ///
+    /// Returns a description of the mismatch if the asset is known to have a different type.
+    /// The default implementation accepts any type.
+    ///
+    /// # Parameters
+    ///
+    /// * `load_handle`: ID allocated by [`Loader`](crate::loader::Loader) to track loading of the asset.
+    /// * `expected`: Asset type expected by the handle.
+    fn check_asset_type(&self, _load: LoadHandle, _expected: AssetTypeId) -> Result<(), String> {
+        Ok(())
+    }
+
+    /// Returns a snapshot of the mappings for an asset, its load dependencies and those of its
+    /// weak load dependencies that are currently loading.
+    ///
+    /// The snapshot can be used to deserialize the asset on another thread, since the handles
+    /// of these dependencies are the only ones that can occur in the asset's data.
+    /// The default implementation only contains the asset itself, so implementations whose assets
+    /// are deserialized on other threads should override it.
+    ///
+    /// # Parameters
+    ///
+    /// * `load_handle`: ID allocated by [`Loader`](crate::loader::Loader) to track loading of the asset.
+    fn dependency_snapshot(&self, load: LoadHandle) -> LoaderInfoSnapshot {
+        let mut snapshot = LoaderInfoSnapshot::default();
+        if let Some(id) = self.get_asset_id(load) {
+            snapshot.insert(id, load);
+        }
+        snapshot
+    }
+}
+
+/// An owned [`LoaderInfoProvider`] with a fixed set of mappings, see [`LoaderInfoProvider::dependency_snapshot`].
+#[derive(Debug, Clone, Default)]
+pub struct LoaderInfoSnapshot {
+    uuid_to_load: HashMap<AssetUuid, LoadHandle>,
+    load_to_uuid: HashMap<LoadHandle, AssetUuid>,
+    asset_types: HashMap<LoadHandle, AssetTypeId>,
+}
+
+impl LoaderInfoSnapshot {
+    /// Adds a mapping between an asset and its load handle.
+    pub fn insert(&mut self, id: AssetUuid, load: LoadHandle) {
+        self.uuid_to_load.insert(id, load);
+        self.load_to_uuid.insert(load, id);
+    }
+
+    /// Adds the asset type of a load handle, which is used by [`LoaderInfoProvider::check_asset_type`].
+    pub fn insert_asset_type(&mut self, load: LoadHandle, asset_type: AssetTypeId) {
+        self.asset_types.insert(load, asset_type);
+    }
+}
+
+impl LoaderInfoProvider for LoaderInfoSnapshot {
+    fn get_load_handle(&self, asset_ref: &AssetRef) -> Option<LoadHandle> {
+        match asset_ref {
+            AssetRef::Uuid(uuid) => self.uuid_to_load.get(uuid).copied(),
+            AssetRef::Path(_) | AssetRef::Tag(_) => None,
+        }
+    }
+
+    fn get_asset_id(&self, load: LoadHandle) -> Option<AssetUuid> {
+        self.load_to_uuid.get(&load).copied()
+    }
+
+    fn check_asset_type(&self, load: LoadHandle, expected: AssetTypeId) -> Result<(), String> {
+        match self.asset_types.get(&load) {
+            Some(found) if *found != expected => Err(LoadFailure::TypeMismatch {
+                expected,
+                found: *found,
+            }
+            .to_string()),
+            _ => Ok(()),
+        }
+    }
+
+    fn dependency_snapshot(&self, _load: LoadHandle) -> LoaderInfoSnapshot {
+        self.clone()
+    }
 }
 
 /// Allocates LoadHandles for [`Loader`](crate::loader::Loader) implementations.
@@ -202,6 +320,11 @@ pub trait HandleAllocator: Send + Sync + 'static {
     fn alloc(&self) -> LoadHandle;
     /// Frees a [`LoadHandle`], allowing the handle to be returned by a future `alloc` call.
     fn free(&self, handle: LoadHandle);
+    /// Returns false if the [`LoadHandle`] has been freed. Allocators that reuse IDs use this to
+    /// reject stale handles. The default implementation treats every handle as valid.
+    fn is_valid(&self, _handle: LoadHandle) -> bool {
+        true
+    }
 }
 
 /// An implementation of [`HandleAllocator`] which uses an incrementing AtomicU64 internally to allocate LoadHandle IDs.
@@ -232,19 +355,114 @@ impl HandleAllocator for &'static AtomicHandleAllocator {
     fn free(&self, _handle: LoadHandle) {}
 }
 
+/// An implementation of [`HandleAllocator`] which reuses the IDs of freed handles, while detecting
+/// handles that are used after being freed.
+///
+/// Each [`LoadHandle`] packs a slot index into the low 32 bits and the generation of the slot into
+/// the next 31 bits, leaving the most significant bit for indirect handles. Freeing a handle
+/// increments the generation of its slot, so a stale handle never equals a handle that reuses the slot.
+pub struct GenerationalHandleAllocator {
+    slots: Mutex<GenerationalSlots>,
+}
+
+struct GenerationalSlots {
+    generations: Vec<u32>,
+    free_slots: Vec<u32>,
+}
+
+const GENERATION_SHIFT: u32 = 32;
+const GENERATION_MASK: u64 = (1 << 31) - 1;
+
+impl GenerationalHandleAllocator {
+    pub fn new() -> Self {
+        Self {
+            slots: Mutex::new(GenerationalSlots {
+                // Slot 0 is never allocated, since `LoadHandle(0)` represents a null handle.
+                generations: vec![0],
+                free_slots: Vec::new(),
+            }),
+        }
+    }
+
+    /// Returns the slot index and generation of a handle.
+    fn unpack(handle: LoadHandle) -> (usize, u32) {
+        let index = handle.0 & u64::from(u32::MAX);
+        let generation = (handle.0 >> GENERATION_SHIFT) & GENERATION_MASK;
+        (index as usize, generation as u32)
+    }
+
+    fn pack(index: u32, generation: u32) -> LoadHandle {
+        LoadHandle((u64::from(generation) << GENERATION_SHIFT) | u64::from(index))
+    }
+}
+
+impl Default for GenerationalHandleAllocator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HandleAllocator for GenerationalHandleAllocator {
+    fn alloc(&self) -> LoadHandle {
+        let mut slots = self.slots.lock().unwrap();
+        let index = match slots.free_slots.pop() {
+            Some(index) => index,
+            None => {
+                let index = u32::try_from(slots.generations.len())
+                    .expect("GenerationalHandleAllocator ran out of slots");
+                slots.generations.push(0);
+                index
+            }
+        };
+        Self::pack(index, slots.generations[index as usize])
+    }
+
+    fn free(&self, handle: LoadHandle) {
+        let (index, generation) = Self::unpack(handle);
+        let mut slots = self.slots.lock().unwrap();
+        // Ignore handles that are already freed, so a slot is never in the free list twice.
+        if index != 0 && slots.generations.get(index) == Some(&generation) {
+            slots.generations[index] = (generation + 1) & GENERATION_MASK as u32;
+            slots.free_slots.push(index as u32);
+        }
+    }
+
+    fn is_valid(&self, handle: LoadHandle) -> bool {
+        let (index, generation) = Self::unpack(handle);
+        let slots = self.slots.lock().unwrap();
+        slots.generations.get(index) == Some(&generation)
+    }
+}
+
 /// An indirect identifier that can be resolved to a specific [`AssetUuid`] by an [`IndirectionResolver`] impl.
+///
+/// Tags are queries against [`AssetMetadata::search_tags`] of the form `key=value`, which matches
+/// assets with the tag `key` set to `value`, or `key`, which matches assets that have the tag `key`.
 #[derive(Clone, PartialEq, Eq, Debug, Hash)]
 pub enum IndirectIdentifier {
     PathWithTagAndType(String, String, AssetTypeId),
     PathWithType(String, AssetTypeId),
     Path(String),
+    /// Matches all assets with the tag, regardless of their path, for example `name=PlayerSword`.
+    Tag(String),
 }
 impl IndirectIdentifier {
+    /// Returns the path of the identifier. [`IndirectIdentifier::Tag`] identifiers have an empty
+    /// path; use [`IndirectIdentifier::as_tag`] to tell them apart.
     pub fn path(&self) -> &str {
         match self {
             IndirectIdentifier::PathWithTagAndType(path, _, _) => path.as_str(),
             IndirectIdentifier::PathWithType(path, _) => path.as_str(),
             IndirectIdentifier::Path(path) => path.as_str(),
+            IndirectIdentifier::Tag(_) => "",
+        }
+    }
+
+    /// Returns the tag query if the identifier only identifies assets by tag.
+    pub fn as_tag(&self) -> Option<&str> {
+        match self {
+            IndirectIdentifier::Tag(tag) => Some(tag.as_str()),
+            _ => None,
         }
     }
 
@@ -252,10 +470,50 @@ impl IndirectIdentifier {
         match self {
             IndirectIdentifier::PathWithTagAndType(_, _, ty) => Some(ty),
             IndirectIdentifier::PathWithType(_, ty) => Some(ty),
-            IndirectIdentifier::Path(_) => None,
+            IndirectIdentifier::Path(_) | IndirectIdentifier::Tag(_) => None,
         }
     }
+
+    /// Returns the tag query of the identifier, if any.
+    pub fn tag(&self) -> Option<&str> {
+        match self {
+            IndirectIdentifier::PathWithTagAndType(_, tag, _) => Some(tag.as_str()),
+            IndirectIdentifier::Tag(tag) => Some(tag.as_str()),
+            IndirectIdentifier::PathWithType(_, _) | IndirectIdentifier::Path(_) => None,
+        }
+    }
+
+    /// Returns true if the search tags match the tag query of the identifier.
+    /// Identifiers without a tag match any search tags.
+    pub fn matches_tags(&self, search_tags: &[(String, Option<String>)]) -> bool {
+        match self.tag() {
+            Some(tag) => distill_core::utils::tag_query_matches(tag, search_tags),
+            None => true,
+        }
+    }
+
+    /// Returns true if the asset has the type and tags required by the identifier.
+    pub fn matches(&self, asset: &AssetMetadata) -> bool {
+        let type_matches = match (self.type_id(), &asset.artifact) {
+            (Some(id_type), Some(artifact)) => *id_type == artifact.type_id,
+            (Some(_), None) => false,
+            (None, _) => true,
+        };
+        type_matches && self.matches_tags(&asset.search_tags)
+    }
+}
+
+/// The outcome of resolving an [`IndirectIdentifier`], see [`IndirectionResolver::try_resolve`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IndirectResolution {
+    /// The identifier resolved to a single asset.
+    Resolved(AssetUuid),
+    /// Multiple assets match the identifier. Sorted by UUID.
+    Ambiguous(Vec<AssetUuid>),
+    /// No asset matches the identifier.
+    NotFound,
 }
+
 /// Resolves ambiguous [`IndirectIdentifier`]s to a single asset ID given a set of candidates.
 pub trait IndirectionResolver {
     fn resolve(
@@ -263,10 +521,25 @@ pub trait IndirectionResolver {
         id: &IndirectIdentifier,
         candidates: Vec<(PathBuf, Vec<AssetMetadata>)>,
     ) -> Option<AssetUuid>;
+
+    /// Resolves the identifier like [`IndirectionResolver::resolve`], but reports when multiple
+    /// assets match. This is what the [`Loader`](crate::loader::Loader) calls.
+    ///
+    /// The default implementation never reports ambiguity.
+    fn try_resolve(
+        &self,
+        id: &IndirectIdentifier,
+        candidates: Vec<(PathBuf, Vec<AssetMetadata>)>,
+    ) -> IndirectResolution {
+        match self.resolve(id, candidates) {
+            Some(asset) => IndirectResolution::Resolved(asset),
+            None => IndirectResolution::NotFound,
+        }
+    }
 }
 
 /// Default implementation of [`IndirectionResolver`] which resolves to the first asset in the list of candidates
-/// of the appropriate type.
+/// of the appropriate type. Tags of the identifier are ignored, see [`TagIndirectionResolver`].
 pub struct DefaultIndirectionResolver;
 impl IndirectionResolver for DefaultIndirectionResolver {
     fn resolve(
@@ -288,11 +561,123 @@ impl IndirectionResolver for DefaultIndirectionResolver {
     }
 }
 
+/// Implementation of [`IndirectionResolver`] which resolves to the asset of the appropriate type
+/// that matches the tag of the identifier.
+///
+/// If multiple assets match, the identifier is ambiguous and is not resolved.
+pub struct TagIndirectionResolver;
+impl IndirectionResolver for TagIndirectionResolver {
+    fn resolve(
+        &self,
+        id: &IndirectIdentifier,
+        candidates: Vec<(PathBuf, Vec<AssetMetadata>)>,
+    ) -> Option<AssetUuid> {
+        match self.try_resolve(id, candidates) {
+            IndirectResolution::Resolved(asset) => Some(asset),
+            _ => None,
+        }
+    }
+
+    fn try_resolve(
+        &self,
+        id: &IndirectIdentifier,
+        candidates: Vec<(PathBuf, Vec<AssetMetadata>)>,
+    ) -> IndirectResolution {
+        let mut matches: Vec<_> = candidates
+            .into_iter()
+            .flat_map(|(_, assets)| assets)
+            .filter(|asset| id.matches(asset))
+            .map(|asset| asset.id)
+            .collect();
+        matches.sort_unstable();
+        matches.dedup();
+        match matches.len() {
+            0 => IndirectResolution::NotFound,
+            1 => IndirectResolution::Resolved(matches[0]),
+            _ => IndirectResolution::Ambiguous(matches),
+        }
+    }
+}
+
+/// A change to the direct handle that an indirect handle resolves to, see [`IndirectionTable::subscribe`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IndirectionUpdate {
+    /// The indirect handle that was re-resolved.
+    pub indirect_handle: LoadHandle,
+    /// The direct handle that the indirect handle resolved to before the update.
+    pub previous: Option<LoadHandle>,
+    /// The direct handle that the indirect handle resolves to after the update.
+    pub current: Option<LoadHandle>,
+}
+
 /// Resolves indirect [`LoadHandle`]s. See [`LoadHandle::is_indirect`] for details.
 #[derive(Clone)]
-pub struct IndirectionTable(pub(crate) Arc<DashMap<LoadHandle, LoadHandle>>);
+pub struct IndirectionTable {
+    table: Arc<RwLock<HashMap<LoadHandle, LoadHandle>>>,
+    subscribers: Arc<Mutex<Vec<Sender<Vec<IndirectionUpdate>>>>>,
+    handle_allocator: Arc<dyn HandleAllocator>,
+}
 impl IndirectionTable {
+    pub(crate) fn new(handle_allocator: Arc<dyn HandleAllocator>) -> Self {
+        Self {
+            table: Arc::new(RwLock::new(HashMap::new())),
+            subscribers: Arc::new(Mutex::new(Vec::new())),
+            handle_allocator,
+        }
+    }
+
+    /// Returns a channel that receives the changes to the table.
+    ///
+    /// Each message contains the changes of one update, which are applied to the table at once, so
+    /// [`IndirectionTable::resolve`] never observes a partially applied update.
+    pub fn subscribe(&self) -> Receiver<Vec<IndirectionUpdate>> {
+        let (tx, rx) = unbounded();
+        self.subscribers.lock().unwrap().push(tx);
+        rx
+    }
+
+    /// Sets the direct handles that indirect handles resolve to, where `None` removes the resolution.
+    pub(crate) fn update(
+        &self,
+        updates: impl IntoIterator<Item = (LoadHandle, Option<LoadHandle>)>,
+    ) {
+        let mut changes = Vec::new();
+        {
+            let mut table = self.table.write().unwrap();
+            for (indirect_handle, current) in updates {
+                let previous = match current {
+                    Some(load) => table.insert(indirect_handle, load),
+                    None => table.remove(&indirect_handle),
+                };
+                if previous != current {
+                    changes.push(IndirectionUpdate {
+                        indirect_handle,
+                        previous,
+                        current,
+                    });
+                }
+            }
+        }
+        if !changes.is_empty() {
+            self.subscribers
+                .lock()
+                .unwrap()
+                .retain(|subscriber| subscriber.send(changes.clone()).is_ok());
+        }
+    }
+
+    /// Returns the direct handle that an indirect handle currently resolves to.
+    ///
+    /// Returns `None` if the handle is unresolved, or has been freed by the [`HandleAllocator`].
     pub fn resolve(&self, indirect_handle: LoadHandle) -> Option<LoadHandle> {
-        self.0.get(&indirect_handle).map(|l| *l)
+        if !self.handle_allocator.is_valid(indirect_handle) {
+            return None;
+        }
+        self.table
+            .read()
+            .unwrap()
+            .get(&indirect_handle)
+            .copied()
+            .filter(|l| self.handle_allocator.is_valid(*l))
     }
 }
@@ -37,6 +37,11 @@ impl From<data_capnp::CompressionType> for distill_core::CompressionType {
 
 pub use crate::{data_capnp as data, pack_capnp as pack, service_capnp as service};
 
+/// Version of the RPC protocol spoken between the daemon and its clients, reported by `getDaemonInfo`.
+///
+/// Must be incremented whenever a change to the schemas breaks compatibility with older clients.
+pub const PROTOCOL_VERSION: u32 = 3;
+
 fn set_assetref_list(
     asset_ids: &[AssetRef],
     builder: &mut capnp::struct_list::Builder<'_, data::asset_ref::Owned>,
@@ -54,6 +59,9 @@ fn set_assetref_list(
             AssetRef::Uuid(uuid) => {
                 builder.init_uuid().set_id(&uuid.0);
             }
+            AssetRef::Tag(tag) => {
+                builder.set_tag(tag);
+            }
         }
     }
 }
@@ -68,6 +76,10 @@ pub fn parse_db_asset_ref(asset_ref: &data::asset_ref::Reader<'_>) -> AssetRef {
             uuid.and_then(|id| id.get_id())
                 .expect("capnp: failed to read asset ref uuid"),
         )),
+        data::asset_ref::Tag(tag) => AssetRef::Tag(
+            tag.expect("capnp: failed to read asset ref tag")
+                .to_string(),
+        ),
     }
 }
 
@@ -108,6 +120,12 @@ pub fn parse_artifact_metadata(artifact: &data::artifact_metadata::Reader<'_>) -
             .iter()
             .map(|dep| parse_db_asset_ref(&dep))
             .collect(),
+        weak_deps: artifact
+            .get_weak_deps()
+            .expect("capnp: failed to read weak deps")
+            .iter()
+            .map(|dep| parse_db_asset_ref(&dep))
+            .collect(),
         type_id: make_array(
             artifact
                 .get_type_id()
@@ -172,11 +190,21 @@ pub fn parse_db_metadata(metadata: &data::asset_metadata::Reader<'_>) -> AssetMe
     } else {
         None
     };
+    let error = match metadata.which().expect("capnp: failed to read error") {
+        data::asset_metadata::Error(error) if metadata.has_error() => Some(
+            error
+                .and_then(|error| error.get_text())
+                .expect("capnp: failed to read error text")
+                .to_string(),
+        ),
+        _ => None,
+    };
     AssetMetadata {
         id: asset_id,
         search_tags,
         build_pipeline,
         artifact: artifact_metadata,
+        error,
     }
 }
 pub fn build_artifact_metadata(
@@ -203,6 +231,12 @@ pub fn build_artifact_metadata(
             .reborrow()
             .init_build_deps(artifact_metadata.build_deps.len() as u32),
     );
+    set_assetref_list(
+        &artifact_metadata.weak_deps,
+        &mut artifact
+            .reborrow()
+            .init_weak_deps(artifact_metadata.weak_deps.len() as u32),
+    );
     artifact
         .reborrow()
         .set_compression(artifact_metadata.compression.into());
@@ -247,6 +281,11 @@ pub fn build_asset_metadata(
     } else {
         m.reborrow().init_latest_artifact().set_none(());
     }
+    if let Some(error) = &metadata.error {
+        m.reborrow().init_error().set_text(error);
+    } else {
+        m.reborrow().set_no_error(());
+    }
     m.reborrow().set_source(source);
 }
 
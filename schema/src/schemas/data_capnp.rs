@@ -198,7 +198,7 @@ pub mod asset_uuid {
 }
 
 pub mod asset_ref {
-    pub use self::Which::{Path, Uuid};
+    pub use self::Which::{Path, Tag, Uuid};
 
     #[derive(Copy, Clone)]
     pub struct Owned(());
@@ -278,6 +278,13 @@ pub mod asset_ref {
             !self.reader.get_pointer_field(0).is_null()
         }
 
+        pub fn has_tag(&self) -> bool {
+            if self.reader.get_data_field::<u16>(0) != 2 {
+                return false;
+            }
+            !self.reader.get_pointer_field(0).is_null()
+        }
+
         #[inline]
         pub fn which(self) -> ::core::result::Result<WhichReader<'a>, ::capnp::NotInSchema> {
             match self.reader.get_data_field::<u16>(0) {
@@ -293,6 +300,12 @@ pub mod asset_ref {
                         ::core::option::Option::None,
                     ),
                 )),
+                2 => ::core::result::Result::Ok(Tag(
+                    ::capnp::traits::FromPointerReader::get_from_pointer(
+                        &self.reader.get_pointer_field(0),
+                        ::core::option::Option::None,
+                    ),
+                )),
                 x => ::core::result::Result::Err(::capnp::NotInSchema(x)),
             }
         }
@@ -416,6 +429,25 @@ pub mod asset_ref {
             !self.builder.get_pointer_field(0).is_null()
         }
 
+        #[inline]
+        pub fn set_tag(&mut self, value: ::capnp::text::Reader<'_>) {
+            self.builder.set_data_field::<u16>(0, 2);
+            self.builder.get_pointer_field(0).set_text(value);
+        }
+
+        #[inline]
+        pub fn init_tag(self, size: u32) -> ::capnp::text::Builder<'a> {
+            self.builder.set_data_field::<u16>(0, 2);
+            self.builder.get_pointer_field(0).init_text(size)
+        }
+
+        pub fn has_tag(&self) -> bool {
+            if self.builder.get_data_field::<u16>(0) != 2 {
+                return false;
+            }
+            !self.builder.get_pointer_field(0).is_null()
+        }
+
         #[inline]
         pub fn which(self) -> ::core::result::Result<WhichBuilder<'a>, ::capnp::NotInSchema> {
             match self.builder.get_data_field::<u16>(0) {
@@ -431,6 +463,12 @@ pub mod asset_ref {
                         ::core::option::Option::None,
                     ),
                 )),
+                2 => ::core::result::Result::Ok(Tag(
+                    ::capnp::traits::FromPointerBuilder::get_from_pointer(
+                        self.builder.get_pointer_field(0),
+                        ::core::option::Option::None,
+                    ),
+                )),
                 x => ::core::result::Result::Err(::capnp::NotInSchema(x)),
             }
         }
@@ -455,17 +493,20 @@ pub mod asset_ref {
         };
         pub const TYPE_ID: u64 = 0x96de_6a1f_9774_a2a1;
     }
-    pub enum Which<A0, A1> {
+    pub enum Which<A0, A1, A2> {
         Uuid(A0),
         Path(A1),
+        Tag(A2),
     }
     pub type WhichReader<'a> = Which<
         ::capnp::Result<crate::data_capnp::asset_uuid::Reader<'a>>,
         ::capnp::Result<::capnp::data::Reader<'a>>,
+        ::capnp::Result<::capnp::text::Reader<'a>>,
     >;
     pub type WhichBuilder<'a> = Which<
         ::capnp::Result<crate::data_capnp::asset_uuid::Builder<'a>>,
         ::capnp::Result<::capnp::data::Builder<'a>>,
+        ::capnp::Result<::capnp::text::Builder<'a>>,
     >;
 }
 
@@ -2120,6 +2161,18 @@ pub mod source_metadata {
             self.reader.get_data_field::<u32>(2)
         }
 
+        #[inline]
+        pub fn get_source_dependencies(self) -> ::capnp::Result<::capnp::data_list::Reader<'a>> {
+            ::capnp::traits::FromPointerReader::get_from_pointer(
+                &self.reader.get_pointer_field(10),
+                ::core::option::Option::None,
+            )
+        }
+
+        pub fn has_source_dependencies(&self) -> bool {
+            !self.reader.get_pointer_field(10).is_null()
+        }
+
         #[inline]
         pub fn which(self) -> ::core::result::Result<WhichReader<'a>, ::capnp::NotInSchema> {
             match self.reader.get_data_field::<u16>(2) {
@@ -2501,6 +2554,38 @@ pub mod source_metadata {
             self.builder.set_data_field::<u32>(2, value);
         }
 
+        #[inline]
+        pub fn get_source_dependencies(self) -> ::capnp::Result<::capnp::data_list::Builder<'a>> {
+            ::capnp::traits::FromPointerBuilder::get_from_pointer(
+                self.builder.get_pointer_field(10),
+                ::core::option::Option::None,
+            )
+        }
+
+        #[inline]
+        pub fn set_source_dependencies(
+            &mut self,
+            value: ::capnp::data_list::Reader<'a>,
+        ) -> ::capnp::Result<()> {
+            ::capnp::traits::SetPointerBuilder::set_pointer_builder(
+                self.builder.get_pointer_field(10),
+                value,
+                false,
+            )
+        }
+
+        #[inline]
+        pub fn init_source_dependencies(self, size: u32) -> ::capnp::data_list::Builder<'a> {
+            ::capnp::traits::FromPointerBuilder::init_pointer(
+                self.builder.get_pointer_field(10),
+                size,
+            )
+        }
+
+        pub fn has_source_dependencies(&self) -> bool {
+            !self.builder.get_pointer_field(10).is_null()
+        }
+
         #[inline]
         pub fn which(self) -> ::core::result::Result<WhichBuilder<'a>, ::capnp::NotInSchema> {
             match self.builder.get_data_field::<u16>(2) {
@@ -2531,7 +2616,7 @@ pub mod source_metadata {
         use capnp::private::layout;
         pub const STRUCT_SIZE: layout::StructSize = layout::StructSize {
             data: 2,
-            pointers: 10,
+            pointers: 11,
         };
         pub const TYPE_ID: u64 = 0x8b58_45d1_f338_0aa8;
     }
@@ -3088,6 +3173,21 @@ pub mod artifact_metadata {
         pub fn has_type_id(&self) -> bool {
             !self.reader.get_pointer_field(4).is_null()
         }
+
+        #[inline]
+        pub fn get_weak_deps(
+            self,
+        ) -> ::capnp::Result<::capnp::struct_list::Reader<'a, crate::data_capnp::asset_ref::Owned>>
+        {
+            ::capnp::traits::FromPointerReader::get_from_pointer(
+                &self.reader.get_pointer_field(5),
+                ::core::option::Option::None,
+            )
+        }
+
+        pub fn has_weak_deps(&self) -> bool {
+            !self.reader.get_pointer_field(5).is_null()
+        }
     }
 
     pub struct Builder<'a> {
@@ -3344,6 +3444,44 @@ pub mod artifact_metadata {
         pub fn has_type_id(&self) -> bool {
             !self.builder.get_pointer_field(4).is_null()
         }
+
+        #[inline]
+        pub fn get_weak_deps(
+            self,
+        ) -> ::capnp::Result<::capnp::struct_list::Builder<'a, crate::data_capnp::asset_ref::Owned>>
+        {
+            ::capnp::traits::FromPointerBuilder::get_from_pointer(
+                self.builder.get_pointer_field(5),
+                ::core::option::Option::None,
+            )
+        }
+
+        #[inline]
+        pub fn set_weak_deps(
+            &mut self,
+            value: ::capnp::struct_list::Reader<'a, crate::data_capnp::asset_ref::Owned>,
+        ) -> ::capnp::Result<()> {
+            ::capnp::traits::SetPointerBuilder::set_pointer_builder(
+                self.builder.get_pointer_field(5),
+                value,
+                false,
+            )
+        }
+
+        #[inline]
+        pub fn init_weak_deps(
+            self,
+            size: u32,
+        ) -> ::capnp::struct_list::Builder<'a, crate::data_capnp::asset_ref::Owned> {
+            ::capnp::traits::FromPointerBuilder::init_pointer(
+                self.builder.get_pointer_field(5),
+                size,
+            )
+        }
+
+        pub fn has_weak_deps(&self) -> bool {
+            !self.builder.get_pointer_field(5).is_null()
+        }
     }
 
     pub struct Pipeline {
@@ -3365,7 +3503,7 @@ pub mod artifact_metadata {
         use capnp::private::layout;
         pub const STRUCT_SIZE: layout::StructSize = layout::StructSize {
             data: 3,
-            pointers: 5,
+            pointers: 6,
         };
         pub const TYPE_ID: u64 = 0xf0ac_1e9d_bec2_4dbf;
     }
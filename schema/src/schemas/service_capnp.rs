@@ -719,6 +719,269 @@ pub mod asset_data {
     }
 }
 
+pub mod path_ref {
+    #[derive(Copy, Clone)]
+    pub struct Owned(());
+    impl<'a> ::capnp::traits::Owned<'a> for Owned {
+        type Builder = Builder<'a>;
+        type Reader = Reader<'a>;
+    }
+    impl<'a> ::capnp::traits::OwnedStruct<'a> for Owned {
+        type Builder = Builder<'a>;
+        type Reader = Reader<'a>;
+    }
+    impl ::capnp::traits::Pipelined for Owned {
+        type Pipeline = Pipeline;
+    }
+
+    #[derive(Clone, Copy)]
+    pub struct Reader<'a> {
+        reader: ::capnp::private::layout::StructReader<'a>,
+    }
+
+    impl<'a> ::capnp::traits::HasTypeId for Reader<'a> {
+        #[inline]
+        fn type_id() -> u64 {
+            _private::TYPE_ID
+        }
+    }
+    impl<'a> ::capnp::traits::FromStructReader<'a> for Reader<'a> {
+        fn new(reader: ::capnp::private::layout::StructReader<'a>) -> Reader<'a> {
+            Reader { reader }
+        }
+    }
+
+    impl<'a> ::capnp::traits::FromPointerReader<'a> for Reader<'a> {
+        fn get_from_pointer(
+            reader: &::capnp::private::layout::PointerReader<'a>,
+            default: ::core::option::Option<&'a [capnp::Word]>,
+        ) -> ::capnp::Result<Reader<'a>> {
+            ::core::result::Result::Ok(::capnp::traits::FromStructReader::new(
+                reader.get_struct(default)?,
+            ))
+        }
+    }
+
+    impl<'a> ::capnp::traits::IntoInternalStructReader<'a> for Reader<'a> {
+        fn into_internal_struct_reader(self) -> ::capnp::private::layout::StructReader<'a> {
+            self.reader
+        }
+    }
+
+    impl<'a> ::capnp::traits::Imbue<'a> for Reader<'a> {
+        fn imbue(&mut self, cap_table: &'a ::capnp::private::layout::CapTable) {
+            self.reader
+                .imbue(::capnp::private::layout::CapTableReader::Plain(cap_table))
+        }
+    }
+
+    impl<'a> Reader<'a> {
+        pub fn reborrow(&self) -> Reader<'_> {
+            Reader { ..*self }
+        }
+
+        pub fn total_size(&self) -> ::capnp::Result<::capnp::MessageSize> {
+            self.reader.total_size()
+        }
+
+        #[inline]
+        pub fn get_source(self) -> ::capnp::Result<::capnp::data::Reader<'a>> {
+            ::capnp::traits::FromPointerReader::get_from_pointer(
+                &self.reader.get_pointer_field(0),
+                ::core::option::Option::None,
+            )
+        }
+
+        pub fn has_source(&self) -> bool {
+            !self.reader.get_pointer_field(0).is_null()
+        }
+
+        #[inline]
+        pub fn get_path(self) -> ::capnp::Result<::capnp::data::Reader<'a>> {
+            ::capnp::traits::FromPointerReader::get_from_pointer(
+                &self.reader.get_pointer_field(1),
+                ::core::option::Option::None,
+            )
+        }
+
+        pub fn has_path(&self) -> bool {
+            !self.reader.get_pointer_field(1).is_null()
+        }
+
+        #[inline]
+        pub fn get_reason(self) -> ::capnp::Result<::capnp::text::Reader<'a>> {
+            ::capnp::traits::FromPointerReader::get_from_pointer(
+                &self.reader.get_pointer_field(2),
+                ::core::option::Option::None,
+            )
+        }
+
+        pub fn has_reason(&self) -> bool {
+            !self.reader.get_pointer_field(2).is_null()
+        }
+    }
+
+    pub struct Builder<'a> {
+        builder: ::capnp::private::layout::StructBuilder<'a>,
+    }
+    impl<'a> ::capnp::traits::HasStructSize for Builder<'a> {
+        #[inline]
+        fn struct_size() -> ::capnp::private::layout::StructSize {
+            _private::STRUCT_SIZE
+        }
+    }
+    impl<'a> ::capnp::traits::HasTypeId for Builder<'a> {
+        #[inline]
+        fn type_id() -> u64 {
+            _private::TYPE_ID
+        }
+    }
+    impl<'a> ::capnp::traits::FromStructBuilder<'a> for Builder<'a> {
+        fn new(builder: ::capnp::private::layout::StructBuilder<'a>) -> Builder<'a> {
+            Builder { builder }
+        }
+    }
+
+    impl<'a> ::capnp::traits::ImbueMut<'a> for Builder<'a> {
+        fn imbue_mut(&mut self, cap_table: &'a mut ::capnp::private::layout::CapTable) {
+            self.builder
+                .imbue(::capnp::private::layout::CapTableBuilder::Plain(cap_table))
+        }
+    }
+
+    impl<'a> ::capnp::traits::FromPointerBuilder<'a> for Builder<'a> {
+        fn init_pointer(
+            builder: ::capnp::private::layout::PointerBuilder<'a>,
+            _size: u32,
+        ) -> Builder<'a> {
+            ::capnp::traits::FromStructBuilder::new(builder.init_struct(_private::STRUCT_SIZE))
+        }
+
+        fn get_from_pointer(
+            builder: ::capnp::private::layout::PointerBuilder<'a>,
+            default: ::core::option::Option<&'a [capnp::Word]>,
+        ) -> ::capnp::Result<Builder<'a>> {
+            ::core::result::Result::Ok(::capnp::traits::FromStructBuilder::new(
+                builder.get_struct(_private::STRUCT_SIZE, default)?,
+            ))
+        }
+    }
+
+    impl<'a> ::capnp::traits::SetPointerBuilder for Reader<'a> {
+        fn set_pointer_builder<'b>(
+            pointer: ::capnp::private::layout::PointerBuilder<'b>,
+            value: Reader<'a>,
+            canonicalize: bool,
+        ) -> ::capnp::Result<()> {
+            pointer.set_struct(&value.reader, canonicalize)
+        }
+    }
+
+    impl<'a> Builder<'a> {
+        pub fn into_reader(self) -> Reader<'a> {
+            ::capnp::traits::FromStructReader::new(self.builder.into_reader())
+        }
+
+        pub fn reborrow(&mut self) -> Builder<'_> {
+            Builder { ..*self }
+        }
+
+        pub fn reborrow_as_reader(&self) -> Reader<'_> {
+            ::capnp::traits::FromStructReader::new(self.builder.into_reader())
+        }
+
+        pub fn total_size(&self) -> ::capnp::Result<::capnp::MessageSize> {
+            self.builder.into_reader().total_size()
+        }
+
+        #[inline]
+        pub fn get_source(self) -> ::capnp::Result<::capnp::data::Builder<'a>> {
+            ::capnp::traits::FromPointerBuilder::get_from_pointer(
+                self.builder.get_pointer_field(0),
+                ::core::option::Option::None,
+            )
+        }
+
+        #[inline]
+        pub fn set_source(&mut self, value: ::capnp::data::Reader<'_>) {
+            self.builder.get_pointer_field(0).set_data(value);
+        }
+
+        #[inline]
+        pub fn init_source(self, size: u32) -> ::capnp::data::Builder<'a> {
+            self.builder.get_pointer_field(0).init_data(size)
+        }
+
+        pub fn has_source(&self) -> bool {
+            !self.builder.get_pointer_field(0).is_null()
+        }
+
+        #[inline]
+        pub fn get_path(self) -> ::capnp::Result<::capnp::data::Builder<'a>> {
+            ::capnp::traits::FromPointerBuilder::get_from_pointer(
+                self.builder.get_pointer_field(1),
+                ::core::option::Option::None,
+            )
+        }
+
+        #[inline]
+        pub fn set_path(&mut self, value: ::capnp::data::Reader<'_>) {
+            self.builder.get_pointer_field(1).set_data(value);
+        }
+
+        #[inline]
+        pub fn init_path(self, size: u32) -> ::capnp::data::Builder<'a> {
+            self.builder.get_pointer_field(1).init_data(size)
+        }
+
+        pub fn has_path(&self) -> bool {
+            !self.builder.get_pointer_field(1).is_null()
+        }
+
+        #[inline]
+        pub fn get_reason(self) -> ::capnp::Result<::capnp::text::Builder<'a>> {
+            ::capnp::traits::FromPointerBuilder::get_from_pointer(
+                self.builder.get_pointer_field(2),
+                ::core::option::Option::None,
+            )
+        }
+
+        #[inline]
+        pub fn set_reason(&mut self, value: ::capnp::text::Reader<'_>) {
+            self.builder.get_pointer_field(2).set_text(value);
+        }
+
+        #[inline]
+        pub fn init_reason(self, size: u32) -> ::capnp::text::Builder<'a> {
+            self.builder.get_pointer_field(2).init_text(size)
+        }
+
+        pub fn has_reason(&self) -> bool {
+            !self.builder.get_pointer_field(2).is_null()
+        }
+    }
+
+    pub struct Pipeline {
+        _typeless: ::capnp::any_pointer::Pipeline,
+    }
+    impl ::capnp::capability::FromTypelessPipeline for Pipeline {
+        fn new(typeless: ::capnp::any_pointer::Pipeline) -> Pipeline {
+            Pipeline {
+                _typeless: typeless,
+            }
+        }
+    }
+    impl Pipeline {}
+    mod _private {
+        use capnp::private::layout;
+        pub const STRUCT_SIZE: layout::StructSize = layout::StructSize {
+            data: 0,
+            pointers: 3,
+        };
+        pub const TYPE_ID: u64 = 0xb8f3_2c6a_91d4_e5a7;
+    }
+}
+
 pub mod asset_hub {
     #![allow(unused_variables)]
     pub type RegisterListenerParams = ::capnp::capability::Params<
@@ -731,6 +994,11 @@ pub mod asset_hub {
         ::capnp::capability::Params<crate::service_capnp::asset_hub::get_snapshot_params::Owned>;
     pub type GetSnapshotResults =
         ::capnp::capability::Results<crate::service_capnp::asset_hub::get_snapshot_results::Owned>;
+    pub type GetDaemonInfoParams =
+        ::capnp::capability::Params<crate::service_capnp::asset_hub::get_daemon_info_params::Owned>;
+    pub type GetDaemonInfoResults = ::capnp::capability::Results<
+        crate::service_capnp::asset_hub::get_daemon_info_results::Owned,
+    >;
 
     pub struct Client {
         pub client: ::capnp::capability::Client,
@@ -820,6 +1088,15 @@ pub mod asset_hub {
         > {
             self.client.new_call(_private::TYPE_ID, 1, None)
         }
+
+        pub fn get_daemon_info_request(
+            &self,
+        ) -> ::capnp::capability::Request<
+            crate::service_capnp::asset_hub::get_daemon_info_params::Owned,
+            crate::service_capnp::asset_hub::get_daemon_info_results::Owned,
+        > {
+            self.client.new_call(_private::TYPE_ID, 2, None)
+        }
     }
     pub trait Server {
         fn register_listener(
@@ -840,6 +1117,15 @@ pub mod asset_hub {
                 "method not implemented".to_string(),
             ))
         }
+        fn get_daemon_info(
+            &mut self,
+            _: GetDaemonInfoParams,
+            _: GetDaemonInfoResults,
+        ) -> ::capnp::capability::Promise<(), ::capnp::Error> {
+            ::capnp::capability::Promise::err(::capnp::Error::unimplemented(
+                "method not implemented".to_string(),
+            ))
+        }
     }
     pub struct ServerDispatch<_T> {
         pub server: _T,
@@ -900,8 +1186,12 @@ pub mod asset_hub {
                     ::capnp::private::capability::internal_get_typed_params(params),
                     ::capnp::private::capability::internal_get_typed_results(results),
                 ),
-                _ => ::capnp::capability::Promise::err(::capnp::Error::unimplemented(
-                    "Method not implemented.".to_string(),
+                2 => server.get_daemon_info(
+                    ::capnp::private::capability::internal_get_typed_params(params),
+                    ::capnp::private::capability::internal_get_typed_results(results),
+                ),
+                _ => ::capnp::capability::Promise::err(::capnp::Error::unimplemented(
+                    "Method not implemented.".to_string(),
                 )),
             }
         }
@@ -980,6 +1270,30 @@ pub mod asset_hub {
         pub type DeleteFileResults = ::capnp::capability::Results<
             crate::service_capnp::asset_hub::snapshot::delete_file_results::Owned,
         >;
+        pub type RenameFileParams = ::capnp::capability::Params<
+            crate::service_capnp::asset_hub::snapshot::rename_file_params::Owned,
+        >;
+        pub type RenameFileResults = ::capnp::capability::Results<
+            crate::service_capnp::asset_hub::snapshot::rename_file_results::Owned,
+        >;
+        pub type GetMetaFileIssuesParams = ::capnp::capability::Params<
+            crate::service_capnp::asset_hub::snapshot::get_meta_file_issues_params::Owned,
+        >;
+        pub type GetMetaFileIssuesResults = ::capnp::capability::Results<
+            crate::service_capnp::asset_hub::snapshot::get_meta_file_issues_results::Owned,
+        >;
+        pub type RegenerateMetaFileParams = ::capnp::capability::Params<
+            crate::service_capnp::asset_hub::snapshot::regenerate_meta_file_params::Owned,
+        >;
+        pub type RegenerateMetaFileResults = ::capnp::capability::Results<
+            crate::service_capnp::asset_hub::snapshot::regenerate_meta_file_results::Owned,
+        >;
+        pub type RemoveOrphanedMetaFileParams = ::capnp::capability::Params<
+            crate::service_capnp::asset_hub::snapshot::remove_orphaned_meta_file_params::Owned,
+        >;
+        pub type RemoveOrphanedMetaFileResults = ::capnp::capability::Results<
+            crate::service_capnp::asset_hub::snapshot::remove_orphaned_meta_file_results::Owned,
+        >;
 
         pub struct Client {
             pub client: ::capnp::capability::Client,
@@ -1154,6 +1468,42 @@ pub mod asset_hub {
             > {
                 self.client.new_call(_private::TYPE_ID, 11, None)
             }
+
+            pub fn rename_file_request(
+                &self,
+            ) -> ::capnp::capability::Request<
+                crate::service_capnp::asset_hub::snapshot::rename_file_params::Owned,
+                crate::service_capnp::asset_hub::snapshot::rename_file_results::Owned,
+            > {
+                self.client.new_call(_private::TYPE_ID, 12, None)
+            }
+
+            pub fn get_meta_file_issues_request(
+                &self,
+            ) -> ::capnp::capability::Request<
+                crate::service_capnp::asset_hub::snapshot::get_meta_file_issues_params::Owned,
+                crate::service_capnp::asset_hub::snapshot::get_meta_file_issues_results::Owned,
+            > {
+                self.client.new_call(_private::TYPE_ID, 13, None)
+            }
+
+            pub fn regenerate_meta_file_request(
+                &self,
+            ) -> ::capnp::capability::Request<
+                crate::service_capnp::asset_hub::snapshot::regenerate_meta_file_params::Owned,
+                crate::service_capnp::asset_hub::snapshot::regenerate_meta_file_results::Owned,
+            > {
+                self.client.new_call(_private::TYPE_ID, 14, None)
+            }
+
+            pub fn remove_orphaned_meta_file_request(
+                &self,
+            ) -> ::capnp::capability::Request<
+                crate::service_capnp::asset_hub::snapshot::remove_orphaned_meta_file_params::Owned,
+                crate::service_capnp::asset_hub::snapshot::remove_orphaned_meta_file_results::Owned,
+            > {
+                self.client.new_call(_private::TYPE_ID, 15, None)
+            }
         }
         pub trait Server {
             fn get_asset_metadata(
@@ -1264,6 +1614,42 @@ pub mod asset_hub {
                     "method not implemented".to_string(),
                 ))
             }
+            fn rename_file(
+                &mut self,
+                _: RenameFileParams,
+                _: RenameFileResults,
+            ) -> ::capnp::capability::Promise<(), ::capnp::Error> {
+                ::capnp::capability::Promise::err(::capnp::Error::unimplemented(
+                    "method not implemented".to_string(),
+                ))
+            }
+            fn get_meta_file_issues(
+                &mut self,
+                _: GetMetaFileIssuesParams,
+                _: GetMetaFileIssuesResults,
+            ) -> ::capnp::capability::Promise<(), ::capnp::Error> {
+                ::capnp::capability::Promise::err(::capnp::Error::unimplemented(
+                    "method not implemented".to_string(),
+                ))
+            }
+            fn regenerate_meta_file(
+                &mut self,
+                _: RegenerateMetaFileParams,
+                _: RegenerateMetaFileResults,
+            ) -> ::capnp::capability::Promise<(), ::capnp::Error> {
+                ::capnp::capability::Promise::err(::capnp::Error::unimplemented(
+                    "method not implemented".to_string(),
+                ))
+            }
+            fn remove_orphaned_meta_file(
+                &mut self,
+                _: RemoveOrphanedMetaFileParams,
+                _: RemoveOrphanedMetaFileResults,
+            ) -> ::capnp::capability::Promise<(), ::capnp::Error> {
+                ::capnp::capability::Promise::err(::capnp::Error::unimplemented(
+                    "method not implemented".to_string(),
+                ))
+            }
         }
         pub struct ServerDispatch<_T> {
             pub server: _T,
@@ -1364,6 +1750,22 @@ pub mod asset_hub {
                         ::capnp::private::capability::internal_get_typed_params(params),
                         ::capnp::private::capability::internal_get_typed_results(results),
                     ),
+                    12 => server.rename_file(
+                        ::capnp::private::capability::internal_get_typed_params(params),
+                        ::capnp::private::capability::internal_get_typed_results(results),
+                    ),
+                    13 => server.get_meta_file_issues(
+                        ::capnp::private::capability::internal_get_typed_params(params),
+                        ::capnp::private::capability::internal_get_typed_results(results),
+                    ),
+                    14 => server.regenerate_meta_file(
+                        ::capnp::private::capability::internal_get_typed_params(params),
+                        ::capnp::private::capability::internal_get_typed_results(results),
+                    ),
+                    15 => server.remove_orphaned_meta_file(
+                        ::capnp::private::capability::internal_get_typed_params(params),
+                        ::capnp::private::capability::internal_get_typed_results(results),
+                    ),
                     _ => ::capnp::capability::Promise::err(::capnp::Error::unimplemented(
                         "Method not implemented.".to_string(),
                     )),
@@ -6440,174 +6842,8 @@ pub mod asset_hub {
                 pub const TYPE_ID: u64 = 0xce96_d824_50b6_20c1;
             }
         }
-    }
-
-    pub mod listener {
-        #![allow(unused_variables)]
-        pub type UpdateParams = ::capnp::capability::Params<
-            crate::service_capnp::asset_hub::listener::update_params::Owned,
-        >;
-        pub type UpdateResults = ::capnp::capability::Results<
-            crate::service_capnp::asset_hub::listener::update_results::Owned,
-        >;
-
-        pub struct Client {
-            pub client: ::capnp::capability::Client,
-        }
-        impl ::capnp::capability::FromClientHook for Client {
-            fn new(hook: Box<dyn (::capnp::private::capability::ClientHook)>) -> Client {
-                Client {
-                    client: ::capnp::capability::Client::new(hook),
-                }
-            }
-        }
-        #[derive(Copy, Clone)]
-        pub struct Owned(());
-        impl<'a> ::capnp::traits::Owned<'a> for Owned {
-            type Builder = Client;
-            type Reader = Client;
-        }
-        impl ::capnp::traits::Pipelined for Owned {
-            type Pipeline = Client;
-        }
-        impl<'a> ::capnp::traits::FromPointerReader<'a> for Client {
-            fn get_from_pointer(
-                reader: &::capnp::private::layout::PointerReader<'a>,
-                _default: ::core::option::Option<&'a [capnp::Word]>,
-            ) -> ::capnp::Result<Client> {
-                ::core::result::Result::Ok(::capnp::capability::FromClientHook::new(
-                    reader.get_capability()?,
-                ))
-            }
-        }
-        impl<'a> ::capnp::traits::FromPointerBuilder<'a> for Client {
-            fn init_pointer(
-                _builder: ::capnp::private::layout::PointerBuilder<'a>,
-                _size: u32,
-            ) -> Client {
-                unimplemented!()
-            }
-
-            fn get_from_pointer(
-                builder: ::capnp::private::layout::PointerBuilder<'a>,
-                _default: ::core::option::Option<&'a [capnp::Word]>,
-            ) -> ::capnp::Result<Client> {
-                ::core::result::Result::Ok(::capnp::capability::FromClientHook::new(
-                    builder.get_capability()?,
-                ))
-            }
-        }
-
-        impl ::capnp::traits::SetPointerBuilder for Client {
-            fn set_pointer_builder(
-                pointer: ::capnp::private::layout::PointerBuilder<'_>,
-                from: Client,
-                _canonicalize: bool,
-            ) -> ::capnp::Result<()> {
-                pointer.set_capability(from.client.hook);
-                ::core::result::Result::Ok(())
-            }
-        }
-        impl ::capnp::traits::HasTypeId for Client {
-            #[inline]
-            fn type_id() -> u64 {
-                _private::TYPE_ID
-            }
-        }
-        impl Clone for Client {
-            fn clone(&self) -> Client {
-                Client {
-                    client: ::capnp::capability::Client::new(self.client.hook.add_ref()),
-                }
-            }
-        }
-        impl Client {
-            pub fn update_request(
-                &self,
-            ) -> ::capnp::capability::Request<
-                crate::service_capnp::asset_hub::listener::update_params::Owned,
-                crate::service_capnp::asset_hub::listener::update_results::Owned,
-            > {
-                self.client.new_call(_private::TYPE_ID, 0, None)
-            }
-        }
-        pub trait Server {
-            fn update(
-                &mut self,
-                _: UpdateParams,
-                _: UpdateResults,
-            ) -> ::capnp::capability::Promise<(), ::capnp::Error> {
-                ::capnp::capability::Promise::err(::capnp::Error::unimplemented(
-                    "method not implemented".to_string(),
-                ))
-            }
-        }
-        pub struct ServerDispatch<_T> {
-            pub server: _T,
-        }
-        impl<_S: Server + 'static> ::capnp::capability::FromServer<_S> for Client {
-            type Dispatch = ServerDispatch<_S>;
-
-            fn from_server(s: _S) -> ServerDispatch<_S> {
-                ServerDispatch { server: s }
-            }
-        }
-        impl<_T: Server> ::core::ops::Deref for ServerDispatch<_T> {
-            type Target = _T;
-
-            fn deref(&self) -> &_T {
-                &self.server
-            }
-        }
-        impl<_T: Server> ::core::ops::DerefMut for ServerDispatch<_T> {
-            fn deref_mut(&mut self) -> &mut _T {
-                &mut self.server
-            }
-        }
-        impl<_T: Server> ::capnp::capability::Server for ServerDispatch<_T> {
-            fn dispatch_call(
-                &mut self,
-                interface_id: u64,
-                method_id: u16,
-                params: ::capnp::capability::Params<::capnp::any_pointer::Owned>,
-                results: ::capnp::capability::Results<::capnp::any_pointer::Owned>,
-            ) -> ::capnp::capability::Promise<(), ::capnp::Error> {
-                match interface_id {
-                    _private::TYPE_ID => ServerDispatch::<_T>::dispatch_call_internal(
-                        &mut self.server,
-                        method_id,
-                        params,
-                        results,
-                    ),
-                    _ => ::capnp::capability::Promise::err(::capnp::Error::unimplemented(
-                        "Method not implemented.".to_string(),
-                    )),
-                }
-            }
-        }
-        impl<_T: Server> ServerDispatch<_T> {
-            pub fn dispatch_call_internal(
-                server: &mut _T,
-                method_id: u16,
-                params: ::capnp::capability::Params<::capnp::any_pointer::Owned>,
-                results: ::capnp::capability::Results<::capnp::any_pointer::Owned>,
-            ) -> ::capnp::capability::Promise<(), ::capnp::Error> {
-                match method_id {
-                    0 => server.update(
-                        ::capnp::private::capability::internal_get_typed_params(params),
-                        ::capnp::private::capability::internal_get_typed_results(results),
-                    ),
-                    _ => ::capnp::capability::Promise::err(::capnp::Error::unimplemented(
-                        "Method not implemented.".to_string(),
-                    )),
-                }
-            }
-        }
-        pub mod _private {
-            pub const TYPE_ID: u64 = 0xdf44_c664_bbec_8d36;
-        }
 
-        pub mod update_params {
+        pub mod rename_file_params {
             #[derive(Copy, Clone)]
             pub struct Owned(());
             impl<'a> ::capnp::traits::Owned<'a> for Owned {
@@ -6673,25 +6909,31 @@ pub mod asset_hub {
                 }
 
                 #[inline]
-                pub fn get_latest_change(self) -> u64 {
-                    self.reader.get_data_field::<u64>(0)
+                pub fn get_from(self) -> ::capnp::Result<::capnp::data::Reader<'a>> {
+                    ::capnp::traits::FromPointerReader::get_from_pointer(
+                        &self.reader.get_pointer_field(0),
+                        ::core::option::Option::None,
+                    )
                 }
 
-                #[inline]
-                pub fn get_snapshot(
-                    self,
-                ) -> ::capnp::Result<crate::service_capnp::asset_hub::snapshot::Client>
-                {
-                    match self.reader.get_pointer_field(0).get_capability() {
-                        ::core::result::Result::Ok(c) => {
-                            ::core::result::Result::Ok(::capnp::capability::FromClientHook::new(c))
-                        }
-                        ::core::result::Result::Err(e) => ::core::result::Result::Err(e),
-                    }
+                pub fn has_from(&self) -> bool {
+                    !self.reader.get_pointer_field(0).is_null()
                 }
-            }
 
-            pub struct Builder<'a> {
+                #[inline]
+                pub fn get_to(self) -> ::capnp::Result<::capnp::data::Reader<'a>> {
+                    ::capnp::traits::FromPointerReader::get_from_pointer(
+                        &self.reader.get_pointer_field(1),
+                        ::core::option::Option::None,
+                    )
+                }
+
+                pub fn has_to(&self) -> bool {
+                    !self.reader.get_pointer_field(1).is_null()
+                }
+            }
+
+            pub struct Builder<'a> {
                 builder: ::capnp::private::layout::StructBuilder<'a>,
             }
             impl<'a> ::capnp::traits::HasStructSize for Builder<'a> {
@@ -6767,36 +7009,47 @@ pub mod asset_hub {
                 }
 
                 #[inline]
-                pub fn get_latest_change(self) -> u64 {
-                    self.builder.get_data_field::<u64>(0)
+                pub fn get_from(self) -> ::capnp::Result<::capnp::data::Builder<'a>> {
+                    ::capnp::traits::FromPointerBuilder::get_from_pointer(
+                        self.builder.get_pointer_field(0),
+                        ::core::option::Option::None,
+                    )
                 }
 
                 #[inline]
-                pub fn set_latest_change(&mut self, value: u64) {
-                    self.builder.set_data_field::<u64>(0, value);
+                pub fn set_from(&mut self, value: ::capnp::data::Reader<'_>) {
+                    self.builder.get_pointer_field(0).set_data(value);
                 }
 
                 #[inline]
-                pub fn get_snapshot(
-                    self,
-                ) -> ::capnp::Result<crate::service_capnp::asset_hub::snapshot::Client>
-                {
-                    match self.builder.get_pointer_field(0).get_capability() {
-                        ::core::result::Result::Ok(c) => {
-                            ::core::result::Result::Ok(::capnp::capability::FromClientHook::new(c))
-                        }
-                        ::core::result::Result::Err(e) => ::core::result::Result::Err(e),
-                    }
+                pub fn init_from(self, size: u32) -> ::capnp::data::Builder<'a> {
+                    self.builder.get_pointer_field(0).init_data(size)
+                }
+
+                pub fn has_from(&self) -> bool {
+                    !self.builder.get_pointer_field(0).is_null()
                 }
 
                 #[inline]
-                pub fn set_snapshot(
-                    &mut self,
-                    value: crate::service_capnp::asset_hub::snapshot::Client,
-                ) {
-                    self.builder
-                        .get_pointer_field(0)
-                        .set_capability(value.client.hook);
+                pub fn get_to(self) -> ::capnp::Result<::capnp::data::Builder<'a>> {
+                    ::capnp::traits::FromPointerBuilder::get_from_pointer(
+                        self.builder.get_pointer_field(1),
+                        ::core::option::Option::None,
+                    )
+                }
+
+                #[inline]
+                pub fn set_to(&mut self, value: ::capnp::data::Reader<'_>) {
+                    self.builder.get_pointer_field(1).set_data(value);
+                }
+
+                #[inline]
+                pub fn init_to(self, size: u32) -> ::capnp::data::Builder<'a> {
+                    self.builder.get_pointer_field(1).init_data(size)
+                }
+
+                pub fn has_to(&self) -> bool {
+                    !self.builder.get_pointer_field(1).is_null()
                 }
             }
 
@@ -6810,24 +7063,18 @@ pub mod asset_hub {
                     }
                 }
             }
-            impl Pipeline {
-                pub fn get_snapshot(&self) -> crate::service_capnp::asset_hub::snapshot::Client {
-                    ::capnp::capability::FromClientHook::new(
-                        self._typeless.get_pointer_field(0).as_cap(),
-                    )
-                }
-            }
+            impl Pipeline {}
             mod _private {
                 use capnp::private::layout;
                 pub const STRUCT_SIZE: layout::StructSize = layout::StructSize {
-                    data: 1,
-                    pointers: 1,
+                    data: 0,
+                    pointers: 2,
                 };
-                pub const TYPE_ID: u64 = 0xb906_8ad0_71c0_62a8;
+                pub const TYPE_ID: u64 = 0xc41e_7a93_5d20_8f6b;
             }
         }
 
-        pub mod update_results {
+        pub mod rename_file_results {
             #[derive(Copy, Clone)]
             pub struct Owned(());
             impl<'a> ::capnp::traits::Owned<'a> for Owned {
@@ -6891,6 +7138,22 @@ pub mod asset_hub {
                 pub fn total_size(&self) -> ::capnp::Result<::capnp::MessageSize> {
                     self.reader.total_size()
                 }
+
+                #[inline]
+                pub fn get_dangling_refs(
+                    self,
+                ) -> ::capnp::Result<
+                    ::capnp::struct_list::Reader<'a, crate::service_capnp::path_ref::Owned>,
+                > {
+                    ::capnp::traits::FromPointerReader::get_from_pointer(
+                        &self.reader.get_pointer_field(0),
+                        ::core::option::Option::None,
+                    )
+                }
+
+                pub fn has_dangling_refs(&self) -> bool {
+                    !self.reader.get_pointer_field(0).is_null()
+                }
             }
 
             pub struct Builder<'a> {
@@ -6921,77 +7184,2179 @@ pub mod asset_hub {
                 }
             }
 
-            impl<'a> ::capnp::traits::FromPointerBuilder<'a> for Builder<'a> {
-                fn init_pointer(
-                    builder: ::capnp::private::layout::PointerBuilder<'a>,
-                    _size: u32,
-                ) -> Builder<'a> {
-                    ::capnp::traits::FromStructBuilder::new(
-                        builder.init_struct(_private::STRUCT_SIZE),
-                    )
-                }
+            impl<'a> ::capnp::traits::FromPointerBuilder<'a> for Builder<'a> {
+                fn init_pointer(
+                    builder: ::capnp::private::layout::PointerBuilder<'a>,
+                    _size: u32,
+                ) -> Builder<'a> {
+                    ::capnp::traits::FromStructBuilder::new(
+                        builder.init_struct(_private::STRUCT_SIZE),
+                    )
+                }
+
+                fn get_from_pointer(
+                    builder: ::capnp::private::layout::PointerBuilder<'a>,
+                    default: ::core::option::Option<&'a [capnp::Word]>,
+                ) -> ::capnp::Result<Builder<'a>> {
+                    ::core::result::Result::Ok(::capnp::traits::FromStructBuilder::new(
+                        builder.get_struct(_private::STRUCT_SIZE, default)?,
+                    ))
+                }
+            }
+
+            impl<'a> ::capnp::traits::SetPointerBuilder for Reader<'a> {
+                fn set_pointer_builder<'b>(
+                    pointer: ::capnp::private::layout::PointerBuilder<'b>,
+                    value: Reader<'a>,
+                    canonicalize: bool,
+                ) -> ::capnp::Result<()> {
+                    pointer.set_struct(&value.reader, canonicalize)
+                }
+            }
+
+            impl<'a> Builder<'a> {
+                pub fn into_reader(self) -> Reader<'a> {
+                    ::capnp::traits::FromStructReader::new(self.builder.into_reader())
+                }
+
+                pub fn reborrow(&mut self) -> Builder<'_> {
+                    Builder { ..*self }
+                }
+
+                pub fn reborrow_as_reader(&self) -> Reader<'_> {
+                    ::capnp::traits::FromStructReader::new(self.builder.into_reader())
+                }
+
+                pub fn total_size(&self) -> ::capnp::Result<::capnp::MessageSize> {
+                    self.builder.into_reader().total_size()
+                }
+
+                #[inline]
+                pub fn get_dangling_refs(
+                    self,
+                ) -> ::capnp::Result<
+                    ::capnp::struct_list::Builder<'a, crate::service_capnp::path_ref::Owned>,
+                > {
+                    ::capnp::traits::FromPointerBuilder::get_from_pointer(
+                        self.builder.get_pointer_field(0),
+                        ::core::option::Option::None,
+                    )
+                }
+
+                #[inline]
+                pub fn set_dangling_refs(
+                    &mut self,
+                    value: ::capnp::struct_list::Reader<'a, crate::service_capnp::path_ref::Owned>,
+                ) -> ::capnp::Result<()> {
+                    ::capnp::traits::SetPointerBuilder::set_pointer_builder(
+                        self.builder.get_pointer_field(0),
+                        value,
+                        false,
+                    )
+                }
+
+                #[inline]
+                pub fn init_dangling_refs(
+                    self,
+                    size: u32,
+                ) -> ::capnp::struct_list::Builder<'a, crate::service_capnp::path_ref::Owned>
+                {
+                    ::capnp::traits::FromPointerBuilder::init_pointer(
+                        self.builder.get_pointer_field(0),
+                        size,
+                    )
+                }
+
+                pub fn has_dangling_refs(&self) -> bool {
+                    !self.builder.get_pointer_field(0).is_null()
+                }
+            }
+
+            pub struct Pipeline {
+                _typeless: ::capnp::any_pointer::Pipeline,
+            }
+            impl ::capnp::capability::FromTypelessPipeline for Pipeline {
+                fn new(typeless: ::capnp::any_pointer::Pipeline) -> Pipeline {
+                    Pipeline {
+                        _typeless: typeless,
+                    }
+                }
+            }
+            impl Pipeline {}
+            mod _private {
+                use capnp::private::layout;
+                pub const STRUCT_SIZE: layout::StructSize = layout::StructSize {
+                    data: 0,
+                    pointers: 1,
+                };
+                pub const TYPE_ID: u64 = 0x9a6d_0e5b_73c1_f248;
+            }
+        }
+
+        pub mod get_meta_file_issues_params {
+            #[derive(Copy, Clone)]
+            pub struct Owned(());
+            impl<'a> ::capnp::traits::Owned<'a> for Owned {
+                type Builder = Builder<'a>;
+                type Reader = Reader<'a>;
+            }
+            impl<'a> ::capnp::traits::OwnedStruct<'a> for Owned {
+                type Builder = Builder<'a>;
+                type Reader = Reader<'a>;
+            }
+            impl ::capnp::traits::Pipelined for Owned {
+                type Pipeline = Pipeline;
+            }
+
+            #[derive(Clone, Copy)]
+            pub struct Reader<'a> {
+                reader: ::capnp::private::layout::StructReader<'a>,
+            }
+
+            impl<'a> ::capnp::traits::HasTypeId for Reader<'a> {
+                #[inline]
+                fn type_id() -> u64 {
+                    _private::TYPE_ID
+                }
+            }
+            impl<'a> ::capnp::traits::FromStructReader<'a> for Reader<'a> {
+                fn new(reader: ::capnp::private::layout::StructReader<'a>) -> Reader<'a> {
+                    Reader { reader }
+                }
+            }
+
+            impl<'a> ::capnp::traits::FromPointerReader<'a> for Reader<'a> {
+                fn get_from_pointer(
+                    reader: &::capnp::private::layout::PointerReader<'a>,
+                    default: ::core::option::Option<&'a [capnp::Word]>,
+                ) -> ::capnp::Result<Reader<'a>> {
+                    ::core::result::Result::Ok(::capnp::traits::FromStructReader::new(
+                        reader.get_struct(default)?,
+                    ))
+                }
+            }
+
+            impl<'a> ::capnp::traits::IntoInternalStructReader<'a> for Reader<'a> {
+                fn into_internal_struct_reader(self) -> ::capnp::private::layout::StructReader<'a> {
+                    self.reader
+                }
+            }
+
+            impl<'a> ::capnp::traits::Imbue<'a> for Reader<'a> {
+                fn imbue(&mut self, cap_table: &'a ::capnp::private::layout::CapTable) {
+                    self.reader
+                        .imbue(::capnp::private::layout::CapTableReader::Plain(cap_table))
+                }
+            }
+
+            impl<'a> Reader<'a> {
+                pub fn reborrow(&self) -> Reader<'_> {
+                    Reader { ..*self }
+                }
+
+                pub fn total_size(&self) -> ::capnp::Result<::capnp::MessageSize> {
+                    self.reader.total_size()
+                }
+            }
+
+            pub struct Builder<'a> {
+                builder: ::capnp::private::layout::StructBuilder<'a>,
+            }
+            impl<'a> ::capnp::traits::HasStructSize for Builder<'a> {
+                #[inline]
+                fn struct_size() -> ::capnp::private::layout::StructSize {
+                    _private::STRUCT_SIZE
+                }
+            }
+            impl<'a> ::capnp::traits::HasTypeId for Builder<'a> {
+                #[inline]
+                fn type_id() -> u64 {
+                    _private::TYPE_ID
+                }
+            }
+            impl<'a> ::capnp::traits::FromStructBuilder<'a> for Builder<'a> {
+                fn new(builder: ::capnp::private::layout::StructBuilder<'a>) -> Builder<'a> {
+                    Builder { builder }
+                }
+            }
+
+            impl<'a> ::capnp::traits::ImbueMut<'a> for Builder<'a> {
+                fn imbue_mut(&mut self, cap_table: &'a mut ::capnp::private::layout::CapTable) {
+                    self.builder
+                        .imbue(::capnp::private::layout::CapTableBuilder::Plain(cap_table))
+                }
+            }
+
+            impl<'a> ::capnp::traits::FromPointerBuilder<'a> for Builder<'a> {
+                fn init_pointer(
+                    builder: ::capnp::private::layout::PointerBuilder<'a>,
+                    _size: u32,
+                ) -> Builder<'a> {
+                    ::capnp::traits::FromStructBuilder::new(
+                        builder.init_struct(_private::STRUCT_SIZE),
+                    )
+                }
+
+                fn get_from_pointer(
+                    builder: ::capnp::private::layout::PointerBuilder<'a>,
+                    default: ::core::option::Option<&'a [capnp::Word]>,
+                ) -> ::capnp::Result<Builder<'a>> {
+                    ::core::result::Result::Ok(::capnp::traits::FromStructBuilder::new(
+                        builder.get_struct(_private::STRUCT_SIZE, default)?,
+                    ))
+                }
+            }
+
+            impl<'a> ::capnp::traits::SetPointerBuilder for Reader<'a> {
+                fn set_pointer_builder<'b>(
+                    pointer: ::capnp::private::layout::PointerBuilder<'b>,
+                    value: Reader<'a>,
+                    canonicalize: bool,
+                ) -> ::capnp::Result<()> {
+                    pointer.set_struct(&value.reader, canonicalize)
+                }
+            }
+
+            impl<'a> Builder<'a> {
+                pub fn into_reader(self) -> Reader<'a> {
+                    ::capnp::traits::FromStructReader::new(self.builder.into_reader())
+                }
+
+                pub fn reborrow(&mut self) -> Builder<'_> {
+                    Builder { ..*self }
+                }
+
+                pub fn reborrow_as_reader(&self) -> Reader<'_> {
+                    ::capnp::traits::FromStructReader::new(self.builder.into_reader())
+                }
+
+                pub fn total_size(&self) -> ::capnp::Result<::capnp::MessageSize> {
+                    self.builder.into_reader().total_size()
+                }
+            }
+
+            pub struct Pipeline {
+                _typeless: ::capnp::any_pointer::Pipeline,
+            }
+            impl ::capnp::capability::FromTypelessPipeline for Pipeline {
+                fn new(typeless: ::capnp::any_pointer::Pipeline) -> Pipeline {
+                    Pipeline {
+                        _typeless: typeless,
+                    }
+                }
+            }
+            impl Pipeline {}
+            mod _private {
+                use capnp::private::layout;
+                pub const STRUCT_SIZE: layout::StructSize = layout::StructSize {
+                    data: 0,
+                    pointers: 0,
+                };
+                pub const TYPE_ID: u64 = 0xdcc0_8ccc_8334_4233;
+            }
+        }
+
+        pub mod get_meta_file_issues_results {
+            #[derive(Copy, Clone)]
+            pub struct Owned(());
+            impl<'a> ::capnp::traits::Owned<'a> for Owned {
+                type Builder = Builder<'a>;
+                type Reader = Reader<'a>;
+            }
+            impl<'a> ::capnp::traits::OwnedStruct<'a> for Owned {
+                type Builder = Builder<'a>;
+                type Reader = Reader<'a>;
+            }
+            impl ::capnp::traits::Pipelined for Owned {
+                type Pipeline = Pipeline;
+            }
+
+            #[derive(Clone, Copy)]
+            pub struct Reader<'a> {
+                reader: ::capnp::private::layout::StructReader<'a>,
+            }
+
+            impl<'a> ::capnp::traits::HasTypeId for Reader<'a> {
+                #[inline]
+                fn type_id() -> u64 {
+                    _private::TYPE_ID
+                }
+            }
+            impl<'a> ::capnp::traits::FromStructReader<'a> for Reader<'a> {
+                fn new(reader: ::capnp::private::layout::StructReader<'a>) -> Reader<'a> {
+                    Reader { reader }
+                }
+            }
+
+            impl<'a> ::capnp::traits::FromPointerReader<'a> for Reader<'a> {
+                fn get_from_pointer(
+                    reader: &::capnp::private::layout::PointerReader<'a>,
+                    default: ::core::option::Option<&'a [capnp::Word]>,
+                ) -> ::capnp::Result<Reader<'a>> {
+                    ::core::result::Result::Ok(::capnp::traits::FromStructReader::new(
+                        reader.get_struct(default)?,
+                    ))
+                }
+            }
+
+            impl<'a> ::capnp::traits::IntoInternalStructReader<'a> for Reader<'a> {
+                fn into_internal_struct_reader(self) -> ::capnp::private::layout::StructReader<'a> {
+                    self.reader
+                }
+            }
+
+            impl<'a> ::capnp::traits::Imbue<'a> for Reader<'a> {
+                fn imbue(&mut self, cap_table: &'a ::capnp::private::layout::CapTable) {
+                    self.reader
+                        .imbue(::capnp::private::layout::CapTableReader::Plain(cap_table))
+                }
+            }
+
+            impl<'a> Reader<'a> {
+                pub fn reborrow(&self) -> Reader<'_> {
+                    Reader { ..*self }
+                }
+
+                pub fn total_size(&self) -> ::capnp::Result<::capnp::MessageSize> {
+                    self.reader.total_size()
+                }
+
+                #[inline]
+                pub fn get_orphaned_metas(self) -> ::capnp::Result<::capnp::data_list::Reader<'a>> {
+                    ::capnp::traits::FromPointerReader::get_from_pointer(
+                        &self.reader.get_pointer_field(0),
+                        ::core::option::Option::None,
+                    )
+                }
+
+                pub fn has_orphaned_metas(&self) -> bool {
+                    !self.reader.get_pointer_field(0).is_null()
+                }
+
+                #[inline]
+                pub fn get_missing_metas(
+                    self,
+                ) -> ::capnp::Result<
+                    ::capnp::struct_list::Reader<'a, crate::service_capnp::path_assets::Owned>,
+                > {
+                    ::capnp::traits::FromPointerReader::get_from_pointer(
+                        &self.reader.get_pointer_field(1),
+                        ::core::option::Option::None,
+                    )
+                }
+
+                pub fn has_missing_metas(&self) -> bool {
+                    !self.reader.get_pointer_field(1).is_null()
+                }
+            }
+
+            pub struct Builder<'a> {
+                builder: ::capnp::private::layout::StructBuilder<'a>,
+            }
+            impl<'a> ::capnp::traits::HasStructSize for Builder<'a> {
+                #[inline]
+                fn struct_size() -> ::capnp::private::layout::StructSize {
+                    _private::STRUCT_SIZE
+                }
+            }
+            impl<'a> ::capnp::traits::HasTypeId for Builder<'a> {
+                #[inline]
+                fn type_id() -> u64 {
+                    _private::TYPE_ID
+                }
+            }
+            impl<'a> ::capnp::traits::FromStructBuilder<'a> for Builder<'a> {
+                fn new(builder: ::capnp::private::layout::StructBuilder<'a>) -> Builder<'a> {
+                    Builder { builder }
+                }
+            }
+
+            impl<'a> ::capnp::traits::ImbueMut<'a> for Builder<'a> {
+                fn imbue_mut(&mut self, cap_table: &'a mut ::capnp::private::layout::CapTable) {
+                    self.builder
+                        .imbue(::capnp::private::layout::CapTableBuilder::Plain(cap_table))
+                }
+            }
+
+            impl<'a> ::capnp::traits::FromPointerBuilder<'a> for Builder<'a> {
+                fn init_pointer(
+                    builder: ::capnp::private::layout::PointerBuilder<'a>,
+                    _size: u32,
+                ) -> Builder<'a> {
+                    ::capnp::traits::FromStructBuilder::new(
+                        builder.init_struct(_private::STRUCT_SIZE),
+                    )
+                }
+
+                fn get_from_pointer(
+                    builder: ::capnp::private::layout::PointerBuilder<'a>,
+                    default: ::core::option::Option<&'a [capnp::Word]>,
+                ) -> ::capnp::Result<Builder<'a>> {
+                    ::core::result::Result::Ok(::capnp::traits::FromStructBuilder::new(
+                        builder.get_struct(_private::STRUCT_SIZE, default)?,
+                    ))
+                }
+            }
+
+            impl<'a> ::capnp::traits::SetPointerBuilder for Reader<'a> {
+                fn set_pointer_builder<'b>(
+                    pointer: ::capnp::private::layout::PointerBuilder<'b>,
+                    value: Reader<'a>,
+                    canonicalize: bool,
+                ) -> ::capnp::Result<()> {
+                    pointer.set_struct(&value.reader, canonicalize)
+                }
+            }
+
+            impl<'a> Builder<'a> {
+                pub fn into_reader(self) -> Reader<'a> {
+                    ::capnp::traits::FromStructReader::new(self.builder.into_reader())
+                }
+
+                pub fn reborrow(&mut self) -> Builder<'_> {
+                    Builder { ..*self }
+                }
+
+                pub fn reborrow_as_reader(&self) -> Reader<'_> {
+                    ::capnp::traits::FromStructReader::new(self.builder.into_reader())
+                }
+
+                pub fn total_size(&self) -> ::capnp::Result<::capnp::MessageSize> {
+                    self.builder.into_reader().total_size()
+                }
+
+                #[inline]
+                pub fn get_orphaned_metas(
+                    self,
+                ) -> ::capnp::Result<::capnp::data_list::Builder<'a>> {
+                    ::capnp::traits::FromPointerBuilder::get_from_pointer(
+                        self.builder.get_pointer_field(0),
+                        ::core::option::Option::None,
+                    )
+                }
+
+                #[inline]
+                pub fn set_orphaned_metas(
+                    &mut self,
+                    value: ::capnp::data_list::Reader<'a>,
+                ) -> ::capnp::Result<()> {
+                    ::capnp::traits::SetPointerBuilder::set_pointer_builder(
+                        self.builder.get_pointer_field(0),
+                        value,
+                        false,
+                    )
+                }
+
+                #[inline]
+                pub fn init_orphaned_metas(self, size: u32) -> ::capnp::data_list::Builder<'a> {
+                    ::capnp::traits::FromPointerBuilder::init_pointer(
+                        self.builder.get_pointer_field(0),
+                        size,
+                    )
+                }
+
+                pub fn has_orphaned_metas(&self) -> bool {
+                    !self.builder.get_pointer_field(0).is_null()
+                }
+
+                #[inline]
+                pub fn get_missing_metas(
+                    self,
+                ) -> ::capnp::Result<
+                    ::capnp::struct_list::Builder<'a, crate::service_capnp::path_assets::Owned>,
+                > {
+                    ::capnp::traits::FromPointerBuilder::get_from_pointer(
+                        self.builder.get_pointer_field(1),
+                        ::core::option::Option::None,
+                    )
+                }
+
+                #[inline]
+                pub fn set_missing_metas(
+                    &mut self,
+                    value: ::capnp::struct_list::Reader<
+                        'a,
+                        crate::service_capnp::path_assets::Owned,
+                    >,
+                ) -> ::capnp::Result<()> {
+                    ::capnp::traits::SetPointerBuilder::set_pointer_builder(
+                        self.builder.get_pointer_field(1),
+                        value,
+                        false,
+                    )
+                }
+
+                #[inline]
+                pub fn init_missing_metas(
+                    self,
+                    size: u32,
+                ) -> ::capnp::struct_list::Builder<'a, crate::service_capnp::path_assets::Owned>
+                {
+                    ::capnp::traits::FromPointerBuilder::init_pointer(
+                        self.builder.get_pointer_field(1),
+                        size,
+                    )
+                }
+
+                pub fn has_missing_metas(&self) -> bool {
+                    !self.builder.get_pointer_field(1).is_null()
+                }
+            }
+
+            pub struct Pipeline {
+                _typeless: ::capnp::any_pointer::Pipeline,
+            }
+            impl ::capnp::capability::FromTypelessPipeline for Pipeline {
+                fn new(typeless: ::capnp::any_pointer::Pipeline) -> Pipeline {
+                    Pipeline {
+                        _typeless: typeless,
+                    }
+                }
+            }
+            impl Pipeline {}
+            mod _private {
+                use capnp::private::layout;
+                pub const STRUCT_SIZE: layout::StructSize = layout::StructSize {
+                    data: 0,
+                    pointers: 2,
+                };
+                pub const TYPE_ID: u64 = 0xa7ef_3f46_226c_438c;
+            }
+        }
+
+        pub mod regenerate_meta_file_params {
+            #[derive(Copy, Clone)]
+            pub struct Owned(());
+            impl<'a> ::capnp::traits::Owned<'a> for Owned {
+                type Builder = Builder<'a>;
+                type Reader = Reader<'a>;
+            }
+            impl<'a> ::capnp::traits::OwnedStruct<'a> for Owned {
+                type Builder = Builder<'a>;
+                type Reader = Reader<'a>;
+            }
+            impl ::capnp::traits::Pipelined for Owned {
+                type Pipeline = Pipeline;
+            }
+
+            #[derive(Clone, Copy)]
+            pub struct Reader<'a> {
+                reader: ::capnp::private::layout::StructReader<'a>,
+            }
+
+            impl<'a> ::capnp::traits::HasTypeId for Reader<'a> {
+                #[inline]
+                fn type_id() -> u64 {
+                    _private::TYPE_ID
+                }
+            }
+            impl<'a> ::capnp::traits::FromStructReader<'a> for Reader<'a> {
+                fn new(reader: ::capnp::private::layout::StructReader<'a>) -> Reader<'a> {
+                    Reader { reader }
+                }
+            }
+
+            impl<'a> ::capnp::traits::FromPointerReader<'a> for Reader<'a> {
+                fn get_from_pointer(
+                    reader: &::capnp::private::layout::PointerReader<'a>,
+                    default: ::core::option::Option<&'a [capnp::Word]>,
+                ) -> ::capnp::Result<Reader<'a>> {
+                    ::core::result::Result::Ok(::capnp::traits::FromStructReader::new(
+                        reader.get_struct(default)?,
+                    ))
+                }
+            }
+
+            impl<'a> ::capnp::traits::IntoInternalStructReader<'a> for Reader<'a> {
+                fn into_internal_struct_reader(self) -> ::capnp::private::layout::StructReader<'a> {
+                    self.reader
+                }
+            }
+
+            impl<'a> ::capnp::traits::Imbue<'a> for Reader<'a> {
+                fn imbue(&mut self, cap_table: &'a ::capnp::private::layout::CapTable) {
+                    self.reader
+                        .imbue(::capnp::private::layout::CapTableReader::Plain(cap_table))
+                }
+            }
+
+            impl<'a> Reader<'a> {
+                pub fn reborrow(&self) -> Reader<'_> {
+                    Reader { ..*self }
+                }
+
+                pub fn total_size(&self) -> ::capnp::Result<::capnp::MessageSize> {
+                    self.reader.total_size()
+                }
+
+                #[inline]
+                pub fn get_path(self) -> ::capnp::Result<::capnp::data::Reader<'a>> {
+                    ::capnp::traits::FromPointerReader::get_from_pointer(
+                        &self.reader.get_pointer_field(0),
+                        ::core::option::Option::None,
+                    )
+                }
+
+                pub fn has_path(&self) -> bool {
+                    !self.reader.get_pointer_field(0).is_null()
+                }
+            }
+
+            pub struct Builder<'a> {
+                builder: ::capnp::private::layout::StructBuilder<'a>,
+            }
+            impl<'a> ::capnp::traits::HasStructSize for Builder<'a> {
+                #[inline]
+                fn struct_size() -> ::capnp::private::layout::StructSize {
+                    _private::STRUCT_SIZE
+                }
+            }
+            impl<'a> ::capnp::traits::HasTypeId for Builder<'a> {
+                #[inline]
+                fn type_id() -> u64 {
+                    _private::TYPE_ID
+                }
+            }
+            impl<'a> ::capnp::traits::FromStructBuilder<'a> for Builder<'a> {
+                fn new(builder: ::capnp::private::layout::StructBuilder<'a>) -> Builder<'a> {
+                    Builder { builder }
+                }
+            }
+
+            impl<'a> ::capnp::traits::ImbueMut<'a> for Builder<'a> {
+                fn imbue_mut(&mut self, cap_table: &'a mut ::capnp::private::layout::CapTable) {
+                    self.builder
+                        .imbue(::capnp::private::layout::CapTableBuilder::Plain(cap_table))
+                }
+            }
+
+            impl<'a> ::capnp::traits::FromPointerBuilder<'a> for Builder<'a> {
+                fn init_pointer(
+                    builder: ::capnp::private::layout::PointerBuilder<'a>,
+                    _size: u32,
+                ) -> Builder<'a> {
+                    ::capnp::traits::FromStructBuilder::new(
+                        builder.init_struct(_private::STRUCT_SIZE),
+                    )
+                }
+
+                fn get_from_pointer(
+                    builder: ::capnp::private::layout::PointerBuilder<'a>,
+                    default: ::core::option::Option<&'a [capnp::Word]>,
+                ) -> ::capnp::Result<Builder<'a>> {
+                    ::core::result::Result::Ok(::capnp::traits::FromStructBuilder::new(
+                        builder.get_struct(_private::STRUCT_SIZE, default)?,
+                    ))
+                }
+            }
+
+            impl<'a> ::capnp::traits::SetPointerBuilder for Reader<'a> {
+                fn set_pointer_builder<'b>(
+                    pointer: ::capnp::private::layout::PointerBuilder<'b>,
+                    value: Reader<'a>,
+                    canonicalize: bool,
+                ) -> ::capnp::Result<()> {
+                    pointer.set_struct(&value.reader, canonicalize)
+                }
+            }
+
+            impl<'a> Builder<'a> {
+                pub fn into_reader(self) -> Reader<'a> {
+                    ::capnp::traits::FromStructReader::new(self.builder.into_reader())
+                }
+
+                pub fn reborrow(&mut self) -> Builder<'_> {
+                    Builder { ..*self }
+                }
+
+                pub fn reborrow_as_reader(&self) -> Reader<'_> {
+                    ::capnp::traits::FromStructReader::new(self.builder.into_reader())
+                }
+
+                pub fn total_size(&self) -> ::capnp::Result<::capnp::MessageSize> {
+                    self.builder.into_reader().total_size()
+                }
+
+                #[inline]
+                pub fn get_path(self) -> ::capnp::Result<::capnp::data::Builder<'a>> {
+                    ::capnp::traits::FromPointerBuilder::get_from_pointer(
+                        self.builder.get_pointer_field(0),
+                        ::core::option::Option::None,
+                    )
+                }
+
+                #[inline]
+                pub fn set_path(&mut self, value: ::capnp::data::Reader<'_>) {
+                    self.builder.get_pointer_field(0).set_data(value);
+                }
+
+                #[inline]
+                pub fn init_path(self, size: u32) -> ::capnp::data::Builder<'a> {
+                    self.builder.get_pointer_field(0).init_data(size)
+                }
+
+                pub fn has_path(&self) -> bool {
+                    !self.builder.get_pointer_field(0).is_null()
+                }
+            }
+
+            pub struct Pipeline {
+                _typeless: ::capnp::any_pointer::Pipeline,
+            }
+            impl ::capnp::capability::FromTypelessPipeline for Pipeline {
+                fn new(typeless: ::capnp::any_pointer::Pipeline) -> Pipeline {
+                    Pipeline {
+                        _typeless: typeless,
+                    }
+                }
+            }
+            impl Pipeline {}
+            mod _private {
+                use capnp::private::layout;
+                pub const STRUCT_SIZE: layout::StructSize = layout::StructSize {
+                    data: 0,
+                    pointers: 1,
+                };
+                pub const TYPE_ID: u64 = 0xb8b9_8b62_ee88_49c2;
+            }
+        }
+
+        pub mod regenerate_meta_file_results {
+            #[derive(Copy, Clone)]
+            pub struct Owned(());
+            impl<'a> ::capnp::traits::Owned<'a> for Owned {
+                type Builder = Builder<'a>;
+                type Reader = Reader<'a>;
+            }
+            impl<'a> ::capnp::traits::OwnedStruct<'a> for Owned {
+                type Builder = Builder<'a>;
+                type Reader = Reader<'a>;
+            }
+            impl ::capnp::traits::Pipelined for Owned {
+                type Pipeline = Pipeline;
+            }
+
+            #[derive(Clone, Copy)]
+            pub struct Reader<'a> {
+                reader: ::capnp::private::layout::StructReader<'a>,
+            }
+
+            impl<'a> ::capnp::traits::HasTypeId for Reader<'a> {
+                #[inline]
+                fn type_id() -> u64 {
+                    _private::TYPE_ID
+                }
+            }
+            impl<'a> ::capnp::traits::FromStructReader<'a> for Reader<'a> {
+                fn new(reader: ::capnp::private::layout::StructReader<'a>) -> Reader<'a> {
+                    Reader { reader }
+                }
+            }
+
+            impl<'a> ::capnp::traits::FromPointerReader<'a> for Reader<'a> {
+                fn get_from_pointer(
+                    reader: &::capnp::private::layout::PointerReader<'a>,
+                    default: ::core::option::Option<&'a [capnp::Word]>,
+                ) -> ::capnp::Result<Reader<'a>> {
+                    ::core::result::Result::Ok(::capnp::traits::FromStructReader::new(
+                        reader.get_struct(default)?,
+                    ))
+                }
+            }
+
+            impl<'a> ::capnp::traits::IntoInternalStructReader<'a> for Reader<'a> {
+                fn into_internal_struct_reader(self) -> ::capnp::private::layout::StructReader<'a> {
+                    self.reader
+                }
+            }
+
+            impl<'a> ::capnp::traits::Imbue<'a> for Reader<'a> {
+                fn imbue(&mut self, cap_table: &'a ::capnp::private::layout::CapTable) {
+                    self.reader
+                        .imbue(::capnp::private::layout::CapTableReader::Plain(cap_table))
+                }
+            }
+
+            impl<'a> Reader<'a> {
+                pub fn reborrow(&self) -> Reader<'_> {
+                    Reader { ..*self }
+                }
+
+                pub fn total_size(&self) -> ::capnp::Result<::capnp::MessageSize> {
+                    self.reader.total_size()
+                }
+            }
+
+            pub struct Builder<'a> {
+                builder: ::capnp::private::layout::StructBuilder<'a>,
+            }
+            impl<'a> ::capnp::traits::HasStructSize for Builder<'a> {
+                #[inline]
+                fn struct_size() -> ::capnp::private::layout::StructSize {
+                    _private::STRUCT_SIZE
+                }
+            }
+            impl<'a> ::capnp::traits::HasTypeId for Builder<'a> {
+                #[inline]
+                fn type_id() -> u64 {
+                    _private::TYPE_ID
+                }
+            }
+            impl<'a> ::capnp::traits::FromStructBuilder<'a> for Builder<'a> {
+                fn new(builder: ::capnp::private::layout::StructBuilder<'a>) -> Builder<'a> {
+                    Builder { builder }
+                }
+            }
+
+            impl<'a> ::capnp::traits::ImbueMut<'a> for Builder<'a> {
+                fn imbue_mut(&mut self, cap_table: &'a mut ::capnp::private::layout::CapTable) {
+                    self.builder
+                        .imbue(::capnp::private::layout::CapTableBuilder::Plain(cap_table))
+                }
+            }
+
+            impl<'a> ::capnp::traits::FromPointerBuilder<'a> for Builder<'a> {
+                fn init_pointer(
+                    builder: ::capnp::private::layout::PointerBuilder<'a>,
+                    _size: u32,
+                ) -> Builder<'a> {
+                    ::capnp::traits::FromStructBuilder::new(
+                        builder.init_struct(_private::STRUCT_SIZE),
+                    )
+                }
+
+                fn get_from_pointer(
+                    builder: ::capnp::private::layout::PointerBuilder<'a>,
+                    default: ::core::option::Option<&'a [capnp::Word]>,
+                ) -> ::capnp::Result<Builder<'a>> {
+                    ::core::result::Result::Ok(::capnp::traits::FromStructBuilder::new(
+                        builder.get_struct(_private::STRUCT_SIZE, default)?,
+                    ))
+                }
+            }
+
+            impl<'a> ::capnp::traits::SetPointerBuilder for Reader<'a> {
+                fn set_pointer_builder<'b>(
+                    pointer: ::capnp::private::layout::PointerBuilder<'b>,
+                    value: Reader<'a>,
+                    canonicalize: bool,
+                ) -> ::capnp::Result<()> {
+                    pointer.set_struct(&value.reader, canonicalize)
+                }
+            }
+
+            impl<'a> Builder<'a> {
+                pub fn into_reader(self) -> Reader<'a> {
+                    ::capnp::traits::FromStructReader::new(self.builder.into_reader())
+                }
+
+                pub fn reborrow(&mut self) -> Builder<'_> {
+                    Builder { ..*self }
+                }
+
+                pub fn reborrow_as_reader(&self) -> Reader<'_> {
+                    ::capnp::traits::FromStructReader::new(self.builder.into_reader())
+                }
+
+                pub fn total_size(&self) -> ::capnp::Result<::capnp::MessageSize> {
+                    self.builder.into_reader().total_size()
+                }
+            }
+
+            pub struct Pipeline {
+                _typeless: ::capnp::any_pointer::Pipeline,
+            }
+            impl ::capnp::capability::FromTypelessPipeline for Pipeline {
+                fn new(typeless: ::capnp::any_pointer::Pipeline) -> Pipeline {
+                    Pipeline {
+                        _typeless: typeless,
+                    }
+                }
+            }
+            impl Pipeline {}
+            mod _private {
+                use capnp::private::layout;
+                pub const STRUCT_SIZE: layout::StructSize = layout::StructSize {
+                    data: 0,
+                    pointers: 0,
+                };
+                pub const TYPE_ID: u64 = 0xb4b7_4f03_8b8c_44b8;
+            }
+        }
+
+        pub mod remove_orphaned_meta_file_params {
+            #[derive(Copy, Clone)]
+            pub struct Owned(());
+            impl<'a> ::capnp::traits::Owned<'a> for Owned {
+                type Builder = Builder<'a>;
+                type Reader = Reader<'a>;
+            }
+            impl<'a> ::capnp::traits::OwnedStruct<'a> for Owned {
+                type Builder = Builder<'a>;
+                type Reader = Reader<'a>;
+            }
+            impl ::capnp::traits::Pipelined for Owned {
+                type Pipeline = Pipeline;
+            }
+
+            #[derive(Clone, Copy)]
+            pub struct Reader<'a> {
+                reader: ::capnp::private::layout::StructReader<'a>,
+            }
+
+            impl<'a> ::capnp::traits::HasTypeId for Reader<'a> {
+                #[inline]
+                fn type_id() -> u64 {
+                    _private::TYPE_ID
+                }
+            }
+            impl<'a> ::capnp::traits::FromStructReader<'a> for Reader<'a> {
+                fn new(reader: ::capnp::private::layout::StructReader<'a>) -> Reader<'a> {
+                    Reader { reader }
+                }
+            }
+
+            impl<'a> ::capnp::traits::FromPointerReader<'a> for Reader<'a> {
+                fn get_from_pointer(
+                    reader: &::capnp::private::layout::PointerReader<'a>,
+                    default: ::core::option::Option<&'a [capnp::Word]>,
+                ) -> ::capnp::Result<Reader<'a>> {
+                    ::core::result::Result::Ok(::capnp::traits::FromStructReader::new(
+                        reader.get_struct(default)?,
+                    ))
+                }
+            }
+
+            impl<'a> ::capnp::traits::IntoInternalStructReader<'a> for Reader<'a> {
+                fn into_internal_struct_reader(self) -> ::capnp::private::layout::StructReader<'a> {
+                    self.reader
+                }
+            }
+
+            impl<'a> ::capnp::traits::Imbue<'a> for Reader<'a> {
+                fn imbue(&mut self, cap_table: &'a ::capnp::private::layout::CapTable) {
+                    self.reader
+                        .imbue(::capnp::private::layout::CapTableReader::Plain(cap_table))
+                }
+            }
+
+            impl<'a> Reader<'a> {
+                pub fn reborrow(&self) -> Reader<'_> {
+                    Reader { ..*self }
+                }
+
+                pub fn total_size(&self) -> ::capnp::Result<::capnp::MessageSize> {
+                    self.reader.total_size()
+                }
+
+                #[inline]
+                pub fn get_path(self) -> ::capnp::Result<::capnp::data::Reader<'a>> {
+                    ::capnp::traits::FromPointerReader::get_from_pointer(
+                        &self.reader.get_pointer_field(0),
+                        ::core::option::Option::None,
+                    )
+                }
+
+                pub fn has_path(&self) -> bool {
+                    !self.reader.get_pointer_field(0).is_null()
+                }
+            }
+
+            pub struct Builder<'a> {
+                builder: ::capnp::private::layout::StructBuilder<'a>,
+            }
+            impl<'a> ::capnp::traits::HasStructSize for Builder<'a> {
+                #[inline]
+                fn struct_size() -> ::capnp::private::layout::StructSize {
+                    _private::STRUCT_SIZE
+                }
+            }
+            impl<'a> ::capnp::traits::HasTypeId for Builder<'a> {
+                #[inline]
+                fn type_id() -> u64 {
+                    _private::TYPE_ID
+                }
+            }
+            impl<'a> ::capnp::traits::FromStructBuilder<'a> for Builder<'a> {
+                fn new(builder: ::capnp::private::layout::StructBuilder<'a>) -> Builder<'a> {
+                    Builder { builder }
+                }
+            }
+
+            impl<'a> ::capnp::traits::ImbueMut<'a> for Builder<'a> {
+                fn imbue_mut(&mut self, cap_table: &'a mut ::capnp::private::layout::CapTable) {
+                    self.builder
+                        .imbue(::capnp::private::layout::CapTableBuilder::Plain(cap_table))
+                }
+            }
+
+            impl<'a> ::capnp::traits::FromPointerBuilder<'a> for Builder<'a> {
+                fn init_pointer(
+                    builder: ::capnp::private::layout::PointerBuilder<'a>,
+                    _size: u32,
+                ) -> Builder<'a> {
+                    ::capnp::traits::FromStructBuilder::new(
+                        builder.init_struct(_private::STRUCT_SIZE),
+                    )
+                }
+
+                fn get_from_pointer(
+                    builder: ::capnp::private::layout::PointerBuilder<'a>,
+                    default: ::core::option::Option<&'a [capnp::Word]>,
+                ) -> ::capnp::Result<Builder<'a>> {
+                    ::core::result::Result::Ok(::capnp::traits::FromStructBuilder::new(
+                        builder.get_struct(_private::STRUCT_SIZE, default)?,
+                    ))
+                }
+            }
+
+            impl<'a> ::capnp::traits::SetPointerBuilder for Reader<'a> {
+                fn set_pointer_builder<'b>(
+                    pointer: ::capnp::private::layout::PointerBuilder<'b>,
+                    value: Reader<'a>,
+                    canonicalize: bool,
+                ) -> ::capnp::Result<()> {
+                    pointer.set_struct(&value.reader, canonicalize)
+                }
+            }
+
+            impl<'a> Builder<'a> {
+                pub fn into_reader(self) -> Reader<'a> {
+                    ::capnp::traits::FromStructReader::new(self.builder.into_reader())
+                }
+
+                pub fn reborrow(&mut self) -> Builder<'_> {
+                    Builder { ..*self }
+                }
+
+                pub fn reborrow_as_reader(&self) -> Reader<'_> {
+                    ::capnp::traits::FromStructReader::new(self.builder.into_reader())
+                }
+
+                pub fn total_size(&self) -> ::capnp::Result<::capnp::MessageSize> {
+                    self.builder.into_reader().total_size()
+                }
+
+                #[inline]
+                pub fn get_path(self) -> ::capnp::Result<::capnp::data::Builder<'a>> {
+                    ::capnp::traits::FromPointerBuilder::get_from_pointer(
+                        self.builder.get_pointer_field(0),
+                        ::core::option::Option::None,
+                    )
+                }
+
+                #[inline]
+                pub fn set_path(&mut self, value: ::capnp::data::Reader<'_>) {
+                    self.builder.get_pointer_field(0).set_data(value);
+                }
+
+                #[inline]
+                pub fn init_path(self, size: u32) -> ::capnp::data::Builder<'a> {
+                    self.builder.get_pointer_field(0).init_data(size)
+                }
+
+                pub fn has_path(&self) -> bool {
+                    !self.builder.get_pointer_field(0).is_null()
+                }
+            }
+
+            pub struct Pipeline {
+                _typeless: ::capnp::any_pointer::Pipeline,
+            }
+            impl ::capnp::capability::FromTypelessPipeline for Pipeline {
+                fn new(typeless: ::capnp::any_pointer::Pipeline) -> Pipeline {
+                    Pipeline {
+                        _typeless: typeless,
+                    }
+                }
+            }
+            impl Pipeline {}
+            mod _private {
+                use capnp::private::layout;
+                pub const STRUCT_SIZE: layout::StructSize = layout::StructSize {
+                    data: 0,
+                    pointers: 1,
+                };
+                pub const TYPE_ID: u64 = 0xcfb2_f43e_d718_4a8f;
+            }
+        }
+
+        pub mod remove_orphaned_meta_file_results {
+            #[derive(Copy, Clone)]
+            pub struct Owned(());
+            impl<'a> ::capnp::traits::Owned<'a> for Owned {
+                type Builder = Builder<'a>;
+                type Reader = Reader<'a>;
+            }
+            impl<'a> ::capnp::traits::OwnedStruct<'a> for Owned {
+                type Builder = Builder<'a>;
+                type Reader = Reader<'a>;
+            }
+            impl ::capnp::traits::Pipelined for Owned {
+                type Pipeline = Pipeline;
+            }
+
+            #[derive(Clone, Copy)]
+            pub struct Reader<'a> {
+                reader: ::capnp::private::layout::StructReader<'a>,
+            }
+
+            impl<'a> ::capnp::traits::HasTypeId for Reader<'a> {
+                #[inline]
+                fn type_id() -> u64 {
+                    _private::TYPE_ID
+                }
+            }
+            impl<'a> ::capnp::traits::FromStructReader<'a> for Reader<'a> {
+                fn new(reader: ::capnp::private::layout::StructReader<'a>) -> Reader<'a> {
+                    Reader { reader }
+                }
+            }
+
+            impl<'a> ::capnp::traits::FromPointerReader<'a> for Reader<'a> {
+                fn get_from_pointer(
+                    reader: &::capnp::private::layout::PointerReader<'a>,
+                    default: ::core::option::Option<&'a [capnp::Word]>,
+                ) -> ::capnp::Result<Reader<'a>> {
+                    ::core::result::Result::Ok(::capnp::traits::FromStructReader::new(
+                        reader.get_struct(default)?,
+                    ))
+                }
+            }
+
+            impl<'a> ::capnp::traits::IntoInternalStructReader<'a> for Reader<'a> {
+                fn into_internal_struct_reader(self) -> ::capnp::private::layout::StructReader<'a> {
+                    self.reader
+                }
+            }
+
+            impl<'a> ::capnp::traits::Imbue<'a> for Reader<'a> {
+                fn imbue(&mut self, cap_table: &'a ::capnp::private::layout::CapTable) {
+                    self.reader
+                        .imbue(::capnp::private::layout::CapTableReader::Plain(cap_table))
+                }
+            }
+
+            impl<'a> Reader<'a> {
+                pub fn reborrow(&self) -> Reader<'_> {
+                    Reader { ..*self }
+                }
+
+                pub fn total_size(&self) -> ::capnp::Result<::capnp::MessageSize> {
+                    self.reader.total_size()
+                }
+            }
+
+            pub struct Builder<'a> {
+                builder: ::capnp::private::layout::StructBuilder<'a>,
+            }
+            impl<'a> ::capnp::traits::HasStructSize for Builder<'a> {
+                #[inline]
+                fn struct_size() -> ::capnp::private::layout::StructSize {
+                    _private::STRUCT_SIZE
+                }
+            }
+            impl<'a> ::capnp::traits::HasTypeId for Builder<'a> {
+                #[inline]
+                fn type_id() -> u64 {
+                    _private::TYPE_ID
+                }
+            }
+            impl<'a> ::capnp::traits::FromStructBuilder<'a> for Builder<'a> {
+                fn new(builder: ::capnp::private::layout::StructBuilder<'a>) -> Builder<'a> {
+                    Builder { builder }
+                }
+            }
+
+            impl<'a> ::capnp::traits::ImbueMut<'a> for Builder<'a> {
+                fn imbue_mut(&mut self, cap_table: &'a mut ::capnp::private::layout::CapTable) {
+                    self.builder
+                        .imbue(::capnp::private::layout::CapTableBuilder::Plain(cap_table))
+                }
+            }
+
+            impl<'a> ::capnp::traits::FromPointerBuilder<'a> for Builder<'a> {
+                fn init_pointer(
+                    builder: ::capnp::private::layout::PointerBuilder<'a>,
+                    _size: u32,
+                ) -> Builder<'a> {
+                    ::capnp::traits::FromStructBuilder::new(
+                        builder.init_struct(_private::STRUCT_SIZE),
+                    )
+                }
+
+                fn get_from_pointer(
+                    builder: ::capnp::private::layout::PointerBuilder<'a>,
+                    default: ::core::option::Option<&'a [capnp::Word]>,
+                ) -> ::capnp::Result<Builder<'a>> {
+                    ::core::result::Result::Ok(::capnp::traits::FromStructBuilder::new(
+                        builder.get_struct(_private::STRUCT_SIZE, default)?,
+                    ))
+                }
+            }
+
+            impl<'a> ::capnp::traits::SetPointerBuilder for Reader<'a> {
+                fn set_pointer_builder<'b>(
+                    pointer: ::capnp::private::layout::PointerBuilder<'b>,
+                    value: Reader<'a>,
+                    canonicalize: bool,
+                ) -> ::capnp::Result<()> {
+                    pointer.set_struct(&value.reader, canonicalize)
+                }
+            }
+
+            impl<'a> Builder<'a> {
+                pub fn into_reader(self) -> Reader<'a> {
+                    ::capnp::traits::FromStructReader::new(self.builder.into_reader())
+                }
+
+                pub fn reborrow(&mut self) -> Builder<'_> {
+                    Builder { ..*self }
+                }
+
+                pub fn reborrow_as_reader(&self) -> Reader<'_> {
+                    ::capnp::traits::FromStructReader::new(self.builder.into_reader())
+                }
+
+                pub fn total_size(&self) -> ::capnp::Result<::capnp::MessageSize> {
+                    self.builder.into_reader().total_size()
+                }
+            }
+
+            pub struct Pipeline {
+                _typeless: ::capnp::any_pointer::Pipeline,
+            }
+            impl ::capnp::capability::FromTypelessPipeline for Pipeline {
+                fn new(typeless: ::capnp::any_pointer::Pipeline) -> Pipeline {
+                    Pipeline {
+                        _typeless: typeless,
+                    }
+                }
+            }
+            impl Pipeline {}
+            mod _private {
+                use capnp::private::layout;
+                pub const STRUCT_SIZE: layout::StructSize = layout::StructSize {
+                    data: 0,
+                    pointers: 0,
+                };
+                pub const TYPE_ID: u64 = 0x9645_0e9e_d231_4c67;
+            }
+        }
+    }
+
+    pub mod listener {
+        #![allow(unused_variables)]
+        pub type UpdateParams = ::capnp::capability::Params<
+            crate::service_capnp::asset_hub::listener::update_params::Owned,
+        >;
+        pub type UpdateResults = ::capnp::capability::Results<
+            crate::service_capnp::asset_hub::listener::update_results::Owned,
+        >;
+
+        pub struct Client {
+            pub client: ::capnp::capability::Client,
+        }
+        impl ::capnp::capability::FromClientHook for Client {
+            fn new(hook: Box<dyn (::capnp::private::capability::ClientHook)>) -> Client {
+                Client {
+                    client: ::capnp::capability::Client::new(hook),
+                }
+            }
+        }
+        #[derive(Copy, Clone)]
+        pub struct Owned(());
+        impl<'a> ::capnp::traits::Owned<'a> for Owned {
+            type Builder = Client;
+            type Reader = Client;
+        }
+        impl ::capnp::traits::Pipelined for Owned {
+            type Pipeline = Client;
+        }
+        impl<'a> ::capnp::traits::FromPointerReader<'a> for Client {
+            fn get_from_pointer(
+                reader: &::capnp::private::layout::PointerReader<'a>,
+                _default: ::core::option::Option<&'a [capnp::Word]>,
+            ) -> ::capnp::Result<Client> {
+                ::core::result::Result::Ok(::capnp::capability::FromClientHook::new(
+                    reader.get_capability()?,
+                ))
+            }
+        }
+        impl<'a> ::capnp::traits::FromPointerBuilder<'a> for Client {
+            fn init_pointer(
+                _builder: ::capnp::private::layout::PointerBuilder<'a>,
+                _size: u32,
+            ) -> Client {
+                unimplemented!()
+            }
+
+            fn get_from_pointer(
+                builder: ::capnp::private::layout::PointerBuilder<'a>,
+                _default: ::core::option::Option<&'a [capnp::Word]>,
+            ) -> ::capnp::Result<Client> {
+                ::core::result::Result::Ok(::capnp::capability::FromClientHook::new(
+                    builder.get_capability()?,
+                ))
+            }
+        }
+
+        impl ::capnp::traits::SetPointerBuilder for Client {
+            fn set_pointer_builder(
+                pointer: ::capnp::private::layout::PointerBuilder<'_>,
+                from: Client,
+                _canonicalize: bool,
+            ) -> ::capnp::Result<()> {
+                pointer.set_capability(from.client.hook);
+                ::core::result::Result::Ok(())
+            }
+        }
+        impl ::capnp::traits::HasTypeId for Client {
+            #[inline]
+            fn type_id() -> u64 {
+                _private::TYPE_ID
+            }
+        }
+        impl Clone for Client {
+            fn clone(&self) -> Client {
+                Client {
+                    client: ::capnp::capability::Client::new(self.client.hook.add_ref()),
+                }
+            }
+        }
+        impl Client {
+            pub fn update_request(
+                &self,
+            ) -> ::capnp::capability::Request<
+                crate::service_capnp::asset_hub::listener::update_params::Owned,
+                crate::service_capnp::asset_hub::listener::update_results::Owned,
+            > {
+                self.client.new_call(_private::TYPE_ID, 0, None)
+            }
+        }
+        pub trait Server {
+            fn update(
+                &mut self,
+                _: UpdateParams,
+                _: UpdateResults,
+            ) -> ::capnp::capability::Promise<(), ::capnp::Error> {
+                ::capnp::capability::Promise::err(::capnp::Error::unimplemented(
+                    "method not implemented".to_string(),
+                ))
+            }
+        }
+        pub struct ServerDispatch<_T> {
+            pub server: _T,
+        }
+        impl<_S: Server + 'static> ::capnp::capability::FromServer<_S> for Client {
+            type Dispatch = ServerDispatch<_S>;
+
+            fn from_server(s: _S) -> ServerDispatch<_S> {
+                ServerDispatch { server: s }
+            }
+        }
+        impl<_T: Server> ::core::ops::Deref for ServerDispatch<_T> {
+            type Target = _T;
+
+            fn deref(&self) -> &_T {
+                &self.server
+            }
+        }
+        impl<_T: Server> ::core::ops::DerefMut for ServerDispatch<_T> {
+            fn deref_mut(&mut self) -> &mut _T {
+                &mut self.server
+            }
+        }
+        impl<_T: Server> ::capnp::capability::Server for ServerDispatch<_T> {
+            fn dispatch_call(
+                &mut self,
+                interface_id: u64,
+                method_id: u16,
+                params: ::capnp::capability::Params<::capnp::any_pointer::Owned>,
+                results: ::capnp::capability::Results<::capnp::any_pointer::Owned>,
+            ) -> ::capnp::capability::Promise<(), ::capnp::Error> {
+                match interface_id {
+                    _private::TYPE_ID => ServerDispatch::<_T>::dispatch_call_internal(
+                        &mut self.server,
+                        method_id,
+                        params,
+                        results,
+                    ),
+                    _ => ::capnp::capability::Promise::err(::capnp::Error::unimplemented(
+                        "Method not implemented.".to_string(),
+                    )),
+                }
+            }
+        }
+        impl<_T: Server> ServerDispatch<_T> {
+            pub fn dispatch_call_internal(
+                server: &mut _T,
+                method_id: u16,
+                params: ::capnp::capability::Params<::capnp::any_pointer::Owned>,
+                results: ::capnp::capability::Results<::capnp::any_pointer::Owned>,
+            ) -> ::capnp::capability::Promise<(), ::capnp::Error> {
+                match method_id {
+                    0 => server.update(
+                        ::capnp::private::capability::internal_get_typed_params(params),
+                        ::capnp::private::capability::internal_get_typed_results(results),
+                    ),
+                    _ => ::capnp::capability::Promise::err(::capnp::Error::unimplemented(
+                        "Method not implemented.".to_string(),
+                    )),
+                }
+            }
+        }
+        pub mod _private {
+            pub const TYPE_ID: u64 = 0xdf44_c664_bbec_8d36;
+        }
+
+        pub mod update_params {
+            #[derive(Copy, Clone)]
+            pub struct Owned(());
+            impl<'a> ::capnp::traits::Owned<'a> for Owned {
+                type Builder = Builder<'a>;
+                type Reader = Reader<'a>;
+            }
+            impl<'a> ::capnp::traits::OwnedStruct<'a> for Owned {
+                type Builder = Builder<'a>;
+                type Reader = Reader<'a>;
+            }
+            impl ::capnp::traits::Pipelined for Owned {
+                type Pipeline = Pipeline;
+            }
+
+            #[derive(Clone, Copy)]
+            pub struct Reader<'a> {
+                reader: ::capnp::private::layout::StructReader<'a>,
+            }
+
+            impl<'a> ::capnp::traits::HasTypeId for Reader<'a> {
+                #[inline]
+                fn type_id() -> u64 {
+                    _private::TYPE_ID
+                }
+            }
+            impl<'a> ::capnp::traits::FromStructReader<'a> for Reader<'a> {
+                fn new(reader: ::capnp::private::layout::StructReader<'a>) -> Reader<'a> {
+                    Reader { reader }
+                }
+            }
+
+            impl<'a> ::capnp::traits::FromPointerReader<'a> for Reader<'a> {
+                fn get_from_pointer(
+                    reader: &::capnp::private::layout::PointerReader<'a>,
+                    default: ::core::option::Option<&'a [capnp::Word]>,
+                ) -> ::capnp::Result<Reader<'a>> {
+                    ::core::result::Result::Ok(::capnp::traits::FromStructReader::new(
+                        reader.get_struct(default)?,
+                    ))
+                }
+            }
+
+            impl<'a> ::capnp::traits::IntoInternalStructReader<'a> for Reader<'a> {
+                fn into_internal_struct_reader(self) -> ::capnp::private::layout::StructReader<'a> {
+                    self.reader
+                }
+            }
+
+            impl<'a> ::capnp::traits::Imbue<'a> for Reader<'a> {
+                fn imbue(&mut self, cap_table: &'a ::capnp::private::layout::CapTable) {
+                    self.reader
+                        .imbue(::capnp::private::layout::CapTableReader::Plain(cap_table))
+                }
+            }
+
+            impl<'a> Reader<'a> {
+                pub fn reborrow(&self) -> Reader<'_> {
+                    Reader { ..*self }
+                }
+
+                pub fn total_size(&self) -> ::capnp::Result<::capnp::MessageSize> {
+                    self.reader.total_size()
+                }
+
+                #[inline]
+                pub fn get_latest_change(self) -> u64 {
+                    self.reader.get_data_field::<u64>(0)
+                }
+
+                #[inline]
+                pub fn get_snapshot(
+                    self,
+                ) -> ::capnp::Result<crate::service_capnp::asset_hub::snapshot::Client>
+                {
+                    match self.reader.get_pointer_field(0).get_capability() {
+                        ::core::result::Result::Ok(c) => {
+                            ::core::result::Result::Ok(::capnp::capability::FromClientHook::new(c))
+                        }
+                        ::core::result::Result::Err(e) => ::core::result::Result::Err(e),
+                    }
+                }
+            }
+
+            pub struct Builder<'a> {
+                builder: ::capnp::private::layout::StructBuilder<'a>,
+            }
+            impl<'a> ::capnp::traits::HasStructSize for Builder<'a> {
+                #[inline]
+                fn struct_size() -> ::capnp::private::layout::StructSize {
+                    _private::STRUCT_SIZE
+                }
+            }
+            impl<'a> ::capnp::traits::HasTypeId for Builder<'a> {
+                #[inline]
+                fn type_id() -> u64 {
+                    _private::TYPE_ID
+                }
+            }
+            impl<'a> ::capnp::traits::FromStructBuilder<'a> for Builder<'a> {
+                fn new(builder: ::capnp::private::layout::StructBuilder<'a>) -> Builder<'a> {
+                    Builder { builder }
+                }
+            }
+
+            impl<'a> ::capnp::traits::ImbueMut<'a> for Builder<'a> {
+                fn imbue_mut(&mut self, cap_table: &'a mut ::capnp::private::layout::CapTable) {
+                    self.builder
+                        .imbue(::capnp::private::layout::CapTableBuilder::Plain(cap_table))
+                }
+            }
+
+            impl<'a> ::capnp::traits::FromPointerBuilder<'a> for Builder<'a> {
+                fn init_pointer(
+                    builder: ::capnp::private::layout::PointerBuilder<'a>,
+                    _size: u32,
+                ) -> Builder<'a> {
+                    ::capnp::traits::FromStructBuilder::new(
+                        builder.init_struct(_private::STRUCT_SIZE),
+                    )
+                }
+
+                fn get_from_pointer(
+                    builder: ::capnp::private::layout::PointerBuilder<'a>,
+                    default: ::core::option::Option<&'a [capnp::Word]>,
+                ) -> ::capnp::Result<Builder<'a>> {
+                    ::core::result::Result::Ok(::capnp::traits::FromStructBuilder::new(
+                        builder.get_struct(_private::STRUCT_SIZE, default)?,
+                    ))
+                }
+            }
+
+            impl<'a> ::capnp::traits::SetPointerBuilder for Reader<'a> {
+                fn set_pointer_builder<'b>(
+                    pointer: ::capnp::private::layout::PointerBuilder<'b>,
+                    value: Reader<'a>,
+                    canonicalize: bool,
+                ) -> ::capnp::Result<()> {
+                    pointer.set_struct(&value.reader, canonicalize)
+                }
+            }
+
+            impl<'a> Builder<'a> {
+                pub fn into_reader(self) -> Reader<'a> {
+                    ::capnp::traits::FromStructReader::new(self.builder.into_reader())
+                }
+
+                pub fn reborrow(&mut self) -> Builder<'_> {
+                    Builder { ..*self }
+                }
+
+                pub fn reborrow_as_reader(&self) -> Reader<'_> {
+                    ::capnp::traits::FromStructReader::new(self.builder.into_reader())
+                }
+
+                pub fn total_size(&self) -> ::capnp::Result<::capnp::MessageSize> {
+                    self.builder.into_reader().total_size()
+                }
+
+                #[inline]
+                pub fn get_latest_change(self) -> u64 {
+                    self.builder.get_data_field::<u64>(0)
+                }
+
+                #[inline]
+                pub fn set_latest_change(&mut self, value: u64) {
+                    self.builder.set_data_field::<u64>(0, value);
+                }
+
+                #[inline]
+                pub fn get_snapshot(
+                    self,
+                ) -> ::capnp::Result<crate::service_capnp::asset_hub::snapshot::Client>
+                {
+                    match self.builder.get_pointer_field(0).get_capability() {
+                        ::core::result::Result::Ok(c) => {
+                            ::core::result::Result::Ok(::capnp::capability::FromClientHook::new(c))
+                        }
+                        ::core::result::Result::Err(e) => ::core::result::Result::Err(e),
+                    }
+                }
+
+                #[inline]
+                pub fn set_snapshot(
+                    &mut self,
+                    value: crate::service_capnp::asset_hub::snapshot::Client,
+                ) {
+                    self.builder
+                        .get_pointer_field(0)
+                        .set_capability(value.client.hook);
+                }
+            }
+
+            pub struct Pipeline {
+                _typeless: ::capnp::any_pointer::Pipeline,
+            }
+            impl ::capnp::capability::FromTypelessPipeline for Pipeline {
+                fn new(typeless: ::capnp::any_pointer::Pipeline) -> Pipeline {
+                    Pipeline {
+                        _typeless: typeless,
+                    }
+                }
+            }
+            impl Pipeline {
+                pub fn get_snapshot(&self) -> crate::service_capnp::asset_hub::snapshot::Client {
+                    ::capnp::capability::FromClientHook::new(
+                        self._typeless.get_pointer_field(0).as_cap(),
+                    )
+                }
+            }
+            mod _private {
+                use capnp::private::layout;
+                pub const STRUCT_SIZE: layout::StructSize = layout::StructSize {
+                    data: 1,
+                    pointers: 1,
+                };
+                pub const TYPE_ID: u64 = 0xb906_8ad0_71c0_62a8;
+            }
+        }
+
+        pub mod update_results {
+            #[derive(Copy, Clone)]
+            pub struct Owned(());
+            impl<'a> ::capnp::traits::Owned<'a> for Owned {
+                type Builder = Builder<'a>;
+                type Reader = Reader<'a>;
+            }
+            impl<'a> ::capnp::traits::OwnedStruct<'a> for Owned {
+                type Builder = Builder<'a>;
+                type Reader = Reader<'a>;
+            }
+            impl ::capnp::traits::Pipelined for Owned {
+                type Pipeline = Pipeline;
+            }
+
+            #[derive(Clone, Copy)]
+            pub struct Reader<'a> {
+                reader: ::capnp::private::layout::StructReader<'a>,
+            }
+
+            impl<'a> ::capnp::traits::HasTypeId for Reader<'a> {
+                #[inline]
+                fn type_id() -> u64 {
+                    _private::TYPE_ID
+                }
+            }
+            impl<'a> ::capnp::traits::FromStructReader<'a> for Reader<'a> {
+                fn new(reader: ::capnp::private::layout::StructReader<'a>) -> Reader<'a> {
+                    Reader { reader }
+                }
+            }
+
+            impl<'a> ::capnp::traits::FromPointerReader<'a> for Reader<'a> {
+                fn get_from_pointer(
+                    reader: &::capnp::private::layout::PointerReader<'a>,
+                    default: ::core::option::Option<&'a [capnp::Word]>,
+                ) -> ::capnp::Result<Reader<'a>> {
+                    ::core::result::Result::Ok(::capnp::traits::FromStructReader::new(
+                        reader.get_struct(default)?,
+                    ))
+                }
+            }
+
+            impl<'a> ::capnp::traits::IntoInternalStructReader<'a> for Reader<'a> {
+                fn into_internal_struct_reader(self) -> ::capnp::private::layout::StructReader<'a> {
+                    self.reader
+                }
+            }
+
+            impl<'a> ::capnp::traits::Imbue<'a> for Reader<'a> {
+                fn imbue(&mut self, cap_table: &'a ::capnp::private::layout::CapTable) {
+                    self.reader
+                        .imbue(::capnp::private::layout::CapTableReader::Plain(cap_table))
+                }
+            }
+
+            impl<'a> Reader<'a> {
+                pub fn reborrow(&self) -> Reader<'_> {
+                    Reader { ..*self }
+                }
+
+                pub fn total_size(&self) -> ::capnp::Result<::capnp::MessageSize> {
+                    self.reader.total_size()
+                }
+            }
+
+            pub struct Builder<'a> {
+                builder: ::capnp::private::layout::StructBuilder<'a>,
+            }
+            impl<'a> ::capnp::traits::HasStructSize for Builder<'a> {
+                #[inline]
+                fn struct_size() -> ::capnp::private::layout::StructSize {
+                    _private::STRUCT_SIZE
+                }
+            }
+            impl<'a> ::capnp::traits::HasTypeId for Builder<'a> {
+                #[inline]
+                fn type_id() -> u64 {
+                    _private::TYPE_ID
+                }
+            }
+            impl<'a> ::capnp::traits::FromStructBuilder<'a> for Builder<'a> {
+                fn new(builder: ::capnp::private::layout::StructBuilder<'a>) -> Builder<'a> {
+                    Builder { builder }
+                }
+            }
+
+            impl<'a> ::capnp::traits::ImbueMut<'a> for Builder<'a> {
+                fn imbue_mut(&mut self, cap_table: &'a mut ::capnp::private::layout::CapTable) {
+                    self.builder
+                        .imbue(::capnp::private::layout::CapTableBuilder::Plain(cap_table))
+                }
+            }
+
+            impl<'a> ::capnp::traits::FromPointerBuilder<'a> for Builder<'a> {
+                fn init_pointer(
+                    builder: ::capnp::private::layout::PointerBuilder<'a>,
+                    _size: u32,
+                ) -> Builder<'a> {
+                    ::capnp::traits::FromStructBuilder::new(
+                        builder.init_struct(_private::STRUCT_SIZE),
+                    )
+                }
+
+                fn get_from_pointer(
+                    builder: ::capnp::private::layout::PointerBuilder<'a>,
+                    default: ::core::option::Option<&'a [capnp::Word]>,
+                ) -> ::capnp::Result<Builder<'a>> {
+                    ::core::result::Result::Ok(::capnp::traits::FromStructBuilder::new(
+                        builder.get_struct(_private::STRUCT_SIZE, default)?,
+                    ))
+                }
+            }
+
+            impl<'a> ::capnp::traits::SetPointerBuilder for Reader<'a> {
+                fn set_pointer_builder<'b>(
+                    pointer: ::capnp::private::layout::PointerBuilder<'b>,
+                    value: Reader<'a>,
+                    canonicalize: bool,
+                ) -> ::capnp::Result<()> {
+                    pointer.set_struct(&value.reader, canonicalize)
+                }
+            }
+
+            impl<'a> Builder<'a> {
+                pub fn into_reader(self) -> Reader<'a> {
+                    ::capnp::traits::FromStructReader::new(self.builder.into_reader())
+                }
+
+                pub fn reborrow(&mut self) -> Builder<'_> {
+                    Builder { ..*self }
+                }
+
+                pub fn reborrow_as_reader(&self) -> Reader<'_> {
+                    ::capnp::traits::FromStructReader::new(self.builder.into_reader())
+                }
+
+                pub fn total_size(&self) -> ::capnp::Result<::capnp::MessageSize> {
+                    self.builder.into_reader().total_size()
+                }
+            }
+
+            pub struct Pipeline {
+                _typeless: ::capnp::any_pointer::Pipeline,
+            }
+            impl ::capnp::capability::FromTypelessPipeline for Pipeline {
+                fn new(typeless: ::capnp::any_pointer::Pipeline) -> Pipeline {
+                    Pipeline {
+                        _typeless: typeless,
+                    }
+                }
+            }
+            impl Pipeline {}
+            mod _private {
+                use capnp::private::layout;
+                pub const STRUCT_SIZE: layout::StructSize = layout::StructSize {
+                    data: 0,
+                    pointers: 0,
+                };
+                pub const TYPE_ID: u64 = 0xf88a_b972_b7a6_725c;
+            }
+        }
+    }
+
+    pub mod register_listener_params {
+        #[derive(Copy, Clone)]
+        pub struct Owned(());
+        impl<'a> ::capnp::traits::Owned<'a> for Owned {
+            type Builder = Builder<'a>;
+            type Reader = Reader<'a>;
+        }
+        impl<'a> ::capnp::traits::OwnedStruct<'a> for Owned {
+            type Builder = Builder<'a>;
+            type Reader = Reader<'a>;
+        }
+        impl ::capnp::traits::Pipelined for Owned {
+            type Pipeline = Pipeline;
+        }
+
+        #[derive(Clone, Copy)]
+        pub struct Reader<'a> {
+            reader: ::capnp::private::layout::StructReader<'a>,
+        }
+
+        impl<'a> ::capnp::traits::HasTypeId for Reader<'a> {
+            #[inline]
+            fn type_id() -> u64 {
+                _private::TYPE_ID
+            }
+        }
+        impl<'a> ::capnp::traits::FromStructReader<'a> for Reader<'a> {
+            fn new(reader: ::capnp::private::layout::StructReader<'a>) -> Reader<'a> {
+                Reader { reader }
+            }
+        }
+
+        impl<'a> ::capnp::traits::FromPointerReader<'a> for Reader<'a> {
+            fn get_from_pointer(
+                reader: &::capnp::private::layout::PointerReader<'a>,
+                default: ::core::option::Option<&'a [capnp::Word]>,
+            ) -> ::capnp::Result<Reader<'a>> {
+                ::core::result::Result::Ok(::capnp::traits::FromStructReader::new(
+                    reader.get_struct(default)?,
+                ))
+            }
+        }
+
+        impl<'a> ::capnp::traits::IntoInternalStructReader<'a> for Reader<'a> {
+            fn into_internal_struct_reader(self) -> ::capnp::private::layout::StructReader<'a> {
+                self.reader
+            }
+        }
+
+        impl<'a> ::capnp::traits::Imbue<'a> for Reader<'a> {
+            fn imbue(&mut self, cap_table: &'a ::capnp::private::layout::CapTable) {
+                self.reader
+                    .imbue(::capnp::private::layout::CapTableReader::Plain(cap_table))
+            }
+        }
+
+        impl<'a> Reader<'a> {
+            pub fn reborrow(&self) -> Reader<'_> {
+                Reader { ..*self }
+            }
+
+            pub fn total_size(&self) -> ::capnp::Result<::capnp::MessageSize> {
+                self.reader.total_size()
+            }
+
+            #[inline]
+            pub fn get_listener(
+                self,
+            ) -> ::capnp::Result<crate::service_capnp::asset_hub::listener::Client> {
+                match self.reader.get_pointer_field(0).get_capability() {
+                    ::core::result::Result::Ok(c) => {
+                        ::core::result::Result::Ok(::capnp::capability::FromClientHook::new(c))
+                    }
+                    ::core::result::Result::Err(e) => ::core::result::Result::Err(e),
+                }
+            }
+        }
+
+        pub struct Builder<'a> {
+            builder: ::capnp::private::layout::StructBuilder<'a>,
+        }
+        impl<'a> ::capnp::traits::HasStructSize for Builder<'a> {
+            #[inline]
+            fn struct_size() -> ::capnp::private::layout::StructSize {
+                _private::STRUCT_SIZE
+            }
+        }
+        impl<'a> ::capnp::traits::HasTypeId for Builder<'a> {
+            #[inline]
+            fn type_id() -> u64 {
+                _private::TYPE_ID
+            }
+        }
+        impl<'a> ::capnp::traits::FromStructBuilder<'a> for Builder<'a> {
+            fn new(builder: ::capnp::private::layout::StructBuilder<'a>) -> Builder<'a> {
+                Builder { builder }
+            }
+        }
+
+        impl<'a> ::capnp::traits::ImbueMut<'a> for Builder<'a> {
+            fn imbue_mut(&mut self, cap_table: &'a mut ::capnp::private::layout::CapTable) {
+                self.builder
+                    .imbue(::capnp::private::layout::CapTableBuilder::Plain(cap_table))
+            }
+        }
+
+        impl<'a> ::capnp::traits::FromPointerBuilder<'a> for Builder<'a> {
+            fn init_pointer(
+                builder: ::capnp::private::layout::PointerBuilder<'a>,
+                _size: u32,
+            ) -> Builder<'a> {
+                ::capnp::traits::FromStructBuilder::new(builder.init_struct(_private::STRUCT_SIZE))
+            }
+
+            fn get_from_pointer(
+                builder: ::capnp::private::layout::PointerBuilder<'a>,
+                default: ::core::option::Option<&'a [capnp::Word]>,
+            ) -> ::capnp::Result<Builder<'a>> {
+                ::core::result::Result::Ok(::capnp::traits::FromStructBuilder::new(
+                    builder.get_struct(_private::STRUCT_SIZE, default)?,
+                ))
+            }
+        }
+
+        impl<'a> ::capnp::traits::SetPointerBuilder for Reader<'a> {
+            fn set_pointer_builder<'b>(
+                pointer: ::capnp::private::layout::PointerBuilder<'b>,
+                value: Reader<'a>,
+                canonicalize: bool,
+            ) -> ::capnp::Result<()> {
+                pointer.set_struct(&value.reader, canonicalize)
+            }
+        }
+
+        impl<'a> Builder<'a> {
+            pub fn into_reader(self) -> Reader<'a> {
+                ::capnp::traits::FromStructReader::new(self.builder.into_reader())
+            }
+
+            pub fn reborrow(&mut self) -> Builder<'_> {
+                Builder { ..*self }
+            }
+
+            pub fn reborrow_as_reader(&self) -> Reader<'_> {
+                ::capnp::traits::FromStructReader::new(self.builder.into_reader())
+            }
+
+            pub fn total_size(&self) -> ::capnp::Result<::capnp::MessageSize> {
+                self.builder.into_reader().total_size()
+            }
+
+            #[inline]
+            pub fn get_listener(
+                self,
+            ) -> ::capnp::Result<crate::service_capnp::asset_hub::listener::Client> {
+                match self.builder.get_pointer_field(0).get_capability() {
+                    ::core::result::Result::Ok(c) => {
+                        ::core::result::Result::Ok(::capnp::capability::FromClientHook::new(c))
+                    }
+                    ::core::result::Result::Err(e) => ::core::result::Result::Err(e),
+                }
+            }
+
+            #[inline]
+            pub fn set_listener(
+                &mut self,
+                value: crate::service_capnp::asset_hub::listener::Client,
+            ) {
+                self.builder
+                    .get_pointer_field(0)
+                    .set_capability(value.client.hook);
+            }
+        }
+
+        pub struct Pipeline {
+            _typeless: ::capnp::any_pointer::Pipeline,
+        }
+        impl ::capnp::capability::FromTypelessPipeline for Pipeline {
+            fn new(typeless: ::capnp::any_pointer::Pipeline) -> Pipeline {
+                Pipeline {
+                    _typeless: typeless,
+                }
+            }
+        }
+        impl Pipeline {
+            pub fn get_listener(&self) -> crate::service_capnp::asset_hub::listener::Client {
+                ::capnp::capability::FromClientHook::new(
+                    self._typeless.get_pointer_field(0).as_cap(),
+                )
+            }
+        }
+        mod _private {
+            use capnp::private::layout;
+            pub const STRUCT_SIZE: layout::StructSize = layout::StructSize {
+                data: 0,
+                pointers: 1,
+            };
+            pub const TYPE_ID: u64 = 0xc474_621f_7679_e4ca;
+        }
+    }
+
+    pub mod register_listener_results {
+        #[derive(Copy, Clone)]
+        pub struct Owned(());
+        impl<'a> ::capnp::traits::Owned<'a> for Owned {
+            type Builder = Builder<'a>;
+            type Reader = Reader<'a>;
+        }
+        impl<'a> ::capnp::traits::OwnedStruct<'a> for Owned {
+            type Builder = Builder<'a>;
+            type Reader = Reader<'a>;
+        }
+        impl ::capnp::traits::Pipelined for Owned {
+            type Pipeline = Pipeline;
+        }
+
+        #[derive(Clone, Copy)]
+        pub struct Reader<'a> {
+            reader: ::capnp::private::layout::StructReader<'a>,
+        }
+
+        impl<'a> ::capnp::traits::HasTypeId for Reader<'a> {
+            #[inline]
+            fn type_id() -> u64 {
+                _private::TYPE_ID
+            }
+        }
+        impl<'a> ::capnp::traits::FromStructReader<'a> for Reader<'a> {
+            fn new(reader: ::capnp::private::layout::StructReader<'a>) -> Reader<'a> {
+                Reader { reader }
+            }
+        }
+
+        impl<'a> ::capnp::traits::FromPointerReader<'a> for Reader<'a> {
+            fn get_from_pointer(
+                reader: &::capnp::private::layout::PointerReader<'a>,
+                default: ::core::option::Option<&'a [capnp::Word]>,
+            ) -> ::capnp::Result<Reader<'a>> {
+                ::core::result::Result::Ok(::capnp::traits::FromStructReader::new(
+                    reader.get_struct(default)?,
+                ))
+            }
+        }
+
+        impl<'a> ::capnp::traits::IntoInternalStructReader<'a> for Reader<'a> {
+            fn into_internal_struct_reader(self) -> ::capnp::private::layout::StructReader<'a> {
+                self.reader
+            }
+        }
+
+        impl<'a> ::capnp::traits::Imbue<'a> for Reader<'a> {
+            fn imbue(&mut self, cap_table: &'a ::capnp::private::layout::CapTable) {
+                self.reader
+                    .imbue(::capnp::private::layout::CapTableReader::Plain(cap_table))
+            }
+        }
+
+        impl<'a> Reader<'a> {
+            pub fn reborrow(&self) -> Reader<'_> {
+                Reader { ..*self }
+            }
+
+            pub fn total_size(&self) -> ::capnp::Result<::capnp::MessageSize> {
+                self.reader.total_size()
+            }
+        }
+
+        pub struct Builder<'a> {
+            builder: ::capnp::private::layout::StructBuilder<'a>,
+        }
+        impl<'a> ::capnp::traits::HasStructSize for Builder<'a> {
+            #[inline]
+            fn struct_size() -> ::capnp::private::layout::StructSize {
+                _private::STRUCT_SIZE
+            }
+        }
+        impl<'a> ::capnp::traits::HasTypeId for Builder<'a> {
+            #[inline]
+            fn type_id() -> u64 {
+                _private::TYPE_ID
+            }
+        }
+        impl<'a> ::capnp::traits::FromStructBuilder<'a> for Builder<'a> {
+            fn new(builder: ::capnp::private::layout::StructBuilder<'a>) -> Builder<'a> {
+                Builder { builder }
+            }
+        }
+
+        impl<'a> ::capnp::traits::ImbueMut<'a> for Builder<'a> {
+            fn imbue_mut(&mut self, cap_table: &'a mut ::capnp::private::layout::CapTable) {
+                self.builder
+                    .imbue(::capnp::private::layout::CapTableBuilder::Plain(cap_table))
+            }
+        }
 
-                fn get_from_pointer(
-                    builder: ::capnp::private::layout::PointerBuilder<'a>,
-                    default: ::core::option::Option<&'a [capnp::Word]>,
-                ) -> ::capnp::Result<Builder<'a>> {
-                    ::core::result::Result::Ok(::capnp::traits::FromStructBuilder::new(
-                        builder.get_struct(_private::STRUCT_SIZE, default)?,
-                    ))
-                }
+        impl<'a> ::capnp::traits::FromPointerBuilder<'a> for Builder<'a> {
+            fn init_pointer(
+                builder: ::capnp::private::layout::PointerBuilder<'a>,
+                _size: u32,
+            ) -> Builder<'a> {
+                ::capnp::traits::FromStructBuilder::new(builder.init_struct(_private::STRUCT_SIZE))
             }
 
-            impl<'a> ::capnp::traits::SetPointerBuilder for Reader<'a> {
-                fn set_pointer_builder<'b>(
-                    pointer: ::capnp::private::layout::PointerBuilder<'b>,
-                    value: Reader<'a>,
-                    canonicalize: bool,
-                ) -> ::capnp::Result<()> {
-                    pointer.set_struct(&value.reader, canonicalize)
-                }
+            fn get_from_pointer(
+                builder: ::capnp::private::layout::PointerBuilder<'a>,
+                default: ::core::option::Option<&'a [capnp::Word]>,
+            ) -> ::capnp::Result<Builder<'a>> {
+                ::core::result::Result::Ok(::capnp::traits::FromStructBuilder::new(
+                    builder.get_struct(_private::STRUCT_SIZE, default)?,
+                ))
             }
+        }
 
-            impl<'a> Builder<'a> {
-                pub fn into_reader(self) -> Reader<'a> {
-                    ::capnp::traits::FromStructReader::new(self.builder.into_reader())
-                }
+        impl<'a> ::capnp::traits::SetPointerBuilder for Reader<'a> {
+            fn set_pointer_builder<'b>(
+                pointer: ::capnp::private::layout::PointerBuilder<'b>,
+                value: Reader<'a>,
+                canonicalize: bool,
+            ) -> ::capnp::Result<()> {
+                pointer.set_struct(&value.reader, canonicalize)
+            }
+        }
 
-                pub fn reborrow(&mut self) -> Builder<'_> {
-                    Builder { ..*self }
-                }
+        impl<'a> Builder<'a> {
+            pub fn into_reader(self) -> Reader<'a> {
+                ::capnp::traits::FromStructReader::new(self.builder.into_reader())
+            }
 
-                pub fn reborrow_as_reader(&self) -> Reader<'_> {
-                    ::capnp::traits::FromStructReader::new(self.builder.into_reader())
-                }
+            pub fn reborrow(&mut self) -> Builder<'_> {
+                Builder { ..*self }
+            }
 
-                pub fn total_size(&self) -> ::capnp::Result<::capnp::MessageSize> {
-                    self.builder.into_reader().total_size()
-                }
+            pub fn reborrow_as_reader(&self) -> Reader<'_> {
+                ::capnp::traits::FromStructReader::new(self.builder.into_reader())
             }
 
-            pub struct Pipeline {
-                _typeless: ::capnp::any_pointer::Pipeline,
+            pub fn total_size(&self) -> ::capnp::Result<::capnp::MessageSize> {
+                self.builder.into_reader().total_size()
             }
-            impl ::capnp::capability::FromTypelessPipeline for Pipeline {
-                fn new(typeless: ::capnp::any_pointer::Pipeline) -> Pipeline {
-                    Pipeline {
-                        _typeless: typeless,
-                    }
+        }
+
+        pub struct Pipeline {
+            _typeless: ::capnp::any_pointer::Pipeline,
+        }
+        impl ::capnp::capability::FromTypelessPipeline for Pipeline {
+            fn new(typeless: ::capnp::any_pointer::Pipeline) -> Pipeline {
+                Pipeline {
+                    _typeless: typeless,
                 }
             }
-            impl Pipeline {}
-            mod _private {
-                use capnp::private::layout;
-                pub const STRUCT_SIZE: layout::StructSize = layout::StructSize {
-                    data: 0,
-                    pointers: 0,
-                };
-                pub const TYPE_ID: u64 = 0xf88a_b972_b7a6_725c;
-            }
+        }
+        impl Pipeline {}
+        mod _private {
+            use capnp::private::layout;
+            pub const STRUCT_SIZE: layout::StructSize = layout::StructSize {
+                data: 0,
+                pointers: 0,
+            };
+            pub const TYPE_ID: u64 = 0x9d68_9421_bd64_ee98;
         }
     }
 
-    pub mod register_listener_params {
+    pub mod get_snapshot_params {
         #[derive(Copy, Clone)]
         pub struct Owned(());
         impl<'a> ::capnp::traits::Owned<'a> for Owned {
@@ -7055,18 +9420,6 @@ pub mod asset_hub {
             pub fn total_size(&self) -> ::capnp::Result<::capnp::MessageSize> {
                 self.reader.total_size()
             }
-
-            #[inline]
-            pub fn get_listener(
-                self,
-            ) -> ::capnp::Result<crate::service_capnp::asset_hub::listener::Client> {
-                match self.reader.get_pointer_field(0).get_capability() {
-                    ::core::result::Result::Ok(c) => {
-                        ::core::result::Result::Ok(::capnp::capability::FromClientHook::new(c))
-                    }
-                    ::core::result::Result::Err(e) => ::core::result::Result::Err(e),
-                }
-            }
         }
 
         pub struct Builder<'a> {
@@ -7141,28 +9494,6 @@ pub mod asset_hub {
             pub fn total_size(&self) -> ::capnp::Result<::capnp::MessageSize> {
                 self.builder.into_reader().total_size()
             }
-
-            #[inline]
-            pub fn get_listener(
-                self,
-            ) -> ::capnp::Result<crate::service_capnp::asset_hub::listener::Client> {
-                match self.builder.get_pointer_field(0).get_capability() {
-                    ::core::result::Result::Ok(c) => {
-                        ::core::result::Result::Ok(::capnp::capability::FromClientHook::new(c))
-                    }
-                    ::core::result::Result::Err(e) => ::core::result::Result::Err(e),
-                }
-            }
-
-            #[inline]
-            pub fn set_listener(
-                &mut self,
-                value: crate::service_capnp::asset_hub::listener::Client,
-            ) {
-                self.builder
-                    .get_pointer_field(0)
-                    .set_capability(value.client.hook);
-            }
         }
 
         pub struct Pipeline {
@@ -7175,24 +9506,18 @@ pub mod asset_hub {
                 }
             }
         }
-        impl Pipeline {
-            pub fn get_listener(&self) -> crate::service_capnp::asset_hub::listener::Client {
-                ::capnp::capability::FromClientHook::new(
-                    self._typeless.get_pointer_field(0).as_cap(),
-                )
-            }
-        }
+        impl Pipeline {}
         mod _private {
             use capnp::private::layout;
             pub const STRUCT_SIZE: layout::StructSize = layout::StructSize {
                 data: 0,
-                pointers: 1,
+                pointers: 0,
             };
-            pub const TYPE_ID: u64 = 0xc474_621f_7679_e4ca;
+            pub const TYPE_ID: u64 = 0x8b18_92c4_9920_f998;
         }
     }
 
-    pub mod register_listener_results {
+    pub mod get_snapshot_results {
         #[derive(Copy, Clone)]
         pub struct Owned(());
         impl<'a> ::capnp::traits::Owned<'a> for Owned {
@@ -7256,6 +9581,18 @@ pub mod asset_hub {
             pub fn total_size(&self) -> ::capnp::Result<::capnp::MessageSize> {
                 self.reader.total_size()
             }
+
+            #[inline]
+            pub fn get_snapshot(
+                self,
+            ) -> ::capnp::Result<crate::service_capnp::asset_hub::snapshot::Client> {
+                match self.reader.get_pointer_field(0).get_capability() {
+                    ::core::result::Result::Ok(c) => {
+                        ::core::result::Result::Ok(::capnp::capability::FromClientHook::new(c))
+                    }
+                    ::core::result::Result::Err(e) => ::core::result::Result::Err(e),
+                }
+            }
         }
 
         pub struct Builder<'a> {
@@ -7330,6 +9667,28 @@ pub mod asset_hub {
             pub fn total_size(&self) -> ::capnp::Result<::capnp::MessageSize> {
                 self.builder.into_reader().total_size()
             }
+
+            #[inline]
+            pub fn get_snapshot(
+                self,
+            ) -> ::capnp::Result<crate::service_capnp::asset_hub::snapshot::Client> {
+                match self.builder.get_pointer_field(0).get_capability() {
+                    ::core::result::Result::Ok(c) => {
+                        ::core::result::Result::Ok(::capnp::capability::FromClientHook::new(c))
+                    }
+                    ::core::result::Result::Err(e) => ::core::result::Result::Err(e),
+                }
+            }
+
+            #[inline]
+            pub fn set_snapshot(
+                &mut self,
+                value: crate::service_capnp::asset_hub::snapshot::Client,
+            ) {
+                self.builder
+                    .get_pointer_field(0)
+                    .set_capability(value.client.hook);
+            }
         }
 
         pub struct Pipeline {
@@ -7342,18 +9701,24 @@ pub mod asset_hub {
                 }
             }
         }
-        impl Pipeline {}
+        impl Pipeline {
+            pub fn get_snapshot(&self) -> crate::service_capnp::asset_hub::snapshot::Client {
+                ::capnp::capability::FromClientHook::new(
+                    self._typeless.get_pointer_field(0).as_cap(),
+                )
+            }
+        }
         mod _private {
             use capnp::private::layout;
             pub const STRUCT_SIZE: layout::StructSize = layout::StructSize {
                 data: 0,
-                pointers: 0,
+                pointers: 1,
             };
-            pub const TYPE_ID: u64 = 0x9d68_9421_bd64_ee98;
+            pub const TYPE_ID: u64 = 0x9dd9_afd2_86e7_766c;
         }
     }
 
-    pub mod get_snapshot_params {
+    pub mod get_daemon_info_params {
         #[derive(Copy, Clone)]
         pub struct Owned(());
         impl<'a> ::capnp::traits::Owned<'a> for Owned {
@@ -7510,11 +9875,11 @@ pub mod asset_hub {
                 data: 0,
                 pointers: 0,
             };
-            pub const TYPE_ID: u64 = 0x8b18_92c4_9920_f998;
+            pub const TYPE_ID: u64 = 0xc3a1_5e0b_7d42_9f18;
         }
     }
 
-    pub mod get_snapshot_results {
+    pub mod get_daemon_info_results {
         #[derive(Copy, Clone)]
         pub struct Owned(());
         impl<'a> ::capnp::traits::Owned<'a> for Owned {
@@ -7580,15 +9945,15 @@ pub mod asset_hub {
             }
 
             #[inline]
-            pub fn get_snapshot(
-                self,
-            ) -> ::capnp::Result<crate::service_capnp::asset_hub::snapshot::Client> {
-                match self.reader.get_pointer_field(0).get_capability() {
-                    ::core::result::Result::Ok(c) => {
-                        ::core::result::Result::Ok(::capnp::capability::FromClientHook::new(c))
-                    }
-                    ::core::result::Result::Err(e) => ::core::result::Result::Err(e),
-                }
+            pub fn get_info(self) -> ::capnp::Result<crate::data_capnp::daemon_info::Reader<'a>> {
+                ::capnp::traits::FromPointerReader::get_from_pointer(
+                    &self.reader.get_pointer_field(0),
+                    ::core::option::Option::None,
+                )
+            }
+
+            pub fn has_info(&self) -> bool {
+                !self.reader.get_pointer_field(0).is_null()
             }
         }
 
@@ -7666,25 +10031,35 @@ pub mod asset_hub {
             }
 
             #[inline]
-            pub fn get_snapshot(
-                self,
-            ) -> ::capnp::Result<crate::service_capnp::asset_hub::snapshot::Client> {
-                match self.builder.get_pointer_field(0).get_capability() {
-                    ::core::result::Result::Ok(c) => {
-                        ::core::result::Result::Ok(::capnp::capability::FromClientHook::new(c))
-                    }
-                    ::core::result::Result::Err(e) => ::core::result::Result::Err(e),
-                }
+            pub fn get_info(self) -> ::capnp::Result<crate::data_capnp::daemon_info::Builder<'a>> {
+                ::capnp::traits::FromPointerBuilder::get_from_pointer(
+                    self.builder.get_pointer_field(0),
+                    ::core::option::Option::None,
+                )
             }
 
             #[inline]
-            pub fn set_snapshot(
+            pub fn set_info(
                 &mut self,
-                value: crate::service_capnp::asset_hub::snapshot::Client,
-            ) {
-                self.builder
-                    .get_pointer_field(0)
-                    .set_capability(value.client.hook);
+                value: crate::data_capnp::daemon_info::Reader<'_>,
+            ) -> ::capnp::Result<()> {
+                ::capnp::traits::SetPointerBuilder::set_pointer_builder(
+                    self.builder.get_pointer_field(0),
+                    value,
+                    false,
+                )
+            }
+
+            #[inline]
+            pub fn init_info(self) -> crate::data_capnp::daemon_info::Builder<'a> {
+                ::capnp::traits::FromPointerBuilder::init_pointer(
+                    self.builder.get_pointer_field(0),
+                    0,
+                )
+            }
+
+            pub fn has_info(&self) -> bool {
+                !self.builder.get_pointer_field(0).is_null()
             }
         }
 
@@ -7699,10 +10074,8 @@ pub mod asset_hub {
             }
         }
         impl Pipeline {
-            pub fn get_snapshot(&self) -> crate::service_capnp::asset_hub::snapshot::Client {
-                ::capnp::capability::FromClientHook::new(
-                    self._typeless.get_pointer_field(0).as_cap(),
-                )
+            pub fn get_info(&self) -> crate::data_capnp::daemon_info::Pipeline {
+                ::capnp::capability::FromTypelessPipeline::new(self._typeless.get_pointer_field(0))
             }
         }
         mod _private {
@@ -7711,7 +10084,7 @@ pub mod asset_hub {
                 data: 0,
                 pointers: 1,
             };
-            pub const TYPE_ID: u64 = 0x9dd9_afd2_86e7_766c;
+            pub const TYPE_ID: u64 = 0xd84f_2b97_1ce6_3a05;
         }
     }
 }
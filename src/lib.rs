@@ -13,7 +13,7 @@ use distill_core::{AssetRef, AssetUuid};
 use distill_loader::handle::{Handle, SerdeContext};
 
 #[cfg(feature = "distill-loader")]
-pub fn make_handle<T>(uuid: AssetUuid) -> Handle<T> {
+pub fn make_handle<T: distill_loader::handle::HandleAssetType>(uuid: AssetUuid) -> Handle<T> {
     SerdeContext::with_active(|loader_info_provider, ref_op_sender| {
         let load_handle = loader_info_provider
             .get_load_handle(&AssetRef::Uuid(uuid))
@@ -23,7 +23,9 @@ pub fn make_handle<T>(uuid: AssetUuid) -> Handle<T> {
 }
 
 #[cfg(feature = "distill-loader")]
-pub fn make_handle_from_str<T>(uuid_str: &str) -> Result<Handle<T>, distill_core::uuid::Error> {
+pub fn make_handle_from_str<T: distill_loader::handle::HandleAssetType>(
+    uuid_str: &str,
+) -> Result<Handle<T>, distill_core::uuid::Error> {
     use std::str::FromStr;
     Ok(make_handle(AssetUuid(
         *distill_core::uuid::Uuid::from_str(uuid_str)?.as_bytes(),
@@ -39,22 +41,29 @@ mod tests {
         path::PathBuf,
         str::FromStr,
         string::FromUtf8Error,
-        sync::{Once, RwLock},
+        sync::{Arc, Once, RwLock},
+        time::Duration,
     };
 
     use distill_core::{type_uuid, type_uuid::TypeUuid, AssetRef, AssetTypeId, AssetUuid};
     use distill_daemon::{init_logging, AssetDaemon};
     use distill_importer::{
-        AsyncImporter, ImportOp, ImportedAsset, ImporterValue, Result as ImportResult,
+        AsyncImporter, ExportAsset, ImportOp, ImportedAsset, ImporterValue, Result as ImportResult,
     };
     use distill_loader::{
+        loader::LoadFailure,
         rpc_io::RpcIO,
         storage::{
             AssetLoadOp, AssetStorage, DefaultIndirectionResolver, LoadStatus, LoaderInfoProvider,
         },
         LoadHandle, Loader,
     };
-    use futures::{future::BoxFuture, io::AsyncReadExt, AsyncRead};
+    use distill_schema::service::asset_hub;
+    use futures::{
+        future::BoxFuture,
+        io::{AsyncReadExt, AsyncWriteExt},
+        AsyncRead, AsyncWrite,
+    };
     use serde::{Deserialize, Serialize};
     use serial_test::serial;
     use uuid::Uuid;
@@ -161,7 +170,7 @@ mod tests {
 
         fn import<'a>(
             &'a self,
-            _op: &'a mut ImportOp,
+            op: &'a mut ImportOp,
             source: &'a mut (dyn AsyncRead + Unpin + Send + Sync),
             txt_format: &'a Self::Options,
             state: &'a mut Self::State,
@@ -175,27 +184,79 @@ mod tests {
                 let parsed_asset_data = txt_format
                     .from_utf8(bytes)
                     .expect("Failed to construct string asset.");
-
-                let load_deps = parsed_asset_data
+                // `include: <path>` lines are replaced with the contents of the file, if it exists
+                let parsed_asset_data = parsed_asset_data
                     .lines()
-                    .filter_map(|line| Uuid::from_str(line).ok())
-                    .map(|uuid| AssetRef::Uuid(AssetUuid(*uuid.as_bytes())))
-                    .collect::<Vec<AssetRef>>();
+                    .map(|line| match line.strip_prefix("include:") {
+                        Some(path) => op
+                            .read_source_dependency(path.trim())
+                            .ok()
+                            .and_then(|bytes| txt_format.from_utf8(bytes).ok())
+                            .unwrap_or_default(),
+                        None => format!("{}\n", line),
+                    })
+                    .collect::<String>();
+
+                Ok(ImporterValue {
+                    assets: vec![txt_asset(
+                        state.id.expect("AssetUuid not generated"),
+                        parsed_asset_data,
+                    )],
+                })
+            })
+        }
 
+        /// Writes the text with its dependencies as UUIDs, which replaces `ref: <path>` lines.
+        fn export<'a>(
+            &'a self,
+            output: &'a mut (dyn AsyncWrite + Unpin + Send + Sync),
+            _options: &'a Self::Options,
+            state: &'a mut Self::State,
+            assets: Vec<ExportAsset>,
+        ) -> BoxFuture<'a, ImportResult<ImporterValue>> {
+            Box::pin(async move {
+                let asset = assets.into_iter().next().expect("no asset to export").asset;
+                let text: String =
+                    bincode::deserialize(&asset.data).expect("Failed to deserialize string asset.");
+                let mut exported = text
+                    .lines()
+                    .filter(|line| !line.starts_with("ref:") && Uuid::from_str(line).is_err())
+                    .map(|line| format!("{}\n", line))
+                    .collect::<String>();
+                for dependency in &asset.metadata.load_deps {
+                    let uuid = Uuid::from_bytes(dependency.expect_uuid().0);
+                    exported.push_str(&format!("{}\n", uuid));
+                }
+                output.write_all(exported.as_bytes()).await?;
+                state.id = Some(asset.metadata.asset_id);
                 Ok(ImporterValue {
-                    assets: vec![ImportedAsset {
-                        id: state.id.expect("AssetUuid not generated"),
-                        search_tags: Vec::new(),
-                        build_deps: Vec::new(),
-                        load_deps,
-                        asset_data: Box::new(parsed_asset_data),
-                        build_pipeline: None,
-                    }],
+                    assets: vec![txt_asset(asset.metadata.asset_id, exported)],
                 })
             })
         }
     }
 
+    /// Lines with a UUID, and `ref: <path>` lines, are load dependencies of the text asset.
+    fn txt_asset(id: AssetUuid, text: String) -> ImportedAsset {
+        let load_deps = text
+            .lines()
+            .filter_map(|line| match line.strip_prefix("ref:") {
+                Some(path) => Some(AssetRef::Path(PathBuf::from(path.trim()))),
+                None => Uuid::from_str(line)
+                    .ok()
+                    .map(|uuid| AssetRef::Uuid(AssetUuid(*uuid.as_bytes()))),
+            })
+            .collect::<Vec<AssetRef>>();
+        ImportedAsset {
+            id,
+            search_tags: Vec::new(),
+            build_deps: Vec::new(),
+            load_deps,
+            asset_data: Box::new(text),
+            build_pipeline: None,
+        }
+    }
+
     fn wait_for_status(
         status: LoadStatus,
         handle: LoadHandle,
@@ -326,40 +387,1432 @@ mod tests {
         daemon_handle.join().unwrap();
     }
 
-    fn asset_tree() -> Vec<(AssetUuid, &'static str)> {
-        [
-            ("d83bb247-2710-4c10-83df-d7daa53e19bf", "asset_a.txt"),
-            ("23da999a-a974-4d0e-918a-f226ea0b3e69", "asset_b.txt"),
-            ("40becaa7-cedb-466a-afee-41fecb1c916f", "asset_c.txt"),
-            ("14f807b9-69ef-484b-9cb8-44787883b86d", "asset_d.txt"),
-        ]
-        .iter()
-        .map(|(id, file_name)| {
-            let asset_uuid = *uuid::Uuid::parse_str(id)
-                .unwrap_or_else(|_| panic!("Failed to parse `{}` as `Uuid`.", id))
-                .as_bytes();
+    #[test]
+    #[serial]
+    fn test_load_blocking() {
+        INIT.call_once(|| {
+            init_logging().unwrap();
+        });
 
-            (AssetUuid(asset_uuid), *file_name)
-        })
-        .collect::<Vec<(AssetUuid, &'static str)>>()
+        // Start daemon in a separate thread
+        let daemon_port = 2510;
+        let daemon_address = format!("127.0.0.1:{}", daemon_port);
+
+        let (daemon_handle, tx) = spawn_daemon(&daemon_address);
+
+        let mut loader = Loader::new(Box::new(RpcIO::new(daemon_address).unwrap()));
+        let storage = &mut Storage {
+            map: RwLock::new(HashMap::new()),
+        };
+        let asset_ids = asset_tree()
+            .iter()
+            .map(|(asset_uuid, _)| *asset_uuid)
+            .collect::<Vec<_>>();
+        let results = loader
+            .load_blocking(
+                &asset_ids,
+                storage,
+                &DefaultIndirectionResolver,
+                Duration::from_secs(10),
+            )
+            .unwrap();
+        for (result, (_, file_name)) in results.iter().zip(asset_tree()) {
+            let handle = result
+                .as_ref()
+                .unwrap_or_else(|err| panic!("Expected `{}` to be loaded: {}", file_name, err));
+            loader.remove_ref(*handle);
+        }
+
+        let missing_asset = AssetUuid::from("00000000-0000-0000-0000-000000000001");
+        let results = loader
+            .load_blocking(
+                &[missing_asset],
+                storage,
+                &DefaultIndirectionResolver,
+                Duration::from_secs(1),
+            )
+            .unwrap();
+        let err = results[0].as_ref().unwrap_err();
+        assert_eq!(vec![missing_asset], err.dependency_chain);
+        assert_eq!(LoadFailure::DoesNotExist, err.failure);
+
+        tx.send(true).unwrap();
+        daemon_handle.join().unwrap();
     }
 
-    fn spawn_daemon(
-        daemon_address: &str,
-    ) -> (
-        std::thread::JoinHandle<()>,
-        tokio::sync::oneshot::Sender<bool>,
-    ) {
-        let daemon_address = daemon_address
-            .parse()
-            .expect("Failed to parse string as `SocketAddr`.");
-        let tests_path = PathBuf::from_iter(&[env!("CARGO_MANIFEST_DIR"), "tests"]);
+    #[test]
+    #[serial]
+    fn test_handle_type_mismatch() {
+        use distill_loader::{
+            crossbeam_channel::unbounded,
+            handle::{self, AssetHandle, Handle},
+        };
+
+        INIT.call_once(|| {
+            init_logging().unwrap();
+        });
+
+        // Start daemon in a separate thread
+        let daemon_port = 2512;
+        let daemon_address = format!("127.0.0.1:{}", daemon_port);
+
+        let (daemon_handle, tx) = spawn_daemon(&daemon_address);
+
+        let mut loader = Loader::new(Box::new(RpcIO::new(daemon_address).unwrap()));
+        let storage = &mut Storage {
+            map: RwLock::new(HashMap::new()),
+        };
+        let (refop_tx, refop_rx) = unbounded();
+        let (asset_id, _) = asset_tree()[0];
+        // The asset is a `String`, so a handle expecting `TxtFormat` must fail to load.
+        let handle = Handle::<TxtFormat>::new(refop_tx, loader.add_ref(asset_id));
+        handle::process_ref_ops(&loader, &refop_rx);
+        assert!(wait_for_status(
+            LoadStatus::Error("".into()),
+            handle.load_handle(),
+            &mut loader,
+            storage
+        ));
+        if let LoadStatus::Error(err) = loader.get_load_status(handle.load_handle()) {
+            let expected = AssetTypeId(TxtFormat::UUID).to_string();
+            assert!(err.to_string().contains(&expected));
+        } else {
+            unreachable!("Expected the load to fail");
+        }
+        drop(handle);
+        handle::process_ref_ops(&loader, &refop_rx);
+
+        tx.send(true).unwrap();
+        daemon_handle.join().unwrap();
+    }
+
+    #[test]
+    #[serial]
+    fn test_stale_generational_handle() {
+        use distill_loader::storage::{GenerationalHandleAllocator, HandleAllocator};
+
+        INIT.call_once(|| {
+            init_logging().unwrap();
+        });
+
+        // Start daemon in a separate thread
+        let daemon_port = 2513;
+        let daemon_address = format!("127.0.0.1:{}", daemon_port);
+
+        let (daemon_handle, tx) = spawn_daemon(&daemon_address);
+
+        let allocator = Arc::new(GenerationalHandleAllocator::new());
+        let mut loader = Loader::new_with_handle_allocator(
+            Box::new(RpcIO::new(daemon_address).unwrap()),
+            allocator.clone(),
+        );
+        let storage = &mut Storage {
+            map: RwLock::new(HashMap::new()),
+        };
+        let (asset_id, _) = asset_tree()[3];
+        let handle = loader.add_ref(asset_id);
+        wait_for_status(LoadStatus::Loaded, handle, &mut loader, storage);
+        loader.remove_ref(handle);
+        wait_for_status(LoadStatus::NotRequested, handle, &mut loader, storage);
+        loader
+            .process(storage, &DefaultIndirectionResolver)
+            .unwrap();
+        assert!(!allocator.is_valid(handle));
+
+        // The slot of the freed handle is reused with a new generation.
+        let new_handle = loader.add_ref(asset_id);
+        assert_ne!(handle, new_handle);
+        wait_for_status(LoadStatus::Loaded, new_handle, &mut loader, storage);
+        assert!(matches!(
+            loader.get_load_status(handle),
+            LoadStatus::NotRequested
+        ));
+        assert!(loader.get_load_info(handle).is_none());
+        loader.remove_ref(new_handle);
+        wait_for_status(LoadStatus::NotRequested, new_handle, &mut loader, storage);
+
+        tx.send(true).unwrap();
+        daemon_handle.join().unwrap();
+    }
+
+    #[test]
+    #[serial]
+    fn test_weak_handle_upgrade() {
+        use distill_loader::{
+            crossbeam_channel::unbounded,
+            handle::{self, AssetHandle, GenericHandle},
+        };
+
+        INIT.call_once(|| {
+            init_logging().unwrap();
+        });
+
+        // Start daemon in a separate thread
+        let daemon_port = 2514;
+        let daemon_address = format!("127.0.0.1:{}", daemon_port);
+
+        let (daemon_handle, tx) = spawn_daemon(&daemon_address);
 
-        AssetDaemon::default()
-            .with_db_path(tests_path.join("assets_db"))
-            .with_address(daemon_address)
-            .with_importer("txt", TxtImporter)
-            .with_asset_dirs(vec![tests_path.join("assets")])
-            .run()
+        let mut loader = Loader::new(Box::new(RpcIO::new(daemon_address).unwrap()));
+        let storage = &mut Storage {
+            map: RwLock::new(HashMap::new()),
+        };
+        let (refop_tx, refop_rx) = unbounded();
+        let (asset_id, _) = asset_tree()[3];
+        let handle = GenericHandle::new(refop_tx.clone(), loader.add_ref(asset_id));
+        let weak = handle.downgrade();
+        assert!(weak.upgrade(&loader, &refop_tx).is_none());
+        wait_for_status(LoadStatus::Loaded, weak.load_handle(), &mut loader, storage);
+
+        // The upgraded handle keeps the asset loaded after the original handle is dropped.
+        let upgraded = weak.upgrade(&loader, &refop_tx).unwrap();
+        drop(handle);
+        handle::process_ref_ops(&loader, &refop_rx);
+        loader
+            .process(storage, &DefaultIndirectionResolver)
+            .unwrap();
+        assert!(matches!(upgraded.load_status(&loader), LoadStatus::Loaded));
+
+        drop(upgraded);
+        handle::process_ref_ops(&loader, &refop_rx);
+        wait_for_status(
+            LoadStatus::NotRequested,
+            weak.load_handle(),
+            &mut loader,
+            storage,
+        );
+        assert!(weak.upgrade(&loader, &refop_tx).is_none());
+
+        tx.send(true).unwrap();
+        daemon_handle.join().unwrap();
+    }
+
+    #[test]
+    #[serial]
+    fn test_indirection_table_subscribe() {
+        use distill_loader::storage::{IndirectIdentifier, IndirectionUpdate};
+
+        INIT.call_once(|| {
+            init_logging().unwrap();
+        });
+
+        // Start daemon in a separate thread
+        let daemon_port = 2515;
+        let daemon_address = format!("127.0.0.1:{}", daemon_port);
+
+        let (daemon_handle, tx) = spawn_daemon(&daemon_address);
+
+        let mut loader = Loader::new(Box::new(RpcIO::new(daemon_address).unwrap()));
+        let storage = &mut Storage {
+            map: RwLock::new(HashMap::new()),
+        };
+        let updates = loader.indirection_table().subscribe();
+        let path =
+            PathBuf::from_iter(&[env!("CARGO_MANIFEST_DIR"), "tests", "assets", "asset_d.txt"]);
+        let indirect = loader.add_ref_indirect(IndirectIdentifier::Path(
+            path.to_string_lossy().into_owned(),
+        ));
+        wait_for_status(LoadStatus::Loaded, indirect, &mut loader, storage);
+
+        let resolved = loader.indirection_table().resolve(indirect);
+        assert!(resolved.is_some());
+        assert_eq!(
+            updates.try_recv().unwrap(),
+            vec![IndirectionUpdate {
+                indirect_handle: indirect,
+                previous: None,
+                current: resolved,
+            }]
+        );
+
+        tx.send(true).unwrap();
+        daemon_handle.join().unwrap();
+    }
+
+    #[test]
+    #[serial]
+    fn test_dependency_cycle() {
+        INIT.call_once(|| {
+            init_logging().unwrap();
+        });
+
+        // Start daemon in a separate thread
+        let daemon_port = 2517;
+        let daemon_address = format!("127.0.0.1:{}", daemon_port);
+
+        let (daemon_handle, tx) = spawn_daemon(&daemon_address);
+
+        let mut loader = Loader::new(Box::new(RpcIO::new(daemon_address).unwrap()));
+        let storage = &mut Storage {
+            map: RwLock::new(HashMap::new()),
+        };
+        // asset uuids of "tests/assets/cycle_a.txt" and "tests/assets/cycle_b.txt"
+        let cycle_a = "5f0e1d2c-3b4a-4958-8776-a5b4c3d2e1f0";
+        let cycle_b = "9a8b7c6d-5e4f-4a3b-9c2d-1e0f2a3b4c5d";
+        let handle = loader.add_ref(cycle_a);
+        assert!(wait_for_status(
+            LoadStatus::Error("".into()),
+            handle,
+            &mut loader,
+            storage
+        ));
+        if let LoadStatus::Error(err) = loader.get_load_status(handle) {
+            let expected = format!("{} -> {} -> {}", cycle_a, cycle_b, cycle_a);
+            assert!(err.to_string().contains(&expected));
+        } else {
+            unreachable!("Expected the load to fail");
+        }
+        loader.remove_ref(handle);
+
+        tx.send(true).unwrap();
+        daemon_handle.join().unwrap();
+    }
+
+    #[test]
+    #[serial]
+    fn test_dependency_cycle_is_cleared() {
+        INIT.call_once(|| {
+            init_logging().unwrap();
+        });
+
+        let root = tempfile::tempdir().unwrap();
+        let asset_dir = root.path().join("assets");
+        std::fs::create_dir_all(&asset_dir).unwrap();
+        let a_id = "1a3c5e7f-9b2d-4f6a-8c0e-2d4f6a8c0e1b";
+        let b_id = "3c5e7a9b-1d3f-4b5d-9e7f-4b6d8f0a2c3d";
+        // UUID references, since path references would reimport "b.txt" when "a.txt" changes
+        write_with_meta(&asset_dir, "a.txt", "no references yet\n", a_id);
+        write_with_meta(&asset_dir, "b.txt", &format!("{}\n", a_id), b_id);
+
+        let daemon_port = 2526;
+        let daemon_address = format!("127.0.0.1:{}", daemon_port);
+        let db_path = root.path().join("db");
+        let asset_dirs = vec![asset_dir.clone()];
+        let (daemon_handle, tx) = spawn_daemon_with(&daemon_address, |daemon| {
+            daemon.with_db_path(db_path).with_asset_dirs(asset_dirs)
+        });
+
+        let wait_for_error =
+            |expect_error| wait_for_asset_error(&daemon_address, b_id, expect_error);
+        wait_for_error(false);
+
+        // "b.txt" is flagged when "a.txt" completes the cycle
+        std::fs::write(asset_dir.join("a.txt"), format!("{}\n", b_id)).unwrap();
+        wait_for_error(true);
+
+        // "b.txt" is not reimported when "a.txt" breaks the cycle, but its error is cleared
+        std::fs::write(asset_dir.join("a.txt"), "no more references\n").unwrap();
+        wait_for_error(false);
+
+        tx.send(true).unwrap();
+        daemon_handle.join().unwrap();
+    }
+
+    #[test]
+    #[serial]
+    fn test_pinned_importer() {
+        INIT.call_once(|| {
+            init_logging().unwrap();
+        });
+
+        // Start daemon in a separate thread
+        let daemon_port = 2518;
+        let daemon_address = format!("127.0.0.1:{}", daemon_port);
+
+        let (daemon_handle, tx) = spawn_daemon(&daemon_address);
+
+        let mut loader = Loader::new(Box::new(RpcIO::new(daemon_address).unwrap()));
+        let storage = &mut Storage {
+            map: RwLock::new(HashMap::new()),
+        };
+        // "tests/assets/pinned.notes" has no importer for its extension, but its .meta file pins
+        // the TxtImporter
+        let handle = loader.add_ref("3d5f7a9c-1b2e-4c6d-8f0a-2b4c6d8e0f1a");
+        assert!(wait_for_status(
+            LoadStatus::Loaded,
+            handle,
+            &mut loader,
+            storage
+        ));
+        loader.remove_ref(handle);
+
+        tx.send(true).unwrap();
+        daemon_handle.join().unwrap();
+    }
+
+    #[test]
+    #[serial]
+    fn test_source_dependency_reimport() {
+        INIT.call_once(|| {
+            init_logging().unwrap();
+        });
+
+        // "tests/assets/include.txt" includes "tests/assets/include.part", which doesn't exist
+        // until it is written by the test
+        let part_path = PathBuf::from_iter(&[
+            env!("CARGO_MANIFEST_DIR"),
+            "tests",
+            "assets",
+            "include.part",
+        ]);
+        let _ = std::fs::remove_file(&part_path);
+
+        // Start daemon in a separate thread
+        let daemon_port = 2519;
+        let daemon_address = format!("127.0.0.1:{}", daemon_port);
+
+        let (daemon_handle, tx) = spawn_daemon(&daemon_address);
+
+        let mut loader = Loader::new(Box::new(RpcIO::new(daemon_address).unwrap()));
+        let storage = &mut Storage {
+            map: RwLock::new(HashMap::new()),
+        };
+        let handle = loader.add_ref("6e1a2b3c-4d5e-4f60-8a7b-9c0d1e2f3a4b");
+        assert!(wait_for_status(
+            LoadStatus::Loaded,
+            handle,
+            &mut loader,
+            storage
+        ));
+        let loaded_size = || {
+            storage
+                .map
+                .read()
+                .unwrap()
+                .get(&handle)
+                .and_then(|s| s.size)
+        };
+
+        let text = "This text includes another file:\n";
+        let part = "text from the included file\n";
+        std::fs::write(&part_path, part).unwrap();
+        // the asset is a string, serialized with its length
+        let expected_size = Some(8 + text.len() + part.len());
+        let mut reimported = false;
+        for _ in 0..100 {
+            std::thread::sleep(std::time::Duration::from_millis(100));
+            loader
+                .process(storage, &DefaultIndirectionResolver)
+                .unwrap();
+            if loaded_size() == expected_size {
+                reimported = true;
+                break;
+            }
+        }
+        std::fs::remove_file(&part_path).unwrap();
+        assert!(
+            reimported,
+            "changing the included file did not reimport the source"
+        );
+        loader.remove_ref(handle);
+
+        tx.send(true).unwrap();
+        daemon_handle.join().unwrap();
+    }
+
+    #[test]
+    #[serial]
+    fn test_duplicate_asset_id_repair() {
+        INIT.call_once(|| {
+            init_logging().unwrap();
+        });
+
+        let assets_path = PathBuf::from_iter(&[env!("CARGO_MANIFEST_DIR"), "tests", "assets"]);
+        let copy_path = assets_path.join("duplicate_copy.txt");
+        let copy_meta_path = assets_path.join("duplicate_copy.txt.meta");
+        let _ = std::fs::remove_file(&copy_path);
+        let _ = std::fs::remove_file(&copy_meta_path);
+
+        // Start daemon in a separate thread
+        let daemon_port = 2520;
+        let daemon_address = format!("127.0.0.1:{}", daemon_port);
+
+        let (daemon_handle, tx) = spawn_daemon_with(&daemon_address, |daemon| {
+            daemon.with_duplicate_asset_id_repair(true)
+        });
+
+        let mut loader = Loader::new(Box::new(RpcIO::new(daemon_address).unwrap()));
+        let storage = &mut Storage {
+            map: RwLock::new(HashMap::new()),
+        };
+        let handle = loader.add_ref("8c2d4e6f-1a3b-4c5d-9e7f-0a1b2c3d4e5f");
+        assert!(wait_for_status(
+            LoadStatus::Loaded,
+            handle,
+            &mut loader,
+            storage
+        ));
+        let loaded_size = || {
+            storage
+                .map
+                .read()
+                .unwrap()
+                .get(&handle)
+                .and_then(|s| s.size)
+        };
+        let text = "duplicated text\n";
+        // the asset is a string, serialized with its length
+        assert_eq!(loaded_size(), Some(8 + text.len()));
+
+        // copy the source with its .meta file, which then claims the same asset ID
+        std::fs::copy(assets_path.join("duplicate.txt.meta"), &copy_meta_path).unwrap();
+        std::fs::write(&copy_path, "a copy of the duplicated text with changes\n").unwrap();
+        let mut repaired = false;
+        for _ in 0..100 {
+            std::thread::sleep(std::time::Duration::from_millis(100));
+            loader
+                .process(storage, &DefaultIndirectionResolver)
+                .unwrap();
+            let copy_meta = std::fs::read_to_string(&copy_meta_path).unwrap_or_default();
+            if copy_meta.contains("id: Some(") && !copy_meta.contains("8c2d4e6f") {
+                repaired = true;
+                break;
+            }
+        }
+        // give the daemon time to import the copy with its new ID
+        for _ in 0..10 {
+            std::thread::sleep(std::time::Duration::from_millis(100));
+            loader
+                .process(storage, &DefaultIndirectionResolver)
+                .unwrap();
+        }
+        let size_after_copy = loaded_size();
+        std::fs::remove_file(&copy_path).unwrap();
+        std::fs::remove_file(&copy_meta_path).unwrap();
+        assert!(repaired, "the .meta file of the copy was not rewritten");
+        assert_eq!(
+            size_after_copy,
+            Some(8 + text.len()),
+            "the original source lost its asset ID to the copy"
+        );
+        loader.remove_ref(handle);
+
+        tx.send(true).unwrap();
+        daemon_handle.join().unwrap();
+    }
+
+    #[test]
+    #[serial]
+    fn test_rename_rewrites_path_refs() {
+        INIT.call_once(|| {
+            init_logging().unwrap();
+        });
+
+        let rename_dir =
+            PathBuf::from_iter(&[env!("CARGO_MANIFEST_DIR"), "tests", "assets", "rename"]);
+        let _ = std::fs::remove_dir_all(&rename_dir);
+        std::fs::create_dir_all(&rename_dir).unwrap();
+        let write_txt = |name: &str, text: &str, id: &str| {
+            std::fs::write(rename_dir.join(name), text).unwrap();
+            std::fs::write(
+                rename_dir.join(format!("{}.meta", name)),
+                format!(
+                    "(\n    version: 2,\n    importer_options: (),\n    importer_state: (\n        id: Some(\"{}\"),\n    ),\n)",
+                    id
+                ),
+            )
+            .unwrap();
+        };
+        let target_id = "3b5f1c2e-8a4d-4e6f-9b7c-1d2e3f4a5b6c";
+        let other_id = "5c7e9a1b-3d5f-4b7a-9c1e-3f5a7b9c1d3e";
+        write_txt("other.txt", "text next to the moved file\n", other_id);
+        // the reference of the moved file itself is relative to its old directory
+        write_txt(
+            "target.txt",
+            "text that is moved\nref: other.txt\n",
+            target_id,
+        );
+        write_txt(
+            "ref.txt",
+            "text that references the moved file\nref: target.txt\n",
+            "7e9a2b4c-6d8f-4a1b-8c3d-5e7f9a1b3c5d",
+        );
+
+        // Start daemon in a separate thread
+        let daemon_port = 2521;
+        let daemon_address = format!("127.0.0.1:{}", daemon_port);
+
+        let (daemon_handle, tx) = spawn_daemon(&daemon_address);
+
+        let mut loader = Loader::new(Box::new(RpcIO::new(daemon_address.clone()).unwrap()));
+        let storage = &mut Storage {
+            map: RwLock::new(HashMap::new()),
+        };
+        // the asset is loaded with its dependency once the path reference resolves
+        let handle = loader.add_ref("7e9a2b4c-6d8f-4a1b-8c3d-5e7f9a1b3c5d");
+        assert!(wait_for_status(
+            LoadStatus::Loaded,
+            handle,
+            &mut loader,
+            storage
+        ));
+
+        let dangling_refs = rename_file(
+            &daemon_address,
+            "rename/target.txt",
+            "rename/moved/target.txt",
+        );
+        let moved = rename_dir.join("moved").join("target.txt").exists()
+            && rename_dir.join("moved").join("target.txt.meta").exists()
+            && !rename_dir.join("target.txt").exists()
+            && !rename_dir.join("target.txt.meta").exists();
+        let ref_text = std::fs::read_to_string(rename_dir.join("ref.txt")).unwrap();
+        let moved_text =
+            std::fs::read_to_string(rename_dir.join("moved").join("target.txt")).unwrap();
+        loader.remove_ref(handle);
+        tx.send(true).unwrap();
+        daemon_handle.join().unwrap();
+        std::fs::remove_dir_all(&rename_dir).unwrap();
+
+        assert_eq!(dangling_refs, Vec::new());
+        assert!(moved, "the source file and its .meta file were not moved");
+        assert_eq!(
+            ref_text,
+            format!("text that references the moved file\n{}\n", target_id)
+        );
+        assert_eq!(moved_text, format!("text that is moved\n{}\n", other_id));
+    }
+
+    #[test]
+    #[serial]
+    fn test_unresolved_path_ref_is_import_error() {
+        INIT.call_once(|| {
+            init_logging().unwrap();
+        });
+
+        let root = tempfile::tempdir().unwrap();
+        let asset_dir = root.path().join("assets");
+        std::fs::create_dir_all(&asset_dir).unwrap();
+        let id = "9d1f3b5c-7e9a-4c2d-8f1a-3b5c7d9e1f2a";
+        write_with_meta(&asset_dir, "ref.txt", "ref: missing.txt\n", id);
+
+        let daemon_port = 2527;
+        let daemon_address = format!("127.0.0.1:{}", daemon_port);
+        let db_path = root.path().join("db");
+        let asset_dirs = vec![asset_dir.clone()];
+        let (daemon_handle, tx) = spawn_daemon_with(&daemon_address, |daemon| {
+            daemon.with_db_path(db_path).with_asset_dirs(asset_dirs)
+        });
+
+        let error = wait_for_asset_error(&daemon_address, id, true).unwrap();
+        assert!(error.contains("missing.txt"), "{}", error);
+
+        // the error is cleared once the reference resolves
+        write_with_meta(
+            &asset_dir,
+            "missing.txt",
+            "no longer missing\n",
+            "1b3d5f7a-9c2e-4d6f-8a1b-5c7e9f1a3b5d",
+        );
+        wait_for_asset_error(&daemon_address, id, false);
+
+        tx.send(true).unwrap();
+        daemon_handle.join().unwrap();
+    }
+
+    #[cfg(feature = "directory_io")]
+    #[test]
+    #[serial]
+    fn test_export_dir_round_trip() {
+        use distill_loader::directory_io::DirectoryIO;
+
+        INIT.call_once(|| {
+            init_logging().unwrap();
+        });
+
+        let daemon_port = 2523;
+        let daemon_address = format!("127.0.0.1:{}", daemon_port);
+        let (daemon_handle, tx) = spawn_daemon(&daemon_address);
+
+        // wait for the daemon to have imported the asset before exporting
+        let mut loader = Loader::new(Box::new(RpcIO::new(daemon_address.clone()).unwrap()));
+        let handle = loader.add_ref(
+            // asset uuid of "tests/assets/asset.txt"
+            "b24d209d-6622-4d78-a983-731e8b76f04d",
+        );
+        let storage = Storage {
+            map: RwLock::new(HashMap::new()),
+        };
+        assert!(wait_for_status(
+            LoadStatus::Loaded,
+            handle,
+            &mut loader,
+            &storage
+        ));
+        let expected_size = storage.map.read().unwrap()[&handle].size;
+
+        let export_dir = tempfile::tempdir().unwrap();
+        let export_path = export_dir.path().to_path_buf();
+        // left behind by an earlier export of an asset that no longer exists
+        let stale_artifact = export_dir
+            .path()
+            .join("00000000-0000-0000-0000-000000000001.artifact");
+        std::fs::write(&stale_artifact, b"stale").unwrap();
+        let (num_assets, _) = with_snapshot(&daemon_address, |snapshot| async move {
+            distill_cli::export_dir(&snapshot, &export_path)
+                .await
+                .unwrap()
+        });
+        assert!(num_assets > 0);
+        assert!(export_dir.path().join("index.pack").exists());
+        assert!(!export_dir.path().join("index.pack.tmp").exists());
+        assert!(
+            !stale_artifact.exists(),
+            "the stale artifact was not removed"
+        );
+        let artifact_path = export_dir
+            .path()
+            .join("b24d209d-6622-4d78-a983-731e8b76f04d.artifact");
+        assert!(artifact_path.exists());
+        assert!(!artifact_path.with_extension("artifact.tmp").exists());
+
+        tx.send(true).unwrap();
+        daemon_handle.join().unwrap();
+
+        // the exported directory loads without the daemon
+        let mut dir_loader = Loader::new(Box::new(DirectoryIO::new(export_dir.path()).unwrap()));
+        let handle = dir_loader.add_ref("b24d209d-6622-4d78-a983-731e8b76f04d");
+        let dir_storage = Storage {
+            map: RwLock::new(HashMap::new()),
+        };
+        assert!(wait_for_status(
+            LoadStatus::Loaded,
+            handle,
+            &mut dir_loader,
+            &dir_storage
+        ));
+        assert_eq!(dir_storage.map.read().unwrap()[&handle].size, expected_size);
+    }
+
+    #[test]
+    #[serial]
+    fn test_rpc_cache_offline() {
+        INIT.call_once(|| {
+            init_logging().unwrap();
+        });
+
+        let daemon_port = 2524;
+        let daemon_address = format!("127.0.0.1:{}", daemon_port);
+        let (daemon_handle, tx) = spawn_daemon(&daemon_address);
+
+        let cache_dir = tempfile::tempdir().unwrap();
+        let mut loader = Loader::new(Box::new(
+            RpcIO::new(daemon_address.clone())
+                .unwrap()
+                .with_cache_dir(cache_dir.path())
+                .unwrap(),
+        ));
+        let handle = loader.add_ref(
+            // asset uuid of "tests/assets/asset_a.txt"
+            "d83bb247-2710-4c10-83df-d7daa53e19bf",
+        );
+        let storage = Storage {
+            map: RwLock::new(HashMap::new()),
+        };
+        assert!(wait_for_status(
+            LoadStatus::Loaded,
+            handle,
+            &mut loader,
+            &storage
+        ));
+        // dropping the loader writes the cache index
+        drop(loader);
+        tx.send(true).unwrap();
+        daemon_handle.join().unwrap();
+
+        // the asset and its dependencies load from the cache while the daemon is down
+        let mut loader = Loader::new(Box::new(
+            RpcIO::new(daemon_address)
+                .unwrap()
+                .with_cache_dir(cache_dir.path())
+                .unwrap(),
+        ));
+        let handle = loader.add_ref("d83bb247-2710-4c10-83df-d7daa53e19bf");
+        let storage = Storage {
+            map: RwLock::new(HashMap::new()),
+        };
+        assert!(wait_for_status(
+            LoadStatus::Loaded,
+            handle,
+            &mut loader,
+            &storage
+        ));
+        for (asset_uuid, file_name) in asset_tree().iter() {
+            let load = loader
+                .get_load(*asset_uuid)
+                .unwrap_or_else(|| panic!("Expected `{}` to be loading.", file_name));
+            assert!(
+                matches!(loader.get_load_status(load), LoadStatus::Loaded),
+                "Expected `{}` to be loaded from the cache.",
+                file_name
+            );
+        }
+    }
+
+    #[test]
+    #[serial]
+    fn test_orphaned_meta_files() {
+        INIT.call_once(|| {
+            init_logging().unwrap();
+        });
+
+        let root = tempfile::tempdir().unwrap();
+        let check_dir = root.path().join("assets");
+        std::fs::create_dir_all(&check_dir).unwrap();
+        let text = "text with a .meta file\n";
+        let deleted_id = "2d4f6a8c-1e3b-4d5f-8a7c-9e1b3d5f7a9c";
+        write_with_meta(
+            &check_dir,
+            "kept.txt",
+            text,
+            "6b8d0f2a-4c6e-4a8b-9d0f-2a4c6e8b0d1f",
+        );
+        write_with_meta(&check_dir, "deleted.txt", text, deleted_id);
+
+        // Start daemon in a separate thread
+        let daemon_port = 2522;
+        let daemon_address = format!("127.0.0.1:{}", daemon_port);
+        let db_path = root.path().join("db");
+        let asset_dirs = vec![check_dir.clone()];
+        let (daemon_handle, tx) = spawn_daemon_with(&daemon_address, |daemon| {
+            daemon.with_db_path(db_path).with_asset_dirs(asset_dirs)
+        });
+
+        let mut loader = Loader::new(Box::new(RpcIO::new(daemon_address.clone()).unwrap()));
+        let storage = &mut Storage {
+            map: RwLock::new(HashMap::new()),
+        };
+        let handle = loader.add_ref(deleted_id);
+        assert!(wait_for_status(
+            LoadStatus::Loaded,
+            handle,
+            &mut loader,
+            storage
+        ));
+        loader.remove_ref(handle);
+
+        std::fs::remove_file(check_dir.join("deleted.txt")).unwrap();
+        let deleted_uuid = *Uuid::parse_str(deleted_id).unwrap().as_bytes();
+        // waits for the asset to be removed and the .meta file to be reported
+        let mut deleted = false;
+        let mut orphaned_metas = Vec::new();
+        for _ in 0..100 {
+            std::thread::sleep(std::time::Duration::from_millis(100));
+            let asset_count = with_snapshot(&daemon_address, |snapshot| async move {
+                let mut request = snapshot.get_asset_metadata_request();
+                request.get().init_assets(1).get(0).set_id(&deleted_uuid);
+                let response = request.send().promise.await.unwrap();
+                response.get().unwrap().get_assets().unwrap().len()
+            });
+            deleted = asset_count == 0;
+            orphaned_metas = with_snapshot(&daemon_address, |snapshot| async move {
+                let response = snapshot
+                    .get_meta_file_issues_request()
+                    .send()
+                    .promise
+                    .await
+                    .unwrap();
+                let orphaned_metas = response.get().unwrap().get_orphaned_metas().unwrap();
+                orphaned_metas
+                    .iter()
+                    .map(|path| String::from_utf8_lossy(path.unwrap()).into_owned())
+                    .collect::<Vec<_>>()
+            });
+            if deleted && !orphaned_metas.is_empty() {
+                break;
+            }
+        }
+        let orphan_kept = check_dir.join("deleted.txt.meta").exists();
+        let remove_meta = |path: &'static str| {
+            with_snapshot(&daemon_address, |snapshot| async move {
+                let mut request = snapshot.remove_orphaned_meta_file_request();
+                request.get().set_path(path.as_bytes());
+                request.send().promise.await.map(|_| ())
+            })
+        };
+        let removed_kept = remove_meta("kept.txt.meta");
+        let removed_orphan = remove_meta("deleted.txt.meta");
+        let orphan_exists = check_dir.join("deleted.txt.meta").exists();
+        let regenerated_kept = with_snapshot(&daemon_address, |snapshot| async move {
+            let mut request = snapshot.regenerate_meta_file_request();
+            request.get().set_path(b"kept.txt");
+            request.send().promise.await.map(|_| ())
+        });
+        tx.send(true).unwrap();
+        daemon_handle.join().unwrap();
+
+        assert!(
+            deleted,
+            "the asset of the deleted source file was not removed"
+        );
+        assert!(
+            orphan_kept,
+            "the orphaned .meta file was deleted by the daemon"
+        );
+        assert_eq!(orphaned_metas, vec!["deleted.txt.meta".to_string()]);
+        assert!(
+            removed_kept.is_err(),
+            "a .meta file with a source file was removed"
+        );
+        assert!(removed_orphan.is_ok());
+        assert!(!orphan_exists, "the orphaned .meta file was not removed");
+        assert!(
+            regenerated_kept.is_err(),
+            "an existing .meta file was overwritten"
+        );
+    }
+
+    /// Writes a source file to `dir` with a .meta file that gives its asset the `id`.
+    #[test]
+    #[serial]
+    fn test_missing_meta_file() {
+        INIT.call_once(|| {
+            init_logging().unwrap();
+        });
+
+        let root = tempfile::tempdir().unwrap();
+        let check_dir = root.path().join("assets");
+        std::fs::create_dir_all(&check_dir).unwrap();
+        let id = "4e6a8c0e-2b4d-4f6a-8c0e-2b4d6f8a0c2e";
+        write_with_meta(&check_dir, "lost.txt", "text with a lost .meta file\n", id);
+
+        // Start daemon in a separate thread
+        let daemon_port = 2528;
+        let daemon_address = format!("127.0.0.1:{}", daemon_port);
+        let db_path = root.path().join("db");
+        let asset_dirs = vec![check_dir.clone()];
+        let (daemon_handle, tx) = spawn_daemon_with(&daemon_address, |daemon| {
+            daemon.with_db_path(db_path).with_asset_dirs(asset_dirs)
+        });
+        wait_for_asset_error(&daemon_address, id, false);
+
+        let meta_path = check_dir.join("lost.txt.meta");
+        std::fs::remove_file(&meta_path).unwrap();
+        // once a file added after the deletion is imported, the deletion has been processed
+        let marker_id = "6c8e0a2c-4d6f-4a8c-9e0a-4d6f8a0c2e4a";
+        write_with_meta(&check_dir, "marker.txt", "marker\n", marker_id);
+        wait_for_asset_error(&daemon_address, marker_id, false);
+        // waits for the source file to be reported with its asset IDs
+        let mut missing_metas = Vec::new();
+        for _ in 0..100 {
+            missing_metas = with_snapshot(&daemon_address, |snapshot| async move {
+                let response = snapshot
+                    .get_meta_file_issues_request()
+                    .send()
+                    .promise
+                    .await
+                    .unwrap();
+                let missing_metas = response.get().unwrap().get_missing_metas().unwrap();
+                missing_metas
+                    .iter()
+                    .map(|missing_meta| {
+                        let path = String::from_utf8_lossy(missing_meta.get_path().unwrap());
+                        let assets = missing_meta
+                            .get_assets()
+                            .unwrap()
+                            .iter()
+                            .map(|asset| Uuid::from_slice(asset.get_id().unwrap()).unwrap())
+                            .collect::<Vec<_>>();
+                        (path.into_owned(), assets)
+                    })
+                    .collect::<Vec<_>>()
+            });
+            if !missing_metas.is_empty() {
+                break;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(100));
+        }
+        let meta_recreated = meta_path.exists();
+        let regenerated = with_snapshot(&daemon_address, |snapshot| async move {
+            let mut request = snapshot.regenerate_meta_file_request();
+            request.get().set_path(b"lost.txt");
+            request.send().promise.await.map(|_| ())
+        });
+        let meta = std::fs::read_to_string(&meta_path).unwrap_or_default();
+        // the asset is still served under the same ID
+        wait_for_asset_error(&daemon_address, id, false);
+        tx.send(true).unwrap();
+        daemon_handle.join().unwrap();
+
+        let uuid = Uuid::parse_str(id).unwrap();
+        assert_eq!(missing_metas, vec![("lost.txt".to_string(), vec![uuid])]);
+        assert!(
+            !meta_recreated,
+            "the missing .meta file was recreated before it was regenerated"
+        );
+        assert!(regenerated.is_ok(), "{:?}", regenerated);
+        assert!(
+            meta.contains(id),
+            "the regenerated .meta file does not keep the asset ID: {}",
+            meta
+        );
+    }
+
+    fn write_with_meta(dir: &std::path::Path, name: &str, text: &str, id: &str) {
+        std::fs::write(dir.join(name), text).unwrap();
+        std::fs::write(
+            dir.join(format!("{}.meta", name)),
+            format!(
+                "(\n    version: 2,\n    importer_options: (),\n    importer_state: (\n        id: Some(\"{}\"),\n    ),\n)",
+                id
+            ),
+        )
+        .unwrap();
+    }
+
+    /// Waits for the import error of the asset `id` to be set or cleared, and returns it.
+    fn wait_for_asset_error(daemon_address: &str, id: &str, expect_error: bool) -> Option<String> {
+        let uuid = *Uuid::parse_str(id).unwrap().as_bytes();
+        let mut error = None;
+        for _ in 0..100 {
+            std::thread::sleep(std::time::Duration::from_millis(100));
+            // a refused connection is retried on the next poll
+            let deadline = std::time::Instant::now();
+            let result = try_with_snapshot(daemon_address, deadline, |snapshot| async move {
+                let mut request = snapshot.get_asset_metadata_request();
+                request.get().init_assets(1).get(0).set_id(&uuid);
+                let response = request.send().promise.await.unwrap();
+                let assets = response.get().unwrap().get_assets().unwrap();
+                assets
+                    .iter()
+                    .next()
+                    .map(|asset| distill_schema::parse_db_metadata(&asset).error)
+            });
+            error = result.ok().flatten();
+            if let Some(error) = &error {
+                if error.is_some() == expect_error {
+                    return error.clone();
+                }
+            }
+        }
+        panic!("error of asset {} is {:?}", id, error);
+    }
+
+    /// Moves a source file with the `renameFile` RPC, and returns the source files and paths of
+    /// the path references that could not be rewritten.
+    fn rename_file(daemon_address: &str, from: &str, to: &str) -> Vec<(String, String)> {
+        with_snapshot(daemon_address, |snapshot| async move {
+            let mut request = snapshot.rename_file_request();
+            request.get().set_from(from.as_bytes());
+            request.get().set_to(to.as_bytes());
+            let response = request.send().promise.await.unwrap();
+            let dangling_refs = response.get().unwrap().get_dangling_refs().unwrap();
+            dangling_refs
+                .iter()
+                .map(|dangling_ref| {
+                    (
+                        String::from_utf8_lossy(dangling_ref.get_source().unwrap()).into_owned(),
+                        String::from_utf8_lossy(dangling_ref.get_path().unwrap()).into_owned(),
+                    )
+                })
+                .collect()
+        })
+    }
+
+    /// Connects to the daemon, and runs `f` with a new snapshot. The connection is retried for a
+    /// while, since the daemon may still be starting.
+    fn with_snapshot<F, Fut, T>(daemon_address: &str, f: F) -> T
+    where
+        F: FnOnce(asset_hub::snapshot::Client) -> Fut,
+        Fut: std::future::Future<Output = T>,
+    {
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(10);
+        try_with_snapshot(daemon_address, deadline, f)
+            .unwrap_or_else(|err| panic!("Failed to connect to {}: {}", daemon_address, err))
+    }
+
+    /// Like `with_snapshot`, but gives up connecting once `deadline` has passed.
+    fn try_with_snapshot<F, Fut, T>(
+        daemon_address: &str,
+        deadline: std::time::Instant,
+        f: F,
+    ) -> std::io::Result<T>
+    where
+        F: FnOnce(asset_hub::snapshot::Client) -> Fut,
+        Fut: std::future::Future<Output = T>,
+    {
+        use capnp_rpc::{rpc_twoparty_capnp, twoparty, RpcSystem};
+        use tokio_util::compat::*;
+
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        let local = tokio::task::LocalSet::new();
+        local.block_on(&runtime, async {
+            let stream = loop {
+                match tokio::net::TcpStream::connect(daemon_address).await {
+                    Ok(stream) => break stream,
+                    Err(_) if std::time::Instant::now() < deadline => {
+                        tokio::time::sleep(std::time::Duration::from_millis(50)).await
+                    }
+                    Err(err) => return Err(err),
+                }
+            };
+            let (reader, writer) = stream.into_split();
+            let network = twoparty::VatNetwork::new(
+                reader.compat(),
+                writer.compat_write(),
+                rpc_twoparty_capnp::Side::Client,
+                Default::default(),
+            );
+            let mut rpc_system = RpcSystem::new(Box::new(network), None);
+            let hub: asset_hub::Client = rpc_system.bootstrap(rpc_twoparty_capnp::Side::Server);
+            tokio::task::spawn_local(rpc_system);
+
+            let response = hub.get_snapshot_request().send().promise.await.unwrap();
+            let snapshot = response.get().unwrap().get_snapshot().unwrap();
+            Ok(f(snapshot).await)
+        })
+    }
+
+    #[cfg(feature = "serde_importers")]
+    #[test]
+    fn test_importer_map_precedence() {
+        use std::path::Path;
+
+        use distill_daemon::ImporterMap;
+        use distill_importer::{BundleImporter, RonImporter};
+
+        let txt_type = AssetTypeId(TxtImporter::UUID);
+        let ron_type = AssetTypeId(RonImporter::UUID);
+        let bundle_type = AssetTypeId(BundleImporter::UUID);
+        let mut importers = ImporterMap::default();
+        importers.insert("ron", Box::new(RonImporter));
+        importers.insert("Prefab.RON", Box::new(TxtImporter));
+        importers.insert_glob("levels/**/*.ron", Box::new(BundleImporter));
+        let importer_type = |path: &str| {
+            importers
+                .get_by_path(Path::new(path))
+                .map(|importer| AssetTypeId(importer.uuid()))
+        };
+
+        assert_eq!(importer_type("assets/item.ron"), Some(ron_type));
+        // the longest extension wins
+        assert_eq!(importer_type("assets/door.prefab.ron"), Some(txt_type));
+        assert_eq!(importer_type("assets/door.PREFAB.ron"), Some(txt_type));
+        assert_eq!(importer_type("assets/.prefab.ron"), Some(ron_type));
+        // globs take precedence over extensions
+        assert_eq!(importer_type("assets/levels/one.ron"), Some(bundle_type));
+        assert_eq!(
+            importer_type("assets/levels/forest/door.prefab.ron"),
+            Some(bundle_type)
+        );
+        assert_eq!(importer_type("assets/levels.ron"), Some(ron_type));
+        assert_eq!(importer_type("assets/item.png"), None);
+
+        assert_eq!(
+            importers
+                .get_by_uuid(&txt_type)
+                .map(|importer| AssetTypeId(importer.uuid())),
+            Some(txt_type)
+        );
+        assert!(importers.get_by_uuid(&AssetTypeId::default()).is_none());
+    }
+
+    #[cfg(feature = "serde_importers")]
+    #[test]
+    #[serial]
+    fn test_load_bundle() {
+        INIT.call_once(|| {
+            init_logging().unwrap();
+        });
+
+        // Start daemon in a separate thread
+        let daemon_port = 2516;
+        let daemon_address = format!("127.0.0.1:{}", daemon_port);
+
+        let (daemon_handle, tx) = spawn_daemon(&daemon_address);
+
+        let mut loader = Loader::new(Box::new(RpcIO::new(daemon_address).unwrap()));
+        let storage = &mut Storage {
+            map: RwLock::new(HashMap::new()),
+        };
+        let bundle = loader.add_ref("7c2c5a34-6a0c-4d25-9f0c-3e1f4bd1f2a5");
+        wait_for_status(LoadStatus::Loaded, bundle, &mut loader, storage);
+
+        // The path and the tag query members are loaded along with the bundle
+        for (asset_id, _) in [asset_tree()[1], asset_tree()[3]].iter() {
+            let member = loader
+                .get_load(*asset_id)
+                .expect("bundle member is not loading");
+            assert!(matches!(loader.get_load_status(member), LoadStatus::Loaded));
+        }
+
+        tx.send(true).unwrap();
+        daemon_handle.join().unwrap();
+    }
+
+    #[cfg(feature = "serde_importers")]
+    #[test]
+    #[serial]
+    fn test_bundle_follows_tag_changes() {
+        INIT.call_once(|| {
+            init_logging().unwrap();
+        });
+
+        let root = tempfile::tempdir().unwrap();
+        let asset_dir = root.path().join("assets");
+        std::fs::create_dir_all(&asset_dir).unwrap();
+        let write_with_meta =
+            |name: &str, text: &str, id: &str| write_with_meta(&asset_dir, name, text, id);
+        let bundle_id = "0d3c5b7a-9e1f-4a2b-8c4d-6e8f0a2b4c6d";
+        let member_id = "2f4e6a8c-0b1d-4e3f-9a5b-7c9d1e3f5a7b";
+        write_with_meta(
+            "level.bundle",
+            "(\n    assets: [\n        Tag(\"file_name=member.txt\"),\n    ],\n)",
+            bundle_id,
+        );
+
+        let daemon_port = 2525;
+        let daemon_address = format!("127.0.0.1:{}", daemon_port);
+        let db_path = root.path().join("db");
+        let asset_dirs = vec![asset_dir.clone()];
+        let (daemon_handle, tx) = spawn_daemon_with(&daemon_address, |daemon| {
+            daemon.with_db_path(db_path).with_asset_dirs(asset_dirs)
+        });
+
+        // waits for the load dependencies of the bundle in the daemon to be `expected`
+        let wait_for_members = |expected: Vec<AssetRef>| {
+            let bundle_uuid = *Uuid::parse_str(bundle_id).unwrap().as_bytes();
+            let mut members = None;
+            for _ in 0..100 {
+                std::thread::sleep(std::time::Duration::from_millis(100));
+                members = with_snapshot(&daemon_address, |snapshot| async move {
+                    let mut request = snapshot.get_asset_metadata_request();
+                    request.get().init_assets(1).get(0).set_id(&bundle_uuid);
+                    let response = request.send().promise.await.unwrap();
+                    let assets = response.get().unwrap().get_assets().unwrap();
+                    assets.iter().next().and_then(|asset| {
+                        distill_schema::parse_db_metadata(&asset)
+                            .artifact
+                            .map(|artifact| artifact.load_deps)
+                    })
+                });
+                if members.as_ref() == Some(&expected) {
+                    return;
+                }
+            }
+            panic!("bundle members are {:?}, expected {:?}", members, expected);
+        };
+        wait_for_members(Vec::new());
+
+        // an asset that gains a matching tag joins the bundle
+        write_with_meta("member.txt", "member of the level\n", member_id);
+        let member = AssetRef::Uuid(AssetUuid(*Uuid::parse_str(member_id).unwrap().as_bytes()));
+        wait_for_members(vec![member]);
+
+        // and leaves it when it is deleted
+        std::fs::remove_file(asset_dir.join("member.txt")).unwrap();
+        std::fs::remove_file(asset_dir.join("member.txt.meta")).unwrap();
+        wait_for_members(Vec::new());
+
+        tx.send(true).unwrap();
+        daemon_handle.join().unwrap();
+    }
+
+    #[test]
+    fn test_handle_human_readable_serde() {
+        use distill_core::importer_context::ImporterContext;
+        use distill_loader::handle::{
+            AssetHandle, GenericHandle, Handle, HandleSerdeContextProvider,
+        };
+
+        let ctx = HandleSerdeContextProvider.handle();
+        let (asset_id, _) = asset_tree()[0];
+        futures::executor::block_on(ctx.scope(Box::pin(async {
+            // UUID references are written as hyphenated strings
+            let handle = crate::make_handle::<TxtFormat>(asset_id);
+            let text = ron::to_string(&handle).unwrap();
+            assert_eq!(text, format!("\"{}\"", asset_id));
+            let deserialized: Handle<TxtFormat> = ron::from_str(&text).unwrap();
+            assert_eq!(deserialized.load_handle(), handle.load_handle());
+
+            // Path references round-trip the path
+            let path_handle: GenericHandle = ron::from_str("\"textures/wall.png\"").unwrap();
+            assert_eq!(
+                ron::to_string(&path_handle).unwrap(),
+                "\"textures/wall.png\""
+            );
+
+            // Tag references round-trip the tag query
+            let tag_handle: GenericHandle = ron::from_str("\"tag:name=PlayerSword\"").unwrap();
+            assert_ne!(tag_handle.load_handle(), path_handle.load_handle());
+            assert_eq!(
+                ron::to_string(&tag_handle).unwrap(),
+                "\"tag:name=PlayerSword\""
+            );
+        })));
+    }
+
+    #[test]
+    fn test_tag_indirection_resolver() {
+        use distill_core::{ArtifactMetadata, AssetMetadata};
+        use distill_loader::storage::{
+            IndirectIdentifier, IndirectResolution, IndirectionResolver, TagIndirectionResolver,
+        };
+
+        let asset = |id: u8, name: &str, type_id: AssetTypeId| AssetMetadata {
+            id: AssetUuid([id; 16]),
+            search_tags: vec![("name".to_string(), Some(name.to_string()))],
+            artifact: Some(ArtifactMetadata {
+                type_id,
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let txt_type = AssetTypeId(TxtFormat::UUID);
+        let candidates = vec![
+            (
+                PathBuf::from("swords.txt"),
+                vec![
+                    asset(2, "PlayerSword", txt_type),
+                    asset(1, "EnemySword", txt_type),
+                ],
+            ),
+            (
+                PathBuf::from("other_swords.txt"),
+                vec![asset(3, "PlayerSword", AssetTypeId::default())],
+            ),
+        ];
+        let resolve =
+            |id: IndirectIdentifier| TagIndirectionResolver.try_resolve(&id, candidates.clone());
+
+        assert_eq!(
+            resolve(IndirectIdentifier::Tag("name=EnemySword".into())),
+            IndirectResolution::Resolved(AssetUuid([1; 16]))
+        );
+        assert_eq!(
+            resolve(IndirectIdentifier::PathWithTagAndType(
+                "swords.txt".into(),
+                "name=PlayerSword".into(),
+                txt_type
+            )),
+            IndirectResolution::Resolved(AssetUuid([2; 16]))
+        );
+        // Matches are reported in UUID order, regardless of the order of the candidates
+        assert_eq!(
+            resolve(IndirectIdentifier::Tag("name".into())),
+            IndirectResolution::Ambiguous(vec![
+                AssetUuid([1; 16]),
+                AssetUuid([2; 16]),
+                AssetUuid([3; 16])
+            ])
+        );
+        assert_eq!(
+            resolve(IndirectIdentifier::Tag("name=Shield".into())),
+            IndirectResolution::NotFound
+        );
+    }
+
+    #[cfg(feature = "generic_storage")]
+    #[test]
+    #[serial]
+    fn test_generic_storage_decode_threads() {
+        use distill_loader::{
+            crossbeam_channel::unbounded,
+            handle::{TypedAssetStorage, WeakHandle},
+            GenericAssetStorage,
+        };
+
+        INIT.call_once(|| {
+            init_logging().unwrap();
+        });
+
+        // Start daemon in a separate thread
+        let daemon_port = 2511;
+        let daemon_address = format!("127.0.0.1:{}", daemon_port);
+
+        let (daemon_handle, tx) = spawn_daemon(&daemon_address);
+
+        let mut loader = Loader::new(Box::new(RpcIO::new(daemon_address).unwrap()));
+        // Only update one asset per `process`, since every asset exceeds the budget.
+        loader.set_max_update_bytes_per_process(Some(1));
+        let (refop_tx, _refop_rx) = unbounded();
+        let storage =
+            GenericAssetStorage::new(refop_tx, loader.indirection_table()).with_decode_threads(2);
+        storage.add_storage::<String>();
+        let asset_ids = asset_tree()
+            .iter()
+            .map(|(asset_uuid, _)| *asset_uuid)
+            .collect::<Vec<_>>();
+        let results = loader
+            .load_blocking(
+                &asset_ids,
+                &storage,
+                &DefaultIndirectionResolver,
+                Duration::from_secs(10),
+            )
+            .unwrap();
+        for (result, (_, file_name)) in results.iter().zip(asset_tree()) {
+            let handle = result
+                .as_ref()
+                .unwrap_or_else(|err| panic!("Expected `{}` to be loaded: {}", file_name, err));
+            let asset: Option<&String> = storage.get(&WeakHandle::new(*handle));
+            assert!(asset.is_some(), "Expected `{}` to be stored", file_name);
+            loader.remove_ref(*handle);
+        }
+
+        tx.send(true).unwrap();
+        daemon_handle.join().unwrap();
+    }
+
+    fn asset_tree() -> Vec<(AssetUuid, &'static str)> {
+        [
+            ("d83bb247-2710-4c10-83df-d7daa53e19bf", "asset_a.txt"),
+            ("23da999a-a974-4d0e-918a-f226ea0b3e69", "asset_b.txt"),
+            ("40becaa7-cedb-466a-afee-41fecb1c916f", "asset_c.txt"),
+            ("14f807b9-69ef-484b-9cb8-44787883b86d", "asset_d.txt"),
+        ]
+        .iter()
+        .map(|(id, file_name)| {
+            let asset_uuid = *uuid::Uuid::parse_str(id)
+                .unwrap_or_else(|_| panic!("Failed to parse `{}` as `Uuid`.", id))
+                .as_bytes();
+
+            (AssetUuid(asset_uuid), *file_name)
+        })
+        .collect::<Vec<(AssetUuid, &'static str)>>()
+    }
+
+    fn spawn_daemon(
+        daemon_address: &str,
+    ) -> (
+        std::thread::JoinHandle<()>,
+        tokio::sync::oneshot::Sender<bool>,
+    ) {
+        spawn_daemon_with(daemon_address, |daemon| daemon)
+    }
+
+    fn spawn_daemon_with(
+        daemon_address: &str,
+        configure: impl FnOnce(AssetDaemon) -> AssetDaemon,
+    ) -> (
+        std::thread::JoinHandle<()>,
+        tokio::sync::oneshot::Sender<bool>,
+    ) {
+        let daemon_address = daemon_address
+            .parse()
+            .expect("Failed to parse string as `SocketAddr`.");
+        let tests_path = PathBuf::from_iter(&[env!("CARGO_MANIFEST_DIR"), "tests"]);
+
+        let daemon = configure(
+            AssetDaemon::default()
+                .with_db_path(tests_path.join("assets_db"))
+                .with_address(daemon_address)
+                .with_importer("txt", TxtImporter)
+                .with_asset_dirs(vec![tests_path.join("assets")]),
+        )
+        .run();
+        // tests can connect as soon as this returns
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(10);
+        while std::net::TcpStream::connect(daemon_address).is_err() {
+            assert!(
+                std::time::Instant::now() < deadline,
+                "daemon did not accept connections"
+            );
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        }
+        daemon
     }
 }